@@ -26,10 +26,13 @@ pub enum DbType {
     Foxlake,
     #[strum(serialize = "tidb")]
     Tidb,
+    #[strum(serialize = "file")]
+    File,
 }
 
-#[derive(Display, EnumString, IntoStaticStr, Debug, Clone)]
+#[derive(Display, EnumString, IntoStaticStr, Debug, Clone, Default)]
 pub enum ExtractType {
+    #[default]
     #[strum(serialize = "snapshot")]
     Snapshot,
     #[strum(serialize = "cdc")]
@@ -42,6 +45,8 @@ pub enum ExtractType {
     Struct,
     #[strum(serialize = "snapshot_file")]
     SnapshotFile,
+    #[strum(serialize = "aof_file")]
+    AofFile,
     #[strum(serialize = "scan")]
     Scan,
     #[strum(serialize = "reshard")]
@@ -110,6 +115,26 @@ pub enum ConflictPolicyEnum {
     Interrupt,
 }
 
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum FileFormat {
+    #[default]
+    #[strum(serialize = "parquet")]
+    Parquet,
+    #[strum(serialize = "csv")]
+    Csv,
+    #[strum(serialize = "jsonl")]
+    Jsonl,
+}
+
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum TicdcProtocol {
+    #[default]
+    #[strum(serialize = "canal-json")]
+    CanalJson,
+    #[strum(serialize = "open-protocol")]
+    OpenProtocol,
+}
+
 #[derive(Display, EnumString, IntoStaticStr, PartialEq)]
 pub enum MetaCenterType {
     #[strum(serialize = "basic")]
@@ -118,6 +143,32 @@ pub enum MetaCenterType {
     DbEngine,
 }
 
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum DeadLetterType {
+    #[default]
+    #[strum(serialize = "log")]
+    Log,
+    #[strum(serialize = "kafka")]
+    Kafka,
+    #[strum(serialize = "table")]
+    Table,
+}
+
+#[derive(Clone, Debug, Display, EnumString, IntoStaticStr, PartialEq, Default)]
+pub enum DdlPolicyType {
+    #[default]
+    #[strum(serialize = "apply")]
+    Apply,
+    #[strum(serialize = "skip")]
+    Skip,
+    #[strum(serialize = "apply_ignore_errors")]
+    ApplyIgnoreErrors,
+    #[strum(serialize = "pause_for_approval")]
+    PauseForApproval,
+    #[strum(serialize = "log_only")]
+    LogOnly,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Display, EnumString, IntoStaticStr)]
 pub enum TaskType {
     #[strum(serialize = "struct")]
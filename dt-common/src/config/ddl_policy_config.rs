@@ -0,0 +1,17 @@
+use super::config_enums::DdlPolicyType;
+
+#[derive(Clone, Debug, Default)]
+pub struct DdlPolicyConfig {
+    // policy of ddls matching none of the policies
+    pub default_policy: DdlPolicyType,
+    // json:[{"ddl_types":"drop_table,truncate_table","tbs":"db_1.*","policy":"skip"}]
+    pub policies: String,
+    // pending ddls of pause_for_approval are written to approval_dir,
+    // operators approve / reject them by creating marker files or by the http api
+    pub approval_dir: String,
+    pub approval_http_host: String,
+    // 0: http api disabled
+    pub approval_http_port: u64,
+    // required by the http api, sent as header: Authorization: Bearer [token]
+    pub approval_http_token: String,
+}
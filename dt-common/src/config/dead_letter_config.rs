@@ -0,0 +1,16 @@
+use super::config_enums::{DbType, DeadLetterType};
+
+#[derive(Clone, Debug, Default)]
+pub struct DeadLetterConfig {
+    // log: write to [runtime].log_dir/dead_letter/dead_letter.log
+    // kafka: send to topic of the kafka cluster: url
+    // table: insert into tb (db.tb / schema.tb) of the mysql / pg: url
+    pub dead_letter_type: DeadLetterType,
+    pub db_type: DbType,
+    pub url: String,
+    pub topic: String,
+    pub tb: String,
+    // stop the task if more than threshold rows are dead-lettered in window_secs, 0: no limit
+    pub threshold: u64,
+    pub window_secs: u64,
+}
@@ -1,5 +1,5 @@
 use super::{
-    config_enums::{DbType, ExtractType},
+    config_enums::{DbType, ExtractType, TicdcProtocol},
     s3_config::S3Config,
 };
 
@@ -38,6 +38,11 @@ pub enum ExtractorConfig {
         heartbeat_tb: String,
         start_time_utc: String,
         end_time_utc: String,
+        osc_enabled: bool,
+        // json:[{"name":"shard_01","url":"mysql://...","server_id":2001}], extract from multiple sources if set
+        sources: String,
+        // column to inject the source name into rows
+        source_col: String,
     },
 
     MysqlCheck {
@@ -52,6 +57,7 @@ pub enum ExtractorConfig {
         tb: String,
         sample_interval: usize,
         batch_size: usize,
+        partition_root: bool,
     },
 
     PgCdc {
@@ -66,6 +72,7 @@ pub enum ExtractorConfig {
         ddl_meta_tb: String,
         start_time_utc: String,
         end_time_utc: String,
+        partition_root: bool,
     },
 
     PgCheck {
@@ -102,6 +109,7 @@ pub enum ExtractorConfig {
     RedisSnapshot {
         url: String,
         repl_port: u64,
+        is_cluster: bool,
     },
 
     RedisCdc {
@@ -113,6 +121,7 @@ pub enum ExtractorConfig {
         heartbeat_interval_secs: u64,
         heartbeat_key: String,
         now_db_id: i64,
+        is_cluster: bool,
     },
 
     RedisSnapshotAndCdc {
@@ -122,16 +131,23 @@ pub enum ExtractorConfig {
         keepalive_interval_secs: u64,
         heartbeat_interval_secs: u64,
         heartbeat_key: String,
+        is_cluster: bool,
     },
 
     RedisSnapshotFile {
         file_path: String,
     },
 
+    RedisAofFile {
+        file_path: String,
+    },
+
     RedisScan {
         url: String,
         scan_count: u64,
+        // empty for data migration, big_key/hot_key for statistics
         statistic_type: String,
+        keyspace_events: bool,
     },
 
     RedisReshard {
@@ -147,6 +163,16 @@ pub enum ExtractorConfig {
         ack_interval_secs: u64,
     },
 
+    TidbCdc {
+        // kafka brokers which TiCDC sinks to
+        url: String,
+        group: String,
+        topic: String,
+        partition: i32,
+        offset: i64,
+        protocol: TicdcProtocol,
+    },
+
     FoxlakeS3 {
         url: String,
         schema: String,
@@ -154,6 +180,13 @@ pub enum ExtractorConfig {
         s3_config: S3Config,
         batch_size: usize,
     },
+
+    // for mysql / pg / mongo, snapshot all tables, then start cdc from
+    // the position recorded before the snapshot
+    SnapshotAndCdc {
+        snapshot: Box<ExtractorConfig>,
+        cdc: Box<ExtractorConfig>,
+    },
 }
 
 #[derive(Clone, Debug)]
@@ -161,4 +194,8 @@ pub struct BasicExtractorConfig {
     pub db_type: DbType,
     pub extract_type: ExtractType,
     pub url: String,
+    // reconnect cdc streams broken by network errors / server restarts, 0: disabled
+    pub max_reconnects: u64,
+    pub reconnect_interval_ms: u64,
+    pub reconnect_max_interval_ms: u64,
 }
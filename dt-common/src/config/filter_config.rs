@@ -9,6 +9,7 @@ pub struct FilterConfig {
     pub do_structures: String,
     pub do_ddls: String,
     pub do_dcls: String,
+    pub do_messages: String,
     pub ignore_cmds: String,
     pub where_conditions: String,
 }
@@ -0,0 +1,5 @@
+#[derive(Clone, Debug, Default)]
+pub struct FlattenerConfig {
+    // json:[{"db":"db_1","tb":"tb_1","cols":[{"col":"city","path":"address.city"}],"child_tbs":[{"tb":"tb_1_items","path":"items","cols":[{"col":"sku"}]}]}]
+    pub tbs: String,
+}
@@ -1,8 +1,11 @@
 pub mod config_enums;
 pub mod config_token_parser;
 pub mod data_marker_config;
+pub mod ddl_policy_config;
+pub mod dead_letter_config;
 pub mod extractor_config;
 pub mod filter_config;
+pub mod flattener_config;
 pub mod ini_loader;
 pub mod meta_center_config;
 pub mod monitor_config;
@@ -13,8 +16,10 @@ pub mod resumer_config;
 pub mod router_config;
 pub mod runtime_config;
 pub mod s3_config;
+pub mod shaper_config;
 pub mod sinker_config;
 pub mod task_config;
+pub mod transformer_config;
 
 #[cfg(feature = "metrics")]
 pub mod metrics_config;
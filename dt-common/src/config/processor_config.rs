@@ -2,4 +2,10 @@
 pub struct ProcessorConfig {
     pub lua_code_file: String,
     pub lua_code: String,
+    pub lua_init_code_file: String,
+    pub lua_init_code: String,
+    // json:[{"tbs":"db_1.tb_1,db_2.*","lua_code_file":"./tb_1.lua"}]
+    pub lua_tb_code_files: String,
+    // (tbs, lua_code), matched in order
+    pub lua_tb_codes: Vec<(String, String)>,
 }
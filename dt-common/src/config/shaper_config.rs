@@ -0,0 +1,5 @@
+#[derive(Clone, Debug, Default)]
+pub struct ShaperConfig {
+    // json:[{"db":"db_1","tb":"orders","id_cols":["order_no"],"embeds":[{"tb":"order_items","field":"items","fk_cols":["order_id"]}]}]
+    pub tbs: String,
+}
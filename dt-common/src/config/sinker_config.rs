@@ -1,5 +1,5 @@
 use super::{
-    config_enums::{ConflictPolicyEnum, DbType},
+    config_enums::{ConflictPolicyEnum, DbType, FileFormat},
     s3_config::S3Config,
 };
 use crate::config::config_enums::SinkType;
@@ -72,6 +72,15 @@ pub enum SinkerConfig {
         is_cluster: bool,
     },
 
+    RedisCheck {
+        url: String,
+        batch_size: usize,
+        is_cluster: bool,
+        check_log_dir: String,
+        sample_percent: u64,
+        ttl_tolerance_secs: u64,
+    },
+
     RedisStatistic {
         statistic_type: String,
         data_size_threshold: usize,
@@ -143,6 +152,15 @@ pub enum SinkerConfig {
     Sql {
         reverse: bool,
     },
+
+    File {
+        root_dir: String,
+        batch_size: usize,
+        file_format: FileFormat,
+        file_max_mb: usize,
+        file_max_secs: u64,
+        s3_config: S3Config,
+    },
 }
 
 #[derive(Clone, Debug, Default)]
@@ -151,4 +169,7 @@ pub struct BasicSinkerConfig {
     pub db_type: DbType,
     pub url: String,
     pub batch_size: usize,
+    pub max_retries: u64,
+    pub retry_interval_ms: u64,
+    pub retry_max_interval_ms: u64,
 }
@@ -13,12 +13,15 @@ use crate::error::Error;
 
 use super::{
     config_enums::{
-        ConflictPolicyEnum, DbType, ExtractType, MetaCenterType, ParallelType, PipelineType,
-        SinkType,
+        ConflictPolicyEnum, DbType, DdlPolicyType, DeadLetterType, ExtractType, FileFormat,
+        MetaCenterType, ParallelType, PipelineType, SinkType,
     },
     data_marker_config::DataMarkerConfig,
+    ddl_policy_config::DdlPolicyConfig,
+    dead_letter_config::DeadLetterConfig,
     extractor_config::{BasicExtractorConfig, ExtractorConfig},
     filter_config::FilterConfig,
+    flattener_config::FlattenerConfig,
     ini_loader::IniLoader,
     meta_center_config::MetaCenterConfig,
     parallelizer_config::ParallelizerConfig,
@@ -28,7 +31,9 @@ use super::{
     router_config::RouterConfig,
     runtime_config::RuntimeConfig,
     s3_config::S3Config,
+    shaper_config::ShaperConfig,
     sinker_config::{BasicSinkerConfig, SinkerConfig},
+    transformer_config::TransformerConfig,
 };
 
 #[derive(Clone)]
@@ -46,6 +51,11 @@ pub struct TaskConfig {
     pub meta_center: Option<MetaCenterConfig>,
     pub data_marker: Option<DataMarkerConfig>,
     pub processor: Option<ProcessorConfig>,
+    pub dead_letter: Option<DeadLetterConfig>,
+    pub ddl_policy: Option<DdlPolicyConfig>,
+    pub transformer: Option<TransformerConfig>,
+    pub flattener: Option<FlattenerConfig>,
+    pub shaper: Option<ShaperConfig>,
     #[cfg(feature = "metrics")]
     pub metrics: MetricsConfig,
 }
@@ -62,6 +72,11 @@ const RESUMER: &str = "resumer";
 const DATA_MARKER: &str = "data_marker";
 const PROCESSOR: &str = "processor";
 const META_CENTER: &str = "metacenter";
+const DEAD_LETTER: &str = "dead_letter";
+const DDL_POLICY: &str = "ddl_policy";
+const TRANSFORMER: &str = "transformer";
+const FLATTENER: &str = "flattener";
+const SHAPER: &str = "shaper";
 // keys
 const CHECK_LOG_DIR: &str = "check_log_dir";
 const DB_TYPE: &str = "db_type";
@@ -78,35 +93,94 @@ const PARALLEL_SIZE: &str = "parallel_size";
 const DDL_CONFLICT_POLICY: &str = "ddl_conflict_policy";
 const REPLACE: &str = "replace";
 const DISABLE_FOREIGN_KEY_CHECKS: &str = "disable_foreign_key_checks";
+const PARTITION_ROOT: &str = "partition_root";
 // default values
 const APE_DTS: &str = "APE_DTS";
 const ASTRISK: &str = "*";
 
 impl TaskConfig {
     pub fn new(task_config_file: &str) -> anyhow::Result<Self> {
-        let loader = IniLoader::new(task_config_file);
+        let loader = IniLoader::load(task_config_file)?;
+        let config = Self::load(&loader);
+        // report all invalid configs together rather than the first one
+        loader.check_errors()?;
+        config
+    }
 
-        let pipeline = Self::load_pipeline_config(&loader);
-        let runtime = Self::load_runtime_config(&loader)?;
-        let resumer = Self::load_resumer_config(&loader, &runtime)?;
-        let (extractor_basic, extractor) = Self::load_extractor_config(&loader, &pipeline)?;
-        let (sinker_basic, sinker) = Self::load_sinker_config(&loader)?;
+    fn load(loader: &IniLoader) -> anyhow::Result<Self> {
+        let pipeline = Self::load_pipeline_config(loader);
+        let runtime = Self::load_runtime_config(loader)?;
+        let resumer = Self::load_resumer_config(loader, &runtime)?;
+        let ddl_policy = Self::load_ddl_policy_config(loader, &runtime)?;
+        let (extractor_basic, extractor) = Self::load_extractor_config(loader, &pipeline)?;
+        let (sinker_basic, sinker) = Self::load_sinker_config(loader)?;
+        // rows changed during the snapshot are extracted again by cdc
+        if let ExtractorConfig::SnapshotAndCdc { .. } = extractor {
+            if let SinkerConfig::Mysql { replace: false, .. }
+            | SinkerConfig::Pg { replace: false, .. } = sinker
+            {
+                bail! {Error::ConfigError(format!(
+                    "[{}].{} should be true for extract_type: {}",
+                    SINKER,
+                    REPLACE,
+                    ExtractType::SnapshotAndCdc
+                ))}
+            }
+        }
+        let dead_letter = Self::load_dead_letter_config(loader)?;
+        // rows of a failed batch are written again one by one before being dead-lettered
+        if dead_letter.is_some() {
+            if let SinkerConfig::Mysql { replace: false, .. }
+            | SinkerConfig::Pg { replace: false, .. } = sinker
+            {
+                bail! {Error::ConfigError(format!(
+                    "[{}].{} should be true if [{}] is set",
+                    SINKER, REPLACE, DEAD_LETTER
+                ))}
+            }
+        }
+        let processor = Self::load_processor_config(loader)?;
+        let flattener = Self::load_flattener_config(loader)?;
+        let shaper = Self::load_shaper_config(loader)?;
+        // rows are written to files by the raw sink method, which only executes [transformer]
+        if let SinkerConfig::File { .. } = sinker {
+            for (section, is_set) in [
+                (PROCESSOR, processor.is_some()),
+                (FLATTENER, flattener.is_some()),
+                (SHAPER, shaper.is_some()),
+                (DEAD_LETTER, dead_letter.is_some()),
+            ] {
+                if is_set {
+                    bail! {Error::ConfigError(format!(
+                        "[{}] is not supported by [{}] db_type={}",
+                        section,
+                        SINKER,
+                        DbType::File
+                    ))}
+                }
+            }
+        }
         Ok(Self {
             extractor_basic,
             extractor,
-            parallelizer: Self::load_parallelizer_config(&loader)?,
+            parallelizer: Self::load_parallelizer_config(loader)?,
             pipeline,
             sinker_basic,
             sinker,
             runtime,
-            filter: Self::load_filter_config(&loader)?,
-            router: Self::load_router_config(&loader)?,
+            filter: Self::load_filter_config(loader)?,
+            router: Self::load_router_config(loader)?,
             resumer,
-            data_marker: Self::load_data_marker_config(&loader)?,
-            processor: Self::load_processor_config(&loader)?,
-            meta_center: Self::load_meta_center_config(&loader)?,
+            data_marker: Self::load_data_marker_config(loader)?,
+            processor,
+            meta_center: Self::load_meta_center_config(loader)?,
+            dead_letter,
+            ddl_policy,
+            transformer: Self::load_transformer_config(loader)?,
+            flattener,
+            shaper,
             #[cfg(feature = "metrics")]
-            metrics: Self::load_metrics_config(&loader)?,
+            metrics: Self::load_metrics_config(loader)?,
         })
     }
 
@@ -128,6 +202,17 @@ impl TaskConfig {
             db_type: db_type.clone(),
             extract_type: extract_type.clone(),
             url: url.clone(),
+            max_reconnects: loader.get_optional(EXTRACTOR, "max_reconnects"),
+            reconnect_interval_ms: loader.get_with_default(
+                EXTRACTOR,
+                "reconnect_interval_ms",
+                1000,
+            ),
+            reconnect_max_interval_ms: loader.get_with_default(
+                EXTRACTOR,
+                "reconnect_max_interval_ms",
+                30000,
+            ),
         };
 
         let not_supported_err =
@@ -135,37 +220,33 @@ impl TaskConfig {
 
         let extractor = match db_type {
             DbType::Mysql => match extract_type {
-                ExtractType::Snapshot => ExtractorConfig::MysqlSnapshot {
-                    url,
-                    db: String::new(),
-                    tb: String::new(),
-                    sample_interval: loader.get_with_default(EXTRACTOR, SAMPLE_INTERVAL, 1),
-                    parallel_size: loader.get_with_default(EXTRACTOR, PARALLEL_SIZE, 1),
-                    batch_size,
-                },
+                ExtractType::Snapshot => Self::load_mysql_snapshot_config(loader, url, batch_size),
 
-                ExtractType::Cdc => ExtractorConfig::MysqlCdc {
-                    url,
-                    binlog_filename: loader.get_optional(EXTRACTOR, "binlog_filename"),
-                    binlog_position: loader.get_optional(EXTRACTOR, "binlog_position"),
-                    server_id: loader.get_required(EXTRACTOR, "server_id"),
-                    gtid_enabled: loader.get_optional(EXTRACTOR, "gtid_enabled"),
-                    gtid_set: loader.get_optional(EXTRACTOR, "gtid_set"),
-                    binlog_heartbeat_interval_secs: loader.get_with_default(
-                        EXTRACTOR,
-                        "binlog_heartbeat_interval_secs",
-                        10,
-                    ),
-                    binlog_timeout_secs: loader.get_with_default(
-                        EXTRACTOR,
-                        "binlog_timeout_secs",
-                        60,
-                    ),
-                    heartbeat_interval_secs,
-                    heartbeat_tb,
-                    start_time_utc: loader.get_optional(EXTRACTOR, "start_time_utc"),
-                    end_time_utc: loader.get_optional(EXTRACTOR, "end_time_utc"),
-                },
+                ExtractType::Cdc => {
+                    Self::load_mysql_cdc_config(loader, url, heartbeat_interval_secs, heartbeat_tb)
+                }
+
+                ExtractType::SnapshotAndCdc => {
+                    let sources: String = loader.get_optional(EXTRACTOR, "sources");
+                    if !sources.is_empty() {
+                        bail! {Error::ConfigError(
+                            "[extractor] sources is only supported by extract_type=cdc".into()
+                        )}
+                    }
+                    ExtractorConfig::SnapshotAndCdc {
+                        snapshot: Box::new(Self::load_mysql_snapshot_config(
+                            loader,
+                            url.clone(),
+                            batch_size,
+                        )),
+                        cdc: Box::new(Self::load_mysql_cdc_config(
+                            loader,
+                            url,
+                            heartbeat_interval_secs,
+                            heartbeat_tb,
+                        )),
+                    }
+                }
 
                 ExtractType::CheckLog => ExtractorConfig::MysqlCheck {
                     url,
@@ -201,27 +282,29 @@ impl TaskConfig {
             },
 
             DbType::Pg => match extract_type {
-                ExtractType::Snapshot => ExtractorConfig::PgSnapshot {
-                    url,
-                    schema: String::new(),
-                    tb: String::new(),
-                    sample_interval: loader.get_with_default(EXTRACTOR, SAMPLE_INTERVAL, 1),
-                    batch_size,
-                },
+                ExtractType::Snapshot => Self::load_pg_snapshot_config(loader, url, batch_size),
 
-                ExtractType::Cdc => ExtractorConfig::PgCdc {
+                ExtractType::Cdc => Self::load_pg_cdc_config(
+                    loader,
                     url,
-                    slot_name: loader.get_required(EXTRACTOR, "slot_name"),
-                    pub_name: loader.get_optional(EXTRACTOR, "pub_name"),
-                    start_lsn: loader.get_optional(EXTRACTOR, "start_lsn"),
-                    recreate_slot_if_exists: loader
-                        .get_optional(EXTRACTOR, "recreate_slot_if_exists"),
                     keepalive_interval_secs,
                     heartbeat_interval_secs,
                     heartbeat_tb,
-                    ddl_meta_tb: loader.get_optional(EXTRACTOR, "ddl_meta_tb"),
-                    start_time_utc: loader.get_optional(EXTRACTOR, "start_time_utc"),
-                    end_time_utc: loader.get_optional(EXTRACTOR, "end_time_utc"),
+                ),
+
+                ExtractType::SnapshotAndCdc => ExtractorConfig::SnapshotAndCdc {
+                    snapshot: Box::new(Self::load_pg_snapshot_config(
+                        loader,
+                        url.clone(),
+                        batch_size,
+                    )),
+                    cdc: Box::new(Self::load_pg_cdc_config(
+                        loader,
+                        url,
+                        keepalive_interval_secs,
+                        heartbeat_interval_secs,
+                        heartbeat_tb,
+                    )),
                 },
 
                 ExtractType::CheckLog => ExtractorConfig::PgCheck {
@@ -250,14 +333,28 @@ impl TaskConfig {
                         tb: String::new(),
                     },
 
-                    ExtractType::Cdc => ExtractorConfig::MongoCdc {
+                    ExtractType::Cdc => Self::load_mongo_cdc_config(
+                        loader,
                         url,
                         app_name,
-                        resume_token: loader.get_optional(EXTRACTOR, "resume_token"),
-                        start_timestamp: loader.get_optional(EXTRACTOR, "start_timestamp"),
-                        source: loader.get_optional(EXTRACTOR, "source"),
                         heartbeat_interval_secs,
                         heartbeat_tb,
+                    ),
+
+                    ExtractType::SnapshotAndCdc => ExtractorConfig::SnapshotAndCdc {
+                        snapshot: Box::new(ExtractorConfig::MongoSnapshot {
+                            url: url.clone(),
+                            app_name: app_name.clone(),
+                            db: String::new(),
+                            tb: String::new(),
+                        }),
+                        cdc: Box::new(Self::load_mongo_cdc_config(
+                            loader,
+                            url,
+                            app_name,
+                            heartbeat_interval_secs,
+                            heartbeat_tb,
+                        )),
                     },
 
                     ExtractType::CheckLog => ExtractorConfig::MongoCheck {
@@ -274,42 +371,69 @@ impl TaskConfig {
             DbType::Redis => match extract_type {
                 ExtractType::Snapshot => {
                     let repl_port = loader.get_with_default(EXTRACTOR, REPL_PORT, 10008);
-                    ExtractorConfig::RedisSnapshot { url, repl_port }
+                    ExtractorConfig::RedisSnapshot {
+                        url,
+                        repl_port,
+                        is_cluster: loader.get_optional(EXTRACTOR, "is_cluster"),
+                    }
                 }
 
                 ExtractType::SnapshotFile => ExtractorConfig::RedisSnapshotFile {
                     file_path: loader.get_required(EXTRACTOR, "file_path"),
                 },
 
+                ExtractType::AofFile => ExtractorConfig::RedisAofFile {
+                    file_path: loader.get_required(EXTRACTOR, "file_path"),
+                },
+
                 ExtractType::Scan => ExtractorConfig::RedisScan {
                     url,
-                    statistic_type: loader.get_required(EXTRACTOR, "statistic_type"),
+                    statistic_type: loader.get_optional(EXTRACTOR, "statistic_type"),
                     scan_count: loader.get_with_default(EXTRACTOR, "scan_count", 1000),
+                    keyspace_events: loader.get_optional(EXTRACTOR, "keyspace_events"),
                 },
 
                 ExtractType::Cdc => {
                     let repl_port = loader.get_with_default(EXTRACTOR, REPL_PORT, 10008);
+                    let repl_id: String = loader.get_optional(EXTRACTOR, "repl_id");
+                    let repl_offset = loader.get_optional(EXTRACTOR, "repl_offset");
+                    let now_db_id = loader.get_optional(EXTRACTOR, "now_db_id");
+                    let is_cluster = loader.get_optional(EXTRACTOR, "is_cluster");
+                    if is_cluster && (!repl_id.is_empty() || repl_offset != 0 || now_db_id != 0) {
+                        bail! {Error::ConfigError(
+                            "[extractor] repl_id / repl_offset / now_db_id are not supported with is_cluster=true, positions of shards are resumed by [resumer]".into()
+                        )}
+                    }
                     ExtractorConfig::RedisCdc {
                         url,
                         repl_port,
-                        repl_id: loader.get_optional(EXTRACTOR, "repl_id"),
-                        repl_offset: loader.get_optional(EXTRACTOR, "repl_offset"),
+                        repl_id,
+                        repl_offset,
                         keepalive_interval_secs,
                         heartbeat_interval_secs,
                         heartbeat_key: loader.get_optional(EXTRACTOR, "heartbeat_key"),
-                        now_db_id: loader.get_optional(EXTRACTOR, "now_db_id"),
+                        now_db_id,
+                        is_cluster,
                     }
                 }
 
                 ExtractType::SnapshotAndCdc => {
                     let repl_port = loader.get_with_default(EXTRACTOR, REPL_PORT, 10008);
+                    let repl_id: String = loader.get_optional(EXTRACTOR, "repl_id");
+                    let is_cluster = loader.get_optional(EXTRACTOR, "is_cluster");
+                    if is_cluster && !repl_id.is_empty() {
+                        bail! {Error::ConfigError(
+                            "[extractor] repl_id is not supported with is_cluster=true".into()
+                        )}
+                    }
                     ExtractorConfig::RedisSnapshotAndCdc {
                         url,
                         repl_port,
-                        repl_id: loader.get_optional(EXTRACTOR, "repl_id"),
+                        repl_id,
                         keepalive_interval_secs,
                         heartbeat_interval_secs,
                         heartbeat_key: loader.get_optional(EXTRACTOR, "heartbeat_key"),
+                        is_cluster,
                     }
                 }
 
@@ -327,6 +451,19 @@ impl TaskConfig {
                 ack_interval_secs: loader.get_optional(EXTRACTOR, "ack_interval_secs"),
             },
 
+            DbType::Tidb => match extract_type {
+                ExtractType::Cdc => ExtractorConfig::TidbCdc {
+                    url,
+                    group: loader.get_required(EXTRACTOR, "group"),
+                    topic: loader.get_required(EXTRACTOR, "topic"),
+                    partition: loader.get_optional(EXTRACTOR, "partition"),
+                    offset: loader.get_optional(EXTRACTOR, "offset"),
+                    protocol: loader.get_optional(EXTRACTOR, "protocol"),
+                },
+
+                _ => bail! { not_supported_err },
+            },
+
             db_type => {
                 bail! {Error::ConfigError(format!(
                     "extractor db type: {} not supported",
@@ -337,6 +474,106 @@ impl TaskConfig {
         Ok((basic, extractor))
     }
 
+    fn load_mysql_snapshot_config(
+        loader: &IniLoader,
+        url: String,
+        batch_size: usize,
+    ) -> ExtractorConfig {
+        ExtractorConfig::MysqlSnapshot {
+            url,
+            db: String::new(),
+            tb: String::new(),
+            sample_interval: loader.get_with_default(EXTRACTOR, SAMPLE_INTERVAL, 1),
+            parallel_size: loader.get_with_default(EXTRACTOR, PARALLEL_SIZE, 1),
+            batch_size,
+        }
+    }
+
+    fn load_mysql_cdc_config(
+        loader: &IniLoader,
+        url: String,
+        heartbeat_interval_secs: u64,
+        heartbeat_tb: String,
+    ) -> ExtractorConfig {
+        ExtractorConfig::MysqlCdc {
+            url,
+            binlog_filename: loader.get_optional(EXTRACTOR, "binlog_filename"),
+            binlog_position: loader.get_optional(EXTRACTOR, "binlog_position"),
+            server_id: loader.get_required(EXTRACTOR, "server_id"),
+            gtid_enabled: loader.get_optional(EXTRACTOR, "gtid_enabled"),
+            gtid_set: loader.get_optional(EXTRACTOR, "gtid_set"),
+            binlog_heartbeat_interval_secs: loader.get_with_default(
+                EXTRACTOR,
+                "binlog_heartbeat_interval_secs",
+                10,
+            ),
+            binlog_timeout_secs: loader.get_with_default(EXTRACTOR, "binlog_timeout_secs", 60),
+            heartbeat_interval_secs,
+            heartbeat_tb,
+            start_time_utc: loader.get_optional(EXTRACTOR, "start_time_utc"),
+            end_time_utc: loader.get_optional(EXTRACTOR, "end_time_utc"),
+            osc_enabled: loader.get_optional(EXTRACTOR, "osc_enabled"),
+            sources: loader.get_optional(EXTRACTOR, "sources"),
+            source_col: loader.get_optional(EXTRACTOR, "source_col"),
+        }
+    }
+
+    fn load_pg_snapshot_config(
+        loader: &IniLoader,
+        url: String,
+        batch_size: usize,
+    ) -> ExtractorConfig {
+        ExtractorConfig::PgSnapshot {
+            url,
+            schema: String::new(),
+            tb: String::new(),
+            sample_interval: loader.get_with_default(EXTRACTOR, SAMPLE_INTERVAL, 1),
+            batch_size,
+            partition_root: loader.get_optional(EXTRACTOR, PARTITION_ROOT),
+        }
+    }
+
+    fn load_pg_cdc_config(
+        loader: &IniLoader,
+        url: String,
+        keepalive_interval_secs: u64,
+        heartbeat_interval_secs: u64,
+        heartbeat_tb: String,
+    ) -> ExtractorConfig {
+        ExtractorConfig::PgCdc {
+            url,
+            slot_name: loader.get_required(EXTRACTOR, "slot_name"),
+            pub_name: loader.get_optional(EXTRACTOR, "pub_name"),
+            start_lsn: loader.get_optional(EXTRACTOR, "start_lsn"),
+            recreate_slot_if_exists: loader.get_optional(EXTRACTOR, "recreate_slot_if_exists"),
+            keepalive_interval_secs,
+            heartbeat_interval_secs,
+            heartbeat_tb,
+            ddl_meta_tb: loader.get_optional(EXTRACTOR, "ddl_meta_tb"),
+            start_time_utc: loader.get_optional(EXTRACTOR, "start_time_utc"),
+            end_time_utc: loader.get_optional(EXTRACTOR, "end_time_utc"),
+            partition_root: loader.get_optional(EXTRACTOR, PARTITION_ROOT),
+        }
+    }
+
+    fn load_mongo_cdc_config(
+        loader: &IniLoader,
+        url: String,
+        app_name: String,
+        heartbeat_interval_secs: u64,
+        heartbeat_tb: String,
+    ) -> ExtractorConfig {
+        ExtractorConfig::MongoCdc {
+            url,
+            app_name,
+            resume_token: loader.get_optional(EXTRACTOR, "resume_token"),
+            start_timestamp: loader.get_optional(EXTRACTOR, "start_timestamp"),
+            source: loader.get_optional(EXTRACTOR, "source"),
+            heartbeat_interval_secs,
+            heartbeat_tb,
+        }
+    }
+
     fn load_sinker_config(loader: &IniLoader) -> anyhow::Result<(BasicSinkerConfig, SinkerConfig)> {
         let sink_type = loader.get_with_default(SINKER, "sink_type", SinkType::Write);
         if let SinkType::Dummy = sink_type {
@@ -352,6 +589,9 @@ impl TaskConfig {
             db_type: db_type.clone(),
             url: url.clone(),
             batch_size,
+            max_retries: loader.get_optional(SINKER, "max_retries"),
+            retry_interval_ms: loader.get_with_default(SINKER, "retry_interval_ms", 1000),
+            retry_max_interval_ms: loader.get_with_default(SINKER, "retry_max_interval_ms", 30000),
         };
 
         let conflict_policy: ConflictPolicyEnum =
@@ -458,6 +698,15 @@ impl TaskConfig {
                     is_cluster: loader.get_optional(SINKER, "is_cluster"),
                 },
 
+                SinkType::Check => SinkerConfig::RedisCheck {
+                    url,
+                    batch_size,
+                    is_cluster: loader.get_optional(SINKER, "is_cluster"),
+                    check_log_dir: loader.get_optional(SINKER, CHECK_LOG_DIR),
+                    sample_percent: loader.get_with_default(SINKER, "sample_percent", 100),
+                    ttl_tolerance_secs: loader.get_with_default(SINKER, "ttl_tolerance_secs", 10),
+                },
+
                 SinkType::Statistic => SinkerConfig::RedisStatistic {
                     statistic_type: loader.get_required(SINKER, "statistic_type"),
                     data_size_threshold: loader.get_optional(SINKER, "data_size_threshold"),
@@ -557,6 +806,35 @@ impl TaskConfig {
                     _ => bail! { not_supported_err },
                 }
             }
+
+            DbType::File => {
+                let s3_config = S3Config {
+                    bucket: loader.get_optional(SINKER, "s3_bucket"),
+                    access_key: loader.get_optional(SINKER, "s3_access_key"),
+                    secret_key: loader.get_optional(SINKER, "s3_secret_key"),
+                    region: loader.get_optional(SINKER, "s3_region"),
+                    endpoint: loader.get_optional(SINKER, "s3_endpoint"),
+                    root_dir: loader.get_optional(SINKER, "s3_root_dir"),
+                    root_url: loader.get_optional(SINKER, "s3_root_url"),
+                };
+
+                match sink_type {
+                    SinkType::Write => SinkerConfig::File {
+                        root_dir: loader.get_optional(SINKER, "root_dir"),
+                        batch_size,
+                        file_format: loader.get_with_default(
+                            SINKER,
+                            "file_format",
+                            FileFormat::Parquet,
+                        ),
+                        file_max_mb: loader.get_with_default(SINKER, "file_max_mb", 128),
+                        file_max_secs: loader.get_with_default(SINKER, "file_max_secs", 60),
+                        s3_config,
+                    },
+
+                    _ => bail! { not_supported_err },
+                }
+            }
         };
         Ok((basic, sinker))
     }
@@ -620,6 +898,7 @@ impl TaskConfig {
             do_events: loader.get_optional(FILTER, "do_events"),
             do_ddls: loader.get_optional(FILTER, "do_ddls"),
             do_dcls: loader.get_optional(FILTER, "do_dcls"),
+            do_messages: loader.get_optional(FILTER, "do_messages"),
             do_structures: loader.get_with_default(FILTER, "do_structures", ASTRISK.to_string()),
             ignore_cmds: loader.get_optional(FILTER, "ignore_cmds"),
             where_conditions: loader.get_optional(FILTER, "where_conditions"),
@@ -678,12 +957,58 @@ impl TaskConfig {
                 .expect("failed to read lua code file");
         }
 
+        let lua_init_code_file = loader.get_optional(PROCESSOR, "lua_init_code_file");
+        let lua_init_code = if lua_init_code_file.is_empty() {
+            String::new()
+        } else {
+            Self::read_lua_code_file(&lua_init_code_file)?
+        };
+
+        // lua_tb_code_files=json:[{"tbs":"db_1.tb_1,db_2.*","lua_code_file":"./tb_1.lua"}]
+        let lua_tb_code_files: String = loader.get_optional(PROCESSOR, "lua_tb_code_files");
+        let mut lua_tb_codes = Vec::new();
+        if !lua_tb_code_files.trim().is_empty() {
+            let items: Vec<HashMap<String, String>> =
+                match serde_json::from_str(lua_tb_code_files.trim_start_matches("json:")) {
+                    Ok(items) => items,
+                    Err(err) => bail! {Error::ConfigError(format!(
+                        "invalid [{}].lua_tb_code_files: {}",
+                        PROCESSOR, err
+                    ))},
+                };
+            for item in items {
+                let (Some(tbs), Some(file)) = (item.get("tbs"), item.get("lua_code_file")) else {
+                    bail! {Error::ConfigError(format!(
+                        "[{}].lua_tb_code_files requires both tbs and lua_code_file",
+                        PROCESSOR
+                    ))}
+                };
+                lua_tb_codes.push((tbs.clone(), Self::read_lua_code_file(file)?));
+            }
+        }
+
         Ok(Some(ProcessorConfig {
             lua_code_file,
             lua_code,
+            lua_init_code_file,
+            lua_init_code,
+            lua_tb_code_files,
+            lua_tb_codes,
         }))
     }
 
+    fn read_lua_code_file(file: &str) -> anyhow::Result<String> {
+        // unlike the legacy lua_code_file, a configured but missing file is an error,
+        // otherwise a mistyped path would silently skip the processing
+        match fs::read_to_string(file) {
+            Ok(code) => Ok(code),
+            Err(err) => bail! {Error::ConfigError(format!(
+                "failed to read lua code file: {}, error: {}",
+                file, err
+            ))},
+        }
+    }
+
     fn load_meta_center_config(loader: &IniLoader) -> anyhow::Result<Option<MetaCenterConfig>> {
         let mut config = MetaCenterConfig::Basic;
         let db_type: DbType = loader.get_required(EXTRACTOR, DB_TYPE);
@@ -693,10 +1018,10 @@ impl TaskConfig {
             let sinker_url: String = loader.get_required(SINKER, URL);
             let meta_center_url: String = loader.get_required(META_CENTER, URL);
             if extractor_url == meta_center_url || sinker_url == meta_center_url {
-                panic!(
-                    "config, [{}].{} should be different with [{}].{} and [{}].{}",
+                bail! {Error::ConfigError(format!(
+                    "[{}].{} should be different with [{}].{} and [{}].{}",
                     META_CENTER, URL, EXTRACTOR, URL, SINKER, URL
-                );
+                ))}
             }
 
             config = MetaCenterConfig::MySqlDbEngine {
@@ -711,6 +1036,113 @@ impl TaskConfig {
         Ok(Some(config))
     }
 
+    fn load_dead_letter_config(loader: &IniLoader) -> anyhow::Result<Option<DeadLetterConfig>> {
+        if !loader.ini.sections().contains(&DEAD_LETTER.to_string()) {
+            return Ok(None);
+        }
+
+        let dead_letter_type: DeadLetterType =
+            loader.get_with_default(DEAD_LETTER, "type", DeadLetterType::Log);
+        let mut config = DeadLetterConfig {
+            dead_letter_type: dead_letter_type.clone(),
+            threshold: loader.get_with_default(DEAD_LETTER, "threshold", 100),
+            window_secs: loader.get_with_default(DEAD_LETTER, "window_secs", 60),
+            ..Default::default()
+        };
+
+        match dead_letter_type {
+            DeadLetterType::Log => {}
+            DeadLetterType::Kafka => {
+                config.url = loader.get_required(DEAD_LETTER, URL);
+                config.topic = loader.get_required(DEAD_LETTER, "topic");
+            }
+            DeadLetterType::Table => {
+                // write to the sinker db by default
+                let sinker_db_type: DbType = loader.get_required(SINKER, DB_TYPE);
+                let sinker_url: String = loader.get_optional(SINKER, URL);
+                config.db_type = loader.get_with_default(DEAD_LETTER, DB_TYPE, sinker_db_type);
+                config.url = loader.get_with_default(DEAD_LETTER, URL, sinker_url);
+                config.tb = loader.get_required(DEAD_LETTER, "tb");
+                if !matches!(config.db_type, DbType::Mysql | DbType::Pg) {
+                    bail! {Error::ConfigError(format!(
+                        "config [{}].type=table only supports db_type: mysql, pg",
+                        DEAD_LETTER
+                    ))}
+                }
+            }
+        }
+        Ok(Some(config))
+    }
+
+    fn load_ddl_policy_config(
+        loader: &IniLoader,
+        runtime: &RuntimeConfig,
+    ) -> anyhow::Result<Option<DdlPolicyConfig>> {
+        if !loader.ini.sections().contains(&DDL_POLICY.to_string()) {
+            return Ok(None);
+        }
+
+        let approval_http_port: u64 = loader.get_optional(DDL_POLICY, "approval_http_port");
+        let approval_http_token: String = loader.get_optional(DDL_POLICY, "approval_http_token");
+        if approval_http_port != 0 && approval_http_token.is_empty() {
+            bail! {Error::ConfigError(format!(
+                "[{}].approval_http_token is required if approval_http_port is set",
+                DDL_POLICY
+            ))}
+        }
+
+        Ok(Some(DdlPolicyConfig {
+            default_policy: loader.get_with_default(
+                DDL_POLICY,
+                "default_policy",
+                DdlPolicyType::Apply,
+            ),
+            policies: loader.get_optional(DDL_POLICY, "policies"),
+            approval_dir: loader.get_with_default(
+                DDL_POLICY,
+                "approval_dir",
+                format!("{}/ddl_approval", runtime.log_dir),
+            ),
+            approval_http_host: loader.get_with_default(
+                DDL_POLICY,
+                "approval_http_host",
+                "127.0.0.1".to_string(),
+            ),
+            approval_http_port,
+            approval_http_token,
+        }))
+    }
+
+    fn load_transformer_config(loader: &IniLoader) -> anyhow::Result<Option<TransformerConfig>> {
+        if !loader.ini.sections().contains(&TRANSFORMER.to_string()) {
+            return Ok(None);
+        }
+
+        Ok(Some(TransformerConfig {
+            transforms: loader.get_optional(TRANSFORMER, "transforms"),
+        }))
+    }
+
+    fn load_flattener_config(loader: &IniLoader) -> anyhow::Result<Option<FlattenerConfig>> {
+        if !loader.ini.sections().contains(&FLATTENER.to_string()) {
+            return Ok(None);
+        }
+
+        Ok(Some(FlattenerConfig {
+            tbs: loader.get_optional(FLATTENER, "tbs"),
+        }))
+    }
+
+    fn load_shaper_config(loader: &IniLoader) -> anyhow::Result<Option<ShaperConfig>> {
+        if !loader.ini.sections().contains(&SHAPER.to_string()) {
+            return Ok(None);
+        }
+
+        Ok(Some(ShaperConfig {
+            tbs: loader.get_optional(SHAPER, "tbs"),
+        }))
+    }
+
     #[cfg(feature = "metrics")]
     fn load_metrics_config(loader: &IniLoader) -> anyhow::Result<MetricsConfig> {
         let metrics_section = "metrics";
@@ -0,0 +1,5 @@
+#[derive(Clone, Debug, Default)]
+pub struct TransformerConfig {
+    // json:[{"db":"db_1","tb":"tb_1","col":"phone","type":"mask","keep_first":3,"keep_last":4}]
+    pub transforms: String,
+}
@@ -4,5 +4,6 @@ pub mod logger;
 pub mod meta;
 pub mod monitor;
 pub mod rdb_filter;
+pub mod row_condition;
 pub mod time_filter;
 pub mod utils;
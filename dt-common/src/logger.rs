@@ -8,6 +8,11 @@ macro_rules! log_diff {
     ($($arg:tt)+) => (log::log!(target: "diff_logger", log::Level::Info, $($arg)+));
 }
 
+#[macro_export(local_inner_macros)]
+macro_rules! log_dead_letter {
+    ($($arg:tt)+) => (log::log!(target: "dead_letter_logger", log::Level::Info, $($arg)+));
+}
+
 #[macro_export(local_inner_macros)]
 macro_rules! log_extra {
     ($($arg:tt)+) => (log::log!(target: "extra_logger", log::Level::Info, $($arg)+));
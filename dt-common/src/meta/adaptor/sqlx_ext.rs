@@ -93,6 +93,7 @@ impl<'q> SqlxMysqlExt<'q> for Query<'q, MySql, MySqlArguments> {
     ) -> Self {
         if let Some(value) = col_value {
             match value {
+                ColValue::Bool(v) => self.bind(v),
                 ColValue::Tiny(v) => self.bind(v),
                 ColValue::UnsignedTiny(v) => self.bind(v),
                 ColValue::Short(v) => self.bind(v),
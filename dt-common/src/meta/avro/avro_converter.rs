@@ -36,6 +36,8 @@ const QUERY: &str = "query";
 const SCHEMA: &str = "schema";
 const TB: &str = "tb";
 const FIELDS: &str = "fields";
+// set in extra if before / after only contain the cols present in the row image
+const PARTIAL: &str = "partial";
 
 impl AvroConverter {
     pub fn new(meta_manager: Option<RdbMetaManager>, with_field_defs: bool) -> Self {
@@ -139,6 +141,15 @@ impl AvroConverter {
             Value::Union(1, Box::new(apache_avro::to_value(fields).unwrap()))
         };
 
+        // extra
+        let extra = if row_data.partial {
+            let col_values = HashMap::from([(PARTIAL.to_string(), ColValue::Bool(true))]);
+            let (avro_values, _) = Self::col_values_to_avro(&Some(col_values));
+            Value::Union(1, Box::new(avro_values))
+        } else {
+            Value::Union(0, Box::new(Value::Null))
+        };
+
         let value = Value::Record(vec![
             (SCHEMA.into(), Value::String(row_data.schema)),
             (TB.into(), Value::String(row_data.tb)),
@@ -149,7 +160,7 @@ impl AvroConverter {
             (FIELDS.into(), fields),
             (BEFORE.into(), before),
             (AFTER.into(), after),
-            (EXTRA.into(), Value::Union(0, Box::new(Value::Null))),
+            (EXTRA.into(), extra),
         ]);
         Ok(to_avro_datum(&self.schema, value)?)
     }
@@ -223,9 +234,14 @@ impl AvroConverter {
             let _fields = self.avro_to_fields(avro_map.remove(FIELDS));
             let before = self.avro_to_col_values(avro_map.remove(BEFORE));
             let after = self.avro_to_col_values(avro_map.remove(AFTER));
-            Ok(DtData::Dml {
-                row_data: RowData::new(schema, tb, RowType::from_str(&operation)?, before, after),
-            })
+            let extra = self.avro_to_col_values(avro_map.remove(EXTRA));
+            let mut row_data =
+                RowData::new(schema, tb, RowType::from_str(&operation)?, before, after);
+            row_data.partial = extra
+                .as_ref()
+                .and_then(|i| i.get(PARTIAL))
+                .is_some_and(|i| *i == ColValue::Bool(true));
+            Ok(DtData::Dml { row_data })
         }
     }
 
@@ -419,6 +435,9 @@ mod tests {
         row_data.after = None;
         row_data.refresh_data_size();
         validate_row_data(&mut avro_converter, &row_data).await;
+        // partial row image
+        row_data.partial = true;
+        validate_row_data(&mut avro_converter, &row_data).await;
     }
 
     #[tokio::test]
@@ -3,7 +3,10 @@ use serde_json::json;
 
 use crate::config::config_enums::DbType;
 
-use super::{ddl_statement::DdlStatement, ddl_type::DdlType};
+use super::{
+    ddl_statement::{DdlStatement, MysqlTruncateTableStatement},
+    ddl_type::DdlType,
+};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
 pub struct DdlData {
@@ -41,6 +44,32 @@ impl DdlData {
         (schema, tb)
     }
 
+    /// convert ddls from other sources to be executed in mysql, only truncate table is converted
+    /// (CASCADE / RESTART IDENTITY are dropped since mysql always resets AUTO_INCREMENT on
+    /// truncate, tables truncated together are truncated one by one), others are kept as they are
+    pub fn to_mysql(&self) -> Vec<Self> {
+        let schema_tbs = match &self.statement {
+            DdlStatement::PgTruncateTable(s) => vec![(s.schema.clone(), s.tb.clone())],
+            DdlStatement::PgTruncateMultiTable(s) => s.schema_tbs.clone(),
+            _ => return vec![self.clone()],
+        };
+
+        schema_tbs
+            .into_iter()
+            .map(|(db, tb)| Self {
+                default_schema: self.default_schema.clone(),
+                query: self.query.clone(),
+                ddl_type: self.ddl_type.clone(),
+                db_type: DbType::Mysql,
+                statement: DdlStatement::MysqlTruncateTable(MysqlTruncateTableStatement {
+                    db,
+                    tb,
+                    unparsed: String::new(),
+                }),
+            })
+            .collect()
+    }
+
     pub fn split_to_multi(self) -> Vec<DdlData> {
         let mut res = Vec::new();
         for statement in self.statement.split_to_multi() {
@@ -1,3 +1,4 @@
+use mongodb::bson::{doc, Bson, Document};
 use serde::{Deserialize, Serialize};
 
 use crate::{config::config_enums::DbType, utils::sql_util::SqlUtil};
@@ -24,8 +25,13 @@ pub enum DdlStatement {
     PgAlterTableRename(PgAlterTableRenameStatement),
     PgAlterTableSetSchema(PgAlterTableSetSchemaStatement),
     PgTruncateTable(PgTruncateTableStatement),
+    PgTruncateMultiTable(PgTruncateMultiTableStatement),
     PgCreateIndex(PgCreateIndexStatement),
 
+    MongoCreateCollection(MongoCreateCollectionStatement),
+    MongoCreateIndex(MongoCreateIndexStatement),
+    MongoDropIndex(MongoDropIndexStatement),
+
     DropMultiTable(DropMultiTableStatement),
     RenameMultiTable(RenameMultiTableStatement),
     PgDropMultiIndex(PgDropMultiIndexStatement),
@@ -63,6 +69,7 @@ impl DdlStatement {
                         new_schema: new_schema.clone(),
                         new_tb: new_tb.clone(),
                         unparsed: s.unparsed.clone(),
+                        ..Default::default()
                     };
                     res.push(DdlStatement::RenameTable(statement));
                 }
@@ -104,8 +111,15 @@ impl DdlStatement {
             DdlStatement::PgCreateTable(s) => (s.schema.clone(), s.tb.clone()),
             DdlStatement::PgAlterTable(s) => (s.schema.clone(), s.tb.clone()),
             DdlStatement::PgTruncateTable(s) => (s.schema.clone(), s.tb.clone()),
+            DdlStatement::PgTruncateMultiTable(s) => {
+                s.schema_tbs.first().cloned().unwrap_or_default()
+            }
             DdlStatement::PgCreateIndex(s) => (s.schema.clone(), s.tb.clone()),
 
+            DdlStatement::MongoCreateCollection(s) => (s.db.clone(), s.tb.clone()),
+            DdlStatement::MongoCreateIndex(s) => (s.db.clone(), s.tb.clone()),
+            DdlStatement::MongoDropIndex(s) => (s.db.clone(), s.tb.clone()),
+
             DdlStatement::DropTable(s) => (s.schema.clone(), s.tb.clone()),
 
             DdlStatement::RenameTable(s) => (s.schema.clone(), s.tb.clone()),
@@ -253,6 +267,19 @@ impl DdlStatement {
                 s.tb = dst_tb;
             }
 
+            DdlStatement::MongoCreateCollection(s) => {
+                s.db = dst_schema;
+                s.tb = dst_tb;
+            }
+            DdlStatement::MongoCreateIndex(s) => {
+                s.db = dst_schema;
+                s.tb = dst_tb;
+            }
+            DdlStatement::MongoDropIndex(s) => {
+                s.db = dst_schema;
+                s.tb = dst_tb;
+            }
+
             DdlStatement::DropTable(s) => {
                 if !s.schema.is_empty() {
                     s.schema = dst_schema;
@@ -269,6 +296,7 @@ impl DdlStatement {
             | DdlStatement::PgDropMultiIndex(_)
             | DdlStatement::DropMultiTable(_)
             | DdlStatement::RenameMultiTable(_)
+            | DdlStatement::PgTruncateMultiTable(_)
             | DdlStatement::Unknown => {}
         }
     }
@@ -409,6 +437,15 @@ pub struct PgTruncateTableStatement {
     pub unparsed: String,
 }
 
+/// relations truncated together, e.g. TRUNCATE tb_1, tb_2 CASCADE, they are not split
+/// since tables referenced by foreign keys can only be truncated with the referencing ones
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct PgTruncateMultiTableStatement {
+    pub schema_tbs: Vec<(String, String)>,
+    pub is_only: bool,
+    pub unparsed: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
 pub struct RenameMultiTableStatement {
     pub schema_tbs: Vec<(String, String)>,
@@ -423,6 +460,9 @@ pub struct RenameTableStatement {
     pub new_schema: String,
     pub new_tb: String,
     pub unparsed: String,
+    // mongo only, the existing target collection was dropped by the rename
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub drop_target: bool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
@@ -447,6 +487,29 @@ pub struct PgCreateIndexStatement {
     pub unparsed: String,
 }
 
+// options / indexes of mongo ddls are kept as relaxed extended json,
+// and converted back to bson when applied by MongoSinker
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct MongoCreateCollectionStatement {
+    pub db: String,
+    pub tb: String,
+    pub options: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct MongoCreateIndexStatement {
+    pub db: String,
+    pub tb: String,
+    pub indexes: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct MongoDropIndexStatement {
+    pub db: String,
+    pub tb: String,
+    pub index_name: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
 pub struct MysqlDropIndexStatement {
     pub db: String,
@@ -473,6 +536,13 @@ pub struct PgDropIndexStatement {
 
 impl DdlStatement {
     pub fn to_sql(&self, db_type: &DbType) -> String {
+        if db_type == &DbType::Mongo {
+            return match self.to_mongo_command() {
+                Ok(Some((db, command))) => format!("{}: {}", db, command),
+                _ => String::new(),
+            };
+        }
+
         match self {
             DdlStatement::CreateDatabase(s) => {
                 let mut sql = "CREATE DATABASE".to_string();
@@ -569,6 +639,8 @@ impl DdlStatement {
                 append_unparsed(sql, &s.unparsed)
             }
 
+            DdlStatement::PgTruncateMultiTable(s) => s.to_sql(db_type),
+
             DdlStatement::MysqlAlterTable(s) => {
                 let mut sql = "ALTER TABLE".to_string();
                 sql = append_tb(&sql, &s.db, &s.tb, db_type);
@@ -793,6 +865,16 @@ impl DdlStatement {
                 size += pg_truncate_table_statement.unparsed.len() as u64;
                 size += 1;
             }
+            DdlStatement::PgTruncateMultiTable(pg_truncate_multi_table_statement) => {
+                size += std::mem::size_of::<Vec<(String, String)>>() as u64;
+                size += pg_truncate_multi_table_statement
+                    .schema_tbs
+                    .iter()
+                    .map(|(s, t)| s.len() as u64 + t.len() as u64)
+                    .sum::<u64>();
+                size += pg_truncate_multi_table_statement.unparsed.len() as u64;
+                size += 1;
+            }
             DdlStatement::PgCreateIndex(pg_create_index_statement) => {
                 size += pg_create_index_statement.schema.len() as u64;
                 size += pg_create_index_statement.tb.len() as u64;
@@ -878,10 +960,74 @@ impl DdlStatement {
                 size += mysql_drop_index_statement.index_name.len() as u64;
                 size += mysql_drop_index_statement.unparsed.len() as u64;
             }
+            DdlStatement::MongoCreateCollection(mongo_create_collection_statement) => {
+                size += mongo_create_collection_statement.db.len() as u64;
+                size += mongo_create_collection_statement.tb.len() as u64;
+                size += mongo_create_collection_statement.options.len() as u64;
+            }
+            DdlStatement::MongoCreateIndex(mongo_create_index_statement) => {
+                size += mongo_create_index_statement.db.len() as u64;
+                size += mongo_create_index_statement.tb.len() as u64;
+                size += mongo_create_index_statement.indexes.len() as u64;
+            }
+            DdlStatement::MongoDropIndex(mongo_drop_index_statement) => {
+                size += mongo_drop_index_statement.db.len() as u64;
+                size += mongo_drop_index_statement.tb.len() as u64;
+                size += mongo_drop_index_statement.index_name.len() as u64;
+            }
             DdlStatement::Unknown => {}
         }
         size
     }
+
+    /// the database command to apply the ddl in mongo: (db to run the command, command)
+    pub fn to_mongo_command(&self) -> anyhow::Result<Option<(String, Document)>> {
+        let command = match self {
+            DdlStatement::DropDatabase(s) => (s.db.clone(), doc! {"dropDatabase": 1}),
+
+            DdlStatement::MongoCreateCollection(s) => {
+                let mut command = doc! {"create": &s.tb};
+                command.extend(Self::parse_extjson_doc(&s.options)?);
+                (s.db.clone(), command)
+            }
+
+            DdlStatement::DropTable(s) => (s.schema.clone(), doc! {"drop": &s.tb}),
+
+            DdlStatement::RenameTable(s) => (
+                "admin".to_string(),
+                doc! {
+                    "renameCollection": format!("{}.{}", s.schema, s.tb),
+                    "to": format!("{}.{}", s.new_schema, s.new_tb),
+                    "dropTarget": s.drop_target,
+                },
+            ),
+
+            DdlStatement::MongoCreateIndex(s) => {
+                let indexes: serde_json::Value = serde_json::from_str(&s.indexes)?;
+                let indexes = Bson::try_from(indexes)?;
+                (
+                    s.db.clone(),
+                    doc! {"createIndexes": &s.tb, "indexes": indexes},
+                )
+            }
+
+            DdlStatement::MongoDropIndex(s) => (
+                s.db.clone(),
+                doc! {"dropIndexes": &s.tb, "index": &s.index_name},
+            ),
+
+            _ => return Ok(None),
+        };
+        Ok(Some(command))
+    }
+
+    fn parse_extjson_doc(extjson: &str) -> anyhow::Result<Document> {
+        if extjson.is_empty() {
+            return Ok(Document::new());
+        }
+        let value: serde_json::Map<String, serde_json::Value> = serde_json::from_str(extjson)?;
+        Ok(Document::try_from(value)?)
+    }
 }
 
 impl DropMultiTableStatement {
@@ -914,6 +1060,31 @@ impl RenameMultiTableStatement {
     }
 }
 
+impl PgTruncateMultiTableStatement {
+    pub fn to_sql(&self, db_type: &DbType) -> String {
+        let mut sql = "TRUNCATE TABLE".to_string();
+        if self.is_only {
+            sql = format!("{} ONLY", sql);
+        }
+        for (i, (schema, tb)) in self.schema_tbs.iter().enumerate() {
+            sql = append_tb(&sql, schema, tb, db_type);
+            if i < self.schema_tbs.len() - 1 {
+                sql = format!("{},", sql);
+            }
+        }
+        append_unparsed(sql, &self.unparsed)
+    }
+
+    pub fn route(&mut self, dst_schema_tbs: Vec<(String, String)>) {
+        for ((schema, tb), (dst_schema, dst_tb)) in self.schema_tbs.iter_mut().zip(dst_schema_tbs) {
+            if !schema.is_empty() {
+                *schema = dst_schema;
+            }
+            *tb = dst_tb;
+        }
+    }
+}
+
 impl PgDropMultiIndexStatement {
     pub fn to_sql(&self, db_type: &DbType) -> String {
         let mut sql = "DROP INDEX".to_string();
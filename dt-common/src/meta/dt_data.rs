@@ -2,8 +2,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::json;
 
 use super::{
-    ddl_meta::ddl_data::DdlData, foxlake::s3_file_meta::S3FileMeta, row_data::RowData,
-    struct_meta::struct_data::StructData,
+    ddl_meta::ddl_data::DdlData, foxlake::s3_file_meta::S3FileMeta, message_data::MessageData,
+    row_data::RowData, struct_meta::struct_data::StructData,
 };
 use crate::meta::dcl_meta::dcl_data::DclData;
 use crate::meta::{position::Position, redis::redis_entry::RedisEntry};
@@ -24,6 +24,10 @@ impl DtItem {
         self.dt_data.is_dcl()
     }
 
+    pub fn is_message(&self) -> bool {
+        self.dt_data.is_message()
+    }
+
     pub fn get_data_size(&self) -> u64 {
         self.dt_data.get_data_size()
     }
@@ -48,6 +52,9 @@ pub enum DtData {
         xid: String,
     },
     Heartbeat {},
+    Message {
+        message_data: MessageData,
+    },
     #[serde(skip)]
     Redis {
         entry: RedisEntry,
@@ -74,11 +81,16 @@ impl DtData {
         matches!(self, DtData::Dcl { .. })
     }
 
+    pub fn is_message(&self) -> bool {
+        matches!(self, DtData::Message { .. })
+    }
+
     pub fn get_data_size(&self) -> u64 {
         match &self {
             DtData::Dml { row_data } => row_data.data_size as u64,
             DtData::Dcl { dcl_data } => dcl_data.get_malloc_size(),
             DtData::Ddl { ddl_data } => ddl_data.get_malloc_size(),
+            DtData::Message { message_data } => message_data.get_malloc_size(),
             DtData::Redis { entry } => entry.get_data_malloc_size() as u64,
             DtData::Foxlake { file_meta } => file_meta.data_size as u64,
             // ignore other item types
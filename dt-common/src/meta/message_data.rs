@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+/// generic messages written into the source log by applications,
+/// e.g. pg_logical_emit_message(transactional, prefix, content) in postgres
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq, Eq)]
+pub struct MessageData {
+    pub prefix: String,
+    pub content: Vec<u8>,
+    // transactional messages are decoded in the transaction that emitted them,
+    // non-transactional ones are decoded immediately
+    pub transactional: bool,
+    // the source log position where the message was written
+    pub lsn: String,
+}
+
+impl std::fmt::Display for MessageData {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}",
+            json!({
+                "prefix": self.prefix,
+                "content": self.get_content_str(),
+                "transactional": self.transactional,
+                "lsn": self.lsn,
+            })
+        )
+    }
+}
+
+impl MessageData {
+    pub fn get_content_str(&self) -> String {
+        String::from_utf8_lossy(&self.content).to_string()
+    }
+
+    pub fn get_malloc_size(&self) -> u64 {
+        (self.prefix.len() + self.content.len() + self.lsn.len() + 1) as u64
+    }
+}
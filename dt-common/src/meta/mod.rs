@@ -10,6 +10,7 @@ pub mod dt_queue;
 pub mod foreign_key;
 pub mod foxlake;
 pub mod kafka;
+pub mod message_data;
 pub mod mongo;
 pub mod mysql;
 pub mod pg;
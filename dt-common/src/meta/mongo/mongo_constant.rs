@@ -4,6 +4,9 @@ impl MongoConstants {
     pub const ID: &'static str = "_id";
     pub const DOC: &'static str = "doc";
     pub const DIFF_DOC: &'static str = "diff_doc";
+    pub const ARRAY_FILTER: &'static str = "array_filter";
     pub const SET: &'static str = "$set";
     pub const UNSET: &'static str = "$unset";
+    pub const PUSH: &'static str = "$push";
+    pub const PULL: &'static str = "$pull";
 }
@@ -18,6 +18,8 @@ pub struct PgMetaManager {
     pub type_registry: TypeRegistry,
     pub name_to_tb_meta: HashMap<String, PgTbMeta>,
     pub oid_to_tb_meta: HashMap<i32, PgTbMeta>,
+    // partition -> (root schema, root tb), None if not a partition
+    pub name_to_partition_root: HashMap<String, Option<(String, String)>>,
 }
 
 impl PgMetaManager {
@@ -28,6 +30,7 @@ impl PgMetaManager {
             type_registry,
             name_to_tb_meta: HashMap::new(),
             oid_to_tb_meta: HashMap::new(),
+            name_to_partition_root: HashMap::new(),
         };
         me.type_registry = me.type_registry.init().await?;
         Ok(me)
@@ -110,7 +113,52 @@ impl PgMetaManager {
         Ok(self.name_to_tb_meta.get(&full_name).unwrap())
     }
 
+    /// get the root of the partition hierarchy which the table belongs to by pg_inherits,
+    /// return None if the table is not a partition
+    pub async fn get_partition_root(
+        &mut self,
+        schema: &str,
+        tb: &str,
+    ) -> anyhow::Result<Option<(String, String)>> {
+        let full_name = format!(r#""{}"."{}""#, schema, tb);
+        if let Some(root) = self.name_to_partition_root.get(&full_name) {
+            return Ok(root.clone());
+        }
+
+        // only follow pg_inherits for partitions, tables using legacy inheritance are not merged
+        let sql = format!(
+            "WITH RECURSIVE ancestors AS (
+                SELECT c.oid AS relid, c.relispartition AS is_partition, 0 AS depth
+                FROM pg_class c
+                JOIN pg_namespace n ON n.oid = c.relnamespace
+                WHERE n.nspname = '{}' AND c.relname = '{}'
+                UNION ALL
+                SELECT i.inhparent, p.relispartition, a.depth + 1
+                FROM ancestors a
+                JOIN pg_inherits i ON i.inhrelid = a.relid
+                JOIN pg_class p ON p.oid = i.inhparent
+                WHERE a.is_partition
+            )
+            SELECT n.nspname AS root_schema, c.relname AS root_tb
+            FROM ancestors a
+            JOIN pg_class c ON c.oid = a.relid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE a.depth > 0
+            ORDER BY a.depth DESC
+            LIMIT 1",
+            schema, tb
+        );
+        let root = match sqlx::query(&sql).fetch_optional(&self.conn_pool).await? {
+            Some(row) => Some((row.try_get("root_schema")?, row.try_get("root_tb")?)),
+            None => None,
+        };
+        self.name_to_partition_root.insert(full_name, root.clone());
+        Ok(root)
+    }
+
     pub fn invalidate_cache(&mut self, schema: &str, tb: &str) {
+        // partitions may be attached / detached by ddls on either the partition or the root
+        self.name_to_partition_root.clear();
         // TODO, if schema is not empty but tb is empty, only clear cache for the schema
         if !schema.is_empty() && !tb.is_empty() {
             let full_name = format!(r#""{}"."{}""#, schema, tb);
@@ -1,4 +1,4 @@
-use std::str::FromStr;
+use std::{collections::BTreeMap, str::FromStr, sync::Arc};
 
 use anyhow::Context;
 use chrono::{DateTime, NaiveDateTime};
@@ -36,6 +36,21 @@ pub enum Position {
         gtid_set: String,
         timestamp: String,
     },
+    MysqlMultiCdc {
+        // key: name of the source,
+        // value: Position::MysqlCdc of the last transaction / ddl committed in the source,
+        // shared by the rows extracted until any source's position changes
+        sources: Arc<BTreeMap<String, Position>>,
+    },
+    TidbCdc {
+        // kafka message which TiCDC sinks the event to
+        topic: String,
+        partition: i32,
+        offset: i64,
+        // tso of the transaction in TiDB
+        commit_ts: u64,
+        timestamp: String,
+    },
     PgCdc {
         lsn: String,
         timestamp: String,
@@ -52,6 +67,11 @@ pub enum Position {
         now_db_id: i64,
         timestamp: String,
     },
+    RedisCluster {
+        // key: id of the master node which the shard stream started from,
+        // value: Position::Redis of the shard, shared by the items drained from the shard in a batch
+        shards: Arc<BTreeMap<String, Position>>,
+    },
     FoxlakeS3 {
         schema: String,
         tb: String,
@@ -67,6 +87,11 @@ impl Position {
             String::new()
         }
     }
+
+    /// the physical part of a TiDB tso is the high 46 bits, in millis
+    pub fn format_tidb_tso(tso: u64) -> String {
+        Self::format_timestamp_millis((tso >> 18) as i64)
+    }
 }
 
 impl std::fmt::Display for Position {
@@ -108,6 +133,7 @@ impl Position {
     pub fn to_timestamp(&self) -> u64 {
         match self {
             Position::MysqlCdc { timestamp, .. }
+            | Position::TidbCdc { timestamp, .. }
             | Position::PgCdc { timestamp, .. }
             | Position::MongoCdc { timestamp, .. }
             | Position::Redis { timestamp, .. } => {
@@ -123,9 +149,21 @@ impl Position {
                 }
                 0
             }
+            // the slowest shard / source decides the position of the whole task
+            Position::RedisCluster { shards } => Self::min_timestamp(shards),
+            Position::MysqlMultiCdc { sources } => Self::min_timestamp(sources),
             _ => 0,
         }
     }
+
+    fn min_timestamp(positions: &BTreeMap<String, Position>) -> u64 {
+        positions
+            .values()
+            .map(|i| i.to_timestamp())
+            .filter(|i| *i > 0)
+            .min()
+            .unwrap_or(0)
+    }
 }
 
 #[cfg(test)]
@@ -143,6 +181,11 @@ mod test {
             "2023-03-28 05:33:47.000",
             Position::format_timestamp_millis(1679981627 * 1000)
         );
+
+        assert_eq!(
+            "2024-05-19 09:42:29.215",
+            Position::format_tidb_tso(449868398386331649)
+        );
     }
 
     #[test]
@@ -150,6 +193,9 @@ mod test {
         let strs = [
             r#"{"type":"None"}"#,
             r#"{"type":"RdbSnapshot","db_type":"mysql","schema":"test_db_1","tb":"numeric_table","order_col":"f_0","value":"127"}"#,
+            r#"{"type":"RedisCluster","shards":{"0e9d360631a20c27f629267bf3e01de8e8c4cbec":{"type":"Redis","repl_id":"ba7d5b1e0dc6ab3ba1b2e1a0e0d5c7d3a1c2b3d4","repl_port":10008,"repl_offset":2048,"now_db_id":0,"timestamp":""}}}"#,
+            r#"{"type":"MysqlMultiCdc","sources":{"shard_01":{"type":"MysqlCdc","server_id":"","binlog_filename":"mysql-bin.000003","next_event_position":1256,"gtid_set":"","timestamp":"2024-05-01 10:00:00.000"}}}"#,
+            r#"{"type":"TidbCdc","topic":"ticdc","partition":0,"offset":8,"commit_ts":449868398386331649,"timestamp":"2024-05-01 10:00:00.000"}"#,
         ];
 
         for str in strs {
@@ -12,3 +12,11 @@ pub struct ClusterNode {
     pub slots: Vec<u16>,
     pub slot_hash_tag_map: HashMap<u16, String>,
 }
+
+#[derive(Clone)]
+pub struct ClusterShard {
+    // None if the shard has no online master, eg: during failover
+    pub master: Option<ClusterNode>,
+    // ids of all nodes in the shard, including the master and its replicas
+    pub node_ids: Vec<String>,
+}
@@ -12,7 +12,7 @@ use crate::meta::adaptor::{
 
 use super::{
     col_value::ColValue, mysql::mysql_tb_meta::MysqlTbMeta, pg::pg_tb_meta::PgTbMeta,
-    rdb_tb_meta::RdbTbMeta, row_type::RowType,
+    position::Position, rdb_tb_meta::RdbTbMeta, row_type::RowType,
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -23,6 +23,13 @@ pub struct RowData {
     pub before: Option<HashMap<String, ColValue>>,
     pub after: Option<HashMap<String, ColValue>>,
     pub data_size: usize,
+    // before / after only contain the cols present in the row image, absent cols are not changed,
+    // eg: mysql with binlog_row_image=MINIMAL / NOBLOB
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub partial: bool,
+    // position of the row in source, only set by the pipeline if rows may be written to dead letter
+    #[serde(skip)]
+    pub position: Option<Position>,
 }
 
 impl std::fmt::Display for RowData {
@@ -46,6 +53,8 @@ impl RowData {
             before,
             after,
             data_size: 0,
+            partial: false,
+            position: None,
         };
         me.data_size = me.get_data_malloc_size();
         me
@@ -65,19 +74,25 @@ impl RowData {
             before: self.after.clone(),
             after: self.before.clone(),
             data_size: self.data_size,
+            partial: self.partial,
+            position: self.position.clone(),
         }
     }
 
     pub fn split_update_row_data(self) -> (RowData, RowData) {
-        let delete = RowData::new(
+        let mut delete = RowData::new(
             self.schema.clone(),
             self.tb.clone(),
             RowType::Delete,
             self.before,
             None,
         );
+        delete.partial = self.partial;
+        delete.position = self.position.clone();
 
-        let insert = RowData::new(self.schema, self.tb, RowType::Insert, None, self.after);
+        let mut insert = RowData::new(self.schema, self.tb, RowType::Insert, None, self.after);
+        insert.partial = self.partial;
+        insert.position = self.position;
         (delete, insert)
     }
 
@@ -183,7 +198,8 @@ impl RowData {
         // refer to: https://docs.oracle.com/javase/6/docs/api/java/util/List.html#hashCode%28%29
         let mut hash_code = 1u128;
         for col in tb_meta.id_cols.iter() {
-            let col_hash_code = col_values.get(col).unwrap().hash_code();
+            // col may be absent in a partial row image
+            let col_hash_code = col_values.get(col).map_or(0, |v| v.hash_code());
             // col_hash_code is 0 if col_value is ColValue::None,
             // consider fowlling case,
             // create table a(id int, value int, unique key(id, value));
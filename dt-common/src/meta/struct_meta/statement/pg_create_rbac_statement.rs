@@ -130,9 +130,11 @@ mod tests {
             ignore_cols: HashMap::new(),
             do_events: HashSet::new(),
             do_dcls: HashSet::new(),
+            do_messages: HashSet::new(),
             do_ddls: HashSet::new(),
             ignore_cmds: HashSet::new(),
             where_conditions: HashMap::new(),
+            row_conditions: HashMap::new(),
         };
         filter.do_structures.insert(StructureType::Rbac.to_string());
         filter
@@ -7,6 +7,10 @@ pub enum CounterType {
     BatchWriteFailures,
     #[strum(serialize = "serial_writes")]
     SerialWrites,
+    #[strum(serialize = "sink_retries")]
+    SinkRetries,
+    #[strum(serialize = "dead_letters")]
+    DeadLetters,
 
     // time window counter, aggregate by: avg by window
     #[strum(serialize = "record_count")]
@@ -80,6 +84,8 @@ impl CounterType {
         match self {
             Self::BatchWriteFailures
             | Self::SerialWrites
+            | Self::SinkRetries
+            | Self::DeadLetters
             | Self::RecordCount
             | Self::RecordsPerQuery
             | Self::RtPerQuery
@@ -119,6 +125,8 @@ impl CounterType {
 
                 Self::BatchWriteFailures
                 | Self::SerialWrites
+                | Self::SinkRetries
+                | Self::DeadLetters
                 | Self::RecordCount
                 | Self::DataBytes
                 | Self::ExtractedRecords
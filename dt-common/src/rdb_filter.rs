@@ -1,6 +1,6 @@
 use std::collections::{HashMap, HashSet};
 
-use anyhow::Context;
+use anyhow::{bail, Context};
 use dashmap::DashMap;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -10,15 +10,19 @@ use crate::{
     config::{
         config_enums::DbType, config_token_parser::ConfigTokenParser, filter_config::FilterConfig,
     },
+    error::Error,
     meta::{
-        ddl_meta::ddl_type::DdlType, row_type::RowType,
+        ddl_meta::ddl_type::DdlType, row_data::RowData, row_type::RowType,
         struct_meta::structure::structure_type::StructureType,
     },
+    row_condition::RowCondition,
     utils::sql_util::SqlUtil,
 };
 
 type IgnoreCols = HashMap<(String, String), HashSet<String>>;
 type WhereConditions = HashMap<(String, String), String>;
+// where_conditions parsed for cdc rows, Err if not supported by RowCondition
+type RowConditions = HashMap<(String, String), Result<RowCondition, String>>;
 
 const JSON_PREFIX: &str = "json:";
 
@@ -34,13 +38,24 @@ pub struct RdbFilter {
     pub do_structures: HashSet<String>,
     pub do_ddls: HashSet<String>,
     pub do_dcls: HashSet<String>,
+    // prefixes of logical messages to capture, e.g. pg_logical_emit_message
+    pub do_messages: HashSet<String>,
     pub ignore_cmds: HashSet<String>,
     pub where_conditions: WhereConditions,
+    pub row_conditions: RowConditions,
     pub cache: DashMap<(String, String), bool>,
 }
 
 impl RdbFilter {
     pub fn from_config(config: &FilterConfig, db_type: &DbType) -> anyhow::Result<Self> {
+        let where_conditions = Self::parse_where_conditions(&config.where_conditions)?;
+        let row_conditions = where_conditions
+            .iter()
+            .map(|(k, v)| {
+                let row_condition = RowCondition::parse(v, db_type).map_err(|e| e.to_string());
+                (k.clone(), row_condition)
+            })
+            .collect();
         Ok(Self {
             db_type: db_type.to_owned(),
             do_schemas: Self::parse_single_tokens(&config.do_schemas, db_type)?,
@@ -52,8 +67,10 @@ impl RdbFilter {
             do_structures: Self::parse_single_tokens(&config.do_structures, db_type)?,
             do_ddls: Self::parse_single_tokens(&config.do_ddls, db_type)?,
             do_dcls: Self::parse_single_tokens(&config.do_dcls, db_type)?,
+            do_messages: Self::parse_message_prefixes(&config.do_messages),
             ignore_cmds: Self::parse_single_tokens(&config.ignore_cmds, db_type)?,
-            where_conditions: Self::parse_where_conditions(&config.where_conditions)?,
+            where_conditions,
+            row_conditions,
             cache: DashMap::new(),
         })
     }
@@ -123,6 +140,14 @@ impl RdbFilter {
         !Self::match_all(&self.do_dcls) && !self.do_dcls.contains(&dcl_type.to_string())
     }
 
+    pub fn filter_all_message(&self) -> bool {
+        self.do_messages.is_empty()
+    }
+
+    pub fn filter_message(&self, prefix: &str) -> bool {
+        !Self::match_all(&self.do_messages) && !self.do_messages.contains(prefix)
+    }
+
     pub fn filter_structure(&self, structure_type: &StructureType) -> bool {
         !Self::match_all(&self.do_structures)
             && !self.do_structures.contains(&structure_type.to_string())
@@ -149,6 +174,31 @@ impl RdbFilter {
             .get(&(schema.to_string(), tb.to_string()))
     }
 
+    /// apply where_conditions to cdc rows of schema.tb, None if filtered,
+    /// updates moving rows out of / into the condition become deletes / inserts
+    pub fn filter_row(
+        &self,
+        schema: &str,
+        tb: &str,
+        row_data: RowData,
+    ) -> anyhow::Result<Option<RowData>> {
+        if self.row_conditions.is_empty() {
+            return Ok(Some(row_data));
+        }
+
+        match self
+            .row_conditions
+            .get(&(schema.to_string(), tb.to_string()))
+        {
+            Some(Ok(row_condition)) => Ok(row_condition.filter_row(row_data)),
+            Some(Err(err)) => bail! {Error::ConfigError(format!(
+                "where_condition of {}.{} can not be applied to cdc rows: {}",
+                schema, tb, err
+            ))},
+            None => Ok(Some(row_data)),
+        }
+    }
+
     pub fn is_pattern(pattern: &str, db_type: &DbType) -> bool {
         for escape_pair in SqlUtil::get_escape_pairs(db_type).iter() {
             if SqlUtil::is_escaped(pattern, escape_pair) {
@@ -230,6 +280,15 @@ impl RdbFilter {
         Ok(results)
     }
 
+    // message prefixes are free text (e.g. app.audit), only split by ','
+    fn parse_message_prefixes(config_str: &str) -> HashSet<String> {
+        config_str
+            .split(',')
+            .map(|i| i.trim().to_string())
+            .filter(|i| !i.is_empty())
+            .collect()
+    }
+
     fn parse_config(config_str: &str, db_type: &DbType) -> anyhow::Result<Vec<String>> {
         let delimiters = vec![',', '.'];
         ConfigTokenParser::parse_config(config_str, db_type, &delimiters)
@@ -872,4 +931,30 @@ mod tests {
         assert!(rdb_fitler.filter_event("test_db_1", "aaaa", &RowType::Update));
         assert!(rdb_fitler.filter_event("test_db_1", "aaaa", &RowType::Delete));
     }
+
+    #[test]
+    fn test_rdb_filter_message() {
+        let db_type = DbType::Pg;
+
+        let config = FilterConfig::default();
+        let rdb_fitler = RdbFilter::from_config(&config, &db_type).unwrap();
+        assert!(rdb_fitler.filter_all_message());
+
+        let config = FilterConfig {
+            do_messages: "app.audit, app_sync".to_string(),
+            ..Default::default()
+        };
+        let rdb_fitler = RdbFilter::from_config(&config, &db_type).unwrap();
+        assert!(!rdb_fitler.filter_all_message());
+        assert!(!rdb_fitler.filter_message("app.audit"));
+        assert!(!rdb_fitler.filter_message("app_sync"));
+        assert!(rdb_fitler.filter_message("app"));
+
+        let config = FilterConfig {
+            do_messages: "*".to_string(),
+            ..Default::default()
+        };
+        let rdb_fitler = RdbFilter::from_config(&config, &db_type).unwrap();
+        assert!(!rdb_fitler.filter_message("any"));
+    }
 }
@@ -0,0 +1,734 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use anyhow::bail;
+use regex::Regex;
+
+use crate::{
+    config::config_enums::DbType,
+    error::Error,
+    meta::{col_value::ColValue, row_data::RowData, row_type::RowType},
+};
+
+/// a subset of sql predicates evaluated on row images, used to apply
+/// [filter] where_conditions to cdc rows, supports:
+/// =, !=, <>, <, <=, >, >=, [NOT] IN, [NOT] BETWEEN, [NOT] LIKE, IS [NOT] NULL,
+/// AND, OR, NOT and parentheses
+#[derive(Debug, Clone)]
+pub struct RowCondition {
+    expr: Expr,
+}
+
+#[derive(Debug, Clone)]
+enum Expr {
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+    Not(Box<Expr>),
+    Compare(Operand, CompareOp, Operand),
+    In(Operand, Vec<Operand>),
+    Between(Operand, Operand, Operand),
+    Like(Operand, Regex),
+    IsNull(Operand),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum CompareOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Col(String),
+    Value(Value),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    QuotedIdent(String),
+    String(String),
+    Number(String),
+    Op(String),
+    LParen,
+    RParen,
+    Comma,
+}
+
+// the image does not contain all columns referenced by the condition
+struct MissingCol;
+
+impl RowCondition {
+    pub fn parse(condition: &str, db_type: &DbType) -> anyhow::Result<Self> {
+        let tokens = Self::tokenize(condition, db_type)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.parse_or()?;
+        if parser.pos < parser.tokens.len() {
+            bail! {Error::ConfigError(format!(
+                "unexpected token: {:?} in condition: {}",
+                parser.tokens[parser.pos], condition
+            ))}
+        }
+        Ok(Self { expr })
+    }
+
+    /// None if the image does not contain all columns referenced by the condition
+    pub fn matches(&self, col_values: &HashMap<String, ColValue>) -> Option<bool> {
+        match Self::eval(&self.expr, col_values) {
+            Ok(res) => Some(res == Some(true)),
+            Err(MissingCol) => None,
+        }
+    }
+
+    /// None if the row is out of the condition, an update moving the row out of
+    /// the condition becomes a delete, an update moving the row into it becomes an insert
+    pub fn filter_row(&self, row_data: RowData) -> Option<RowData> {
+        let matches = |col_values: &Option<HashMap<String, ColValue>>| match col_values {
+            Some(col_values) => self.matches(col_values),
+            None => None,
+        };
+
+        match row_data.row_type {
+            // rows can not be evaluated are kept
+            RowType::Insert => match matches(&row_data.after) {
+                Some(false) => None,
+                _ => Some(row_data),
+            },
+
+            RowType::Delete => match matches(&row_data.before) {
+                Some(false) => None,
+                _ => Some(row_data),
+            },
+
+            RowType::Update => {
+                // before images may only contain keys (e.g. pg without REPLICA IDENTITY FULL),
+                // in which case the condition is considered unchanged, unless the row is out of
+                // the condition after update: it may be moved out, so it is deleted by the keys
+                let after_matches = matches(&row_data.after);
+                let before_matches = match (matches(&row_data.before), after_matches) {
+                    (None, Some(false)) => Some(true),
+                    (before_matches, _) => before_matches.or(after_matches),
+                };
+                let after_matches = after_matches.or(before_matches);
+                let partial = row_data.partial;
+                let mut row_data = match (
+                    before_matches.unwrap_or(true),
+                    after_matches.unwrap_or(true),
+                ) {
+                    (true, true) => return Some(row_data),
+                    (false, false) => return None,
+                    (true, false) => RowData::new(
+                        row_data.schema,
+                        row_data.tb,
+                        RowType::Delete,
+                        row_data.before,
+                        None,
+                    ),
+                    (false, true) => RowData::new(
+                        row_data.schema,
+                        row_data.tb,
+                        RowType::Insert,
+                        None,
+                        row_data.after,
+                    ),
+                };
+                row_data.partial = partial;
+                Some(row_data)
+            }
+        }
+    }
+
+    fn eval(
+        expr: &Expr,
+        col_values: &HashMap<String, ColValue>,
+    ) -> Result<Option<bool>, MissingCol> {
+        // three-valued logic, None for unknown
+        let res = match expr {
+            Expr::And(left, right) => {
+                match (
+                    Self::eval(left, col_values)?,
+                    Self::eval(right, col_values)?,
+                ) {
+                    (Some(false), _) | (_, Some(false)) => Some(false),
+                    (Some(true), Some(true)) => Some(true),
+                    _ => None,
+                }
+            }
+
+            Expr::Or(left, right) => {
+                match (
+                    Self::eval(left, col_values)?,
+                    Self::eval(right, col_values)?,
+                ) {
+                    (Some(true), _) | (_, Some(true)) => Some(true),
+                    (Some(false), Some(false)) => Some(false),
+                    _ => None,
+                }
+            }
+
+            Expr::Not(inner) => Self::eval(inner, col_values)?.map(|v| !v),
+
+            Expr::Compare(left, op, right) => {
+                let left = Self::get_value(left, col_values)?;
+                let right = Self::get_value(right, col_values)?;
+                Self::compare(&left, &right).map(|ord| match op {
+                    CompareOp::Eq => ord == Ordering::Equal,
+                    CompareOp::Ne => ord != Ordering::Equal,
+                    CompareOp::Lt => ord == Ordering::Less,
+                    CompareOp::Le => ord != Ordering::Greater,
+                    CompareOp::Gt => ord == Ordering::Greater,
+                    CompareOp::Ge => ord != Ordering::Less,
+                })
+            }
+
+            Expr::In(left, list) => {
+                let left = Self::get_value(left, col_values)?;
+                let mut res = Some(false);
+                for item in list {
+                    let item = Self::get_value(item, col_values)?;
+                    match Self::compare(&left, &item) {
+                        Some(Ordering::Equal) => {
+                            res = Some(true);
+                            break;
+                        }
+                        None => res = None,
+                        _ => {}
+                    }
+                }
+                res
+            }
+
+            Expr::Between(value, low, high) => {
+                let value = Self::get_value(value, col_values)?;
+                let low = Self::get_value(low, col_values)?;
+                let high = Self::get_value(high, col_values)?;
+                match (Self::compare(&value, &low), Self::compare(&value, &high)) {
+                    (Some(l), Some(h)) => Some(l != Ordering::Less && h != Ordering::Greater),
+                    _ => None,
+                }
+            }
+
+            Expr::Like(value, regex) => match Self::get_value(value, col_values)? {
+                Value::Null => None,
+                Value::Bool(v) => Some(regex.is_match(&(v as u8).to_string())),
+                Value::Number(v) | Value::String(v) => Some(regex.is_match(&v)),
+            },
+
+            Expr::IsNull(value) => Some(Self::get_value(value, col_values)? == Value::Null),
+        };
+        Ok(res)
+    }
+
+    fn get_value(
+        operand: &Operand,
+        col_values: &HashMap<String, ColValue>,
+    ) -> Result<Value, MissingCol> {
+        let col = match operand {
+            Operand::Value(v) => return Ok(v.clone()),
+            Operand::Col(col) => col,
+        };
+
+        let col_value = match col_values.get(col) {
+            Some(v) => v,
+            // column names are case insensitive in mysql and unquoted identifiers are folded in pg
+            None => match col_values.iter().find(|(k, _)| k.eq_ignore_ascii_case(col)) {
+                Some((_, v)) => v,
+                None => return Err(MissingCol),
+            },
+        };
+
+        let value = match col_value {
+            ColValue::None => Value::Null,
+            ColValue::Bool(v) => Value::Bool(*v),
+            ColValue::Tiny(_)
+            | ColValue::UnsignedTiny(_)
+            | ColValue::Short(_)
+            | ColValue::UnsignedShort(_)
+            | ColValue::Long(_)
+            | ColValue::UnsignedLong(_)
+            | ColValue::LongLong(_)
+            | ColValue::UnsignedLongLong(_)
+            | ColValue::Float(_)
+            | ColValue::Double(_)
+            | ColValue::Decimal(_)
+            | ColValue::Year(_)
+            | ColValue::Bit(_) => Value::Number(col_value.to_option_string().unwrap_or_default()),
+            _ => Value::String(col_value.to_option_string().unwrap_or_default()),
+        };
+        Ok(value)
+    }
+
+    fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+        let to_number = |v: &Value| -> Option<String> {
+            match v {
+                Value::Bool(b) => Some((*b as u8).to_string()),
+                Value::Number(n) => Some(n.clone()),
+                // strings compared with numbers are converted to numbers, like mysql does
+                Value::String(s) if s.trim().parse::<f64>().is_ok() => Some(s.trim().to_string()),
+                _ => None,
+            }
+        };
+
+        match (left, right) {
+            (Value::Null, _) | (_, Value::Null) => None,
+            (Value::String(l), Value::String(r)) => Some(l.cmp(r)),
+            _ => {
+                let (l, r) = (to_number(left)?, to_number(right)?);
+                // exact comparison for integers, e.g. unsigned bigint
+                if let (Ok(l), Ok(r)) = (l.parse::<i128>(), r.parse::<i128>()) {
+                    return Some(l.cmp(&r));
+                }
+                l.parse::<f64>().ok()?.partial_cmp(&r.parse::<f64>().ok()?)
+            }
+        }
+    }
+
+    fn tokenize(condition: &str, db_type: &DbType) -> anyhow::Result<Vec<Token>> {
+        let chars: Vec<char> = condition.chars().collect();
+        let mut tokens = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            let c = chars[i];
+            match c {
+                _ if c.is_whitespace() => i += 1,
+                '(' => {
+                    tokens.push(Token::LParen);
+                    i += 1;
+                }
+                ')' => {
+                    tokens.push(Token::RParen);
+                    i += 1;
+                }
+                ',' => {
+                    tokens.push(Token::Comma);
+                    i += 1;
+                }
+
+                '\'' | '"' | '`' => {
+                    // quotes are escaped by doubling them
+                    let mut str = String::new();
+                    i += 1;
+                    loop {
+                        if i >= chars.len() {
+                            bail! {Error::ConfigError(format!(
+                                "unclosed quote in condition: {}",
+                                condition
+                            ))}
+                        }
+                        if chars[i] == c {
+                            if i + 1 < chars.len() && chars[i + 1] == c {
+                                str.push(c);
+                                i += 2;
+                                continue;
+                            }
+                            i += 1;
+                            break;
+                        }
+                        str.push(chars[i]);
+                        i += 1;
+                    }
+                    let is_ident = c == '`' || (c == '"' && *db_type == DbType::Pg);
+                    if is_ident {
+                        tokens.push(Token::QuotedIdent(str));
+                    } else {
+                        tokens.push(Token::String(str));
+                    }
+                }
+
+                '=' | '!' | '<' | '>' => {
+                    let next = chars.get(i + 1).copied();
+                    let op = match (c, next) {
+                        ('!', Some('='))
+                        | ('<', Some('='))
+                        | ('>', Some('='))
+                        | ('<', Some('>')) => {
+                            i += 2;
+                            format!("{}{}", c, next.unwrap())
+                        }
+                        ('!', _) => bail! {Error::ConfigError(format!(
+                            "unexpected char: ! in condition: {}",
+                            condition
+                        ))},
+                        _ => {
+                            i += 1;
+                            c.to_string()
+                        }
+                    };
+                    tokens.push(Token::Op(op));
+                }
+
+                _ if c.is_ascii_digit()
+                    || ((c == '-' || c == '+' || c == '.')
+                        && chars.get(i + 1).is_some_and(|n| n.is_ascii_digit())) =>
+                {
+                    let start = i;
+                    i += 1;
+                    while i < chars.len()
+                        && (chars[i].is_ascii_alphanumeric()
+                            || chars[i] == '.'
+                            || ((chars[i] == '-' || chars[i] == '+')
+                                && matches!(chars[i - 1], 'e' | 'E')))
+                    {
+                        i += 1;
+                    }
+                    let number: String = chars[start..i].iter().collect();
+                    if number.parse::<f64>().is_err() {
+                        bail! {Error::ConfigError(format!(
+                            "invalid number: {} in condition: {}",
+                            number, condition
+                        ))}
+                    }
+                    tokens.push(Token::Number(number.trim_start_matches('+').to_string()));
+                }
+
+                _ if c.is_alphanumeric() || c == '_' || c == '$' => {
+                    let start = i;
+                    while i < chars.len()
+                        && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '$')
+                    {
+                        i += 1;
+                    }
+                    tokens.push(Token::Ident(chars[start..i].iter().collect()));
+                }
+
+                _ => bail! {Error::ConfigError(format!(
+                    "unsupported char: {} in condition: {}",
+                    c, condition
+                ))},
+            }
+        }
+        Ok(tokens)
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn parse_or(&mut self) -> anyhow::Result<Expr> {
+        let mut expr = self.parse_and()?;
+        while self.next_keyword("OR") {
+            expr = Expr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> anyhow::Result<Expr> {
+        let mut expr = self.parse_not()?;
+        while self.next_keyword("AND") {
+            expr = Expr::And(Box::new(expr), Box::new(self.parse_not()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_not(&mut self) -> anyhow::Result<Expr> {
+        if self.next_keyword("NOT") {
+            return Ok(Expr::Not(Box::new(self.parse_not()?)));
+        }
+        self.parse_predicate()
+    }
+
+    fn parse_predicate(&mut self) -> anyhow::Result<Expr> {
+        if self.peek() == Some(&Token::LParen) {
+            self.pos += 1;
+            let expr = self.parse_or()?;
+            self.expect(Token::RParen)?;
+            return Ok(expr);
+        }
+
+        let left = self.parse_operand()?;
+
+        if self.next_keyword("IS") {
+            let not = self.next_keyword("NOT");
+            if !self.next_keyword("NULL") {
+                bail! {Error::ConfigError("expect NULL after IS".into())}
+            }
+            return Ok(Self::negate(Expr::IsNull(left), not));
+        }
+
+        if let Some(Token::Op(op)) = self.peek().cloned() {
+            self.pos += 1;
+            let op = match op.as_str() {
+                "=" => CompareOp::Eq,
+                "!=" | "<>" => CompareOp::Ne,
+                "<" => CompareOp::Lt,
+                "<=" => CompareOp::Le,
+                ">" => CompareOp::Gt,
+                _ => CompareOp::Ge,
+            };
+            let right = self.parse_operand()?;
+            return Ok(Expr::Compare(left, op, right));
+        }
+
+        let not = self.next_keyword("NOT");
+        if self.next_keyword("IN") {
+            self.expect(Token::LParen)?;
+            let mut list = vec![self.parse_operand()?];
+            while self.peek() == Some(&Token::Comma) {
+                self.pos += 1;
+                list.push(self.parse_operand()?);
+            }
+            self.expect(Token::RParen)?;
+            return Ok(Self::negate(Expr::In(left, list), not));
+        }
+
+        if self.next_keyword("BETWEEN") {
+            let low = self.parse_operand()?;
+            if !self.next_keyword("AND") {
+                bail! {Error::ConfigError("expect AND after BETWEEN".into())}
+            }
+            let high = self.parse_operand()?;
+            return Ok(Self::negate(Expr::Between(left, low, high), not));
+        }
+
+        if self.next_keyword("LIKE") {
+            let Operand::Value(Value::String(pattern)) = self.parse_operand()? else {
+                bail! {Error::ConfigError("only string patterns are supported by LIKE".into())}
+            };
+            return Ok(Self::negate(
+                Expr::Like(left, Self::like_to_regex(&pattern)?),
+                not,
+            ));
+        }
+
+        bail! {Error::ConfigError(format!(
+            "expect an operator, got: {:?}",
+            self.peek()
+        ))}
+    }
+
+    fn parse_operand(&mut self) -> anyhow::Result<Operand> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        let operand = match token {
+            Some(Token::QuotedIdent(v)) => Operand::Col(v),
+            Some(Token::String(v)) => Operand::Value(Value::String(v)),
+            Some(Token::Number(v)) => Operand::Value(Value::Number(v)),
+            Some(Token::Ident(v)) => match v.to_uppercase().as_str() {
+                "NULL" => Operand::Value(Value::Null),
+                "TRUE" => Operand::Value(Value::Bool(true)),
+                "FALSE" => Operand::Value(Value::Bool(false)),
+                "AND" | "OR" | "NOT" | "IN" | "IS" | "BETWEEN" | "LIKE" => {
+                    bail! {Error::ConfigError(format!("unexpected keyword: {}", v))}
+                }
+                _ => Operand::Col(v),
+            },
+            _ => bail! {Error::ConfigError(format!(
+                "expect a column or a value, got: {:?}",
+                token
+            ))},
+        };
+        // functions are not supported
+        if self.peek() == Some(&Token::LParen) {
+            bail! {Error::ConfigError(format!(
+                "functions are not supported: {:?}",
+                operand
+            ))}
+        }
+        Ok(operand)
+    }
+
+    fn like_to_regex(pattern: &str) -> anyhow::Result<Regex> {
+        let mut regex = String::from("(?s)^");
+        let mut chars = pattern.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '%' => regex.push_str(".*"),
+                '_' => regex.push('.'),
+                '\\' => {
+                    if let Some(next) = chars.next() {
+                        regex.push_str(&regex::escape(&next.to_string()));
+                    }
+                }
+                _ => regex.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        regex.push('$');
+        Ok(Regex::new(&regex)?)
+    }
+
+    fn negate(expr: Expr, not: bool) -> Expr {
+        if not {
+            Expr::Not(Box::new(expr))
+        } else {
+            expr
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next_keyword(&mut self, keyword: &str) -> bool {
+        if let Some(Token::Ident(v)) = self.peek() {
+            if v.eq_ignore_ascii_case(keyword) {
+                self.pos += 1;
+                return true;
+            }
+        }
+        false
+    }
+
+    fn expect(&mut self, token: Token) -> anyhow::Result<()> {
+        if self.peek() != Some(&token) {
+            bail! {Error::ConfigError(format!(
+                "expect: {:?}, got: {:?}",
+                token,
+                self.peek()
+            ))}
+        }
+        self.pos += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_col_values(kvs: &[(&str, ColValue)]) -> HashMap<String, ColValue> {
+        kvs.iter()
+            .map(|(k, v)| (k.to_string(), v.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_matches() {
+        let col_values = build_col_values(&[
+            ("id", ColValue::Long(10)),
+            ("tenant_id", ColValue::UnsignedLongLong(u64::MAX)),
+            ("name", ColValue::String("abc'd".into())),
+            ("age", ColValue::Decimal("20.5".into())),
+            ("deleted", ColValue::None),
+        ]);
+
+        let cases = [
+            ("id > 1 and `age` > 100", false),
+            ("id > 1 and `age` > 20", true),
+            ("id = 10", true),
+            ("id <> 10 or name = 'abc''d'", true),
+            ("tenant_id IN (1, 18446744073709551615)", true),
+            ("tenant_id not in (1, 2)", true),
+            ("id between 1 and 10", true),
+            ("id not between 1 and 9", true),
+            ("name like 'ab%'", true),
+            ("name like '_bc'", false),
+            ("deleted is null and id >= '10'", true),
+            ("deleted is not null", false),
+            ("deleted = 1", false),
+            ("not (deleted = 1)", false),
+            ("not (deleted = 1) or id = 10", true),
+            ("(id = 1 or id = 10) and not name = 'x'", true),
+        ];
+        for (condition, expect) in cases {
+            let row_condition = RowCondition::parse(condition, &DbType::Mysql).unwrap();
+            assert_eq!(
+                row_condition.matches(&col_values),
+                Some(expect),
+                "condition: {}",
+                condition
+            );
+        }
+
+        let row_condition = RowCondition::parse("f_1 = 1", &DbType::Mysql).unwrap();
+        assert_eq!(row_condition.matches(&col_values), None);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        for condition in [
+            "id >",
+            "id = 1 and",
+            "date(create_time) > '2024-01-01'",
+            "id = 1)",
+            "name = 'abc",
+        ] {
+            assert!(
+                RowCondition::parse(condition, &DbType::Mysql).is_err(),
+                "condition: {}",
+                condition
+            );
+        }
+    }
+
+    #[test]
+    fn test_filter_row() {
+        let row_condition = RowCondition::parse(r#""tenant_id" in (1, 2)"#, &DbType::Pg).unwrap();
+        let build_row = |row_type: RowType, before: Option<i32>, after: Option<i32>| {
+            let to_image = |v: Option<i32>| {
+                v.map(|v| {
+                    build_col_values(&[("id", ColValue::Long(1)), ("tenant_id", ColValue::Long(v))])
+                })
+            };
+            RowData::new(
+                "db_1".into(),
+                "tb_1".into(),
+                row_type,
+                to_image(before),
+                to_image(after),
+            )
+        };
+
+        let res = row_condition.filter_row(build_row(RowType::Insert, None, Some(1)));
+        assert_eq!(res.unwrap().row_type, RowType::Insert);
+        let res = row_condition.filter_row(build_row(RowType::Insert, None, Some(3)));
+        assert!(res.is_none());
+        let res = row_condition.filter_row(build_row(RowType::Delete, Some(3), None));
+        assert!(res.is_none());
+
+        let res = row_condition.filter_row(build_row(RowType::Update, Some(1), Some(2)));
+        assert_eq!(res.unwrap().row_type, RowType::Update);
+        let res = row_condition.filter_row(build_row(RowType::Update, Some(3), Some(4)));
+        assert!(res.is_none());
+
+        // moved out
+        let res = row_condition
+            .filter_row(build_row(RowType::Update, Some(1), Some(3)))
+            .unwrap();
+        assert_eq!(res.row_type, RowType::Delete);
+        assert!(res.before.is_some() && res.after.is_none());
+
+        // moved in
+        let res = row_condition
+            .filter_row(build_row(RowType::Update, Some(3), Some(1)))
+            .unwrap();
+        assert_eq!(res.row_type, RowType::Insert);
+        assert!(res.before.is_none() && res.after.is_some());
+
+        // before image only contains keys
+        let mut row_data = build_row(RowType::Update, Some(1), Some(3));
+        row_data
+            .before
+            .as_mut()
+            .unwrap()
+            .retain(|k, _| k.as_str() == "id");
+        let res = row_condition.filter_row(row_data).unwrap();
+        assert_eq!(res.row_type, RowType::Delete);
+        assert!(res.after.is_none());
+        let before = res.before.unwrap();
+        assert_eq!(before.len(), 1);
+        assert_eq!(before["id"], ColValue::Long(1));
+
+        let mut row_data = build_row(RowType::Update, Some(1), Some(2));
+        row_data
+            .before
+            .as_mut()
+            .unwrap()
+            .retain(|k, _| k.as_str() == "id");
+        let res = row_condition.filter_row(row_data);
+        assert_eq!(res.unwrap().row_type, RowType::Update);
+    }
+}
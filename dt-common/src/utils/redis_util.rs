@@ -1,6 +1,6 @@
 use crate::error::Error;
 use crate::log_info;
-use crate::meta::redis::cluster_node::ClusterNode;
+use crate::meta::redis::cluster_node::{ClusterNode, ClusterShard};
 use crate::meta::redis::command::cmd_encoder::CmdEncoder;
 use crate::meta::redis::command::key_parser::KeyParser;
 use crate::meta::redis::redis_object::RedisCmd;
@@ -13,6 +13,8 @@ use std::str::FromStr;
 pub struct RedisUtil {}
 
 const SLOTS_COUNT: usize = 16384;
+// DUMP payload: [type byte][rdb encoded value][2 bytes rdb version][8 bytes crc64]
+pub const DUMP_FOOTER_LEN: usize = 10;
 
 impl RedisUtil {
     pub async fn create_redis_conn(url: &str) -> anyhow::Result<redis::Connection> {
@@ -46,6 +48,59 @@ impl RedisUtil {
         }
     }
 
+    /// get shards by CLUSTER SHARDS (Redis 7.0+), fall back to CLUSTER NODES for older versions
+    pub fn get_cluster_shards(conn: &mut redis::Connection) -> anyhow::Result<Vec<ClusterShard>> {
+        let cmd = RedisCmd::from_str_args(&["cluster", "shards"]);
+        let value = conn.req_packed_command(&CmdEncoder::encode(&cmd))?;
+        if let Value::Array(_) = value {
+            return Self::parse_cluster_shards(value);
+        }
+
+        log_info!("CLUSTER SHARDS not supported, result: {:?}", value);
+        let cmd = RedisCmd::from_str_args(&["cluster", "nodes"]);
+        let value = conn.req_packed_command(&CmdEncoder::encode(&cmd))?;
+        if let Value::BulkString(data) = value {
+            let nodes = Self::parse_cluster_nodes(&String::from_utf8(data)?)?;
+            let mut shards = Vec::new();
+            for node in nodes.iter().filter(|i| i.is_master) {
+                let mut node_ids = vec![node.id.clone()];
+                for slave in nodes.iter().filter(|i| i.master_id == node.id) {
+                    node_ids.push(slave.id.clone());
+                }
+                shards.push(ClusterShard {
+                    master: Some(node.clone()),
+                    node_ids,
+                });
+            }
+            Ok(shards)
+        } else {
+            bail! {Error::RedisResultError(
+                "can not get redis cluster shards".into(),
+            )}
+        }
+    }
+
+    /// get (master_replid, master_replid2) by INFO replication
+    pub fn get_repl_ids(conn: &mut redis::Connection) -> anyhow::Result<(String, String)> {
+        let cmd = RedisCmd::from_str_args(&["INFO", "replication"]);
+        let value = conn.req_packed_command(&CmdEncoder::encode(&cmd))?;
+        if let Value::BulkString(data) = value {
+            let info = String::from_utf8(data)?;
+            let mut repl_ids = (String::new(), String::new());
+            for line in info.lines() {
+                if let Some(repl_id) = line.strip_prefix("master_replid:") {
+                    repl_ids.0 = repl_id.trim().to_string();
+                } else if let Some(repl_id) = line.strip_prefix("master_replid2:") {
+                    repl_ids.1 = repl_id.trim().to_string();
+                }
+            }
+            return Ok(repl_ids);
+        }
+        bail! {Error::RedisResultError(
+            "can not get redis replication ids by INFO".into(),
+        )}
+    }
+
     pub fn get_slot_address_map(nodes: &[ClusterNode]) -> HashMap<u16, &'static str> {
         let mut slot_address_map = HashMap::new();
         for node in nodes.iter() {
@@ -146,6 +201,129 @@ impl RedisUtil {
         res
     }
 
+    fn parse_cluster_shards(value: Value) -> anyhow::Result<Vec<ClusterShard>> {
+        // RESP2 result of CLUSTER SHARDS:
+        // 1) 1) "slots"
+        //    2) 1) (integer) 0
+        //       2) (integer) 5460
+        //    3) "nodes"
+        //    4) 1)  1) "id"
+        //           2) "0e9d360631a20c27f629267bf3e01de8e8c4cbec"
+        //           3) "port"
+        //           4) (integer) 6379
+        //           5) "ip"
+        //           6) "172.28.0.11"
+        //           ...
+        //           11) "role"
+        //           12) "master"
+        //           ...
+        //           15) "health"
+        //           16) "online"
+        log_info!("cluster shards: {:?}", value);
+        let all_slot_hash_tag_map = Self::get_slot_hash_tag_map();
+        let mut shards = Vec::new();
+        for shard_value in Self::get_array(value)? {
+            let shard = Self::get_map(shard_value)?;
+            let mut slots = Vec::new();
+            if let Some(slot_values) = shard.get("slots") {
+                let ranges = Self::parse_result_as_string(slot_values.clone())?;
+                for range in ranges.chunks(2) {
+                    if range.len() < 2 {
+                        break;
+                    }
+                    for slot in range[0].parse::<u16>()?..=range[1].parse::<u16>()? {
+                        slots.push(slot);
+                    }
+                }
+            }
+
+            let mut master = None;
+            let mut node_ids = Vec::new();
+            let node_values = match shard.get("nodes") {
+                Some(node_values) => Self::get_array(node_values.clone())?,
+                None => Vec::new(),
+            };
+            for node_value in node_values {
+                let node = Self::get_map(node_value)?;
+                let get_str = |key: &str| -> anyhow::Result<String> {
+                    match node.get(key) {
+                        Some(v) => Ok(Self::parse_result_as_string(v.clone())?.join("")),
+                        None => Ok(String::new()),
+                    }
+                };
+
+                let id = get_str("id")?;
+                node_ids.push(id.clone());
+                if get_str("role")? != "master" || get_str("health")? != "online" {
+                    continue;
+                }
+
+                let mut host = get_str("endpoint")?;
+                if host.is_empty() || host == "?" {
+                    host = get_str("ip")?;
+                }
+                let port = get_str("port")?;
+                let address = if host.contains(':') {
+                    format!("[{}]:{}", host, port)
+                } else {
+                    format!("{}:{}", host, port)
+                };
+
+                let mut slot_hash_tag_map = HashMap::with_capacity(slots.len());
+                for i in slots.iter() {
+                    slot_hash_tag_map.insert(*i, all_slot_hash_tag_map.get(i).unwrap().to_owned());
+                }
+                master = Some(ClusterNode {
+                    is_master: true,
+                    id,
+                    master_id: "-".into(),
+                    host,
+                    port,
+                    address,
+                    slots: slots.clone(),
+                    slot_hash_tag_map,
+                });
+            }
+            shards.push(ClusterShard { master, node_ids });
+        }
+        Ok(shards)
+    }
+
+    fn get_array(value: Value) -> anyhow::Result<Vec<Value>> {
+        match value {
+            Value::Array(values) => Ok(values),
+            _ => bail! {Error::RedisResultError(format!(
+                "redis result is not an array, value: {:?}",
+                value
+            ))},
+        }
+    }
+
+    fn get_map(value: Value) -> anyhow::Result<HashMap<String, Value>> {
+        // in RESP2, a map is returned as an array of key-value pairs
+        let mut map = HashMap::new();
+        match value {
+            Value::Map(pairs) => {
+                for (k, v) in pairs {
+                    map.insert(Self::parse_result_as_string(k)?.join(""), v);
+                }
+            }
+            _ => {
+                let values = Self::get_array(value)?;
+                for pair in values.chunks(2) {
+                    if pair.len() < 2 {
+                        break;
+                    }
+                    map.insert(
+                        Self::parse_result_as_string(pair[0].clone())?.join(""),
+                        pair[1].clone(),
+                    );
+                }
+            }
+        }
+        Ok(map)
+    }
+
     fn parse_cluster_nodes(nodes_str: &str) -> anyhow::Result<Vec<ClusterNode>> {
         // refer: https://github.com/tair-opensource/RedisShake/blob/v4/internal/utils/cluster_nodes.go
         let mut all_slots_count = 0;
@@ -290,4 +468,68 @@ mod tests {
         assert!(nodes[1].is_master);
         assert!(!nodes[4].is_master);
     }
+
+    #[test]
+    fn test_parse_cluster_shards() {
+        let bulk = |s: &str| Value::BulkString(s.as_bytes().to_vec());
+        let node = |id: &str, ip: &str, role: &str, health: &str| {
+            Value::Array(vec![
+                bulk("id"),
+                bulk(id),
+                bulk("port"),
+                Value::Int(6379),
+                bulk("ip"),
+                bulk(ip),
+                bulk("endpoint"),
+                bulk(ip),
+                bulk("role"),
+                bulk(role),
+                bulk("replication-offset"),
+                Value::Int(100),
+                bulk("health"),
+                bulk(health),
+            ])
+        };
+        let shard = |slots: Vec<i64>, nodes: Vec<Value>| {
+            Value::Array(vec![
+                bulk("slots"),
+                Value::Array(slots.into_iter().map(Value::Int).collect()),
+                bulk("nodes"),
+                Value::Array(nodes),
+            ])
+        };
+
+        let value = Value::Array(vec![
+            shard(
+                vec![0, 5460, 5462, 5462],
+                vec![
+                    node("id_1", "172.28.0.11", "master", "online"),
+                    node("id_4", "172.28.0.14", "replica", "online"),
+                ],
+            ),
+            shard(
+                vec![5461, 5461, 5463, 16383],
+                vec![
+                    node("id_2", "172.28.0.12", "master", "failed"),
+                    node("id_5", "172.28.0.15", "replica", "online"),
+                ],
+            ),
+        ]);
+        let shards = RedisUtil::parse_cluster_shards(value).unwrap();
+
+        assert_eq!(shards.len(), 2);
+        assert_eq!(shards[0].node_ids, vec!["id_1", "id_4"]);
+        assert_eq!(shards[1].node_ids, vec!["id_2", "id_5"]);
+
+        let master = shards[0].master.as_ref().unwrap();
+        assert_eq!(master.id, "id_1");
+        assert_eq!(master.address, "172.28.0.11:6379");
+        assert_eq!(master.slots.len(), 5462);
+        assert!(master.slots.contains(&5462));
+        assert!(!master.slots.contains(&5461));
+        assert_eq!(master.slot_hash_tag_map.len(), 5462);
+
+        // failed master is not taken as the shard master
+        assert!(shards[1].master.is_none());
+    }
 }
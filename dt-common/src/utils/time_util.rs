@@ -1,4 +1,9 @@
 use anyhow::{bail, Context};
+use std::{
+    cmp,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
 use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
 use tokio::{time::sleep, time::Duration};
 
@@ -12,6 +17,19 @@ impl TimeUtil {
         sleep(Duration::from_millis(millis)).await;
     }
 
+    /// exponential backoff with jitter: [backoff / 2, backoff],
+    /// backoff = min(interval_ms * 2 ^ (attempt - 1), max_interval_ms)
+    pub fn get_backoff_millis(interval_ms: u64, max_interval_ms: u64, attempt: u64) -> u64 {
+        let exp = cmp::min(attempt.saturating_sub(1), 20) as u32;
+        let backoff = cmp::min(interval_ms.saturating_mul(2u64.pow(exp)), max_interval_ms);
+        let half = backoff / 2;
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.subsec_nanos() as u64)
+            .unwrap_or(0);
+        half + nanos % (backoff - half + 1)
+    }
+
     #[inline(always)]
     pub fn date_from_str(str: &str) -> anyhow::Result<NaiveDate> {
         let date = NaiveDate::parse_from_str(str, "%Y-%m-%d")
@@ -38,6 +56,15 @@ impl TimeUtil {
             bail!(format!("timestamp_to_str failed, input: [{}]", timestamp))
         }
     }
+
+    #[inline(always)]
+    pub fn millis_to_str(millis: u64) -> anyhow::Result<String> {
+        if let Some(datetime) = DateTime::from_timestamp_millis(millis as i64) {
+            Ok(datetime.format("%Y-%m-%d %H:%M:%S%.3f").to_string())
+        } else {
+            bail!(format!("millis_to_str failed, input: [{}]", millis))
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,73 @@
+use dt_common::meta::row_data::RowData;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+use crate::check_log::check_log::CheckLog;
+
+/// A dead letter is a miss check log extended with the failed row, so dead_letter.log
+/// can be replayed by the check_log extractor, which ignores the extra fields.
+/// check_log holds the source schema / tb / id cols (routed back from the sinker),
+/// row_data holds the row that failed to sink.
+#[derive(Serialize, Deserialize)]
+pub struct DeadLetter {
+    #[serde(flatten)]
+    pub check_log: CheckLog,
+    pub position: String,
+    pub error: String,
+    pub row_data: RowData,
+}
+
+impl std::fmt::Display for DeadLetter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", json!(self))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, str::FromStr};
+
+    use dt_common::meta::{col_value::ColValue, row_type::RowType};
+
+    use super::*;
+    use crate::check_log::log_type::LogType;
+
+    #[test]
+    fn test_replay_as_check_log() {
+        let mut after = HashMap::new();
+        after.insert("id".to_string(), ColValue::Long(1));
+        after.insert("value".to_string(), ColValue::String("abc".to_string()));
+        let row_data = RowData::new(
+            "dst_db".into(),
+            "dst_tb".into(),
+            RowType::Insert,
+            None,
+            Some(after),
+        );
+
+        let mut id_col_values = HashMap::new();
+        id_col_values.insert("id".to_string(), Some("1".to_string()));
+        let dead_letter = DeadLetter {
+            check_log: CheckLog {
+                log_type: LogType::Miss,
+                schema: "src_db".into(),
+                tb: "src_tb".into(),
+                id_col_values,
+                diff_col_values: HashMap::new(),
+            },
+            position: "binlog_filename:mysql-bin.000001,next_event_position:100".into(),
+            error: "serial sink failed: Duplicate entry '1' for key 'PRIMARY'".into(),
+            row_data,
+        };
+
+        let check_log = CheckLog::from_str(&dead_letter.to_string()).unwrap();
+        assert!(check_log.log_type == LogType::Miss);
+        assert_eq!(check_log.schema, "src_db");
+        assert_eq!(check_log.tb, "src_tb");
+        assert_eq!(
+            check_log.id_col_values.get("id"),
+            Some(&Some("1".to_string()))
+        );
+        assert!(check_log.diff_col_values.is_empty());
+    }
+}
@@ -0,0 +1,219 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::{Arc, Mutex},
+};
+
+use anyhow::{bail, Context};
+use kafka::producer::{Producer, Record};
+use sqlx::{MySql, Pool, Postgres};
+use tokio::time::Instant;
+
+use super::dead_letter::DeadLetter;
+use crate::{
+    check_log::{check_log::CheckLog, log_type::LogType},
+    rdb_router::RdbRouter,
+};
+use dt_common::{
+    config::config_enums::DbType,
+    error::Error,
+    log_dead_letter, log_warn,
+    meta::{
+        col_value::ColValue,
+        mongo::{mongo_constant::MongoConstants, mongo_key::MongoKey},
+        position::Position,
+        rdb_meta_manager::RdbMetaManager,
+        row_data::RowData,
+        row_type::RowType,
+    },
+    monitor::{counter_type::CounterType, monitor::Monitor},
+};
+
+pub enum DeadLetterTarget {
+    Log,
+    Kafka {
+        producer: Arc<Mutex<Producer>>,
+        topic: String,
+    },
+    MysqlTable {
+        conn_pool: Pool<MySql>,
+        tb: String,
+    },
+    PgTable {
+        conn_pool: Pool<Postgres>,
+        tb: String,
+    },
+}
+
+/// write rows failed to sink permanently (constraint violation, value out of range,
+/// missing table, etc.) to the dead letter target, and stop the task if more than
+/// threshold rows are dead-lettered in window_secs
+pub struct DeadLetterWriter {
+    pub target: DeadLetterTarget,
+    pub extractor_db_type: DbType,
+    pub extractor_meta_manager: Option<RdbMetaManager>,
+    pub reverse_router: RdbRouter,
+    pub threshold: u64,
+    pub window_secs: u64,
+    pub monitor: Arc<Monitor>,
+    pub write_times: VecDeque<Instant>,
+}
+
+impl DeadLetterWriter {
+    pub async fn write(
+        &mut self,
+        row_data: RowData,
+        position: &Position,
+        error: &anyhow::Error,
+    ) -> anyhow::Result<()> {
+        let check_log = self.build_check_log(&row_data).await?;
+        let dead_letter = DeadLetter {
+            check_log,
+            position: position.to_string(),
+            error: format!("{:#}", error),
+            row_data,
+        };
+        log_warn!(
+            "row sink failed, written to dead letter, schema: {}, tb: {}, position: {}, error: {}",
+            dead_letter.row_data.schema,
+            dead_letter.row_data.tb,
+            dead_letter.position,
+            dead_letter.error
+        );
+
+        match &mut self.target {
+            DeadLetterTarget::Log => {
+                log_dead_letter!("{}", dead_letter.to_string());
+            }
+
+            DeadLetterTarget::Kafka { producer, topic } => {
+                // the producer blocks until the message is acked, keep it off the async workers
+                let producer = producer.clone();
+                let topic = topic.clone();
+                let value = dead_letter.to_string();
+                tokio::task::spawn_blocking(move || {
+                    let record = Record {
+                        key: String::new(),
+                        value,
+                        topic: topic.as_str(),
+                        partition: -1,
+                    };
+                    producer
+                        .lock()
+                        .unwrap()
+                        .send(&record)
+                        .with_context(|| format!("failed to send dead letter to topic: {}", topic))
+                })
+                .await??;
+            }
+
+            DeadLetterTarget::MysqlTable { conn_pool, tb } => {
+                let sql = format!(
+                    "INSERT INTO {} (schema_name, tb_name, position, error, content) VALUES (?, ?, ?, ?, ?)",
+                    tb
+                );
+                sqlx::query(&sql)
+                    .bind(&dead_letter.check_log.schema)
+                    .bind(&dead_letter.check_log.tb)
+                    .bind(&dead_letter.position)
+                    .bind(&dead_letter.error)
+                    .bind(dead_letter.to_string())
+                    .execute(&*conn_pool)
+                    .await
+                    .with_context(|| format!("failed to write dead letter to table: {}", tb))?;
+            }
+
+            DeadLetterTarget::PgTable { conn_pool, tb } => {
+                let sql = format!(
+                    "INSERT INTO {} (schema_name, tb_name, position, error, content) VALUES ($1, $2, $3, $4, $5)",
+                    tb
+                );
+                sqlx::query(&sql)
+                    .bind(&dead_letter.check_log.schema)
+                    .bind(&dead_letter.check_log.tb)
+                    .bind(&dead_letter.position)
+                    .bind(&dead_letter.error)
+                    .bind(dead_letter.to_string())
+                    .execute(&*conn_pool)
+                    .await
+                    .with_context(|| format!("failed to write dead letter to table: {}", tb))?;
+            }
+        }
+
+        self.monitor.add_counter(CounterType::DeadLetters, 1);
+        self.check_threshold()
+    }
+
+    pub async fn close(&mut self) -> anyhow::Result<()> {
+        match &self.target {
+            DeadLetterTarget::MysqlTable { conn_pool, .. } => conn_pool.close().await,
+            DeadLetterTarget::PgTable { conn_pool, .. } => conn_pool.close().await,
+            _ => {}
+        }
+        if let Some(meta_manager) = &self.extractor_meta_manager {
+            meta_manager.close().await?;
+        }
+        Ok(())
+    }
+
+    fn check_threshold(&mut self) -> anyhow::Result<()> {
+        let now = Instant::now();
+        self.write_times.push_back(now);
+        while let Some(time) = self.write_times.front() {
+            if now.duration_since(*time).as_secs() < self.window_secs {
+                break;
+            }
+            self.write_times.pop_front();
+        }
+
+        if self.threshold > 0 && self.write_times.len() as u64 > self.threshold {
+            bail! {Error::PipelineError(format!(
+                "dead letters exceeded threshold: {} in {} secs",
+                self.threshold, self.window_secs
+            ))}
+        }
+        Ok(())
+    }
+
+    /// build a miss check log with the source schema / tb / id cols of the row,
+    /// so the check_log extractor can fetch the current row from source and sink it again
+    async fn build_check_log(&mut self, row_data: &RowData) -> anyhow::Result<CheckLog> {
+        let reverse_row_data = self.reverse_router.route_row(row_data.clone());
+        let col_values = match reverse_row_data.row_type {
+            RowType::Delete => reverse_row_data.before.as_ref(),
+            _ => reverse_row_data.after.as_ref(),
+        };
+
+        let mut id_col_values = HashMap::new();
+        if let Some(col_values) = col_values {
+            if let Some(meta_manager) = self.extractor_meta_manager.as_mut() {
+                let tb_meta = meta_manager
+                    .get_tb_meta(&reverse_row_data.schema, &reverse_row_data.tb)
+                    .await?;
+                for col in tb_meta.id_cols.iter() {
+                    let value = col_values.get(col).and_then(|v| v.to_option_string());
+                    id_col_values.insert(col.to_owned(), value);
+                }
+            } else if self.extractor_db_type == DbType::Mongo {
+                // for update, the full doc is in before
+                let doc_values = match reverse_row_data.row_type {
+                    RowType::Update => reverse_row_data.before.as_ref(),
+                    _ => Some(col_values),
+                };
+                if let Some(ColValue::MongoDoc(doc)) =
+                    doc_values.and_then(|values| values.get(MongoConstants::DOC))
+                {
+                    let id = MongoKey::from_doc(doc).map(|key| key.to_string());
+                    id_col_values.insert(MongoConstants::ID.to_string(), id);
+                }
+            }
+        }
+
+        Ok(CheckLog {
+            log_type: LogType::Miss,
+            schema: reverse_row_data.schema,
+            tb: reverse_row_data.tb,
+            id_col_values,
+            diff_col_values: HashMap::new(),
+        })
+    }
+}
@@ -0,0 +1,3 @@
+#[allow(clippy::module_inception)]
+pub mod dead_letter;
+pub mod dead_letter_writer;
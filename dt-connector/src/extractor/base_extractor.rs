@@ -13,6 +13,7 @@ use dt_common::{
         dcl_meta::{dcl_data::DclData, dcl_parser::DclParser},
         ddl_meta::ddl_data::DdlData,
         dt_queue::DtQueue,
+        message_data::MessageData,
         struct_meta::struct_data::StructData,
     },
     rdb_filter::RdbFilter,
@@ -123,6 +124,19 @@ impl BaseExtractor {
         self.push_dt_data(DtData::Ddl { ddl_data }, position).await
     }
 
+    pub async fn push_message(
+        &mut self,
+        message_data: MessageData,
+        position: Position,
+    ) -> anyhow::Result<()> {
+        // messages are sinked seperately, wait for the data before them to be sinked first
+        while !self.buffer.is_empty() {
+            TimeUtil::sleep_millis(1).await;
+        }
+        self.push_dt_data(DtData::Message { message_data }, position)
+            .await
+    }
+
     pub async fn push_dcl(&mut self, dcl_data: DclData, position: Position) -> anyhow::Result<()> {
         // Todo: route dcl data
         self.push_dt_data(DtData::Dcl { dcl_data }, position).await
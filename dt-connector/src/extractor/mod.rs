@@ -6,5 +6,7 @@ pub mod kafka;
 pub mod mongo;
 pub mod mysql;
 pub mod pg;
+pub mod reconnector;
 pub mod redis;
 pub mod resumer;
+pub mod tidb;
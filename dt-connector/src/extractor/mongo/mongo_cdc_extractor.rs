@@ -6,19 +6,32 @@ use std::{
     },
 };
 
+use anyhow::bail;
 use async_trait::async_trait;
 use chrono::Utc;
 use serde_json::json;
 use tokio::{sync::Mutex, time::Instant};
 
 use crate::{
-    extractor::{base_extractor::BaseExtractor, resumer::cdc_resumer::CdcResumer},
+    extractor::{
+        base_extractor::BaseExtractor, reconnector::Reconnector, resumer::cdc_resumer::CdcResumer,
+    },
     Extractor,
 };
 use dt_common::{
     config::config_enums::DbType,
-    log_error, log_info,
+    error::Error,
+    log_error, log_info, log_warn,
     meta::col_value::ColValue,
+    meta::ddl_meta::{
+        ddl_data::DdlData,
+        ddl_statement::{
+            DdlStatement, DropDatabaseStatement, DropTableStatement,
+            MongoCreateCollectionStatement, MongoCreateIndexStatement, MongoDropIndexStatement,
+            RenameTableStatement,
+        },
+        ddl_type::DdlType,
+    },
     meta::dt_data::DtData,
     meta::mongo::{mongo_cdc_source::MongoCdcSource, mongo_constant::MongoConstants},
     meta::position::Position,
@@ -30,8 +43,8 @@ use dt_common::{
 };
 use mongodb::{
     bson::{doc, Bson, Document, Timestamp},
-    change_stream::event::{OperationType, ResumeToken},
-    options::{ChangeStreamOptions, FullDocumentBeforeChangeType, FullDocumentType, UpdateOptions},
+    change_stream::event::{ChangeNamespace, ChangeStreamEvent, OperationType, ResumeToken},
+    options::UpdateOptions,
     Client,
 };
 
@@ -49,6 +62,7 @@ pub struct MongoCdcExtractor {
     pub heartbeat_tb: String,
     pub syncer: Arc<Mutex<Syncer>>,
     pub resumer: CdcResumer,
+    pub reconnector: Reconnector,
 }
 
 #[async_trait]
@@ -97,9 +111,32 @@ impl Extractor for MongoCdcExtractor {
 impl MongoCdcExtractor {
     async fn extract_oplog(&mut self) -> anyhow::Result<()> {
         let start_timestamp = self.parse_start_timestamp();
-        let filter = doc! {
+        let mut filter = doc! {
             "ts": { "$gte": start_timestamp }
         };
+        let mut last_ts = None;
+        while let Some(error) = self
+            .extract_oplog_stream(filter.clone(), &mut last_ts)
+            .await?
+        {
+            self.reconnector.wait(error).await?;
+            // resume right after the last received oplog
+            if let Some(ts) = last_ts {
+                filter = doc! {
+                    "ts": { "$gt": ts }
+                };
+            }
+            log_info!("reconnect oplog cursor, filter: {}", filter);
+        }
+        Ok(())
+    }
+
+    /// return the error if the oplog cursor breaks, which can be resumed by reconnecting
+    async fn extract_oplog_stream(
+        &mut self,
+        filter: Document,
+        last_ts: &mut Option<Timestamp>,
+    ) -> anyhow::Result<Option<anyhow::Error>> {
         let options = mongodb::options::FindOptions::builder()
             .cursor_type(mongodb::options::CursorType::TailableAwait)
             .build();
@@ -108,10 +145,23 @@ impl MongoCdcExtractor {
             .mongo_client
             .database("local")
             .collection::<Document>("oplog.rs");
-        let mut cursor = oplog.find(filter, options).await?;
+        let mut cursor = match oplog.find(filter, options).await {
+            Ok(cursor) => cursor,
+            Err(error) => return Ok(Some(error.into())),
+        };
+
+        loop {
+            match cursor.advance().await {
+                Ok(true) => {}
+                Ok(false) => break,
+                Err(error) => return Ok(Some(error.into())),
+            }
+            self.reconnector.reset();
 
-        while cursor.advance().await? {
             let doc: Document = cursor.deserialize_current()?;
+            if let Ok(ts) = doc.get_timestamp("ts") {
+                *last_ts = Some(ts);
+            }
             // https://github.com/mongodb/mongo/blob/master/src/mongo/db/repl/oplog.cpp
             // op:
             //     "i" insert
@@ -214,7 +264,7 @@ impl MongoCdcExtractor {
                 Self::build_oplog_row_data(&ns, &ts, row_type, before, after);
             self.push_row_to_buf(row_data, position).await?;
         }
-        Ok(())
+        Ok(None)
     }
 
     fn get_op(doc: &Document) -> String {
@@ -331,95 +381,344 @@ impl MongoCdcExtractor {
     }
 
     async fn extract_change_stream(&mut self) -> anyhow::Result<()> {
-        let (resume_token, start_timestamp) = if self.resume_token.is_empty() {
-            (None, Some(self.parse_start_timestamp()))
-        } else {
-            let token: ResumeToken = serde_json::from_str(&self.resume_token)?;
-            (Some(token), None)
-        };
+        let version = self.get_server_version().await?;
+        // refer: https://www.mongodb.com/docs/manual/reference/change-events/#expanded-events
+        // create / createIndexes / dropIndexes events are only emitted with showExpandedEvents,
+        // which is supported since 6.0
+        let show_expanded_events = version >= 6 && !self.filter.filter_all_ddl();
+        log_info!(
+            "mongo server major version: {}, show_expanded_events: {}",
+            version,
+            show_expanded_events
+        );
+
+        loop {
+            match self
+                .extract_change_stream_internal(version, show_expanded_events)
+                .await?
+            {
+                Some(error) => {
+                    self.reconnector.wait(error).await?;
+                    log_info!(
+                        "reconnect change stream, resume_token: {}",
+                        self.resume_token
+                    );
+                }
+                // invalidated, restart right after the invalidate event
+                None => log_info!(
+                    "restart change stream after invalidate, resume_token: {}",
+                    self.resume_token
+                ),
+            }
+        }
+    }
 
+    /// return the error if the change stream breaks, which can be resumed by reconnecting
+    /// with the resume token of the last received event, return None if the change stream
+    /// is invalidated
+    async fn extract_change_stream_internal(
+        &mut self,
+        version: i32,
+        show_expanded_events: bool,
+    ) -> anyhow::Result<Option<anyhow::Error>> {
         // refer: https://www.mongodb.com/docs/manual/changeStreams/
         // Starting in MongoDB 6.0, you can use change stream events to output the version of
         // a document before and after changes (the document pre- and post-images)
-        let stream_options = ChangeStreamOptions::builder()
-            .start_at_operation_time(start_timestamp)
-            .start_after(resume_token)
-            .full_document(Some(FullDocumentType::UpdateLookup))
-            .full_document_before_change(Some(FullDocumentBeforeChangeType::WhenAvailable))
-            .build();
+        let mut stage = doc! {
+            "allChangesForCluster": true,
+            "fullDocument": "updateLookup",
+        };
+        if version >= 6 {
+            stage.insert("fullDocumentBeforeChange", "whenAvailable");
+        }
+        if show_expanded_events {
+            stage.insert("showExpandedEvents", true);
+        }
+        if self.resume_token.is_empty() {
+            stage.insert("startAtOperationTime", self.parse_start_timestamp());
+        } else {
+            let token: ResumeToken = serde_json::from_str(&self.resume_token)?;
+            stage.insert("startAfter", mongodb::bson::to_bson(&token)?);
+        }
+
+        // the driver's ChangeStreamOptions does not support showExpandedEvents,
+        // so open the change stream by aggregate on admin
+        let pipeline = vec![doc! {"$changeStream": stage}];
+        let mut cursor = match self
+            .mongo_client
+            .database("admin")
+            .aggregate(pipeline, None)
+            .await
+        {
+            Ok(cursor) => cursor,
+            Err(error) => return Ok(Some(error.into())),
+        };
 
-        let mut change_stream = self.mongo_client.watch(None, stream_options).await?;
         loop {
-            let result = change_stream.next_if_any().await?;
-            if let Some(doc) = result {
-                let resume_token = doc.id;
-                let position = if let Some(operation_time) = doc.cluster_time {
-                    Position::MongoCdc {
-                        resume_token: json!(resume_token).to_string(),
-                        operation_time: operation_time.time,
-                        timestamp: Position::format_timestamp_millis(
-                            operation_time.time as i64 * 1000,
-                        ),
-                    }
-                } else {
-                    Position::MongoCdc {
-                        resume_token: json!(resume_token).to_string(),
-                        operation_time: 0,
-                        timestamp: String::new(),
-                    }
-                };
+            match cursor.advance().await {
+                Ok(true) => {}
+                Ok(false) => {
+                    return Ok(Some(
+                        Error::ExtractorError("change stream cursor closed".into()).into(),
+                    ));
+                }
+                Err(error) => return Ok(Some(error.into())),
+            }
+            self.reconnector.reset();
 
-                let (mut db, mut tb) = (String::new(), String::new());
-                if let Some(ns) = doc.ns {
-                    db = ns.db.clone();
-                    if let Some(coll) = ns.coll {
-                        tb = coll.clone();
-                    }
+            let doc: ChangeStreamEvent<Document> =
+                mongodb::bson::from_slice(cursor.current().as_bytes())?;
+            let description = match cursor.current().get_document("operationDescription") {
+                Ok(description) => Document::try_from(description)?,
+                Err(_) => Document::new(),
+            };
+
+            let resume_token = doc.id;
+            let position = if let Some(operation_time) = doc.cluster_time {
+                Position::MongoCdc {
+                    resume_token: json!(resume_token).to_string(),
+                    operation_time: operation_time.time,
+                    timestamp: Position::format_timestamp_millis(operation_time.time as i64 * 1000),
+                }
+            } else {
+                Position::MongoCdc {
+                    resume_token: json!(resume_token).to_string(),
+                    operation_time: 0,
+                    timestamp: String::new(),
                 }
+            };
+
+            let (mut db, mut tb) = (String::new(), String::new());
+            if let Some(ns) = doc.ns {
+                db = ns.db.clone();
+                if let Some(coll) = ns.coll {
+                    tb = coll.clone();
+                }
+            }
 
-                let mut row_type = RowType::Insert;
-                let mut before = HashMap::new();
-                let mut after = HashMap::new();
+            let mut row_type = RowType::Insert;
+            let mut before = HashMap::new();
+            let mut after = HashMap::new();
+            // the pre-image is available if changeStreamPreAndPostImages is enabled
+            // for the collection, which contains _id as document_key does
+            let before_doc = doc.full_document_before_change.or(doc.document_key);
+
+            match doc.operation_type {
+                OperationType::Insert => {
+                    after.insert(
+                        MongoConstants::DOC.to_string(),
+                        ColValue::MongoDoc(doc.full_document.unwrap()),
+                    );
+                }
 
-                match doc.operation_type {
-                    OperationType::Insert => {
+                OperationType::Delete => {
+                    row_type = RowType::Delete;
+                    before.insert(
+                        MongoConstants::DOC.to_string(),
+                        ColValue::MongoDoc(before_doc.unwrap()),
+                    );
+                }
+
+                OperationType::Update | OperationType::Replace => {
+                    row_type = RowType::Update;
+                    if let Some(document) = doc.full_document {
+                        before.insert(
+                            MongoConstants::DOC.to_string(),
+                            ColValue::MongoDoc(before_doc.unwrap()),
+                        );
                         after.insert(
                             MongoConstants::DOC.to_string(),
-                            ColValue::MongoDoc(doc.full_document.unwrap()),
+                            ColValue::MongoDoc(document),
                         );
                     }
+                }
 
-                    OperationType::Delete => {
-                        row_type = RowType::Delete;
-                        before.insert(
-                            MongoConstants::DOC.to_string(),
-                            ColValue::MongoDoc(doc.document_key.unwrap()),
-                        );
+                OperationType::Invalidate => {
+                    log_warn!(
+                        "change stream invalidated, resume_token: {}",
+                        json!(resume_token)
+                    );
+                    self.resume_token = json!(resume_token).to_string();
+                    return Ok(None);
+                }
+
+                // TODO, heartbeat
+                operation_type => {
+                    let ddls = Self::build_ddl_data(
+                        &operation_type,
+                        &db,
+                        &tb,
+                        doc.to.as_ref(),
+                        &description,
+                    );
+                    if ddls.is_empty() {
+                        self.base_extractor
+                            .push_dt_data(DtData::Heartbeat {}, position)
+                            .await?;
                     }
+                    for ddl_data in ddls {
+                        self.push_ddl_to_buf(ddl_data, position.clone()).await?;
+                    }
+                    self.resume_token = json!(resume_token).to_string();
+                    continue;
+                }
+            }
 
-                    OperationType::Update | OperationType::Replace => {
-                        row_type = RowType::Update;
-                        if let Some(document) = doc.full_document {
-                            before.insert(
-                                MongoConstants::DOC.to_string(),
-                                ColValue::MongoDoc(doc.document_key.unwrap()),
-                            );
-                            after.insert(
-                                MongoConstants::DOC.to_string(),
-                                ColValue::MongoDoc(document),
-                            );
-                        }
+            let row_data = RowData::new(db, tb, row_type, Some(before), Some(after));
+            self.push_row_to_buf(row_data, position).await?;
+            self.resume_token = json!(resume_token).to_string();
+        }
+    }
+
+    /// map collection lifecycle events to ddls, events not applicable to the target
+    /// (modify, shardCollection, etc.) are ignored
+    fn build_ddl_data(
+        operation_type: &OperationType,
+        db: &str,
+        tb: &str,
+        to: Option<&ChangeNamespace>,
+        description: &Document,
+    ) -> Vec<DdlData> {
+        let mut statements = Vec::new();
+        match operation_type {
+            OperationType::Drop => {
+                let statement = DropTableStatement {
+                    schema: db.into(),
+                    tb: tb.into(),
+                    if_exists: true,
+                    ..Default::default()
+                };
+                statements.push((DdlType::DropTable, DdlStatement::DropTable(statement)));
+            }
+
+            OperationType::Rename => {
+                if let Some(to) = to {
+                    // operationDescription: {to: {...}, dropTarget: <uuid of the dropped collection>}
+                    let statement = RenameTableStatement {
+                        schema: db.into(),
+                        tb: tb.into(),
+                        new_schema: to.db.clone(),
+                        new_tb: to.coll.clone().unwrap_or_default(),
+                        drop_target: description.contains_key("dropTarget"),
+                        ..Default::default()
+                    };
+                    statements.push((DdlType::RenameTable, DdlStatement::RenameTable(statement)));
+                }
+            }
+
+            OperationType::DropDatabase => {
+                let statement = DropDatabaseStatement {
+                    db: db.into(),
+                    if_exists: true,
+                    ..Default::default()
+                };
+                statements.push((DdlType::DropDatabase, DdlStatement::DropDatabase(statement)));
+            }
+
+            OperationType::Other(operation_type) => match operation_type.as_str() {
+                // operationDescription: {idIndex: {...}, capped: true, ...}
+                "create" => {
+                    let statement = MongoCreateCollectionStatement {
+                        db: db.into(),
+                        tb: tb.into(),
+                        options: Bson::Document(description.clone())
+                            .into_relaxed_extjson()
+                            .to_string(),
+                    };
+                    statements.push((
+                        DdlType::CreateTable,
+                        DdlStatement::MongoCreateCollection(statement),
+                    ));
+                }
+
+                // operationDescription: {indexes: [{v: 2, key: {...}, name: "..."}]}
+                "createIndexes" => {
+                    if let Ok(indexes) = description.get_array("indexes") {
+                        let statement = MongoCreateIndexStatement {
+                            db: db.into(),
+                            tb: tb.into(),
+                            indexes: Bson::Array(indexes.clone())
+                                .into_relaxed_extjson()
+                                .to_string(),
+                        };
+                        statements.push((
+                            DdlType::CreateIndex,
+                            DdlStatement::MongoCreateIndex(statement),
+                        ));
                     }
+                }
 
-                    // TODO, heartbeat and DDL
-                    _ => {
-                        continue;
+                "dropIndexes" => {
+                    if let Ok(indexes) = description.get_array("indexes") {
+                        for index in indexes.iter().filter_map(|i| i.as_document()) {
+                            if let Ok(index_name) = index.get_str("name") {
+                                let statement = MongoDropIndexStatement {
+                                    db: db.into(),
+                                    tb: tb.into(),
+                                    index_name: index_name.into(),
+                                };
+                                statements.push((
+                                    DdlType::DropIndex,
+                                    DdlStatement::MongoDropIndex(statement),
+                                ));
+                            }
+                        }
                     }
                 }
 
-                let row_data = RowData::new(db, tb, row_type, Some(before), Some(after));
-                self.push_row_to_buf(row_data, position).await?;
-            }
+                _ => {}
+            },
+
+            _ => {}
+        }
+
+        statements
+            .into_iter()
+            .map(|(ddl_type, statement)| DdlData {
+                default_schema: db.into(),
+                query: statement.to_sql(&DbType::Mongo),
+                ddl_type,
+                db_type: DbType::Mongo,
+                statement,
+            })
+            .collect()
+    }
+
+    async fn get_server_version(&self) -> anyhow::Result<i32> {
+        let build_info = self
+            .mongo_client
+            .database("admin")
+            .run_command(doc! {"buildInfo": 1}, None)
+            .await?;
+        let major = build_info
+            .get_array("versionArray")
+            .ok()
+            .and_then(|v| v.first())
+            .and_then(|v| v.as_i32());
+        match major {
+            Some(major) => Ok(major),
+            None => bail! {Error::ExtractorError(format!(
+                "failed to get mongo server version from buildInfo: {}",
+                build_info
+            ))},
+        }
+    }
+
+    async fn push_ddl_to_buf(
+        &mut self,
+        ddl_data: DdlData,
+        position: Position,
+    ) -> anyhow::Result<()> {
+        let (db, tb) = ddl_data.get_schema_tb();
+        if SYSTEM_DBS.contains(&db.as_str())
+            || tb.starts_with("system.")
+            || self.filter.filter_ddl(&db, &tb, &ddl_data.ddl_type)
+        {
+            self.base_extractor
+                .push_dt_data(DtData::Heartbeat {}, position)
+                .await
+        } else {
+            self.base_extractor.push_ddl(ddl_data, position).await
         }
     }
 
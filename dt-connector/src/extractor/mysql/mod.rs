@@ -1,5 +1,8 @@
 pub mod binlog_util;
 pub mod mysql_cdc_extractor;
 pub mod mysql_check_extractor;
+pub mod mysql_multi_cdc_extractor;
 pub mod mysql_snapshot_extractor;
 pub mod mysql_struct_extractor;
+pub mod osc_table;
+pub mod xa_buffer;
@@ -16,7 +16,13 @@ use tokio::{sync::Mutex, time::Instant};
 use crate::{
     close_conn_pool,
     extractor::{
-        base_extractor::BaseExtractor, mysql::binlog_util::BinlogUtil,
+        base_extractor::BaseExtractor,
+        mysql::{
+            binlog_util::BinlogUtil,
+            osc_table::OscTable,
+            xa_buffer::{XaBuffer, XaStatement},
+        },
+        reconnector::Reconnector,
         resumer::cdc_resumer::CdcResumer,
     },
     Extractor,
@@ -24,7 +30,7 @@ use crate::{
 use dt_common::{
     config::config_enums::DbType,
     error::Error,
-    log_debug, log_error, log_info,
+    log_debug, log_error, log_info, log_warn,
     meta::{
         adaptor::mysql_col_value_convertor::MysqlColValueConvertor, col_value::ColValue,
         dt_data::DtData, mysql::mysql_meta_manager::MysqlMetaManager, position::Position,
@@ -57,8 +63,12 @@ pub struct MysqlCdcExtractor {
     pub binlog_timeout_secs: u64,
     pub heartbeat_interval_secs: u64,
     pub heartbeat_tb: String,
+    pub osc_enabled: bool,
     pub syncer: Arc<Mutex<Syncer>>,
     pub resumer: CdcResumer,
+    pub reconnector: Reconnector,
+    // kept across reconnects, prepared XA branches may be committed after the binlog stream breaks
+    pub xa_buffer: XaBuffer,
 }
 
 struct Context {
@@ -68,6 +78,8 @@ struct Context {
 }
 
 const QUERY_BEGIN: &str = "BEGIN";
+// JSON partial updates (binlog_row_value_options=PARTIAL_JSON) of mysql 8.0
+const PARTIAL_UPDATE_ROWS_EVENT: u8 = 39;
 
 #[async_trait]
 impl Extractor for MysqlCdcExtractor {
@@ -122,6 +134,25 @@ impl Extractor for MysqlCdcExtractor {
 
 impl MysqlCdcExtractor {
     async fn extract_internal(&mut self) -> anyhow::Result<()> {
+        // start heartbeat
+        self.start_heartbeat(self.base_extractor.shut_down.clone())?;
+
+        while let Some(error) = self.extract_stream().await? {
+            // binlog_filename, binlog_position and gtid_set have been moved to the end of
+            // the last pushed transaction, events of the uncompleted transaction will be pushed again
+            self.reconnector.wait(error).await?;
+            log_info!(
+                "reconnect binlog stream, binlog_filename: {}, binlog_position: {}, gtid_set: {}",
+                self.binlog_filename,
+                self.binlog_position,
+                self.gtid_set
+            );
+        }
+        Ok(())
+    }
+
+    /// return the error if the binlog stream breaks, which can be resumed by reconnecting
+    async fn extract_stream(&mut self) -> anyhow::Result<Option<anyhow::Error>> {
         let mut client = BinlogClient {
             url: self.url.clone(),
             binlog_filename: self.binlog_filename.clone(),
@@ -132,7 +163,10 @@ impl MysqlCdcExtractor {
             heartbeat_interval_secs: self.binlog_heartbeat_interval_secs,
             timeout_secs: self.binlog_timeout_secs,
         };
-        let mut stream = client.connect().await?;
+        let mut stream = match client.connect().await {
+            Ok(stream) => stream,
+            Err(error) => return Ok(Some(error.into())),
+        };
 
         let mut ctx = Context {
             binlog_filename: self.binlog_filename.clone(),
@@ -143,18 +177,24 @@ impl MysqlCdcExtractor {
             ctx.gtid_set = Some(GtidSet::new(&client.gtid_set)?);
         }
 
-        // start heartbeat
-        self.start_heartbeat(self.base_extractor.shut_down.clone())?;
-
         loop {
             if self.base_extractor.time_filter.ended {
                 stream.close().await?;
-                return Ok(());
+                return Ok(None);
             }
 
-            let (header, data) = stream.read().await?;
+            let (header, data) = match stream.read().await {
+                Ok(event) => event,
+                Err(error) => return Ok(Some(error.into())),
+            };
+            self.reconnector.reset();
             match data {
                 EventData::Rotate(r) => {
+                    // started from the latest binlog, resume from the first received one
+                    if self.binlog_filename.is_empty() {
+                        self.binlog_filename = r.binlog_filename.clone();
+                        self.binlog_position = r.binlog_position as u32;
+                    }
                     ctx.binlog_filename = r.binlog_filename;
                 }
 
@@ -163,6 +203,15 @@ impl MysqlCdcExtractor {
         }
     }
 
+    /// record the end of a transaction / ddl to resume from if the binlog stream breaks
+    fn record_resume_position(&mut self, ctx: &Context, next_event_position: u32) {
+        self.binlog_filename = ctx.binlog_filename.clone();
+        self.binlog_position = next_event_position;
+        if let Some(gtid_set) = &ctx.gtid_set {
+            self.gtid_set = gtid_set.to_string();
+        }
+    }
+
     #[async_recursion]
     async fn parse_events(
         &mut self,
@@ -183,13 +232,15 @@ impl MysqlCdcExtractor {
         if let Some(gtid_set) = &ctx.gtid_set {
             gtid_set_str = gtid_set.to_string();
         }
-        let position = Position::MysqlCdc {
+        let binlog_position = Position::MysqlCdc {
             server_id,
             binlog_filename: ctx.binlog_filename.clone(),
             next_event_position: header.next_event_position,
             gtid_set: gtid_set_str,
             timestamp,
         };
+        // a task restarted from any pushed position should replay the prepared XA branches
+        let position = self.xa_buffer.cap_position(binlog_position.clone());
 
         match data {
             EventData::Gtid(g) => {
@@ -221,13 +272,14 @@ impl MysqlCdcExtractor {
                     let col_values = self
                         .parse_row_data(table_map_event, &w.included_columns, event)
                         .await?;
-                    let row_data = RowData::new(
+                    let mut row_data = RowData::new(
                         table_map_event.database_name.clone(),
                         table_map_event.table_name.clone(),
                         RowType::Insert,
                         None,
                         Some(col_values),
                     );
+                    row_data.partial = Self::is_partial_image(&w.included_columns);
                     self.push_row_to_buf(row_data, position.clone()).await?;
                 }
             }
@@ -242,16 +294,28 @@ impl MysqlCdcExtractor {
                     let col_values_before = self
                         .parse_row_data(table_map_event, &u.included_columns_before, &mut event.0)
                         .await?;
-                    let col_values_after = self
+                    let mut col_values_after = self
                         .parse_row_data(table_map_event, &u.included_columns_after, &mut event.1)
                         .await?;
-                    let row_data = RowData::new(
+                    let partial = Self::is_partial_image(&u.included_columns_before)
+                        || Self::is_partial_image(&u.included_columns_after);
+                    if partial {
+                        // cols absent in after image are not changed, take them from before image,
+                        // so the key cols are always in after image
+                        for (col, value) in col_values_before.iter() {
+                            if !col_values_after.contains_key(col) {
+                                col_values_after.insert(col.clone(), value.clone());
+                            }
+                        }
+                    }
+                    let mut row_data = RowData::new(
                         table_map_event.database_name.clone(),
                         table_map_event.table_name.clone(),
                         RowType::Update,
                         Some(col_values_before),
                         Some(col_values_after),
                     );
+                    row_data.partial = partial;
                     self.push_row_to_buf(row_data, position.clone()).await?;
                 }
             }
@@ -266,18 +330,25 @@ impl MysqlCdcExtractor {
                     let col_values = self
                         .parse_row_data(table_map_event, &d.included_columns, event)
                         .await?;
-                    let row_data = RowData::new(
+                    let mut row_data = RowData::new(
                         table_map_event.database_name.clone(),
                         table_map_event.table_name.clone(),
                         RowType::Delete,
                         Some(col_values),
                         None,
                     );
+                    row_data.partial = Self::is_partial_image(&d.included_columns);
                     self.push_row_to_buf(row_data, position.clone()).await?;
                 }
             }
 
             EventData::Query(query) => {
+                if let Some(statement) = XaStatement::parse(&query.query) {
+                    self.handle_xa_statement(statement, &header, binlog_position, ctx)
+                        .await?;
+                    return Ok(());
+                }
+
                 if query.query == QUERY_BEGIN {
                     BaseExtractor::update_time_filter(
                         &mut self.base_extractor.time_filter,
@@ -286,7 +357,11 @@ impl MysqlCdcExtractor {
                     );
                 }
 
+                let is_begin = query.query == QUERY_BEGIN;
                 self.handle_query_event(query, position.clone()).await?;
+                if !is_begin {
+                    self.record_resume_position(ctx, header.next_event_position);
+                }
             }
 
             EventData::Xid(xid) => {
@@ -296,9 +371,28 @@ impl MysqlCdcExtractor {
                 self.base_extractor
                     .push_dt_data(commit, position.clone())
                     .await?;
+                self.record_resume_position(ctx, header.next_event_position);
+            }
+
+            EventData::XaPrepare(xa_prepare) => {
+                // XA COMMIT xid ONE PHASE is written as XA_PREPARE_LOG_EVENT with one_phase set
+                if let Some((xid, rows)) = self.xa_buffer.prepare(xa_prepare.one_phase) {
+                    let position = self.xa_buffer.cap_position(binlog_position);
+                    self.push_xa_branch(xid, rows, position).await?;
+                }
+                self.record_resume_position(ctx, header.next_event_position);
             }
 
-            _ => {}
+            _ => {
+                // the binlog client parses PARTIAL_UPDATE_ROWS_EVENT as NotSupported without the rows,
+                // so the json diffs can not be applied to the before images
+                if header.event_type == PARTIAL_UPDATE_ROWS_EVENT {
+                    bail! {Error::ExtractorError(format!(
+                        "PARTIAL_UPDATE_ROWS_EVENT is not supported, set binlog_row_value_options='' in source mysql, position: {}",
+                        position
+                    ))}
+                }
+            }
         }
 
         Ok(())
@@ -309,7 +403,89 @@ impl MysqlCdcExtractor {
         row_data: RowData,
         position: Position,
     ) -> anyhow::Result<()> {
-        self.base_extractor.push_row(row_data, position).await
+        // where_conditions of the origin table also apply to its online schema change tables
+        let filter_tb = self
+            .get_filter_tb(&row_data.schema, &row_data.tb)
+            .unwrap_or_else(|| row_data.tb.clone());
+        let schema = row_data.schema.clone();
+        if let Some(row_data) = self.filter.filter_row(&schema, &filter_tb, row_data)? {
+            if self.xa_buffer.is_active() {
+                // rows of branches started before start_time are not parsed
+                if self.base_extractor.time_filter.started {
+                    self.xa_buffer.push_row(row_data);
+                }
+            } else {
+                self.base_extractor.push_row(row_data, position).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn handle_xa_statement(
+        &mut self,
+        statement: XaStatement,
+        header: &EventHeader,
+        binlog_position: Position,
+        ctx: &Context,
+    ) -> anyhow::Result<()> {
+        match statement {
+            XaStatement::Start(xid) => {
+                BaseExtractor::update_time_filter(
+                    &mut self.base_extractor.time_filter,
+                    header.timestamp,
+                    &binlog_position,
+                );
+                // the end of the last transaction, where the branch can be replayed from
+                let start_position = Position::MysqlCdc {
+                    server_id: String::new(),
+                    binlog_filename: self.binlog_filename.clone(),
+                    next_event_position: self.binlog_position,
+                    gtid_set: self.gtid_set.clone(),
+                    timestamp: Position::format_timestamp_millis(header.timestamp as i64 * 1000),
+                };
+                self.xa_buffer.start(&xid, start_position);
+                // like BEGIN, the resume position is not moved until the branch is prepared
+                return Ok(());
+            }
+
+            XaStatement::End(_) => return Ok(()),
+
+            XaStatement::Commit(xid) => match self.xa_buffer.commit(&xid) {
+                Some(rows) => {
+                    let position = self.xa_buffer.cap_position(binlog_position);
+                    self.push_xa_branch(xid, rows, position).await?;
+                }
+                None => log_warn!(
+                    "XA COMMIT of unknown branch: {}, which may be prepared before the task started",
+                    xid
+                ),
+            },
+
+            XaStatement::Rollback(xid) => {
+                if !self.xa_buffer.rollback(&xid) {
+                    log_warn!("XA ROLLBACK of unknown branch: {}", xid);
+                }
+            }
+        }
+
+        self.record_resume_position(ctx, header.next_event_position);
+        Ok(())
+    }
+
+    async fn push_xa_branch(
+        &mut self,
+        xid: String,
+        rows: Vec<RowData>,
+        position: Position,
+    ) -> anyhow::Result<()> {
+        for row_data in rows {
+            self.base_extractor
+                .push_row(row_data, position.clone())
+                .await?;
+        }
+        self.base_extractor
+            .push_dt_data(DtData::Commit { xid }, position)
+            .await
     }
 
     async fn parse_row_data(
@@ -325,7 +501,8 @@ impl MysqlCdcExtractor {
         let db = &table_map_event.database_name;
         let tb = &table_map_event.table_name;
         let tb_meta = self.meta_manager.get_tb_meta(db, tb).await?;
-        let ignore_cols = self.filter.get_ignore_cols(db, tb);
+        let filter_tb = self.get_filter_tb(db, tb).unwrap_or_default();
+        let ignore_cols = self.filter.get_ignore_cols(db, &filter_tb);
 
         if included_columns.len() != event.column_values.len() {
             bail! {Error::ExtractorError(
@@ -341,8 +518,8 @@ impl MysqlCdcExtractor {
                 continue;
             }
 
+            // not in the row image (binlog_row_image=MINIMAL / NOBLOB), which differs from NULL
             if let Some(false) = included_columns.get(i) {
-                data.insert(col.clone(), ColValue::None);
                 continue;
             }
 
@@ -354,6 +531,11 @@ impl MysqlCdcExtractor {
         Ok(data)
     }
 
+    /// with binlog_row_image=MINIMAL / NOBLOB, some cols may be absent in the row image
+    fn is_partial_image(included_columns: &[bool]) -> bool {
+        included_columns.iter().any(|i| !i)
+    }
+
     async fn handle_query_event(
         &mut self,
         query: QueryEvent,
@@ -394,7 +576,17 @@ impl MysqlCdcExtractor {
                     let (db, tb) = sub_ddl_data.get_schema_tb();
                     // invalidate metadata cache
                     self.meta_manager.invalidate_cache(&db, &tb);
-                    if !self.filter.filter_ddl(&db, &tb, &sub_ddl_data.ddl_type) {
+                    // route the renamed-to table of cut-over, eg: rename tb_1 to _tb_1_del
+                    let (to_db, to_tb) = sub_ddl_data.get_rename_to_schema_tb();
+                    self.register_osc_route(&to_db, &to_tb);
+                    let filtered = match self.get_filter_tb(&db, &tb) {
+                        Some(filter_tb) => {
+                            self.filter
+                                .filter_ddl(&db, &filter_tb, &sub_ddl_data.ddl_type)
+                        }
+                        None => true,
+                    };
+                    if !filtered {
                         self.base_extractor
                             .push_ddl(sub_ddl_data.clone(), position.clone())
                             .await?;
@@ -415,13 +607,66 @@ impl MysqlCdcExtractor {
     fn filter_event(&mut self, table_map_event: &TableMapEvent, row_type: RowType) -> bool {
         let db = &table_map_event.database_name;
         let tb = &table_map_event.table_name;
-        let filtered = self.filter.filter_event(db, tb, &row_type);
+        let filtered = match self.get_filter_tb(db, tb) {
+            Some(filter_tb) => self.filter.filter_event(db, &filter_tb, &row_type),
+            None => true,
+        };
         if filtered {
             return !self.base_extractor.is_data_marker_info(db, tb);
         }
         filtered
     }
 
+    /// shadow / old tables of gh-ost and pt-osc are filtered and routed as their original tables,
+    /// so the cut-over rename gives the original table with the new structure on target.
+    /// None if the table is internally used by the tool and should always be filtered.
+    fn get_filter_tb(&mut self, db: &str, tb: &str) -> Option<String> {
+        if !self.osc_enabled {
+            return Some(tb.into());
+        }
+        let osc_table = match OscTable::parse(tb) {
+            Some(osc_table) => osc_table,
+            None => return Some(tb.into()),
+        };
+        if osc_table.is_internal() {
+            return None;
+        }
+        self.register_osc_route(db, tb);
+        Some(osc_table.origin_tb)
+    }
+
+    /// add the route of a shadow / old table of gh-ost and pt-osc to the router when it is first met,
+    /// routed to the shadow / old table of the routed original table. No-op for other tables.
+    fn register_osc_route(&mut self, db: &str, tb: &str) {
+        if !self.osc_enabled {
+            return;
+        }
+        let osc_table = match OscTable::parse(tb) {
+            Some(osc_table) if !osc_table.is_internal() => osc_table,
+            _ => return,
+        };
+
+        let router = &mut self.base_extractor.router;
+        let key = (db.to_string(), tb.to_string());
+        if router.tb_map.contains_key(&key) {
+            return;
+        }
+        let (dst_db, dst_tb) = router.get_tb_map(db, &osc_table.origin_tb);
+        let dst = (dst_db.to_string(), osc_table.to_osc_tb(dst_tb));
+        if let Some(col_map) = router.get_col_map(db, &osc_table.origin_tb).cloned() {
+            router.col_map.insert(key.clone(), col_map);
+        }
+        log_info!(
+            "online schema change table found: {}.{}, origin table: {}, routed to: {}.{}",
+            db,
+            tb,
+            osc_table.origin_tb,
+            dst.0,
+            dst.1
+        );
+        router.tb_map.insert(key, dst);
+    }
+
     fn start_heartbeat(&mut self, shut_down: Arc<AtomicBool>) -> anyhow::Result<()> {
         let db_tb = self.base_extractor.precheck_heartbeat(
             self.heartbeat_interval_secs,
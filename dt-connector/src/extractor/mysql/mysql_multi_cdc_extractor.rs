@@ -0,0 +1,401 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use anyhow::bail;
+use async_trait::async_trait;
+use serde::Deserialize;
+use tokio::{sync::Mutex, task::JoinHandle, time::Instant};
+
+use crate::extractor::base_extractor::BaseExtractor;
+use crate::extractor::extractor_monitor::ExtractorMonitor;
+use crate::extractor::mysql::mysql_cdc_extractor::MysqlCdcExtractor;
+use crate::extractor::resumer::cdc_resumer::CdcResumer;
+use crate::rdb_router::RdbRouter;
+use crate::Extractor;
+use dt_common::error::Error;
+use dt_common::meta::col_value::ColValue;
+use dt_common::meta::dt_data::DtData;
+use dt_common::meta::dt_queue::DtQueue;
+use dt_common::meta::position::Position;
+use dt_common::meta::syncer::Syncer;
+use dt_common::monitor::monitor::Monitor;
+use dt_common::time_filter::TimeFilter;
+use dt_common::utils::time_util::TimeUtil;
+use dt_common::{log_info, log_warn};
+
+const SOURCE_BUFFER_SIZE: usize = 1000;
+const SYNC_POSITION_INTERVAL_SECS: u64 = 1;
+const JSON_PREFIX: &str = "json:";
+
+/// a source of MysqlMultiCdcExtractor, parsed from [extractor] sources
+#[derive(Debug, Clone, Deserialize)]
+pub struct MysqlCdcSource {
+    pub name: String,
+    pub url: String,
+    // 0: use [extractor] server_id + index of the source
+    #[serde(default)]
+    pub server_id: u64,
+    #[serde(default)]
+    pub binlog_filename: String,
+    #[serde(default)]
+    pub binlog_position: u32,
+    #[serde(default)]
+    pub gtid_set: String,
+}
+
+impl MysqlCdcSource {
+    pub fn parse(config_str: &str) -> anyhow::Result<Vec<Self>> {
+        let sources: Vec<Self> =
+            match serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX)) {
+                Ok(sources) => sources,
+                Err(err) => bail! {Error::ConfigError(format!(
+                    "invalid [extractor] sources: {}",
+                    err
+                ))},
+            };
+
+        for (i, source) in sources.iter().enumerate() {
+            if source.name.is_empty() || source.url.is_empty() {
+                bail! {Error::ConfigError(format!(
+                    "[extractor] sources[{}] requires both name and url",
+                    i
+                ))}
+            }
+            if sources[..i].iter().any(|j| j.name == source.name) {
+                bail! {Error::ConfigError(format!(
+                    "[extractor] sources has duplicate name: {}",
+                    source.name
+                ))}
+            }
+        }
+        Ok(sources)
+    }
+}
+
+/// extract binlogs from multiple mysql sources (eg: shards of a sharded database) into one pipeline,
+/// runs a MysqlCdcExtractor for each source, the position is Position::MysqlMultiCdc which holds
+/// a Position::MysqlCdc for each source, so each source resumes from its own binlog position.
+pub struct MysqlMultiCdcExtractor {
+    pub base_extractor: BaseExtractor,
+    // (name, extractor) of each source
+    pub sources: Vec<(String, MysqlCdcExtractor)>,
+    // if set, the source name will be injected into rows as this column
+    pub source_col: String,
+    pub syncer: Arc<Mutex<Syncer>>,
+    pub resumer: CdcResumer,
+}
+
+struct SourceStream {
+    name: String,
+    buffer: Arc<DtQueue>,
+    syncer: Arc<Mutex<Syncer>>,
+    shut_down: Arc<AtomicBool>,
+    handle: Option<JoinHandle<anyhow::Result<()>>>,
+    finished: bool,
+    received_position: Position,
+}
+
+#[async_trait]
+impl Extractor for MysqlMultiCdcExtractor {
+    async fn extract(&mut self) -> anyhow::Result<()> {
+        log_info!(
+            "MysqlMultiCdcExtractor starts, sources: {:?}, source_col: {}, resumer: {}",
+            self.sources.iter().map(|i| &i.0).collect::<Vec<_>>(),
+            self.source_col,
+            self.resumer
+        );
+
+        let resumed_positions =
+            if let Position::MysqlMultiCdc { sources } = &self.resumer.checkpoint_position {
+                sources.clone()
+            } else {
+                Arc::default()
+            };
+
+        // positions of the last transaction / ddl of each source
+        let mut positions = BTreeMap::new();
+        let mut streams = Vec::new();
+        for (name, mut extractor) in self.sources.drain(..) {
+            let position = resumed_positions.get(&name).cloned().unwrap_or_default();
+            // the source extractor pushes a heartbeat with the resumed position after started
+            extractor.resumer.checkpoint_position = position.clone();
+
+            let mut stream = SourceStream {
+                name: name.clone(),
+                buffer: extractor.base_extractor.buffer.clone(),
+                syncer: extractor.syncer.clone(),
+                shut_down: extractor.base_extractor.shut_down.clone(),
+                handle: None,
+                finished: false,
+                received_position: position.clone(),
+            };
+
+            log_info!("start source stream: {}, position: {}", name, position);
+            stream.handle = Some(tokio::spawn(async move {
+                let result = extractor.extract().await;
+                let close_result = extractor.close().await;
+                result.and(close_result)
+            }));
+            positions.insert(name, position);
+            streams.push(stream);
+        }
+
+        let mut positions = Arc::new(positions);
+        if let Err(err) = self.extract_streams(&mut streams, &mut positions).await {
+            Self::stop_streams(&streams);
+            return Err(err);
+        }
+        self.base_extractor.wait_task_finish().await
+    }
+}
+
+impl MysqlMultiCdcExtractor {
+    /// base extractor of a source, data are pushed into its own buffer and drained by MysqlMultiCdcExtractor
+    pub async fn create_source_base_extractor(
+        name: &str,
+        router: RdbRouter,
+        time_filter: TimeFilter,
+    ) -> BaseExtractor {
+        // records are counted by the multi cdc extractor, the source monitor is useless
+        let monitor = Arc::new(Monitor::new("extractor", name, 1, 100, 1));
+        BaseExtractor {
+            buffer: Arc::new(DtQueue::new(SOURCE_BUFFER_SIZE, 0)),
+            router,
+            shut_down: Arc::new(AtomicBool::new(false)),
+            monitor: ExtractorMonitor::new(monitor).await,
+            data_marker: None,
+            time_filter,
+        }
+    }
+
+    async fn extract_streams(
+        &mut self,
+        streams: &mut [SourceStream],
+        positions: &mut Arc<BTreeMap<String, Position>>,
+    ) -> anyhow::Result<()> {
+        let mut sync_time = Instant::now();
+        let mut ddl_sources = HashMap::new();
+        let source_count = streams.len();
+
+        loop {
+            let mut received = false;
+            for stream in streams.iter_mut() {
+                if stream.finished {
+                    continue;
+                }
+
+                received |= self
+                    .drain_stream(stream, positions, &mut ddl_sources, source_count)
+                    .await?;
+
+                if !stream.handle.as_ref().is_some_and(|i| i.is_finished()) {
+                    continue;
+                }
+
+                let result = stream
+                    .handle
+                    .take()
+                    .unwrap()
+                    .await
+                    .unwrap_or_else(|err| Err(err.into()));
+                // data received before the stream finished are still valid
+                self.drain_stream(stream, positions, &mut ddl_sources, source_count)
+                    .await?;
+                stream.shut_down.store(true, Ordering::Release);
+                stream.finished = true;
+                match result {
+                    Ok(()) => log_info!("source stream: {} finished", stream.name),
+                    Err(err) => {
+                        // binlog stream breaks have been retried by the reconnector of the source extractor
+                        bail! {Error::ExtractorError(format!(
+                            "source stream: {} failed, position: {}, error: {:?}",
+                            stream.name,
+                            positions.get(&stream.name).unwrap_or(&Position::None),
+                            err
+                        ))}
+                    }
+                }
+            }
+
+            if streams.iter().all(|i| i.finished) {
+                return Ok(());
+            }
+
+            if sync_time.elapsed().as_secs() >= SYNC_POSITION_INTERVAL_SECS {
+                self.sync_positions(streams).await;
+                sync_time = Instant::now();
+            }
+
+            if !received {
+                TimeUtil::sleep_millis(1).await;
+            }
+        }
+    }
+
+    /// positions are shared by the pushed items, and copied only when a source's position changes
+    async fn drain_stream(
+        &mut self,
+        stream: &mut SourceStream,
+        positions: &mut Arc<BTreeMap<String, Position>>,
+        ddl_sources: &mut HashMap<String, HashSet<String>>,
+        source_count: usize,
+    ) -> anyhow::Result<bool> {
+        let count = stream.buffer.len();
+        for _ in 0..count {
+            let mut item = stream.buffer.pop()?;
+            if let Position::MysqlCdc { .. } = item.position {
+                stream.received_position = item.position.clone();
+                // only positions of transaction ends / ddls / heartbeats can be resumed from,
+                // otherwise a source may resume from the middle of a transaction
+                if matches!(
+                    item.dt_data,
+                    DtData::Commit { .. } | DtData::Heartbeat {} | DtData::Ddl { .. }
+                ) {
+                    Arc::make_mut(positions).insert(stream.name.clone(), item.position);
+                }
+            }
+
+            if let DtData::Ddl { ddl_data } = &item.dt_data {
+                let sql = ddl_data.to_sql();
+                if !Self::check_ddl_first_seen(ddl_sources, source_count, &stream.name, &sql) {
+                    log_info!(
+                        "ddl from source: {} has been applied from another source, skipped: {}",
+                        stream.name,
+                        sql
+                    );
+                    // the position still needs to be committed
+                    item.dt_data = DtData::Heartbeat {};
+                }
+            }
+
+            if let DtData::Dml { row_data } = &mut item.dt_data {
+                if !self.source_col.is_empty() {
+                    for col_values in [&mut row_data.before, &mut row_data.after]
+                        .into_iter()
+                        .flatten()
+                    {
+                        col_values.insert(
+                            self.source_col.clone(),
+                            ColValue::String(stream.name.clone()),
+                        );
+                    }
+                    row_data.refresh_data_size();
+                }
+            }
+
+            let position = Position::MysqlMultiCdc {
+                sources: positions.clone(),
+            };
+            self.base_extractor
+                .push_dt_data(item.dt_data, position)
+                .await?;
+        }
+        Ok(count > 0)
+    }
+
+    /// shards have the same structure, so the same ddl comes from every source and is
+    /// applied only once: when it first comes from any source, the following ones from the other
+    /// sources are skipped. It is applied again if it comes from a source which has sent it before
+    fn check_ddl_first_seen(
+        ddl_sources: &mut HashMap<String, HashSet<String>>,
+        source_count: usize,
+        source: &str,
+        sql: &str,
+    ) -> bool {
+        let sources = ddl_sources.entry(sql.to_string()).or_default();
+        let first_seen = sources.is_empty() || sources.contains(source);
+        if first_seen {
+            sources.clear();
+        }
+        sources.insert(source.to_string());
+        if sources.len() >= source_count {
+            ddl_sources.remove(sql);
+        }
+        first_seen
+    }
+
+    async fn sync_positions(&self, streams: &[SourceStream]) {
+        // heartbeat of each source extractor reads its own received / committed positions
+        let committed_position = self.syncer.lock().await.committed_position.clone();
+        let committed_positions = match committed_position {
+            Position::MysqlMultiCdc { sources } => sources,
+            _ => Arc::default(),
+        };
+
+        for stream in streams.iter().filter(|i| !i.finished) {
+            let mut syncer = stream.syncer.lock().await;
+            syncer.received_position = stream.received_position.clone();
+            if let Some(position @ Position::MysqlCdc { .. }) =
+                committed_positions.get(&stream.name)
+            {
+                syncer.committed_position = position.clone();
+            }
+        }
+    }
+
+    fn stop_streams(streams: &[SourceStream]) {
+        for stream in streams.iter() {
+            stream.shut_down.store(true, Ordering::Release);
+            if let Some(handle) = stream.handle.as_ref() {
+                log_warn!("stop source stream: {}", stream.name);
+                handle.abort();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sources() {
+        let sources = MysqlCdcSource::parse(
+            r#"json:[{"name":"shard_01","url":"mysql://127.0.0.1:3307"},{"name":"shard_02","url":"mysql://127.0.0.1:3308","server_id":2010,"binlog_filename":"mysql-bin.000003","binlog_position":1256,"gtid_set":"a:1-10"}]"#,
+        )
+        .unwrap();
+        assert_eq!(sources.len(), 2);
+        assert_eq!(sources[0].name, "shard_01");
+        assert_eq!(sources[0].server_id, 0);
+        assert!(sources[0].binlog_filename.is_empty());
+        assert_eq!(sources[1].server_id, 2010);
+        assert_eq!(sources[1].binlog_filename, "mysql-bin.000003");
+        assert_eq!(sources[1].binlog_position, 1256);
+        assert_eq!(sources[1].gtid_set, "a:1-10");
+
+        // without json: prefix
+        let sources = MysqlCdcSource::parse(r#"[{"name":"a","url":"mysql://127.0.0.1"}]"#).unwrap();
+        assert_eq!(sources.len(), 1);
+
+        for config_str in [
+            "json:abc",
+            r#"json:[{"name":"a"}]"#,
+            r#"json:[{"name":"","url":"mysql://127.0.0.1"}]"#,
+            r#"json:[{"name":"a","url":"mysql://127.0.0.1"},{"name":"a","url":"mysql://127.0.0.2"}]"#,
+        ] {
+            assert!(MysqlCdcSource::parse(config_str).is_err(), "{}", config_str);
+        }
+    }
+
+    #[test]
+    fn test_check_ddl_first_seen() {
+        let mut ddl_sources = HashMap::new();
+        let sql = "ALTER TABLE `db_1`.`tb_1` ADD COLUMN c_1 INT";
+        let mut check = |source: &str, sql: &str| {
+            MysqlMultiCdcExtractor::check_ddl_first_seen(&mut ddl_sources, 3, source, sql)
+        };
+
+        // applied once for all sources
+        assert!(check("s_1", sql));
+        assert!(check("s_2", "DROP TABLE `db_1`.`tb_2`"));
+        assert!(!check("s_2", sql));
+        assert!(!check("s_3", sql));
+        // a new round
+        assert!(check("s_3", sql));
+        // comes again from a source which has sent it
+        assert!(!check("s_1", sql));
+        assert!(check("s_1", sql));
+        assert_eq!(ddl_sources.len(), 2);
+    }
+}
@@ -0,0 +1,98 @@
+const GH_OST_SUFFIXES: [&str; 3] = ["gho", "ghc", "del"];
+const GH_OST_CHANGELOG_SUFFIX: &str = "ghc";
+const GH_OST_DEL_SUFFIX: &str = "del";
+const PT_OSC_SUFFIXES: [&str; 2] = ["new", "old"];
+
+/// tables created by online schema change tools:
+/// gh-ost: _tb_gho (ghost), _tb_ghc (changelog), _tb_del / _tb_[timestamp]_del (old table after cut-over)
+/// pt-online-schema-change: _tb_new (new), _tb_old (old table after swap),
+/// underscores are prepended to the names if already used
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OscTable {
+    pub origin_tb: String,
+    pub prefix: String,
+    pub suffix: String,
+}
+
+impl OscTable {
+    pub fn parse(tb: &str) -> Option<Self> {
+        let origin_start = tb.find(|c| c != '_')?;
+        if origin_start == 0 {
+            return None;
+        }
+
+        let prefix = &tb[..origin_start];
+        let (mut origin_tb, suffix) = tb[origin_start..].rsplit_once('_')?;
+        if GH_OST_SUFFIXES.contains(&suffix) {
+            if prefix.len() != 1 {
+                return None;
+            }
+            // gh-ost --timestamp-old-table: _tb_20240101123456_del
+            if suffix == GH_OST_DEL_SUFFIX {
+                if let Some((tb, timestamp)) = origin_tb.rsplit_once('_') {
+                    if timestamp.len() == 14 && timestamp.chars().all(|c| c.is_ascii_digit()) {
+                        origin_tb = tb;
+                    }
+                }
+            }
+        } else if !PT_OSC_SUFFIXES.contains(&suffix) {
+            return None;
+        }
+
+        if origin_tb.is_empty() {
+            return None;
+        }
+
+        Some(Self {
+            origin_tb: origin_tb.into(),
+            prefix: prefix.into(),
+            suffix: tb[origin_start + origin_tb.len()..].into(),
+        })
+    }
+
+    /// changelog table of gh-ost, only used by gh-ost itself
+    pub fn is_internal(&self) -> bool {
+        self.suffix == format!("_{}", GH_OST_CHANGELOG_SUFFIX)
+    }
+
+    /// the name of the same kind of table for another original table
+    pub fn to_osc_tb(&self, origin_tb: &str) -> String {
+        format!("{}{}{}", self.prefix, origin_tb, self.suffix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let check = |tb: &str, origin_tb: &str, is_internal: bool| {
+            let osc_table = OscTable::parse(tb).unwrap();
+            assert_eq!(osc_table.origin_tb, origin_tb);
+            assert_eq!(osc_table.is_internal(), is_internal);
+            assert_eq!(osc_table.to_osc_tb(origin_tb), tb);
+        };
+
+        check("_tb_1_gho", "tb_1", false);
+        check("_tb_1_ghc", "tb_1", true);
+        check("_tb_1_del", "tb_1", false);
+        check("_tb_1_20240101123456_del", "tb_1", false);
+        check("_tb_1_new", "tb_1", false);
+        check("__tb_1_old", "tb_1", false);
+
+        assert_eq!(
+            OscTable::parse("_tb_1_20240101123456_del")
+                .unwrap()
+                .to_osc_tb("dst_tb_1"),
+            "_dst_tb_1_20240101123456_del"
+        );
+
+        assert!(OscTable::parse("tb_1_gho").is_none());
+        assert!(OscTable::parse("__tb_1_gho").is_none());
+        assert!(OscTable::parse("_tb_1_bak").is_none());
+        assert!(OscTable::parse("_new").is_none());
+        assert!(OscTable::parse("___").is_none());
+        assert!(OscTable::parse("").is_none());
+    }
+}
@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+
+use dt_common::meta::{position::Position, row_data::RowData};
+
+/// statements of an XA transaction in binlog:
+/// XA START xid -> rows -> XA END xid -> XA_PREPARE_LOG_EVENT -> ... -> XA COMMIT / XA ROLLBACK xid,
+/// the xid is formatted by mysql as X'gtrid',X'bqual',format_id, the same in all statements of a branch
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum XaStatement {
+    Start(String),
+    End(String),
+    Commit(String),
+    Rollback(String),
+}
+
+impl XaStatement {
+    pub fn parse(query: &str) -> Option<Self> {
+        let (xa, rest) = query.trim().split_once(char::is_whitespace)?;
+        if !xa.eq_ignore_ascii_case("XA") {
+            return None;
+        }
+
+        let (keyword, xid) = rest.trim_start().split_once(char::is_whitespace)?;
+        let xid = xid.trim().to_string();
+        match keyword.to_uppercase().as_str() {
+            "START" | "BEGIN" => Some(Self::Start(xid)),
+            "END" => Some(Self::End(xid)),
+            "COMMIT" => Some(Self::Commit(xid)),
+            "ROLLBACK" => Some(Self::Rollback(xid)),
+            _ => None,
+        }
+    }
+}
+
+struct XaBranch {
+    rows: Vec<RowData>,
+    // the position to replay the branch from
+    start_position: Position,
+}
+
+/// other transactions may be written into binlog between XA PREPARE and XA COMMIT of a branch,
+/// so rows of a branch are buffered until it is committed, and dropped if it is rolled back
+#[derive(Default)]
+pub struct XaBuffer {
+    branches: HashMap<String, XaBranch>,
+    // the branch between XA START and XA PREPARE, rows of it are buffered
+    current: Option<String>,
+}
+
+impl XaBuffer {
+    pub fn start(&mut self, xid: &str, start_position: Position) {
+        self.branches.insert(
+            xid.into(),
+            XaBranch {
+                rows: Vec::new(),
+                start_position,
+            },
+        );
+        self.current = Some(xid.into());
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.current.is_some()
+    }
+
+    pub fn push_row(&mut self, row_data: RowData) {
+        if let Some(branch) = self
+            .current
+            .as_ref()
+            .and_then(|xid| self.branches.get_mut(xid))
+        {
+            branch.rows.push(row_data);
+        }
+    }
+
+    /// return (xid, rows) of the branch if it is committed in one phase (XA COMMIT xid ONE PHASE)
+    pub fn prepare(&mut self, one_phase: bool) -> Option<(String, Vec<RowData>)> {
+        let xid = self.current.take()?;
+        if !one_phase {
+            return None;
+        }
+        let branch = self.branches.remove(&xid)?;
+        Some((xid, branch.rows))
+    }
+
+    /// None if the branch is unknown, eg: prepared before the task started
+    pub fn commit(&mut self, xid: &str) -> Option<Vec<RowData>> {
+        self.end_branch(xid).map(|branch| branch.rows)
+    }
+
+    pub fn rollback(&mut self, xid: &str) -> bool {
+        self.end_branch(xid).is_some()
+    }
+
+    /// a task restarted from the returned position will replay all branches not committed yet
+    pub fn cap_position(&self, position: Position) -> Position {
+        let Some(mut capped_key) = Self::binlog_key(&position) else {
+            return position;
+        };
+
+        let mut capped = &position;
+        for branch in self.branches.values() {
+            if let Some(key) = Self::binlog_key(&branch.start_position) {
+                if key < capped_key {
+                    capped = &branch.start_position;
+                    capped_key = key;
+                }
+            }
+        }
+        capped.clone()
+    }
+
+    fn binlog_key(position: &Position) -> Option<(&str, u32)> {
+        if let Position::MysqlCdc {
+            binlog_filename,
+            next_event_position,
+            ..
+        } = position
+        {
+            return Some((binlog_filename, *next_event_position));
+        }
+        None
+    }
+
+    fn end_branch(&mut self, xid: &str) -> Option<XaBranch> {
+        if self.current.as_deref() == Some(xid) {
+            self.current = None;
+        }
+        self.branches.remove(xid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dt_common::meta::row_type::RowType;
+
+    const XID_1: &str = "X'7831',X'',1";
+    const XID_2: &str = "X'7832',X'',1";
+
+    fn position(binlog_filename: &str, next_event_position: u32) -> Position {
+        Position::MysqlCdc {
+            server_id: String::new(),
+            binlog_filename: binlog_filename.into(),
+            next_event_position,
+            gtid_set: String::new(),
+            timestamp: String::new(),
+        }
+    }
+
+    fn row(tb: &str) -> RowData {
+        RowData::new(
+            "test_db_1".into(),
+            tb.into(),
+            RowType::Insert,
+            None,
+            Some(HashMap::new()),
+        )
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            XaStatement::parse("XA START X'7831',X'',1"),
+            Some(XaStatement::Start(XID_1.into()))
+        );
+        assert_eq!(
+            XaStatement::parse("xa begin X'7831',X'',1"),
+            Some(XaStatement::Start(XID_1.into()))
+        );
+        assert_eq!(
+            XaStatement::parse("XA END X'7831',X'',1"),
+            Some(XaStatement::End(XID_1.into()))
+        );
+        assert_eq!(
+            XaStatement::parse("XA COMMIT X'7831',X'',1"),
+            Some(XaStatement::Commit(XID_1.into()))
+        );
+        assert_eq!(
+            XaStatement::parse("XA ROLLBACK X'7831',X'',1"),
+            Some(XaStatement::Rollback(XID_1.into()))
+        );
+        assert_eq!(XaStatement::parse("BEGIN"), None);
+        assert_eq!(XaStatement::parse("XA RECOVER"), None);
+        assert_eq!(XaStatement::parse("ALTER TABLE xa ADD COLUMN c INT"), None);
+    }
+
+    #[test]
+    fn test_two_phase_commit() {
+        let mut buffer = XaBuffer::default();
+        buffer.start(XID_1, position("mysql-bin.000001", 100));
+        assert!(buffer.is_active());
+        buffer.push_row(row("tb_1"));
+        buffer.push_row(row("tb_2"));
+        assert_eq!(buffer.prepare(false), None);
+        assert!(!buffer.is_active());
+
+        // positions of transactions after the prepared branch are capped to the branch start
+        assert_eq!(
+            buffer.cap_position(position("mysql-bin.000002", 4)),
+            position("mysql-bin.000001", 100)
+        );
+
+        let rows = buffer.commit(XID_1).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].tb, "tb_1");
+        assert_eq!(rows[1].tb, "tb_2");
+        assert_eq!(
+            buffer.cap_position(position("mysql-bin.000002", 4)),
+            position("mysql-bin.000002", 4)
+        );
+        assert_eq!(buffer.commit(XID_1), None);
+    }
+
+    #[test]
+    fn test_one_phase_commit() {
+        let mut buffer = XaBuffer::default();
+        buffer.start(XID_1, position("mysql-bin.000001", 100));
+        buffer.push_row(row("tb_1"));
+        let (xid, rows) = buffer.prepare(true).unwrap();
+        assert_eq!(xid, XID_1);
+        assert_eq!(rows.len(), 1);
+        assert!(!buffer.is_active());
+        assert_eq!(buffer.commit(XID_1), None);
+    }
+
+    #[test]
+    fn test_interleaved_branches() {
+        let mut buffer = XaBuffer::default();
+        buffer.start(XID_1, position("mysql-bin.000001", 100));
+        buffer.push_row(row("tb_1"));
+        buffer.prepare(false);
+
+        buffer.start(XID_2, position("mysql-bin.000001", 200));
+        buffer.push_row(row("tb_2"));
+        buffer.prepare(false);
+
+        assert!(buffer.rollback(XID_1));
+        assert!(!buffer.rollback(XID_1));
+        assert_eq!(
+            buffer.cap_position(position("mysql-bin.000001", 300)),
+            position("mysql-bin.000001", 200)
+        );
+
+        let rows = buffer.commit(XID_2).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].tb, "tb_2");
+    }
+}
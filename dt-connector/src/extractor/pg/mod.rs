@@ -1,5 +1,6 @@
 pub mod pg_cdc_client;
 pub mod pg_cdc_extractor;
 pub mod pg_check_extractor;
+pub mod pg_ddl_capture;
 pub mod pg_snapshot_extractor;
 pub mod pg_struct_extractor;
@@ -4,7 +4,7 @@ use dt_common::{log_info, log_warn};
 use postgres_types::PgLsn;
 use tokio_postgres::NoTls;
 use tokio_postgres::SimpleQueryMessage::Row;
-use tokio_postgres::{replication::LogicalReplicationStream, Client};
+use tokio_postgres::{replication::ReplicationStream, Client};
 use url::Url;
 
 pub struct PgCdcClient {
@@ -13,10 +13,14 @@ pub struct PgCdcClient {
     pub pub_name: String,
     pub start_lsn: String,
     pub recreate_slot_if_exists: bool,
+    // receive messages written by pg_logical_emit_message, supported since postgres 14
+    pub capture_messages: bool,
+    // create the publication with publish_via_partition_root, supported since postgres 13
+    pub partition_root: bool,
 }
 
 impl PgCdcClient {
-    pub async fn connect(&mut self) -> anyhow::Result<(LogicalReplicationStream, String)> {
+    pub async fn connect(&mut self) -> anyhow::Result<(ReplicationStream, String)> {
         let url_info = Url::parse(&self.url)?;
         let host = url_info.host_str().unwrap().to_string();
         let port = format!("{}", url_info.port().unwrap());
@@ -56,7 +60,13 @@ impl PgCdcClient {
         log_info!("publication: {} exists: {}", pub_name, pub_exists);
 
         if !pub_exists {
-            let query = format!("CREATE PUBLICATION {} FOR ALL TABLES", pub_name);
+            let mut query = format!("CREATE PUBLICATION {} FOR ALL TABLES", pub_name);
+            if self.partition_root {
+                // otherwise changes of partitions are mapped to partition roots by PgCdcExtractor
+                if self.get_server_version_num(client).await? >= 130000 {
+                    query = format!("{} WITH (publish_via_partition_root = true)", query);
+                }
+            }
             log_info!("execute: {}", query);
             client.simple_query(&query).await?;
         }
@@ -145,7 +155,7 @@ impl PgCdcClient {
     async fn start_replication(
         &mut self,
         client: &Client,
-    ) -> anyhow::Result<(LogicalReplicationStream, String)> {
+    ) -> anyhow::Result<(ReplicationStream, String)> {
         let (pub_name, start_lsn) = self.prepare_slot(client).await?;
 
         // set extra_float_digits to max so no precision will lose
@@ -153,18 +163,44 @@ impl PgCdcClient {
         client.simple_query("SET TIME ZONE 'UTC'").await?;
 
         // start replication slot
-        let options = format!(
-            r#"("proto_version" '{}', "publication_names" '{}')"#,
+        let mut options = format!(
+            r#""proto_version" '{}', "publication_names" '{}'"#,
             "1", pub_name
         );
+        if self.capture_messages {
+            let server_version_num = self.get_server_version_num(client).await?;
+            if server_version_num >= 140000 {
+                options = format!(r#"{}, "messages" '{}'"#, options, "true");
+            } else {
+                log_warn!(
+                    "logical decoding messages are not supported by pgoutput before postgres 14, server_version_num: {}",
+                    server_version_num
+                );
+            }
+        }
+        let options = format!("({})", options);
         let query = format!(
             "START_REPLICATION SLOT {} LOGICAL {} {}",
             self.slot_name, start_lsn, options
         );
         log_info!("execute: {}", query);
 
+        // decode pgoutput messages by PgCdcExtractor since some of them, e.g. 'M'(Message),
+        // are not supported by LogicalReplicationStream
         let copy_stream = client.copy_both_simple::<bytes::Bytes>(&query).await?;
-        let stream = LogicalReplicationStream::new(copy_stream);
+        let stream = ReplicationStream::new(copy_stream);
         Ok((stream, start_lsn))
     }
+
+    async fn get_server_version_num(&self, client: &Client) -> anyhow::Result<u32> {
+        let res = client.simple_query("SHOW server_version_num").await?;
+        if let Some(Row(row)) = res.first() {
+            if let Some(version) = row.get(0) {
+                return Ok(version.parse()?);
+            }
+        }
+        bail! {Error::ExtractorError(
+            "failed to get server_version_num".into()
+        )}
+    }
 }
@@ -12,23 +12,23 @@ use anyhow::bail;
 use async_trait::async_trait;
 use futures::StreamExt;
 use postgres_protocol::message::backend::{
-    DeleteBody, InsertBody,
+    DeleteBody, InsertBody, LogicalReplicationMessage,
     LogicalReplicationMessage::{
         Begin, Commit, Delete, Insert, Origin, Relation, Truncate, Type, Update,
     },
     RelationBody,
     ReplicationMessage::*,
-    TupleData, UpdateBody,
+    TruncateBody, TupleData, UpdateBody,
 };
 use postgres_types::PgLsn;
 use sqlx::{postgres::PgArguments, query::Query, Pool, Postgres};
 use tokio::{sync::Mutex, time::Duration, time::Instant};
-use tokio_postgres::replication::LogicalReplicationStream;
+use tokio_postgres::replication::ReplicationStream;
 
 use crate::{
     close_conn_pool,
     extractor::{
-        base_extractor::BaseExtractor, pg::pg_cdc_client::PgCdcClient,
+        base_extractor::BaseExtractor, pg::pg_cdc_client::PgCdcClient, reconnector::Reconnector,
         resumer::cdc_resumer::CdcResumer,
     },
     Extractor,
@@ -36,10 +36,16 @@ use crate::{
 use dt_common::{
     config::{config_enums::DbType, config_token_parser::ConfigTokenParser},
     error::Error,
-    log_error, log_info,
+    log_error, log_info, log_warn,
     meta::adaptor::pg_col_value_convertor::PgColValueConvertor,
     meta::col_value::ColValue,
+    meta::ddl_meta::{
+        ddl_data::DdlData,
+        ddl_statement::{DdlStatement, PgTruncateMultiTableStatement, PgTruncateTableStatement},
+        ddl_type::DdlType,
+    },
     meta::dt_data::DtData,
+    meta::message_data::MessageData,
     meta::pg::{pg_meta_manager::PgMetaManager, pg_tb_meta::PgTbMeta},
     meta::position::Position,
     meta::rdb_tb_meta::RdbTbMeta,
@@ -47,7 +53,7 @@ use dt_common::{
     meta::row_type::RowType,
     meta::syncer::Syncer,
     rdb_filter::RdbFilter,
-    utils::time_util::TimeUtil,
+    utils::{sql_util::SqlUtil, time_util::TimeUtil},
 };
 
 pub struct PgCdcExtractor {
@@ -64,11 +70,18 @@ pub struct PgCdcExtractor {
     pub heartbeat_interval_secs: u64,
     pub heartbeat_tb: String,
     pub ddl_meta_tb: String,
+    // replicate changes of partitions as changes of their partition roots
+    pub partition_root: bool,
     pub syncer: Arc<Mutex<Syncer>>,
     pub resumer: CdcResumer,
+    pub reconnector: Reconnector,
 }
 
 const SECS_FROM_1970_TO_2000: i64 = 946_684_800;
+// refer: https://www.postgresql.org/docs/current/protocol-logicalrep-message-formats.html
+const MESSAGE_TAG: u8 = b'M';
+const TRUNCATE_CASCADE: i8 = 1;
+const TRUNCATE_RESTART_IDENTITY: i8 = 2;
 
 #[async_trait]
 impl Extractor for PgCdcExtractor {
@@ -105,16 +118,6 @@ impl Extractor for PgCdcExtractor {
 
 impl PgCdcExtractor {
     async fn extract_internal(&mut self) -> anyhow::Result<()> {
-        let mut cdc_client = PgCdcClient {
-            url: self.url.clone(),
-            pub_name: self.pub_name.clone(),
-            slot_name: self.slot_name.clone(),
-            start_lsn: self.start_lsn.clone(),
-            recreate_slot_if_exists: self.recreate_slot_if_exists,
-        };
-        let (stream, actual_start_lsn) = cdc_client.connect().await?;
-        tokio::pin!(stream);
-
         // setup ddl capture
         let ddl_meta = ConfigTokenParser::parse_config(&self.ddl_meta_tb, &DbType::Pg, &['.'])?;
         if ddl_meta.len() == 2 {
@@ -124,6 +127,42 @@ impl PgCdcExtractor {
         // start heartbeat
         self.start_heartbeat(self.base_extractor.shut_down.clone())?;
 
+        while let Some(error) = self.extract_stream(&ddl_meta).await? {
+            // start_lsn has been moved to the end lsn of the last pushed transaction,
+            // the slot must be kept since it holds wal not confirmed yet
+            self.recreate_slot_if_exists = false;
+            self.reconnector.wait(error).await?;
+            log_info!(
+                "reconnect replication stream, slot_name: {}, start_lsn: {}",
+                self.slot_name,
+                self.start_lsn
+            );
+        }
+        Ok(())
+    }
+
+    /// return the error if the replication stream breaks, which can be resumed by reconnecting
+    async fn extract_stream(
+        &mut self,
+        ddl_meta: &[String],
+    ) -> anyhow::Result<Option<anyhow::Error>> {
+        let mut cdc_client = PgCdcClient {
+            url: self.url.clone(),
+            pub_name: self.pub_name.clone(),
+            slot_name: self.slot_name.clone(),
+            start_lsn: self.start_lsn.clone(),
+            recreate_slot_if_exists: self.recreate_slot_if_exists,
+            capture_messages: !self.filter.filter_all_message(),
+            partition_root: self.partition_root,
+        };
+        let (stream, actual_start_lsn) = match cdc_client.connect().await {
+            Ok(result) => result,
+            Err(error) => return Ok(Some(error)),
+        };
+        tokio::pin!(stream);
+        // the slot may be created just now, resume from where it starts if no commit received
+        self.start_lsn = actual_start_lsn.clone();
+
         let mut last_tx_end_lsn = actual_start_lsn.clone();
         let mut xid = String::new();
         let mut start_time = Instant::now();
@@ -142,7 +181,7 @@ impl PgCdcExtractor {
         loop {
             if self.base_extractor.time_filter.ended {
                 // cdc stream will be dropped automaticaly if postgres receives no keepalive ack
-                return Ok(());
+                return Ok(None);
             }
 
             if start_time.elapsed().as_secs() >= self.keepalive_interval_secs {
@@ -150,10 +189,28 @@ impl PgCdcExtractor {
                 start_time = Instant::now();
             }
 
-            match stream.next().await {
-                Some(Ok(XLogData(body))) => {
+            let message = match stream.next().await {
+                Some(Ok(message)) => message,
+                Some(Err(error)) => return Ok(Some(error.into())),
+                None => {
+                    return Ok(Some(anyhow::Error::from(Error::ExtractorError(
+                        "unexpected replication stream end".into(),
+                    ))))
+                }
+            };
+            self.reconnector.reset();
+
+            match message {
+                XLogData(body) => {
                     let data = body.into_data();
-                    match data {
+                    if data.first() == Some(&MESSAGE_TAG) {
+                        if self.base_extractor.time_filter.started {
+                            self.decode_message(&data, &position).await?;
+                        }
+                        continue;
+                    }
+
+                    match LogicalReplicationMessage::parse(&data)? {
                         Relation(relation) => {
                             self.decode_relation(&relation).await?;
                         }
@@ -178,17 +235,23 @@ impl PgCdcExtractor {
                             self.base_extractor
                                 .push_dt_data(commit, position.clone())
                                 .await?;
+                            // resume from here if the stream breaks
+                            self.start_lsn = last_tx_end_lsn.clone();
                         }
 
                         Origin(_origin) => {}
 
-                        Truncate(_truncate) => {}
+                        Truncate(truncate) => {
+                            if self.base_extractor.time_filter.started {
+                                self.decode_truncate(&truncate, &position).await?;
+                            }
+                        }
 
                         Type(_typee) => {}
 
                         Insert(insert) => {
                             if self.base_extractor.time_filter.started {
-                                self.decode_insert(&insert, &position, &ddl_meta).await?;
+                                self.decode_insert(&insert, &position, ddl_meta).await?;
                             }
                         }
 
@@ -208,7 +271,7 @@ impl PgCdcExtractor {
                     }
                 }
 
-                Some(Ok(PrimaryKeepAlive(data))) => {
+                PrimaryKeepAlive(data) => {
                     // Send a standby status update and require a keep alive response
                     if data.reply() == 1 {
                         self.keep_alive_ack(&mut stream, &actual_start_lsn).await?;
@@ -216,20 +279,16 @@ impl PgCdcExtractor {
                     }
                 }
 
-                Some(Ok(data)) => {
+                data => {
                     log_info!("received unknown replication data: {:?}", data);
                 }
-
-                Some(Err(error)) => panic!("unexpected replication stream error: {}", error),
-
-                None => panic!("unexpected replication stream end"),
             }
         }
     }
 
     async fn keep_alive_ack(
         &mut self,
-        stream: &mut Pin<&mut LogicalReplicationStream>,
+        stream: &mut Pin<&mut ReplicationStream>,
         start_lsn: &str,
     ) -> anyhow::Result<()> {
         let lsn: PgLsn =
@@ -262,8 +321,17 @@ impl PgCdcExtractor {
     }
 
     async fn decode_relation(&mut self, event: &RelationBody) -> anyhow::Result<()> {
-        let schema = event.namespace()?;
-        let tb = event.name()?;
+        let (mut schema, mut tb) = (event.namespace()?.to_string(), event.name()?.to_string());
+        // if the publication is not created with publish_via_partition_root,
+        // changes are decoded under partitions, map them to the partition root
+        if self.partition_root {
+            if let Some((root_schema, root_tb)) =
+                self.meta_manager.get_partition_root(&schema, &tb).await?
+            {
+                (schema, tb) = (root_schema, root_tb);
+            }
+        }
+        let (schema, tb) = (schema.as_str(), tb.as_str());
         // if the tb is filtered, we won't try to get the tb_meta since we may get privilege errors,
         // but we need to keep the oid —— tb_meta map which may be used for decoding events,
         // the built-in object used by datamarker, although it is not in filter config, still needs to get tb_meta.
@@ -399,6 +467,123 @@ impl PgCdcExtractor {
         self.push_row_to_buf(row_data, position.clone()).await
     }
 
+    async fn decode_truncate(
+        &mut self,
+        event: &TruncateBody,
+        position: &Position,
+    ) -> anyhow::Result<()> {
+        if self.filter.filter_all_ddl() {
+            return Ok(());
+        }
+
+        // relations truncated together are decoded in one message, e.g. TRUNCATE tb_1, tb_2,
+        // they are sinked as one statement with the same options as source, so that
+        // relations referenced by each other with foreign keys can be truncated without CASCADE
+        let mut schema_tbs = Vec::new();
+        for rel_id in event.rel_ids() {
+            let tb_meta = self.meta_manager.get_tb_meta_by_oid(*rel_id as i32)?;
+            let (schema, tb) = (tb_meta.basic.schema, tb_meta.basic.tb);
+            // the partition is mapped to its root, truncating the root would remove
+            // rows of other partitions
+            if tb_meta.oid != *rel_id as i32 {
+                log_warn!(
+                    "truncate on partition: {} is ignored, partition root: {}.{}",
+                    rel_id,
+                    schema,
+                    tb
+                );
+                continue;
+            }
+            if self
+                .filter
+                .filter_ddl(&schema, &tb, &DdlType::TruncateTable)
+            {
+                continue;
+            }
+            schema_tbs.push((schema, tb));
+        }
+        if schema_tbs.is_empty() {
+            return Ok(());
+        }
+
+        let mut options = Vec::new();
+        if event.options() & TRUNCATE_RESTART_IDENTITY != 0 {
+            options.push("RESTART IDENTITY");
+        }
+        if event.options() & TRUNCATE_CASCADE != 0 {
+            options.push("CASCADE");
+        }
+
+        let default_schema = schema_tbs[0].0.clone();
+        let statement = if schema_tbs.len() == 1 {
+            let (schema, tb) = schema_tbs.remove(0);
+            DdlStatement::PgTruncateTable(PgTruncateTableStatement {
+                schema,
+                tb,
+                is_only: false,
+                unparsed: options.join(" "),
+            })
+        } else {
+            DdlStatement::PgTruncateMultiTable(PgTruncateMultiTableStatement {
+                schema_tbs,
+                is_only: false,
+                unparsed: options.join(" "),
+            })
+        };
+        let ddl_data = DdlData {
+            default_schema,
+            query: statement.to_sql(&DbType::Pg),
+            ddl_type: DdlType::TruncateTable,
+            db_type: DbType::Pg,
+            statement,
+        };
+        self.base_extractor
+            .push_ddl(ddl_data, position.clone())
+            .await
+    }
+
+    async fn decode_message(&mut self, data: &[u8], position: &Position) -> anyhow::Result<()> {
+        let message_data = Self::parse_message(data)?;
+        if self.filter.filter_message(&message_data.prefix) {
+            return Ok(());
+        }
+        self.base_extractor
+            .push_message(message_data, position.clone())
+            .await
+    }
+
+    /// Byte1('M'), Int8 flags (1 if transactional), Int64 lsn, String prefix,
+    /// Int32 length of content, Byten content
+    fn parse_message(data: &[u8]) -> anyhow::Result<MessageData> {
+        let invalid = || Error::ExtractorError(format!("invalid logical message: {:?}", data));
+        if data.len() < 10 || data[0] != MESSAGE_TAG {
+            bail! {invalid()}
+        }
+
+        let transactional = data[1] & 1 != 0;
+        let lsn = u64::from_be_bytes(data[2..10].try_into()?);
+        let rest = &data[10..];
+        let Some(prefix_len) = rest.iter().position(|i| *i == 0) else {
+            bail! {invalid()}
+        };
+        let prefix = String::from_utf8_lossy(&rest[..prefix_len]).to_string();
+        let rest = &rest[prefix_len + 1..];
+        if rest.len() < 4 {
+            bail! {invalid()}
+        }
+        let content_len = u32::from_be_bytes(rest[..4].try_into()?) as usize;
+        if rest.len() < 4 + content_len {
+            bail! {invalid()}
+        }
+
+        Ok(MessageData {
+            prefix,
+            content: rest[4..4 + content_len].to_vec(),
+            transactional,
+            lsn: PgLsn::from(lsn).to_string(),
+        })
+    }
+
     async fn decode_ddl(&mut self, row_data: &RowData, position: &Position) -> anyhow::Result<()> {
         if self.filter.filter_all_ddl() {
             return Ok(());
@@ -433,13 +618,22 @@ impl PgCdcExtractor {
         let ddl_text = get_string(row_data, "ddl_text");
         let _tag = get_string(row_data, "tag");
         let schema = get_string(row_data, "schema");
+        // filled by the capture function installed by PgDdlCapture,
+        // object_name is the object identity, e.g. public."Tb_1"
+        let object_type = get_string(row_data, "object_type");
+        let object_name = get_string(row_data, "object_name");
 
         if let Ok(Some(ddl_data)) = self
             .base_extractor
             .parse_ddl(&DbType::Pg, &schema, &ddl_text)
             .await
         {
-            for ddl_data in ddl_data.split_to_multi() {
+            let mut multi_ddl_data = ddl_data.split_to_multi();
+            if multi_ddl_data.len() == 1 && object_type == "table" {
+                Self::route_by_object_identity(&mut multi_ddl_data[0], &object_name);
+            }
+
+            for ddl_data in multi_ddl_data {
                 // invalidate metadata cache
                 self.meta_manager.invalidate_cache_by_ddl_data(&ddl_data);
                 let (schema, tb) = ddl_data.get_schema_tb();
@@ -454,6 +648,32 @@ impl PgCdcExtractor {
         Ok(())
     }
 
+    /// the identity resolved by postgres is more accurate than the names parsed from ddl text,
+    /// e.g. an unqualified table in ALTER TABLE may be found in any schema of search_path
+    fn route_by_object_identity(ddl_data: &mut DdlData, object_name: &str) {
+        // renames are excluded since the identity is the new name
+        if !matches!(
+            ddl_data.statement,
+            DdlStatement::PgCreateTable(_) | DdlStatement::PgAlterTable(_)
+        ) {
+            return;
+        }
+
+        let escape_pairs = SqlUtil::get_escape_pairs(&DbType::Pg);
+        let tokens = ConfigTokenParser::parse(object_name, &['.'], &escape_pairs);
+        if tokens.len() != 2 {
+            return;
+        }
+        let schema = SqlUtil::unescape_by_db_type(&tokens[0], &DbType::Pg);
+        let tb = SqlUtil::unescape_by_db_type(&tokens[1], &DbType::Pg);
+        if schema.is_empty() || tb.is_empty() {
+            return;
+        }
+
+        ddl_data.statement.route(schema.clone(), tb);
+        ddl_data.default_schema = schema;
+    }
+
     fn parse_row_data(
         &mut self,
         tb_meta: &PgTbMeta,
@@ -497,7 +717,11 @@ impl PgCdcExtractor {
         row_data: RowData,
         position: Position,
     ) -> anyhow::Result<()> {
-        self.base_extractor.push_row(row_data, position).await
+        let (schema, tb) = (row_data.schema.clone(), row_data.tb.clone());
+        if let Some(row_data) = self.filter.filter_row(&schema, &tb, row_data)? {
+            self.base_extractor.push_row(row_data, position).await?;
+        }
+        Ok(())
     }
 
     fn filter_event(&mut self, tb_meta: &PgTbMeta, row_type: RowType) -> bool {
@@ -621,3 +845,63 @@ impl PgCdcExtractor {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dt_common::meta::ddl_meta::ddl_parser::DdlParser;
+
+    #[test]
+    fn test_parse_message() {
+        // SELECT pg_logical_emit_message(true, 'app.audit', 'hello');
+        let mut data = vec![MESSAGE_TAG, 1];
+        data.extend_from_slice(&0x3D583B0_u64.to_be_bytes());
+        data.extend_from_slice(b"app.audit\0");
+        data.extend_from_slice(&5_u32.to_be_bytes());
+        data.extend_from_slice(b"hello");
+
+        let message_data = PgCdcExtractor::parse_message(&data).unwrap();
+        assert_eq!(message_data.prefix, "app.audit");
+        assert_eq!(message_data.get_content_str(), "hello");
+        assert!(message_data.transactional);
+        assert_eq!(message_data.lsn, "0/3D583B0");
+
+        // truncated content
+        assert!(PgCdcExtractor::parse_message(&data[..data.len() - 1]).is_err());
+        // no prefix terminator
+        assert!(PgCdcExtractor::parse_message(&data[..15]).is_err());
+    }
+
+    #[test]
+    fn test_route_by_object_identity() {
+        let parser = DdlParser::new(DbType::Pg);
+
+        let mut ddl_data = parser.parse("CREATE TABLE Tb_1 (id int)").unwrap().unwrap();
+        PgCdcExtractor::route_by_object_identity(&mut ddl_data, "public.tb_1");
+        assert_eq!(
+            ddl_data.get_schema_tb(),
+            ("public".to_string(), "tb_1".to_string())
+        );
+
+        let mut ddl_data = parser
+            .parse(r#"ALTER TABLE "Sch".Tb_1 ADD COLUMN v int"#)
+            .unwrap()
+            .unwrap();
+        PgCdcExtractor::route_by_object_identity(&mut ddl_data, r#""Sch".tb_1"#);
+        assert_eq!(
+            ddl_data.get_schema_tb(),
+            ("Sch".to_string(), "tb_1".to_string())
+        );
+
+        // empty identity, e.g. recorded by a capture function created manually
+        let mut ddl_data = parser
+            .parse("CREATE TABLE sch.Tb_1 (id int)")
+            .unwrap()
+            .unwrap();
+        PgCdcExtractor::route_by_object_identity(&mut ddl_data, "");
+        assert_eq!(
+            ddl_data.get_schema_tb(),
+            ("sch".to_string(), "tb_1".to_string())
+        );
+    }
+}
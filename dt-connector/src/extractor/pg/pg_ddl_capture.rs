@@ -0,0 +1,272 @@
+use anyhow::bail;
+use sqlx::{Pool, Postgres, Row};
+
+use dt_common::{
+    config::{config_enums::DbType, config_token_parser::ConfigTokenParser},
+    error::Error,
+    log_error, log_info,
+    utils::sql_util::SqlUtil,
+};
+
+const CAPTURE_FUNCTION: &str = "ape_dts_capture_ddl";
+const EVENT_TRIGGER: &str = "ape_dts_intercept_ddl";
+const PROBE_TB: &str = "ape_dts_ddl_probe";
+
+/// installs / uninstalls the objects used by PgCdcExtractor to capture ddls:
+/// the ddl_meta_tb, the capture function and the event trigger on ddl_command_end,
+/// the function records each ddl into ddl_meta_tb together with the identity of the object,
+/// inserts into ddl_meta_tb are decoded from wal as ddls
+pub struct PgDdlCapture {
+    pub conn_pool: Pool<Postgres>,
+    pub ddl_meta_tb: String,
+    pub pub_name: String,
+}
+
+impl PgDdlCapture {
+    pub async fn setup(&self) -> anyhow::Result<()> {
+        let (schema, tb) = self.get_ddl_meta_schema_tb()?;
+        let meta_tb = format!(
+            "{}.{}",
+            SqlUtil::escape_by_db_type(&schema, &DbType::Pg),
+            SqlUtil::escape_by_db_type(&tb, &DbType::Pg)
+        );
+        let function = format!(
+            "{}.{}",
+            SqlUtil::escape_by_db_type(&schema, &DbType::Pg),
+            CAPTURE_FUNCTION
+        );
+
+        let sqls = vec![
+            format!("DROP EVENT TRIGGER IF EXISTS {}", EVENT_TRIGGER),
+            format!(
+                r#"CREATE TABLE IF NOT EXISTS {}
+(
+  ddl_text text COLLATE pg_catalog."default",
+  id bigserial primary key,
+  event text COLLATE pg_catalog."default",
+  tag text COLLATE pg_catalog."default",
+  username character varying COLLATE pg_catalog."default",
+  database character varying COLLATE pg_catalog."default",
+  schema character varying COLLATE pg_catalog."default",
+  object_type character varying COLLATE pg_catalog."default",
+  object_name character varying COLLATE pg_catalog."default",
+  client_address character varying COLLATE pg_catalog."default",
+  client_port integer,
+  event_time timestamp with time zone,
+  txid_current character varying(128) COLLATE pg_catalog."default",
+  message text COLLATE pg_catalog."default"
+)"#,
+                meta_tb
+            ),
+            Self::get_capture_function_sql(&function, &meta_tb, &self.pub_name),
+        ];
+        for sql in sqls.iter() {
+            log_info!("execute: {}", sql);
+            sqlx::query(sql).execute(&self.conn_pool).await?;
+        }
+
+        // once the trigger exists, every ddl in the database runs the function,
+        // remove them if the capture can not be completed
+        if let Err(err) = self.enable(&schema, &tb, &meta_tb, &function).await {
+            log_error!(
+                "ddl capture setup failed, dropping event trigger and function: {}",
+                err
+            );
+            if let Err(drop_err) = self.drop_trigger_and_function(&function).await {
+                log_error!("failed to drop event trigger and function: {}", drop_err);
+            }
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    pub async fn teardown(&self) -> anyhow::Result<()> {
+        let (schema, tb) = self.get_ddl_meta_schema_tb()?;
+        let schema = SqlUtil::escape_by_db_type(&schema, &DbType::Pg);
+        let tb = SqlUtil::escape_by_db_type(&tb, &DbType::Pg);
+        // the table is removed from publications when dropped
+        let sqls = vec![
+            format!("DROP EVENT TRIGGER IF EXISTS {}", EVENT_TRIGGER),
+            format!("DROP FUNCTION IF EXISTS {}.{}()", schema, CAPTURE_FUNCTION),
+            format!("DROP TABLE IF EXISTS {}.{}", schema, tb),
+        ];
+        for sql in sqls.iter() {
+            log_info!("execute: {}", sql);
+            sqlx::query(sql).execute(&self.conn_pool).await?;
+        }
+        Ok(())
+    }
+
+    async fn enable(
+        &self,
+        schema: &str,
+        tb: &str,
+        meta_tb: &str,
+        function: &str,
+    ) -> anyhow::Result<()> {
+        let sql = format!(
+            "CREATE EVENT TRIGGER {} ON ddl_command_end EXECUTE PROCEDURE {}()",
+            EVENT_TRIGGER, function
+        );
+        log_info!("execute: {}", sql);
+        sqlx::query(&sql).execute(&self.conn_pool).await?;
+
+        self.add_to_publication(schema, tb, meta_tb).await?;
+        self.verify(schema, meta_tb).await
+    }
+
+    async fn drop_trigger_and_function(&self, function: &str) -> anyhow::Result<()> {
+        let sqls = vec![
+            format!("DROP EVENT TRIGGER IF EXISTS {}", EVENT_TRIGGER),
+            format!("DROP FUNCTION IF EXISTS {}()", function),
+        ];
+        for sql in sqls.iter() {
+            log_info!("execute: {}", sql);
+            sqlx::query(sql).execute(&self.conn_pool).await?;
+        }
+        Ok(())
+    }
+
+    /// inserts into ddl_meta_tb should be published, publications for all tables contain it already
+    async fn add_to_publication(
+        &self,
+        schema: &str,
+        tb: &str,
+        meta_tb: &str,
+    ) -> anyhow::Result<()> {
+        let sql = format!(
+            "SELECT puballtables FROM pg_catalog.pg_publication WHERE pubname = '{}'",
+            self.pub_name
+        );
+        let sql = match sqlx::query(&sql).fetch_optional(&self.conn_pool).await? {
+            // the same as PgCdcClient
+            None => format!("CREATE PUBLICATION {} FOR ALL TABLES", self.pub_name),
+            Some(row) => {
+                let all_tables: bool = row.try_get(0)?;
+                let sql = format!(
+                    "SELECT 1 FROM pg_catalog.pg_publication_tables WHERE pubname = '{}' AND schemaname = '{}' AND tablename = '{}'",
+                    self.pub_name, schema, tb
+                );
+                if all_tables
+                    || sqlx::query(&sql)
+                        .fetch_optional(&self.conn_pool)
+                        .await?
+                        .is_some()
+                {
+                    return Ok(());
+                }
+                format!("ALTER PUBLICATION {} ADD TABLE {}", self.pub_name, meta_tb)
+            }
+        };
+        log_info!("execute: {}", sql);
+        sqlx::query(&sql).execute(&self.conn_pool).await?;
+        Ok(())
+    }
+
+    /// run a probe ddl in a transaction and check it is captured, the transaction is
+    /// rolled back so nothing will be decoded from wal
+    async fn verify(&self, schema: &str, meta_tb: &str) -> anyhow::Result<()> {
+        let probe_tb = format!(
+            "{}.{}",
+            SqlUtil::escape_by_db_type(schema, &DbType::Pg),
+            PROBE_TB
+        );
+        let mut tx = self.conn_pool.begin().await?;
+        let sql = format!("CREATE TABLE {} (id int)", probe_tb);
+        sqlx::query(&sql).execute(&mut tx).await?;
+
+        let sql = format!(
+            "SELECT object_type, object_name FROM {} ORDER BY id DESC LIMIT 1",
+            meta_tb
+        );
+        let row = sqlx::query(&sql).fetch_optional(&mut tx).await?;
+        tx.rollback().await?;
+
+        let captured = match row {
+            Some(row) => {
+                let object_type: Option<String> = row.try_get(0)?;
+                let object_name: Option<String> = row.try_get(1)?;
+                object_type.as_deref() == Some("table")
+                    && object_name.is_some_and(|i| i.ends_with(PROBE_TB))
+            }
+            None => false,
+        };
+        if !captured {
+            bail! {Error::ExtractorError(format!(
+                "ddl capture is not working, probe ddl on {} is not recorded into {}",
+                probe_tb, meta_tb
+            ))}
+        }
+        log_info!("ddl capture verified, probe ddl recorded into {}", meta_tb);
+        Ok(())
+    }
+
+    fn get_ddl_meta_schema_tb(&self) -> anyhow::Result<(String, String)> {
+        let tokens = ConfigTokenParser::parse_config(&self.ddl_meta_tb, &DbType::Pg, &['.'])?;
+        if tokens.len() != 2 {
+            bail! {Error::ConfigError(format!(
+                "ddl_meta_tb should be in format: schema.tb, got: {}",
+                self.ddl_meta_tb
+            ))}
+        }
+        Ok((
+            SqlUtil::unescape_by_db_type(&tokens[0], &DbType::Pg),
+            SqlUtil::unescape_by_db_type(&tokens[1], &DbType::Pg),
+        ))
+    }
+
+    /// the object identity (e.g. schema.tb) is taken from pg_event_trigger_ddl_commands,
+    /// so the extractor does not need to derive it from the ddl text,
+    /// tables created later are added to the publication if it is not for all tables
+    fn get_capture_function_sql(function: &str, meta_tb: &str, pub_name: &str) -> String {
+        format!(
+            r#"CREATE OR REPLACE FUNCTION {function}()
+  RETURNS event_trigger
+  LANGUAGE 'plpgsql'
+  COST 100
+  VOLATILE NOT LEAKPROOF SECURITY DEFINER
+AS $BODY$
+  declare ddl_text text;
+  declare max_rows int := 10000;
+  declare current_rows int;
+  declare ddl_schema varchar := current_schema;
+  declare ddl_object_type varchar := '';
+  declare ddl_object_identity varchar := '';
+  declare record_object record;
+  declare message text;
+begin
+
+  select current_query() into ddl_text;
+
+  for record_object in (select * from pg_event_trigger_ddl_commands()) loop
+    if record_object.command_tag = TG_TAG and ddl_object_identity = '' then
+      ddl_schema := coalesce(record_object.schema_name, ddl_schema);
+      ddl_object_type := coalesce(record_object.object_type, '');
+      ddl_object_identity := coalesce(record_object.object_identity, '');
+    end if;
+  end loop;
+
+  if TG_TAG = 'CREATE TABLE' then
+    if ddl_object_identity = '' then
+      message := 'CREATE TABLE, but object_identity is empty, ddl_text=' || ddl_text;
+    elsif exists (select 1 from pg_publication where pubname = '{pub_name}' and not puballtables) then
+      BEGIN
+        execute 'alter publication {pub_name} add table ' || ddl_object_identity;
+      EXCEPTION WHEN OTHERS THEN
+        message := 'failed to add table to publication {pub_name}: ' || SQLERRM;
+      END;
+    end if;
+  end if;
+
+  insert into {meta_tb}(id,event,tag,username,database,schema,object_type,object_name,client_address,client_port,event_time,ddl_text,txid_current,message)
+  values (default,TG_EVENT,TG_TAG,current_user,current_database(),ddl_schema,ddl_object_type,ddl_object_identity,inet_client_addr(),inet_client_port(),current_timestamp,ddl_text,cast(TXID_CURRENT() as varchar(16)),message);
+
+  select count(id) into current_rows from {meta_tb};
+  if current_rows > max_rows then
+    delete from {meta_tb} where id in (select min(id) from {meta_tb});
+  end if;
+end
+$BODY$"#
+        )
+    }
+}
@@ -0,0 +1,53 @@
+use dt_common::{
+    config::extractor_config::BasicExtractorConfig, log_info, log_warn, utils::time_util::TimeUtil,
+};
+
+/// reconnect broken cdc streams (network errors, server restarts, binlog timeout, etc.)
+/// with exponential backoff and jitter, the extractor resumes from the last pushed position
+#[derive(Clone, Default)]
+pub struct Reconnector {
+    pub max_reconnects: u64,
+    pub interval_ms: u64,
+    pub max_interval_ms: u64,
+    pub reconnects: u64,
+}
+
+impl Reconnector {
+    pub fn from_config(config: &BasicExtractorConfig) -> Self {
+        Self {
+            max_reconnects: config.max_reconnects,
+            interval_ms: config.reconnect_interval_ms,
+            max_interval_ms: config.reconnect_max_interval_ms,
+            reconnects: 0,
+        }
+    }
+
+    /// wait before the next reconnection,
+    /// return the error if reconnection is disabled or all attempts failed
+    pub async fn wait(&mut self, error: anyhow::Error) -> anyhow::Result<()> {
+        if self.reconnects >= self.max_reconnects {
+            return Err(error);
+        }
+
+        self.reconnects += 1;
+        let interval_ms =
+            TimeUtil::get_backoff_millis(self.interval_ms, self.max_interval_ms, self.reconnects);
+        log_warn!(
+            "cdc stream broken, reconnect: {}/{} after {} ms, error: {:#}",
+            self.reconnects,
+            self.max_reconnects,
+            interval_ms,
+            error
+        );
+        TimeUtil::sleep_millis(interval_ms).await;
+        Ok(())
+    }
+
+    /// reset attempts once the stream works again
+    pub fn reset(&mut self) {
+        if self.reconnects > 0 {
+            log_info!("cdc stream reconnected after {} attempts", self.reconnects);
+            self.reconnects = 0;
+        }
+    }
+}
@@ -18,6 +18,7 @@ const K_FLAG_EXPIRE_MS: u8 = 0xfc; // (252) Expire time in milliseconds.
 const K_FLAG_EXPIRE: u8 = 0xfd; // (253) Old expire time in seconds.
 const K_FLAG_SELECT: u8 = 0xfe; // (254) DB number of the following keys.
 const K_EOF: u8 = 0xff; // (255) End of the RDB file.
+const RDB_CHECKSUM_LEN: usize = 8;
 
 const RDB_MODULE_OPCODE_EOF: u64 = 0; // End of module value.
 const RDB_MODULE_OPCODE_SINT: u64 = 1; // Signed integer.
@@ -173,9 +174,10 @@ impl RdbParser<'_> {
 
             K_EOF => {
                 self.is_end = true;
-                self.reader
-                    .read_bytes(self.reader.rdb_length - self.reader.position)
-                    .await?;
+                // only the 8 bytes checksum (rdb version >= 5) follows EOF, do not read further
+                // since the rdb may be the preamble of an aof file followed by aof commands
+                let left = self.reader.rdb_length - self.reader.position;
+                self.reader.read_bytes(left.min(RDB_CHECKSUM_LEN)).await?;
             }
 
             _ => {
@@ -0,0 +1,334 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use tokio::{
+    fs::{metadata, read_dir, read_to_string, File},
+    io::{AsyncBufReadExt, AsyncReadExt, BufReader},
+};
+
+use super::StreamReader;
+use crate::extractor::base_extractor::BaseExtractor;
+use crate::extractor::redis::rdb::rdb_parser::RdbParser;
+use crate::extractor::redis::rdb::reader::rdb_reader::RdbReader;
+use crate::extractor::redis::redis_psync_extractor::RedisPsyncExtractor;
+use crate::Extractor;
+use dt_common::error::Error;
+use dt_common::meta::position::Position;
+use dt_common::meta::redis::redis_entry::RedisEntry;
+use dt_common::meta::redis::redis_object::RedisCmd;
+use dt_common::rdb_filter::RdbFilter;
+use dt_common::{log_info, log_warn};
+
+const RDB_MAGIC: &[u8] = b"REDIS";
+const MANIFEST_SUFFIX: &str = ".manifest";
+
+/// extract data from an append-only file, supports:
+/// 1, a single aof file, with or without rdb preamble (aof-use-rdb-preamble).
+/// 2, Redis 7.0+ multi-part aof, file_path is the appendonlydir or the manifest file,
+/// the base file (rdb or aof) and incr aof files are loaded in the order of the manifest.
+pub struct RedisAofFileExtractor {
+    pub file_path: String,
+    pub filter: RdbFilter,
+    pub base_extractor: BaseExtractor,
+}
+
+struct AofFileReader {
+    pub reader: BufReader<File>,
+}
+
+#[derive(PartialEq)]
+enum AofFileType {
+    Base,
+    History,
+    Incr,
+}
+
+struct AofFileInfo {
+    pub file_name: String,
+    pub seq: u64,
+    pub file_type: AofFileType,
+}
+
+#[async_trait]
+impl Extractor for RedisAofFileExtractor {
+    async fn extract(&mut self) -> anyhow::Result<()> {
+        let aof_files = self.get_aof_files().await?;
+        log_info!("RedisAofFileExtractor starts, aof files: {:?}", aof_files);
+
+        let mut now_db_id = 0;
+        for aof_file in aof_files.iter() {
+            now_db_id = self.extract_file(aof_file, now_db_id).await?;
+        }
+
+        log_info!(
+            "end extracting data from aof, all count: {}",
+            self.base_extractor.monitor.counters.pushed_record_count
+        );
+        self.base_extractor.wait_task_finish().await
+    }
+}
+
+impl RedisAofFileExtractor {
+    async fn extract_file(&mut self, file_path: &Path, now_db_id: i64) -> anyhow::Result<i64> {
+        log_info!("start extracting aof file: {}", file_path.display());
+        let file = File::open(file_path).await?;
+        let file_length = metadata(file_path).await?.len() as usize;
+        let mut file_reader = AofFileReader {
+            reader: BufReader::new(file),
+        };
+
+        let mut now_db_id = now_db_id;
+        if file_reader.reader.fill_buf().await?.starts_with(RDB_MAGIC) {
+            now_db_id = self.extract_rdb(&mut file_reader, file_length).await?;
+        }
+
+        loop {
+            let cmd = match file_reader.read_cmd().await {
+                Ok(Some(cmd)) => cmd,
+                Ok(None) => break,
+                Err(error) => {
+                    if !file_reader.reader.fill_buf().await?.is_empty() {
+                        return Err(error);
+                    }
+                    // the last command may be incomplete if redis crashed while writing aof,
+                    // ignore it as redis does by aof-load-truncated
+                    log_warn!(
+                        "aof file: {} may be truncated, error: {:?}",
+                        file_path.display(),
+                        error
+                    );
+                    break;
+                }
+            };
+
+            let cmd_name = cmd.get_name().to_ascii_lowercase();
+            match cmd_name.as_str() {
+                "select" => {
+                    now_db_id = cmd.get_str_arg(1).parse()?;
+                    continue;
+                }
+                // transactions are not kept, same as psync
+                "multi" | "exec" => continue,
+                _ => {}
+            }
+
+            // filter dangerous cmds, eg: flushdb, flushall
+            if self.filter.filter_cmd(&cmd_name) {
+                continue;
+            }
+
+            let mut entry = RedisEntry::new();
+            entry.cmd = cmd;
+            entry.db_id = now_db_id;
+            RedisPsyncExtractor::push_to_buf(
+                &mut self.base_extractor,
+                &mut self.filter,
+                entry,
+                Position::None,
+            )
+            .await?;
+        }
+        Ok(now_db_id)
+    }
+
+    async fn extract_rdb(
+        &mut self,
+        file_reader: &mut AofFileReader,
+        file_length: usize,
+    ) -> anyhow::Result<i64> {
+        let mut stream_reader: Box<&mut (dyn StreamReader + Send)> = Box::new(file_reader);
+        let reader = RdbReader {
+            conn: &mut stream_reader,
+            rdb_length: file_length,
+            position: 0,
+            copy_raw: false,
+            raw_bytes: Vec::new(),
+        };
+
+        let mut parser = RdbParser {
+            reader,
+            repl_stream_db_id: 0,
+            now_db_id: 0,
+            expire_ms: 0,
+            idle: 0,
+            freq: 0,
+            is_end: false,
+        };
+
+        let version = parser.load_meta().await?;
+        log_info!("source redis version: {:?}", version);
+
+        while !parser.is_end {
+            if let Some(entry) = parser.load_entry().await? {
+                RedisPsyncExtractor::push_to_buf(
+                    &mut self.base_extractor,
+                    &mut self.filter,
+                    entry,
+                    Position::None,
+                )
+                .await?;
+            }
+        }
+        Ok(parser.now_db_id)
+    }
+
+    async fn get_aof_files(&self) -> anyhow::Result<Vec<PathBuf>> {
+        let path = PathBuf::from(&self.file_path);
+        let manifest_path = if metadata(&path).await?.is_dir() {
+            Self::find_manifest(&path).await?
+        } else if self.file_path.ends_with(MANIFEST_SUFFIX) {
+            path
+        } else {
+            return Ok(vec![path]);
+        };
+
+        let dir = manifest_path
+            .parent()
+            .unwrap_or(Path::new("."))
+            .to_path_buf();
+        let content = read_to_string(&manifest_path).await?;
+        let mut aof_file_infos = Vec::new();
+        for line in content.lines() {
+            if let Some(info) = Self::parse_manifest_line(line)? {
+                aof_file_infos.push(info);
+            }
+        }
+
+        // base file first, then incr files by seq, history files are useless
+        let mut aof_files = Vec::new();
+        for info in aof_file_infos.iter() {
+            if info.file_type == AofFileType::Base {
+                aof_files.push(dir.join(&info.file_name));
+            }
+        }
+        let mut incr_infos: Vec<&AofFileInfo> = aof_file_infos
+            .iter()
+            .filter(|i| i.file_type == AofFileType::Incr)
+            .collect();
+        incr_infos.sort_by_key(|i| i.seq);
+        for info in incr_infos {
+            aof_files.push(dir.join(&info.file_name));
+        }
+        Ok(aof_files)
+    }
+
+    async fn find_manifest(dir: &Path) -> anyhow::Result<PathBuf> {
+        let mut entries = read_dir(dir).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            if entry
+                .file_name()
+                .to_string_lossy()
+                .ends_with(MANIFEST_SUFFIX)
+            {
+                return Ok(entry.path());
+            }
+        }
+        bail! {Error::ConfigError(format!(
+            "no aof manifest file found in dir: {}",
+            dir.display()
+        ))}
+    }
+
+    /// line format: file appendonly.aof.1.base.rdb seq 1 type b
+    fn parse_manifest_line(line: &str) -> anyhow::Result<Option<AofFileInfo>> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(None);
+        }
+
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        let mut file_name = String::new();
+        let mut seq = 0;
+        let mut file_type = None;
+        for pair in tokens.chunks(2) {
+            if pair.len() < 2 {
+                break;
+            }
+            match pair[0] {
+                "file" => file_name = pair[1].trim_matches('"').to_string(),
+                "seq" => seq = pair[1].parse()?,
+                "type" => {
+                    file_type = match pair[1] {
+                        "b" => Some(AofFileType::Base),
+                        "h" => Some(AofFileType::History),
+                        "i" => Some(AofFileType::Incr),
+                        _ => None,
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        match file_type {
+            Some(file_type) if !file_name.is_empty() => Ok(Some(AofFileInfo {
+                file_name,
+                seq,
+                file_type,
+            })),
+            _ => bail! {Error::ExtractorError(format!("invalid aof manifest line: {}", line))},
+        }
+    }
+}
+
+impl AofFileReader {
+    /// commands in aof are RESP arrays of bulk strings, eg: *2\r\n$6\r\nSELECT\r\n$1\r\n0\r\n,
+    /// annotations like #TS:1628217470\r\n (aof-timestamp-enabled) are skipped
+    async fn read_cmd(&mut self) -> anyhow::Result<Option<RedisCmd>> {
+        loop {
+            let line = self.read_line().await?;
+            if line.is_empty() {
+                return Ok(None);
+            }
+
+            match line[0] {
+                b'*' => {
+                    let count = Self::parse_length(&line[1..])?;
+                    let mut cmd = RedisCmd::new();
+                    for _ in 0..count {
+                        let line = self.read_line().await?;
+                        if line.first() != Some(&b'$') {
+                            bail! {Error::RedisCmdError(format!(
+                                "invalid bulk string in aof: {}",
+                                String::from_utf8_lossy(&line)
+                            ))}
+                        }
+                        let length = Self::parse_length(&line[1..])?;
+                        let mut buf = vec![0; length + 2];
+                        self.reader.read_exact(&mut buf).await?;
+                        buf.truncate(length);
+                        cmd.add_arg(buf);
+                    }
+                    return Ok(Some(cmd));
+                }
+                b'#' | b'\r' | b'\n' => continue,
+                _ => {
+                    bail! {Error::RedisCmdError(format!(
+                        "invalid RESP line in aof: {}",
+                        String::from_utf8_lossy(&line)
+                    ))}
+                }
+            }
+        }
+    }
+
+    async fn read_line(&mut self) -> anyhow::Result<Vec<u8>> {
+        let mut line = Vec::new();
+        self.reader.read_until(b'\n', &mut line).await?;
+        Ok(line)
+    }
+
+    fn parse_length(bytes: &[u8]) -> anyhow::Result<usize> {
+        let str = String::from_utf8_lossy(bytes);
+        Ok(str.trim_end().parse::<usize>()?)
+    }
+}
+
+#[async_trait]
+impl StreamReader for AofFileReader {
+    async fn read_bytes(&mut self, size: usize) -> anyhow::Result<Vec<u8>> {
+        let mut buf = vec![0; size];
+        self.reader.read_exact(&mut buf).await?;
+        Ok(buf)
+    }
+}
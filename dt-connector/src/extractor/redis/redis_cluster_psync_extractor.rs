@@ -0,0 +1,615 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use redis::Value;
+use tokio::{sync::Mutex, task::JoinHandle, time::Instant};
+use url::Url;
+
+use super::redis_client::RedisClient;
+use crate::extractor::base_extractor::BaseExtractor;
+use crate::extractor::extractor_monitor::ExtractorMonitor;
+use crate::extractor::reconnector::Reconnector;
+use crate::extractor::redis::redis_psync_extractor::RedisPsyncExtractor;
+use crate::extractor::resumer::cdc_resumer::CdcResumer;
+use crate::Extractor;
+use dt_common::config::config_enums::{DbType, ExtractType};
+use dt_common::config::config_token_parser::ConfigTokenParser;
+use dt_common::error::Error;
+use dt_common::meta::dt_data::DtData;
+use dt_common::meta::dt_queue::DtQueue;
+use dt_common::meta::position::Position;
+use dt_common::meta::redis::cluster_node::{ClusterNode, ClusterShard};
+use dt_common::meta::syncer::Syncer;
+use dt_common::monitor::monitor::Monitor;
+use dt_common::rdb_filter::RdbFilter;
+use dt_common::time_filter::TimeFilter;
+use dt_common::utils::redis_util::RedisUtil;
+use dt_common::utils::sql_util::SqlUtil;
+use dt_common::utils::time_util::TimeUtil;
+use dt_common::{log_error, log_info, log_warn};
+
+const SHARD_BUFFER_SIZE: usize = 1000;
+const TOPOLOGY_REFRESH_INTERVAL_SECS: u64 = 10;
+const PENDING_SHARD_RETRY_INTERVAL_SECS: u64 = 1;
+
+/// extract data from a redis cluster, runs a PSYNC stream for each shard and merges
+/// all streams into one pipeline, the position is Position::RedisCluster which holds
+/// a Position::Redis for each shard.
+/// failovers and slot migrations are followed by:
+/// 1, restarting the failed shard stream from the new master of the shard with the last position.
+/// 2, refreshing topology by CLUSTER SHARDS periodically, or once MOVED / ASK is returned
+/// by heartbeat, and starting streams for new masters.
+/// keys migrated between shards during cdc are not supported: commands of different shards
+/// are not ordered, the DEL of MIGRATE on the source shard may be synced after the RESTORE-ASKING
+/// on the target shard, so the task fails once RESTORE-ASKING is received.
+pub struct RedisClusterPsyncExtractor {
+    pub base_extractor: BaseExtractor,
+    pub url: String,
+    pub repl_port: u64,
+    pub keepalive_interval_secs: u64,
+    pub heartbeat_interval_secs: u64,
+    pub heartbeat_key: String,
+    pub syncer: Arc<Mutex<Syncer>>,
+    pub filter: RdbFilter,
+    pub resumer: CdcResumer,
+    pub extract_type: ExtractType,
+}
+
+struct ShardStream {
+    // id of the master node which the shard stream started from, also the key in Position::RedisCluster
+    key: String,
+    // ids of all nodes ever seen in the shard, used to find the shard after failover
+    node_ids: Vec<String>,
+    // None if the stream failed and is waiting for restart
+    master: Option<ClusterNode>,
+    heartbeat_key: String,
+    heartbeat_conn: Option<redis::Connection>,
+    buffer: Arc<DtQueue>,
+    syncer: Arc<Mutex<Syncer>>,
+    shut_down: Arc<AtomicBool>,
+    handle: Option<JoinHandle<anyhow::Result<()>>>,
+    finished: bool,
+}
+
+#[async_trait]
+impl Extractor for RedisClusterPsyncExtractor {
+    async fn extract(&mut self) -> anyhow::Result<()> {
+        log_info!(
+            "RedisClusterPsyncExtractor starts, extract_type: {}, resumer: {},
+             keepalive_interval_secs: {}, heartbeat_interval_secs: {}, heartbeat_key: {}",
+            self.extract_type,
+            self.resumer,
+            self.keepalive_interval_secs,
+            self.heartbeat_interval_secs,
+            self.heartbeat_key
+        );
+
+        let resumed_positions =
+            if let Position::RedisCluster { shards } = &self.resumer.checkpoint_position {
+                shards.clone()
+            } else {
+                Arc::default()
+            };
+
+        let mut conn = RedisUtil::create_redis_conn(&self.url).await?;
+        let shards = RedisUtil::get_cluster_shards(&mut conn)?;
+
+        // master id -> (key, position) of the resumed shard
+        let mut resumed_shards = HashMap::new();
+        for (key, position) in resumed_positions.iter() {
+            let shard = self.find_shard(&[key.clone()], position, &shards).await;
+            if let Some(ClusterShard {
+                master: Some(master),
+                ..
+            }) = shard
+            {
+                resumed_shards.insert(master.id.clone(), (key.clone(), position.clone()));
+            }
+        }
+
+        let mut positions = BTreeMap::new();
+        let mut streams = Vec::new();
+        for shard in shards.iter() {
+            let master = match &shard.master {
+                Some(master) => master.clone(),
+                None => bail! {Error::ExtractorError(format!(
+                    "redis cluster shard has no online master, nodes: {:?}",
+                    shard.node_ids
+                ))},
+            };
+
+            let (key, position) = resumed_shards
+                .remove(&master.id)
+                .unwrap_or((master.id.clone(), Position::None));
+            let mut stream = ShardStream::new(&key, &shard.node_ids);
+            self.start_stream(&mut stream, master, &position, self.extract_type.clone())
+                .await?;
+            positions.insert(key, position);
+            streams.push(stream);
+        }
+
+        let mut positions = Arc::new(positions);
+        self.extract_streams(&mut streams, &mut positions).await?;
+        self.base_extractor.wait_task_finish().await
+    }
+}
+
+impl RedisClusterPsyncExtractor {
+    async fn extract_streams(
+        &mut self,
+        streams: &mut Vec<ShardStream>,
+        positions: &mut Arc<BTreeMap<String, Position>>,
+    ) -> anyhow::Result<()> {
+        let mut refresh_time = Instant::now();
+        let mut heartbeat_time = Instant::now();
+        let mut keepalive_time = Instant::now();
+        let mut need_refresh = false;
+
+        loop {
+            let mut received = false;
+            for stream in streams.iter_mut() {
+                received |= self.drain_stream(stream, positions).await?;
+
+                if !stream.handle.as_ref().is_some_and(|i| i.is_finished()) {
+                    continue;
+                }
+
+                // the stream may be aborted by heartbeat
+                let result = stream
+                    .handle
+                    .take()
+                    .unwrap()
+                    .await
+                    .unwrap_or_else(|err| Err(err.into()));
+                // data received before the stream finished are still valid
+                self.drain_stream(stream, positions).await?;
+                stream.shut_down.store(true, Ordering::Release);
+                match result {
+                    Ok(()) => {
+                        log_info!("shard stream: {} finished", stream.key);
+                        stream.finished = true;
+                    }
+                    Err(err) => {
+                        log_warn!(
+                            "shard stream: {} failed, will restart from position: {}, error: {:?}",
+                            stream.key,
+                            positions.get(&stream.key).unwrap_or(&Position::None),
+                            err
+                        );
+                        stream.master = None;
+                        need_refresh = true;
+                    }
+                }
+            }
+
+            if streams.iter().all(|i| i.finished) {
+                return Ok(());
+            }
+
+            let refresh_interval_secs = if need_refresh {
+                PENDING_SHARD_RETRY_INTERVAL_SECS
+            } else {
+                TOPOLOGY_REFRESH_INTERVAL_SECS
+            };
+            if refresh_time.elapsed().as_secs() >= refresh_interval_secs {
+                match self.fetch_shards(streams).await {
+                    Ok(shards) => self.refresh_streams(streams, positions, &shards).await?,
+                    Err(err) => log_warn!("failed to refresh cluster topology, error: {:?}", err),
+                }
+                need_refresh = streams.iter().any(|i| i.master.is_none() && !i.finished);
+                refresh_time = Instant::now();
+            }
+
+            if self.heartbeat_interval_secs > 0
+                && heartbeat_time.elapsed().as_secs() >= self.heartbeat_interval_secs
+            {
+                for stream in streams.iter_mut() {
+                    if self.heartbeat(stream).await {
+                        need_refresh = true;
+                    }
+                }
+                heartbeat_time = Instant::now();
+            }
+
+            if self.keepalive_interval_secs > 0
+                && keepalive_time.elapsed().as_secs() >= self.keepalive_interval_secs
+            {
+                self.sync_committed_positions(streams).await;
+                keepalive_time = Instant::now();
+            }
+
+            if !received {
+                TimeUtil::sleep_millis(1).await;
+            }
+        }
+    }
+
+    /// positions are shared by the items drained in a batch, and copied once per batch:
+    /// the items before the last one carry the shard position of the previous batch,
+    /// which is behind them, so they are synced again if the task resumes from it
+    async fn drain_stream(
+        &mut self,
+        stream: &mut ShardStream,
+        positions: &mut Arc<BTreeMap<String, Position>>,
+    ) -> anyhow::Result<bool> {
+        let count = stream.buffer.len();
+        let mut items = Vec::with_capacity(count);
+        for _ in 0..count {
+            items.push(stream.buffer.pop()?);
+        }
+
+        let last_index = items
+            .iter()
+            .rposition(|i| matches!(i.position, Position::Redis { .. }));
+        for (i, item) in items.into_iter().enumerate() {
+            if let DtData::Redis { entry } = &item.dt_data {
+                if !entry.is_base && entry.cmd.get_name().eq_ignore_ascii_case("restore-asking") {
+                    bail! {Error::ExtractorError(format!(
+                        "keys migrated between shards are not supported by cluster cdc, received RESTORE-ASKING from shard stream: {}, restart the task with extract_type=snapshot_and_cdc after the migration",
+                        stream.key
+                    ))}
+                }
+            }
+
+            // rdb entries have no position
+            let position = if let Position::Redis { .. } = item.position {
+                if Some(i) == last_index {
+                    Arc::make_mut(positions).insert(stream.key.clone(), item.position);
+                }
+                Position::RedisCluster {
+                    shards: positions.clone(),
+                }
+            } else {
+                Position::None
+            };
+            self.base_extractor
+                .push_dt_data(item.dt_data, position)
+                .await?;
+        }
+        Ok(count > 0)
+    }
+
+    async fn refresh_streams(
+        &mut self,
+        streams: &mut Vec<ShardStream>,
+        positions: &mut Arc<BTreeMap<String, Position>>,
+        shards: &[ClusterShard],
+    ) -> anyhow::Result<()> {
+        // restart failed streams from the new masters of their shards
+        for stream in streams.iter_mut() {
+            if stream.finished {
+                continue;
+            }
+
+            let position = positions.get(&stream.key).cloned().unwrap_or_default();
+            let shard = match self.find_shard(&stream.node_ids, &position, shards).await {
+                Some(shard) => shard,
+                None => {
+                    if stream.master.is_none() {
+                        log_warn!("can not find shard for stream: {}", stream.key);
+                    }
+                    continue;
+                }
+            };
+
+            for id in shard.node_ids.iter() {
+                if !stream.node_ids.contains(id) {
+                    stream.node_ids.push(id.clone());
+                }
+            }
+
+            if stream.master.is_some() {
+                continue;
+            }
+            if let Some(master) = &shard.master {
+                log_info!(
+                    "restart shard stream: {} from master: {}, position: {}",
+                    stream.key,
+                    master.address,
+                    position
+                );
+                // the new master may not have the offset, then all data should be synced by rdb
+                let extract_type = match (&self.extract_type, &position) {
+                    (ExtractType::Cdc, Position::Redis { .. }) => {
+                        log_warn!(
+                            "shard stream: {} restarts as snapshot_and_cdc, the shard will be fully synced by rdb again if master: {} can not continue from position: {}",
+                            stream.key,
+                            master.address,
+                            position
+                        );
+                        ExtractType::SnapshotAndCdc
+                    }
+                    (extract_type, _) => extract_type.clone(),
+                };
+                self.start_stream(stream, master.clone(), &position, extract_type)
+                    .await?;
+            }
+        }
+
+        // start streams for new masters, eg: a new shard was added by resharding
+        if matches!(self.extract_type, ExtractType::Snapshot) {
+            return Ok(());
+        }
+        for shard in shards.iter() {
+            let master = match &shard.master {
+                Some(master) => master,
+                None => continue,
+            };
+            if streams
+                .iter()
+                .any(|i| shard.node_ids.iter().any(|id| i.node_ids.contains(id)))
+            {
+                continue;
+            }
+
+            log_info!("found new shard, master: {}", master.address);
+            let mut stream = ShardStream::new(&master.id, &shard.node_ids);
+            // data may have been migrated to the new shard, sync them by rdb
+            self.start_stream(
+                &mut stream,
+                master.clone(),
+                &Position::None,
+                ExtractType::SnapshotAndCdc,
+            )
+            .await?;
+            Arc::make_mut(positions).insert(stream.key.clone(), Position::None);
+            streams.push(stream);
+        }
+        Ok(())
+    }
+
+    async fn start_stream(
+        &self,
+        stream: &mut ShardStream,
+        master: ClusterNode,
+        position: &Position,
+        extract_type: ExtractType,
+    ) -> anyhow::Result<()> {
+        let (repl_id, repl_offset, now_db_id) = match position {
+            Position::Redis {
+                repl_id,
+                repl_offset,
+                now_db_id,
+                ..
+            } => (repl_id.clone(), *repl_offset, *now_db_id),
+            _ => (String::new(), 0, 0),
+        };
+
+        stream.buffer = Arc::new(DtQueue::new(SHARD_BUFFER_SIZE, 0));
+        stream.shut_down = Arc::new(AtomicBool::new(false));
+        stream.syncer = Arc::new(Mutex::new(Syncer {
+            received_position: position.clone(),
+            committed_position: position.clone(),
+        }));
+        stream.heartbeat_key = self.get_heartbeat_key(&master);
+        stream.heartbeat_conn = None;
+
+        // records are counted by the cluster extractor, the shard monitor is useless
+        let monitor = Arc::new(Monitor::new("extractor", &stream.key, 1, 100, 1));
+        let base_extractor = BaseExtractor {
+            buffer: stream.buffer.clone(),
+            router: self.base_extractor.router.clone(),
+            shut_down: stream.shut_down.clone(),
+            monitor: ExtractorMonitor::new(monitor).await,
+            data_marker: None,
+            time_filter: TimeFilter::default(),
+        };
+
+        let mut extractor = RedisPsyncExtractor {
+            conn: RedisClient::new(&self.get_node_url(&master.address)?).await?,
+            repl_id,
+            repl_offset,
+            repl_port: self.repl_port,
+            now_db_id,
+            keepalive_interval_secs: self.keepalive_interval_secs,
+            // heartbeat is sent by the cluster extractor to handle MOVED / ASK
+            heartbeat_interval_secs: 0,
+            heartbeat_key: stream.heartbeat_key.clone(),
+            syncer: stream.syncer.clone(),
+            filter: self.filter.clone(),
+            resumer: CdcResumer::default(),
+            extract_type,
+            // failed shard streams are restarted by the cluster extractor
+            reconnector: Reconnector::default(),
+            base_extractor,
+        };
+
+        log_info!(
+            "start shard stream: {}, master: {}, slots count: {}",
+            stream.key,
+            master.address,
+            master.slots.len()
+        );
+        stream.handle = Some(tokio::spawn(async move { extractor.extract().await }));
+        stream.master = Some(master);
+        Ok(())
+    }
+
+    async fn fetch_shards(&self, streams: &[ShardStream]) -> anyhow::Result<Vec<ClusterShard>> {
+        // the node in url may be down, try other known masters
+        let mut urls = vec![self.url.clone()];
+        for master in streams.iter().filter_map(|i| i.master.as_ref()) {
+            urls.push(self.get_node_url(&master.address)?);
+        }
+
+        let mut last_err = None;
+        for url in urls.iter() {
+            let result = match RedisUtil::create_redis_conn(url).await {
+                Ok(mut conn) => RedisUtil::get_cluster_shards(&mut conn),
+                Err(err) => Err(err),
+            };
+            match result {
+                Ok(shards) => return Ok(shards),
+                Err(err) => last_err = Some(err),
+            }
+        }
+        Err(last_err.unwrap())
+    }
+
+    async fn find_shard<'a>(
+        &self,
+        node_ids: &[String],
+        position: &Position,
+        shards: &'a [ClusterShard],
+    ) -> Option<&'a ClusterShard> {
+        if let Some(shard) = shards
+            .iter()
+            .find(|i| i.node_ids.iter().any(|id| node_ids.contains(id)))
+        {
+            return Some(shard);
+        }
+
+        // all nodes of the shard were replaced, the new master still inherits
+        // the replication history as master_replid2
+        let repl_id = match position {
+            Position::Redis { repl_id, .. } if !repl_id.is_empty() => repl_id,
+            _ => return None,
+        };
+        for shard in shards.iter() {
+            let master = match &shard.master {
+                Some(master) => master,
+                None => continue,
+            };
+            let url = match self.get_node_url(&master.address) {
+                Ok(url) => url,
+                Err(_) => continue,
+            };
+            if let Ok(mut conn) = RedisUtil::create_redis_conn(&url).await {
+                if let Ok((repl_id_1, repl_id_2)) = RedisUtil::get_repl_ids(&mut conn) {
+                    if repl_id == &repl_id_1 || repl_id == &repl_id_2 {
+                        return Some(shard);
+                    }
+                }
+            }
+        }
+        None
+    }
+
+    /// returns true if MOVED / ASK is received, which means the slot of heartbeat key was migrated
+    async fn heartbeat(&self, stream: &mut ShardStream) -> bool {
+        if stream.master.is_none() || stream.heartbeat_key.is_empty() {
+            return false;
+        }
+
+        let db_key = ConfigTokenParser::parse(
+            &stream.heartbeat_key,
+            &['.'],
+            &SqlUtil::get_escape_pairs(&DbType::Redis),
+        );
+        if db_key.len() != 2 {
+            return false;
+        }
+
+        if stream.heartbeat_conn.is_none() {
+            let address = &stream.master.as_ref().unwrap().address;
+            let conn = match self.get_node_url(address) {
+                Ok(url) => RedisUtil::create_redis_conn(&url).await,
+                Err(err) => Err(err),
+            };
+            match conn {
+                Ok(conn) => stream.heartbeat_conn = Some(conn),
+                Err(err) => {
+                    log_error!("heartbeat failed, error: {:?}", err);
+                    return false;
+                }
+            }
+        }
+
+        let since_epoch = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let timestamp =
+            since_epoch.as_secs() * 1000 + since_epoch.subsec_nanos() as u64 / 1_000_000;
+        let heartbeat_value = Position::format_timestamp_millis(timestamp as i64);
+
+        let conn = stream.heartbeat_conn.as_mut().unwrap();
+        let cmds = [
+            vec!["SELECT", db_key[0].as_str()],
+            vec!["SET", db_key[1].as_str(), heartbeat_value.as_str()],
+        ];
+        for cmd in cmds.iter() {
+            match RedisUtil::send_cmd(conn, cmd) {
+                Ok(Value::ServerError(err)) if matches!(err.code(), "MOVED" | "ASK") => {
+                    log_info!(
+                        "heartbeat key: {} of shard stream: {} was migrated, error: {:?}",
+                        stream.heartbeat_key,
+                        stream.key,
+                        err
+                    );
+                    // restart the stream with a new heartbeat key
+                    if let Some(handle) = stream.handle.as_ref() {
+                        handle.abort();
+                    }
+                    return true;
+                }
+                Ok(Value::ServerError(err)) => {
+                    log_error!("heartbeat failed, error: {:?}", err);
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    log_error!("heartbeat failed, error: {:?}", err);
+                    stream.heartbeat_conn = None;
+                    break;
+                }
+            }
+        }
+        false
+    }
+
+    async fn sync_committed_positions(&self, streams: &[ShardStream]) {
+        // keep_alive_ack of each shard stream reads its own committed position
+        let committed_position = self.syncer.lock().await.committed_position.clone();
+        if let Position::RedisCluster { shards } = committed_position {
+            for stream in streams.iter() {
+                if let Some(position @ Position::Redis { .. }) = shards.get(&stream.key) {
+                    stream.syncer.lock().await.committed_position = position.clone();
+                }
+            }
+        }
+    }
+
+    fn get_heartbeat_key(&self, master: &ClusterNode) -> String {
+        if self.heartbeat_key.is_empty() {
+            return String::new();
+        }
+        // the heartbeat key must be in a slot of the shard
+        match master
+            .slots
+            .first()
+            .and_then(|slot| master.slot_hash_tag_map.get(slot))
+        {
+            Some(hash_tag) => format!("{}{{{}}}", self.heartbeat_key, hash_tag),
+            None => String::new(),
+        }
+    }
+
+    fn get_node_url(&self, address: &str) -> anyhow::Result<String> {
+        let url_info = Url::parse(&self.url)?;
+        let username = url_info.username();
+        let password = url_info.password().unwrap_or("");
+        Ok(format!("redis://{}:{}@{}", username, password, address))
+    }
+}
+
+impl ShardStream {
+    fn new(key: &str, node_ids: &[String]) -> Self {
+        let mut node_ids = node_ids.to_vec();
+        if !node_ids.iter().any(|i| i == key) {
+            node_ids.push(key.to_string());
+        }
+        Self {
+            key: key.to_string(),
+            node_ids,
+            master: None,
+            heartbeat_key: String::new(),
+            heartbeat_conn: None,
+            buffer: Arc::new(DtQueue::new(1, 0)),
+            syncer: Arc::new(Mutex::new(Syncer::default())),
+            shut_down: Arc::new(AtomicBool::new(false)),
+            handle: None,
+            finished: false,
+        }
+    }
+}
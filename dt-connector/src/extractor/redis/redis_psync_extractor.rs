@@ -8,6 +8,7 @@ use async_trait::async_trait;
 use tokio::{sync::Mutex, time::Instant};
 
 use crate::extractor::base_extractor::BaseExtractor;
+use crate::extractor::reconnector::Reconnector;
 use crate::extractor::redis::rdb::rdb_parser::RdbParser;
 use crate::extractor::redis::rdb::reader::rdb_reader::RdbReader;
 use crate::extractor::redis::redis_resp_types::Value;
@@ -41,6 +42,7 @@ pub struct RedisPsyncExtractor {
     pub filter: RdbFilter,
     pub resumer: CdcResumer,
     pub extract_type: ExtractType,
+    pub reconnector: Reconnector,
 }
 
 #[async_trait]
@@ -67,7 +69,7 @@ impl Extractor for RedisPsyncExtractor {
             self.extract_type,
             ExtractType::Cdc | ExtractType::SnapshotAndCdc
         ) {
-            self.receive_aof().await?;
+            self.receive_aof(full_sync).await?;
         }
 
         self.base_extractor.wait_task_finish().await
@@ -93,7 +95,7 @@ impl RedisPsyncExtractor {
             )}
         }
 
-        let full_sync = self.repl_id.is_empty() && self.repl_offset == 0;
+        let mut full_sync = self.repl_id.is_empty() && self.repl_offset == 0;
         let (repl_id, repl_offset) = if full_sync {
             ("?".to_string(), "-1".to_string())
         } else {
@@ -108,11 +110,24 @@ impl RedisPsyncExtractor {
 
         if let Value::Status(s) = value {
             log_info!("PSYNC command response status: {:?}", s);
+            let tokens: Vec<&str> = s.split_whitespace().collect();
             if full_sync {
-                let tokens: Vec<&str> = s.split_whitespace().collect();
                 self.repl_id = tokens[1].to_string();
                 self.repl_offset = tokens[2].parse::<u64>()?;
-            } else if s != "CONTINUE" {
+            } else if tokens[0] == "CONTINUE" {
+                // +CONTINUE <new_repl_id> if the master changed its replication id, eg: after failover
+                if tokens.len() > 1 {
+                    self.repl_id = tokens[1].to_string();
+                }
+            } else if tokens[0] == "FULLRESYNC"
+                && matches!(self.extract_type, ExtractType::SnapshotAndCdc)
+            {
+                // the requested offset is no longer available, all data will be synced by rdb
+                log_warn!("PSYNC partial resync failed, will do full resync");
+                self.repl_id = tokens[1].to_string();
+                self.repl_offset = tokens[2].parse::<u64>()?;
+                full_sync = true;
+            } else {
                 bail! {Error::ExtractorError(
                     "PSYNC command response is NOT CONTINUE".into(),
                 )}
@@ -217,7 +232,7 @@ impl RedisPsyncExtractor {
         Ok(())
     }
 
-    async fn receive_aof(&mut self) -> anyhow::Result<()> {
+    async fn receive_aof(&mut self, mut full_sync: bool) -> anyhow::Result<()> {
         let heartbeat_db_key = ConfigTokenParser::parse(
             &self.heartbeat_key,
             &['.'],
@@ -242,6 +257,54 @@ impl RedisPsyncExtractor {
         }
 
         let mut heartbeat_timestamp = String::new();
+        loop {
+            let mut error = self
+                .receive_aof_stream(heartbeat_db_id, &heartbeat_db_key, &mut heartbeat_timestamp)
+                .await?;
+            loop {
+                self.reconnector.wait(error).await?;
+                match self.reconnect(full_sync).await {
+                    Ok(full_resync) => {
+                        full_sync = full_sync || full_resync;
+                        break;
+                    }
+                    Err(err) => error = err,
+                }
+            }
+        }
+    }
+
+    /// reconnect and continue replication from the last received offset
+    async fn reconnect(&mut self, full_sync: bool) -> anyhow::Result<bool> {
+        log_info!(
+            "reconnect psync stream, repl_id: {}, repl_offset: {}",
+            self.repl_id,
+            self.repl_offset
+        );
+        self.conn = RedisClient::new(&self.conn.url).await?;
+
+        // if the stream started by FULLRESYNC <repl_id> <offset>, repl_offset is based on
+        // the offset before the first received byte, while PSYNC requires the next one
+        let repl_offset = self.repl_offset;
+        if full_sync {
+            self.repl_offset += 1;
+        }
+        let full_resync = self.start_psync().await?;
+        if full_resync {
+            self.receive_rdb().await?;
+        } else {
+            self.repl_offset = repl_offset;
+        }
+        Ok(full_resync)
+    }
+
+    /// return the error if the psync stream breaks, which can be resumed by reconnecting
+    async fn receive_aof_stream(
+        &mut self,
+        heartbeat_db_id: i64,
+        heartbeat_db_key: &[String],
+        heartbeat_timestamp: &mut String,
+    ) -> anyhow::Result<anyhow::Error> {
         let mut start_time = Instant::now();
         loop {
             // heartbeat
@@ -250,12 +313,16 @@ impl RedisPsyncExtractor {
                 start_time = Instant::now();
             }
 
-            let (value, n) = self.conn.read_with_len().await?;
+            let (value, n) = match self.conn.read_with_len().await {
+                Ok(result) => result,
+                Err(error) => return Ok(error),
+            };
             if Value::Nil == value {
                 TimeUtil::sleep_millis(1).await;
                 continue;
             }
 
+            self.reconnector.reset();
             self.repl_offset += n as u64;
             let cmd = self.handle_redis_value(value).await?;
             log_debug!("received cmd: [{}]", cmd);
@@ -275,7 +342,7 @@ impl RedisPsyncExtractor {
                         .get_str_arg(1)
                         .eq_ignore_ascii_case(&heartbeat_db_key[1])
                 {
-                    heartbeat_timestamp = cmd.get_str_arg(2);
+                    *heartbeat_timestamp = cmd.get_str_arg(2);
                     continue;
                 }
 
@@ -1,26 +1,45 @@
+use std::io::Cursor;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
 use anyhow::bail;
 use async_trait::async_trait;
-use dt_common::{error::Error, log_info, rdb_filter::RdbFilter};
+use dt_common::{error::Error, log_error, log_info, log_warn, rdb_filter::RdbFilter};
 use dt_common::{
     meta::{
         dt_data::DtData,
         position::Position,
         redis::{
+            command::cmd_encoder::CmdEncoder,
             redis_entry::RedisEntry,
-            redis_object::{RedisObject, RedisString},
+            redis_object::{RedisCmd, RedisObject, RedisString},
             redis_statistic_type::RedisStatisticType,
         },
     },
-    utils::redis_util::RedisUtil,
+    utils::redis_util::{RedisUtil, DUMP_FOOTER_LEN},
 };
-use redis::{Connection, Value};
+use redis::{Connection, ConnectionLike, Value};
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
 
+use crate::extractor::redis::rdb::entry_parser::entry_parser::EntryParser;
+use crate::extractor::redis::rdb::reader::rdb_reader::RdbReader;
+use crate::extractor::redis::redis_psync_extractor::RedisPsyncExtractor;
+use crate::extractor::redis::StreamReader;
 use crate::{extractor::base_extractor::BaseExtractor, Extractor};
 
+const KEYEVENT_CHANNEL_PATTERN: &str = "__keyevent@*__:*";
+// event classes of all changes: generic, string, list, set, hash, zset, expired, evicted, stream,
+// "A" is the alias of them
+const KEYEVENT_CLASSES: &str = "g$lshzxet";
+
 pub struct RedisScanExtractor {
     pub base_extractor: BaseExtractor,
-    pub statistic_type: RedisStatisticType,
+    pub url: String,
+    // None: migrate data by DUMP, otherwise: analyze keys for statistics
+    pub statistic_type: Option<RedisStatisticType>,
     pub scan_count: u64,
+    pub keyspace_events: bool,
     pub conn: Connection,
     pub filter: RdbFilter,
 }
@@ -28,10 +47,14 @@ pub struct RedisScanExtractor {
 #[async_trait]
 impl Extractor for RedisScanExtractor {
     async fn extract(&mut self) -> anyhow::Result<()> {
-        log_info!("RedisScanExtractor starts");
+        log_info!(
+            "RedisScanExtractor starts, scan_count: {}, keyspace_events: {}",
+            self.scan_count,
+            self.keyspace_events
+        );
 
-        if let RedisStatisticType::HotKey = self.statistic_type {
-            let maxmemory_policy = self.get_maxmemory_policy().await?;
+        if let Some(RedisStatisticType::HotKey) = self.statistic_type {
+            let maxmemory_policy = self.get_config("maxmemory-policy").await?;
             if maxmemory_policy != "allkeys-lfu" {
                 bail! {Error::MetadataError(format!(
                     "maxmemory_policy is {}, should be allkeys-lfu",
@@ -40,7 +63,13 @@ impl Extractor for RedisScanExtractor {
             }
         }
 
-        let count = &self.scan_count.to_string();
+        // subscribe before scanning, so that keys changed during scanning won't be missed
+        let keyspace_event_receiver = if self.statistic_type.is_none() && self.keyspace_events {
+            Some(self.start_keyspace_event_listener().await?)
+        } else {
+            None
+        };
+
         for db in self.get_dbs().await? {
             if self.filter.filter_schema(&db) {
                 continue;
@@ -54,29 +83,298 @@ impl Extractor for RedisScanExtractor {
 
             // scan
             let db_id: i64 = db.parse()?;
-            let mut cursor = 0;
+            let mut cursor = "0".to_string();
             loop {
-                let cmd = ["SCAN", &cursor.to_string(), "COUNT", count];
-                let result = self.query(&cmd).await?;
-
-                cursor = result[0].parse()?;
-                for key in result.iter().skip(1) {
+                let (next_cursor, keys) = self.scan(&cursor).await?;
+                cursor = next_cursor;
+                for key in keys {
                     match self.statistic_type {
-                        RedisStatisticType::HotKey => self.analyze_hot_key(db_id, key).await?,
-                        RedisStatisticType::BigKey => self.analyze_big_key(db_id, key).await?,
+                        Some(RedisStatisticType::HotKey) => {
+                            let key = String::from_utf8_lossy(&key).to_string();
+                            self.analyze_hot_key(db_id, &key).await?
+                        }
+                        Some(RedisStatisticType::BigKey) => {
+                            let key = String::from_utf8_lossy(&key).to_string();
+                            self.analyze_big_key(db_id, &key).await?
+                        }
+                        None => self.migrate_key(db_id, key).await?,
                     }
                 }
 
-                if cursor == 0 {
+                if cursor == "0" {
                     break;
                 }
             }
         }
 
+        log_info!(
+            "end scanning keys, all count: {}",
+            self.base_extractor.monitor.counters.pushed_record_count
+        );
+
+        if let Some(receiver) = keyspace_event_receiver {
+            self.receive_keyspace_events(receiver).await?;
+        }
+
         self.base_extractor.wait_task_finish().await
     }
 }
 
+/// migration
+impl RedisScanExtractor {
+    async fn scan(&mut self, cursor: &str) -> anyhow::Result<(String, Vec<Vec<u8>>)> {
+        let count = self.scan_count.to_string();
+        let cmd = ["SCAN", cursor, "COUNT", &count];
+        let result = RedisUtil::send_cmd(&mut self.conn, &cmd)?;
+
+        // keys are binary safe, do not parse them as string
+        if let Value::Array(mut values) = result {
+            if values.len() == 2 {
+                let keys_value = values.pop().unwrap();
+                let cursor_value = values.pop().unwrap();
+                let next_cursor = RedisUtil::parse_result_as_string(cursor_value)?.remove(0);
+
+                let mut keys = Vec::new();
+                if let Value::Array(key_values) = keys_value {
+                    for key_value in key_values {
+                        if let Value::BulkString(key) = key_value {
+                            keys.push(key);
+                        }
+                    }
+                }
+                return Ok((next_cursor, keys));
+            }
+        }
+
+        bail! {Error::RedisResultError(format!(
+            "unexpected result for \"SCAN {}\"",
+            cursor
+        ))}
+    }
+
+    async fn migrate_key(&mut self, db_id: i64, key: Vec<u8>) -> anyhow::Result<()> {
+        let entry = if let Some(entry) = Self::dump_key(&mut self.conn, db_id, key).await? {
+            entry
+        } else {
+            // the key was deleted or expired after SCAN returned it
+            return Ok(());
+        };
+
+        if let Some(data_marker) = &self.base_extractor.data_marker {
+            if data_marker.is_redis_marker_info(&entry) {
+                return Ok(());
+            }
+        }
+
+        RedisPsyncExtractor::push_to_buf(
+            &mut self.base_extractor,
+            &mut self.filter,
+            entry,
+            Position::None,
+        )
+        .await
+    }
+
+    /// Read a key by DUMP + PTTL and build an entry the same as it was parsed from RDB,
+    /// returns None if the key no longer exists.
+    pub async fn dump_key(
+        conn: &mut Connection,
+        db_id: i64,
+        key: Vec<u8>,
+    ) -> anyhow::Result<Option<RedisEntry>> {
+        let dump_cmd = RedisCmd::from_args(vec!["DUMP".as_bytes().to_vec(), key.clone()]);
+        let payload = match conn.req_packed_command(&CmdEncoder::encode(&dump_cmd))? {
+            Value::BulkString(payload) => payload,
+            Value::Nil => return Ok(None),
+            v => {
+                bail! {Error::RedisResultError(format!(
+                    "unexpected result for DUMP, key: {}, result: {:?}",
+                    String::from_utf8_lossy(&key),
+                    v
+                ))}
+            }
+        };
+
+        if payload.len() <= DUMP_FOOTER_LEN {
+            bail! {Error::RedisRdbError(format!(
+                "invalid DUMP payload, key: {}",
+                String::from_utf8_lossy(&key)
+            ))}
+        }
+
+        let pttl_cmd = RedisCmd::from_args(vec!["PTTL".as_bytes().to_vec(), key.clone()]);
+        let expire_ms = match conn.req_packed_command(&CmdEncoder::encode(&pttl_cmd))? {
+            // -2: key not exists, expired between DUMP and PTTL
+            Value::Int(-2) => return Ok(None),
+            // -1: no expire
+            Value::Int(pttl) if pttl > 0 => pttl,
+            _ => 0,
+        };
+
+        let type_byte = payload[0];
+        let value_bytes = &payload[1..payload.len() - DUMP_FOOTER_LEN];
+        let key = RedisString::from(key);
+
+        // DUMP serializes the value in RDB format, so parse it by EntryParser
+        let mut cursor = Cursor::new(value_bytes);
+        let mut stream_reader: Box<&mut (dyn StreamReader + Send)> = Box::new(&mut cursor);
+        let mut reader = RdbReader {
+            conn: &mut stream_reader,
+            rdb_length: value_bytes.len(),
+            position: 0,
+            copy_raw: false,
+            raw_bytes: Vec::new(),
+        };
+        let value = match EntryParser::parse_object(&mut reader, type_byte, key.clone()).await {
+            Ok(value) => value,
+            Err(error) => {
+                bail! {Error::RedisRdbError(format!(
+                    "parsing DUMP payload failed, type_byte: {}, key: {}, error: {:?}",
+                    type_byte, key, error
+                ))}
+            }
+        };
+
+        let mut entry = RedisEntry::new();
+        entry.is_base = true;
+        entry.db_id = db_id;
+        entry.key = key;
+        entry.value = value;
+        entry.value_type_byte = type_byte;
+        entry.raw_bytes = value_bytes.to_vec();
+        entry.expire_ms = expire_ms;
+        Ok(Some(entry))
+    }
+}
+
+/// incremental sync by keyspace notifications
+impl RedisScanExtractor {
+    async fn start_keyspace_event_listener(
+        &mut self,
+    ) -> anyhow::Result<UnboundedReceiver<(i64, Vec<u8>)>> {
+        let notify_config = self.get_config("notify-keyspace-events").await?;
+        Self::check_notify_config(&notify_config)?;
+
+        let mut conn = RedisUtil::create_redis_conn(&self.url).await?;
+        // read timeout to check shut_down periodically
+        conn.set_read_timeout(Some(Duration::from_secs(1)))?;
+
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let shut_down = self.base_extractor.shut_down.clone();
+        tokio::task::spawn_blocking(move || {
+            if let Err(err) = Self::listen_keyspace_events(&mut conn, sender, shut_down) {
+                log_error!("keyspace events listener exits, error: {:?}", err);
+            }
+        });
+        log_info!("keyspace events listener started");
+        Ok(receiver)
+    }
+
+    /// notify-keyspace-events should contain E (keyevent) and A or all the event classes,
+    /// otherwise changes of some keys are not notified
+    fn check_notify_config(notify_config: &str) -> anyhow::Result<()> {
+        let missing: String = if notify_config.contains('A') {
+            String::new()
+        } else {
+            KEYEVENT_CLASSES
+                .chars()
+                .filter(|c| !notify_config.contains(*c))
+                .collect()
+        };
+        if !notify_config.contains('E') || !missing.is_empty() {
+            bail! {Error::MetadataError(format!(
+                "notify-keyspace-events is [{}], should contain E and A (or all of: {}), e.g. KEA",
+                notify_config, KEYEVENT_CLASSES
+            ))}
+        }
+        Ok(())
+    }
+
+    fn listen_keyspace_events(
+        conn: &mut Connection,
+        sender: UnboundedSender<(i64, Vec<u8>)>,
+        shut_down: Arc<AtomicBool>,
+    ) -> anyhow::Result<()> {
+        let mut pubsub = conn.as_pubsub();
+        pubsub.psubscribe(KEYEVENT_CHANNEL_PATTERN)?;
+
+        while !shut_down.load(Ordering::Acquire) {
+            let msg = match pubsub.get_message() {
+                Ok(msg) => msg,
+                Err(err) if err.is_timeout() => continue,
+                Err(err) => return Err(err.into()),
+            };
+
+            // channel: __keyevent@0__:set, payload: key
+            let channel = msg.get_channel_name();
+            let db_id = channel
+                .strip_prefix("__keyevent@")
+                .and_then(|s| s.split("__:").next())
+                .and_then(|s| s.parse::<i64>().ok());
+            if let Some(db_id) = db_id {
+                if sender
+                    .send((db_id, msg.get_payload_bytes().to_vec()))
+                    .is_err()
+                {
+                    break;
+                }
+            } else {
+                log_warn!("unexpected keyspace event channel: {}", channel);
+            }
+        }
+        Ok(())
+    }
+
+    async fn receive_keyspace_events(
+        &mut self,
+        mut receiver: UnboundedReceiver<(i64, Vec<u8>)>,
+    ) -> anyhow::Result<()> {
+        log_info!("start receiving keyspace events");
+        let mut now_db_id = -1;
+        while let Some((db_id, key)) = receiver.recv().await {
+            if self.filter.filter_schema(&db_id.to_string()) {
+                continue;
+            }
+
+            if db_id != now_db_id {
+                let db = db_id.to_string();
+                let cmd = ["SELECT", &db];
+                if Value::Okay != RedisUtil::send_cmd(&mut self.conn, &cmd)? {
+                    bail! {Error::RedisResultError(format!("\"SELECT {}\" failed", db))}
+                }
+                now_db_id = db_id;
+            }
+
+            // the event only tells which key changed, read the latest value of the key,
+            // if the key no longer exists, delete it in target
+            let entry = match Self::dump_key(&mut self.conn, db_id, key.clone()).await? {
+                Some(entry) => entry,
+                None => {
+                    let mut entry = RedisEntry::new();
+                    entry.db_id = db_id;
+                    entry.cmd = RedisCmd::from_args(vec!["DEL".as_bytes().to_vec(), key]);
+                    entry
+                }
+            };
+
+            if let Some(data_marker) = &self.base_extractor.data_marker {
+                if data_marker.is_redis_marker_info(&entry) {
+                    continue;
+                }
+            }
+
+            RedisPsyncExtractor::push_to_buf(
+                &mut self.base_extractor,
+                &mut self.filter,
+                entry,
+                Position::None,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+}
+
 impl RedisScanExtractor {
     async fn get_dbs(&mut self) -> anyhow::Result<Vec<String>> {
         let mut dbs = Vec::new();
@@ -90,8 +388,8 @@ impl RedisScanExtractor {
         Ok(dbs)
     }
 
-    async fn get_maxmemory_policy(&mut self) -> anyhow::Result<String> {
-        let cmd = ["CONFIG", "GET", "maxmemory-policy"];
+    async fn get_config(&mut self, name: &str) -> anyhow::Result<String> {
+        let cmd = ["CONFIG", "GET", name];
         let result = self.query(&cmd).await?;
         if result.len() > 1 {
             return Ok(result[1].clone());
@@ -99,6 +397,14 @@ impl RedisScanExtractor {
         Ok(String::new())
     }
 
+    async fn query(&mut self, cmd: &[&str]) -> anyhow::Result<Vec<String>> {
+        let result = RedisUtil::send_cmd(&mut self.conn, cmd)?;
+        RedisUtil::parse_result_as_string(result)
+    }
+}
+
+/// statistic
+impl RedisScanExtractor {
     async fn analyze_hot_key(&mut self, db_id: i64, key: &str) -> anyhow::Result<()> {
         let cmd = ["OBJECT", "FREQ", key];
         let result = self.query(&cmd).await?;
@@ -133,9 +439,23 @@ impl RedisScanExtractor {
             .push_dt_data(DtData::Redis { entry }, Position::None)
             .await
     }
+}
 
-    async fn query(&mut self, cmd: &[&str]) -> anyhow::Result<Vec<String>> {
-        let result = RedisUtil::send_cmd(&mut self.conn, cmd)?;
-        RedisUtil::parse_result_as_string(result)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_notify_config() {
+        assert!(RedisScanExtractor::check_notify_config("KEA").is_ok());
+        assert!(RedisScanExtractor::check_notify_config("AE").is_ok());
+        assert!(RedisScanExtractor::check_notify_config("g$lshzxetE").is_ok());
+        // no keyevent notifications
+        assert!(RedisScanExtractor::check_notify_config("KA").is_err());
+        // no event classes
+        assert!(RedisScanExtractor::check_notify_config("E").is_err());
+        // missing some event classes
+        assert!(RedisScanExtractor::check_notify_config("Eg$").is_err());
+        assert!(RedisScanExtractor::check_notify_config("").is_err());
     }
 }
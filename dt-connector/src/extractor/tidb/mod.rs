@@ -0,0 +1,2 @@
+pub mod ticdc_decoder;
+pub mod tidb_cdc_extractor;
@@ -0,0 +1,703 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use base64::{prelude::BASE64_STANDARD, Engine};
+use serde::Deserialize;
+use serde_json::Value;
+
+use dt_common::{
+    config::config_enums::TicdcProtocol,
+    error::Error,
+    meta::{col_value::ColValue, row_data::RowData, row_type::RowType},
+};
+
+// refer: https://docs.pingcap.com/tidb/stable/ticdc-open-protocol
+const OPEN_PROTOCOL_BATCH_VERSION: u64 = 1;
+const OPEN_PROTOCOL_ROW: u8 = 1;
+const OPEN_PROTOCOL_DDL: u8 = 2;
+const OPEN_PROTOCOL_RESOLVED: u8 = 3;
+const BINARY_FLAG: u64 = 1;
+const GENERATED_COLUMN_FLAG: u64 = 1 << 2;
+const UNSIGNED_FLAG: u64 = 1 << 7;
+
+// refer: https://docs.pingcap.com/tidb/stable/ticdc-canal-json
+const CANAL_JSON_WATERMARK: &str = "TIDB_WATERMARK";
+
+/// an event decoded from a kafka message sinked by TiCDC
+#[derive(Debug, Clone)]
+pub enum TicdcEvent {
+    Row {
+        row_data: RowData,
+        commit_ts: u64,
+    },
+    Ddl {
+        schema: String,
+        query: String,
+        commit_ts: u64,
+    },
+    // all events with commit_ts <= resolved_ts have been sinked
+    Resolved {
+        resolved_ts: u64,
+    },
+}
+
+impl TicdcEvent {
+    pub fn get_ts(&self) -> u64 {
+        match self {
+            Self::Row { commit_ts, .. } | Self::Ddl { commit_ts, .. } => *commit_ts,
+            Self::Resolved { resolved_ts } => *resolved_ts,
+        }
+    }
+}
+
+pub struct TicdcDecoder {
+    pub protocol: TicdcProtocol,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ColType {
+    Tiny,
+    Short,
+    Long,
+    LongLong,
+    Float,
+    Double,
+    Decimal,
+    Date,
+    Time,
+    DateTime,
+    Timestamp,
+    Year,
+    Bit,
+    Enum,
+    Set,
+    Json,
+    String,
+    Blob,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CanalJsonMessage {
+    #[serde(default)]
+    database: String,
+    #[serde(default)]
+    table: String,
+    #[serde(default)]
+    is_ddl: bool,
+    #[serde(rename = "type", default)]
+    event_type: String,
+    // commit time of the transaction in millis
+    #[serde(default)]
+    es: u64,
+    #[serde(default)]
+    sql: String,
+    #[serde(default)]
+    mysql_type: Option<HashMap<String, String>>,
+    #[serde(default)]
+    data: Option<Vec<HashMap<String, Value>>>,
+    #[serde(default)]
+    old: Option<Vec<HashMap<String, Value>>>,
+    // only exists if enable-tidb-extension=true in sink-uri
+    #[serde(rename = "_tidb", default)]
+    tidb: Option<CanalJsonTidbExtension>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CanalJsonTidbExtension {
+    #[serde(default)]
+    commit_ts: u64,
+    #[serde(default)]
+    watermark_ts: u64,
+}
+
+#[derive(Deserialize)]
+struct OpenProtocolKey {
+    ts: u64,
+    #[serde(default)]
+    scm: String,
+    #[serde(default)]
+    tbl: String,
+    t: u8,
+}
+
+#[derive(Deserialize)]
+struct OpenProtocolColumn {
+    t: u64,
+    #[serde(default)]
+    f: u64,
+    #[serde(default)]
+    v: Value,
+}
+
+#[derive(Deserialize)]
+struct OpenProtocolRow {
+    // insert / update
+    u: Option<HashMap<String, OpenProtocolColumn>>,
+    // old values of update, only exists if enable-old-value=true
+    p: Option<HashMap<String, OpenProtocolColumn>>,
+    // delete
+    d: Option<HashMap<String, OpenProtocolColumn>>,
+}
+
+#[derive(Deserialize)]
+struct OpenProtocolDdl {
+    q: String,
+}
+
+impl TicdcDecoder {
+    pub fn decode(
+        &self,
+        key: Option<&[u8]>,
+        value: Option<&[u8]>,
+    ) -> anyhow::Result<Vec<TicdcEvent>> {
+        match self.protocol {
+            TicdcProtocol::CanalJson => Self::decode_canal_json(value.unwrap_or_default()),
+            TicdcProtocol::OpenProtocol => {
+                Self::decode_open_protocol(key.unwrap_or_default(), value.unwrap_or_default())
+            }
+        }
+    }
+
+    fn decode_canal_json(value: &[u8]) -> anyhow::Result<Vec<TicdcEvent>> {
+        let mut events = Vec::new();
+        // a message may contain multiple events separated by "\n"
+        for message in serde_json::Deserializer::from_slice(value).into_iter::<CanalJsonMessage>() {
+            let message = match message {
+                Ok(message) => message,
+                Err(err) => bail! {Error::ExtractorError(format!(
+                    "invalid canal-json message: {}",
+                    err
+                ))},
+            };
+
+            let tidb = message.tidb.as_ref();
+            if message.event_type == CANAL_JSON_WATERMARK {
+                let resolved_ts = tidb.map_or(0, |i| i.watermark_ts);
+                events.push(TicdcEvent::Resolved { resolved_ts });
+                continue;
+            }
+
+            // without enable-tidb-extension, use the commit time as the physical part of the tso
+            let commit_ts = match tidb {
+                Some(tidb) if tidb.commit_ts > 0 => tidb.commit_ts,
+                _ => message.es << 18,
+            };
+
+            if message.is_ddl {
+                events.push(TicdcEvent::Ddl {
+                    schema: message.database,
+                    query: message.sql,
+                    commit_ts,
+                });
+                continue;
+            }
+
+            let row_type = match message.event_type.as_str() {
+                "INSERT" => RowType::Insert,
+                "UPDATE" => RowType::Update,
+                "DELETE" => RowType::Delete,
+                event_type => bail! {Error::ExtractorError(format!(
+                    "unsupported canal-json event type: {}",
+                    event_type
+                ))},
+            };
+
+            let mysql_types = message.mysql_type.unwrap_or_default();
+            let old = message.old.unwrap_or_default();
+            for (i, data) in message.data.unwrap_or_default().iter().enumerate() {
+                let values = Self::canal_json_col_values(&mysql_types, data)?;
+                let (before, after) = match row_type {
+                    RowType::Insert => (None, Some(values)),
+                    RowType::Delete => (Some(values), None),
+                    RowType::Update => {
+                        // old may only contain the updated cols
+                        let mut before = values.clone();
+                        if let Some(old) = old.get(i) {
+                            before.extend(Self::canal_json_col_values(&mysql_types, old)?);
+                        }
+                        (Some(before), Some(values))
+                    }
+                };
+                let row_data = RowData::new(
+                    message.database.clone(),
+                    message.table.clone(),
+                    row_type.clone(),
+                    before,
+                    after,
+                );
+                events.push(TicdcEvent::Row {
+                    row_data,
+                    commit_ts,
+                });
+            }
+        }
+        Ok(events)
+    }
+
+    fn canal_json_col_values(
+        mysql_types: &HashMap<String, String>,
+        data: &HashMap<String, Value>,
+    ) -> anyhow::Result<HashMap<String, ColValue>> {
+        let mut col_values = HashMap::new();
+        for (col, value) in data.iter() {
+            let (col_type, unsigned) = match mysql_types.get(col) {
+                Some(mysql_type) => ColType::from_mysql_type(mysql_type),
+                None => (ColType::String, false),
+            };
+            // binary values are encoded in ISO-8859-1
+            let decode_binary =
+                |v: &str| -> Vec<u8> { v.chars().map(|c| c as u32 as u8).collect() };
+            let col_value = Self::to_col_value(col, col_type, unsigned, value, decode_binary)?;
+            col_values.insert(col.clone(), col_value);
+        }
+        Ok(col_values)
+    }
+
+    fn decode_open_protocol(key: &[u8], value: &[u8]) -> anyhow::Result<Vec<TicdcEvent>> {
+        // key: [version][key_len][key][key_len][key]..., value: [value_len][value][value_len][value]...
+        if key.len() < 8 {
+            bail! {Error::ExtractorError(
+                "invalid open-protocol message, key is too short".into()
+            )}
+        }
+        let version = u64::from_be_bytes(key[..8].try_into()?);
+        if version != OPEN_PROTOCOL_BATCH_VERSION {
+            bail! {Error::ExtractorError(format!(
+                "unsupported open-protocol batch version: {}",
+                version
+            ))}
+        }
+
+        let keys = Self::split_open_protocol_batch(&key[8..])?;
+        let values = Self::split_open_protocol_batch(value)?;
+        let mut events = Vec::new();
+        for (i, key) in keys.iter().enumerate() {
+            let key: OpenProtocolKey = Self::from_open_protocol_json(key)?;
+            let value = values.get(i).copied().unwrap_or_default();
+            match key.t {
+                OPEN_PROTOCOL_ROW => {
+                    let row: OpenProtocolRow = Self::from_open_protocol_json(value)?;
+                    let row_data = Self::open_protocol_row_data(key.scm, key.tbl, row)?;
+                    events.push(TicdcEvent::Row {
+                        row_data,
+                        commit_ts: key.ts,
+                    });
+                }
+
+                OPEN_PROTOCOL_DDL => {
+                    let ddl: OpenProtocolDdl = Self::from_open_protocol_json(value)?;
+                    events.push(TicdcEvent::Ddl {
+                        schema: key.scm,
+                        query: ddl.q,
+                        commit_ts: key.ts,
+                    });
+                }
+
+                OPEN_PROTOCOL_RESOLVED => events.push(TicdcEvent::Resolved {
+                    resolved_ts: key.ts,
+                }),
+
+                t => bail! {Error::ExtractorError(format!(
+                    "unsupported open-protocol event type: {}",
+                    t
+                ))},
+            }
+        }
+        Ok(events)
+    }
+
+    fn split_open_protocol_batch(mut data: &[u8]) -> anyhow::Result<Vec<&[u8]>> {
+        let mut items = Vec::new();
+        while !data.is_empty() {
+            if data.len() < 8 {
+                bail! {Error::ExtractorError(
+                    "invalid open-protocol message, length is too short".into()
+                )}
+            }
+            let len = u64::from_be_bytes(data[..8].try_into()?) as usize;
+            if data.len() < 8 + len {
+                bail! {Error::ExtractorError(format!(
+                    "invalid open-protocol message, length: {} exceeds the message",
+                    len
+                ))}
+            }
+            items.push(&data[8..8 + len]);
+            data = &data[8 + len..];
+        }
+        Ok(items)
+    }
+
+    fn from_open_protocol_json<'a, T: Deserialize<'a>>(data: &'a [u8]) -> anyhow::Result<T> {
+        match serde_json::from_slice(data) {
+            Ok(v) => Ok(v),
+            Err(err) => bail! {Error::ExtractorError(format!(
+                "invalid open-protocol message: {}, data: {}",
+                err,
+                String::from_utf8_lossy(data)
+            ))},
+        }
+    }
+
+    fn open_protocol_row_data(
+        schema: String,
+        tb: String,
+        row: OpenProtocolRow,
+    ) -> anyhow::Result<RowData> {
+        let row_data = match (row.u, row.p, row.d) {
+            (Some(after), Some(before), _) => RowData::new(
+                schema,
+                tb,
+                RowType::Update,
+                Some(Self::open_protocol_col_values(before)?),
+                Some(Self::open_protocol_col_values(after)?),
+            ),
+            // updates without old values are written as inserts
+            (Some(after), None, _) => RowData::new(
+                schema,
+                tb,
+                RowType::Insert,
+                None,
+                Some(Self::open_protocol_col_values(after)?),
+            ),
+            (None, _, Some(before)) => RowData::new(
+                schema,
+                tb,
+                RowType::Delete,
+                Some(Self::open_protocol_col_values(before)?),
+                None,
+            ),
+            _ => bail! {Error::ExtractorError(format!(
+                "invalid open-protocol row event of {}.{}",
+                schema, tb
+            ))},
+        };
+        Ok(row_data)
+    }
+
+    fn open_protocol_col_values(
+        columns: HashMap<String, OpenProtocolColumn>,
+    ) -> anyhow::Result<HashMap<String, ColValue>> {
+        let mut col_values = HashMap::new();
+        for (col, column) in columns.iter() {
+            // generated columns can not be written
+            if column.f & GENERATED_COLUMN_FLAG != 0 {
+                continue;
+            }
+            let col_type = ColType::from_type_code(column.t, column.f & BINARY_FLAG != 0);
+            let unsigned = column.f & UNSIGNED_FLAG != 0;
+            // binary values are encoded in base64
+            let decode_binary = |v: &str| {
+                BASE64_STANDARD
+                    .decode(v)
+                    .unwrap_or_else(|_| v.as_bytes().to_vec())
+            };
+            let col_value = Self::to_col_value(col, col_type, unsigned, &column.v, decode_binary)?;
+            col_values.insert(col.clone(), col_value);
+        }
+        Ok(col_values)
+    }
+
+    fn to_col_value(
+        col: &str,
+        col_type: ColType,
+        unsigned: bool,
+        value: &Value,
+        decode_binary: impl Fn(&str) -> Vec<u8>,
+    ) -> anyhow::Result<ColValue> {
+        let str = match value {
+            Value::Null => return Ok(ColValue::None),
+            Value::String(v) => v.to_owned(),
+            v => v.to_string(),
+        };
+
+        let col_value = match col_type {
+            ColType::Tiny if unsigned => str.parse().map(ColValue::UnsignedTiny).ok(),
+            ColType::Tiny => str.parse().map(ColValue::Tiny).ok(),
+            ColType::Short if unsigned => str.parse().map(ColValue::UnsignedShort).ok(),
+            ColType::Short => str.parse().map(ColValue::Short).ok(),
+            ColType::Long if unsigned => str.parse().map(ColValue::UnsignedLong).ok(),
+            ColType::Long => str.parse().map(ColValue::Long).ok(),
+            ColType::LongLong if unsigned => str.parse().map(ColValue::UnsignedLongLong).ok(),
+            ColType::LongLong => str.parse().map(ColValue::LongLong).ok(),
+            ColType::Float => str.parse().map(ColValue::Float).ok(),
+            ColType::Double => str.parse().map(ColValue::Double).ok(),
+            ColType::Year => str.parse().map(ColValue::Year).ok(),
+            ColType::Bit => str.parse().map(ColValue::Bit).ok(),
+            ColType::Decimal => Some(ColValue::Decimal(str)),
+            ColType::Date => Some(ColValue::Date(str)),
+            ColType::Time => Some(ColValue::Time(str)),
+            ColType::DateTime => Some(ColValue::DateTime(str)),
+            ColType::Timestamp => Some(ColValue::Timestamp(str)),
+            ColType::Json => Some(ColValue::Json2(str)),
+            ColType::String => Some(ColValue::String(str)),
+            ColType::Blob => Some(ColValue::Blob(decode_binary(&str))),
+            // the index of enum / the bitmap of set, or the literal values
+            ColType::Enum => match str.parse() {
+                Ok(v) => Some(ColValue::Enum(v)),
+                Err(_) => Some(ColValue::Enum2(str)),
+            },
+            ColType::Set => match str.parse() {
+                Ok(v) => Some(ColValue::Set(v)),
+                Err(_) => Some(ColValue::Set2(str)),
+            },
+        };
+
+        match col_value {
+            Some(col_value) => Ok(col_value),
+            None => bail! {Error::ExtractorError(format!(
+                "invalid value of col: {}, type: {:?}, value: {}",
+                col, col_type, value
+            ))},
+        }
+    }
+}
+
+impl ColType {
+    /// mysqlType in canal-json, eg: int, int unsigned, varchar(255)
+    fn from_mysql_type(mysql_type: &str) -> (Self, bool) {
+        let mysql_type = mysql_type.to_lowercase();
+        let unsigned = mysql_type.contains("unsigned");
+        let name = mysql_type.split(['(', ' ']).next().unwrap_or_default();
+        let col_type = match name {
+            "tinyint" | "bool" | "boolean" => Self::Tiny,
+            "smallint" => Self::Short,
+            "mediumint" | "int" | "integer" => Self::Long,
+            "bigint" => Self::LongLong,
+            "float" => Self::Float,
+            "double" | "real" => Self::Double,
+            "decimal" | "numeric" => Self::Decimal,
+            "date" => Self::Date,
+            "time" => Self::Time,
+            "datetime" => Self::DateTime,
+            "timestamp" => Self::Timestamp,
+            "year" => Self::Year,
+            "bit" => Self::Bit,
+            "enum" => Self::Enum,
+            "set" => Self::Set,
+            "json" => Self::Json,
+            "binary" | "varbinary" | "tinyblob" | "blob" | "mediumblob" | "longblob" => Self::Blob,
+            _ => Self::String,
+        };
+        (col_type, unsigned)
+    }
+
+    /// mysql type code in open-protocol, refer: https://docs.pingcap.com/tidb/stable/ticdc-open-protocol#column-type-code
+    fn from_type_code(code: u64, binary: bool) -> Self {
+        match code {
+            1 => Self::Tiny,
+            2 => Self::Short,
+            3 | 9 => Self::Long,
+            8 => Self::LongLong,
+            4 => Self::Float,
+            5 => Self::Double,
+            0 | 246 => Self::Decimal,
+            10 | 14 => Self::Date,
+            11 => Self::Time,
+            12 => Self::DateTime,
+            7 => Self::Timestamp,
+            13 => Self::Year,
+            16 => Self::Bit,
+            245 => Self::Json,
+            247 => Self::Enum,
+            248 => Self::Set,
+            15 | 249..=254 if binary => Self::Blob,
+            _ => Self::String,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_protocol_message(items: &[(&str, &str)]) -> (Vec<u8>, Vec<u8>) {
+        let mut key = OPEN_PROTOCOL_BATCH_VERSION.to_be_bytes().to_vec();
+        let mut value = Vec::new();
+        for (k, v) in items {
+            key.extend_from_slice(&(k.len() as u64).to_be_bytes());
+            key.extend_from_slice(k.as_bytes());
+            value.extend_from_slice(&(v.len() as u64).to_be_bytes());
+            value.extend_from_slice(v.as_bytes());
+        }
+        (key, value)
+    }
+
+    fn decode(protocol: TicdcProtocol, key: &[u8], value: &[u8]) -> Vec<TicdcEvent> {
+        TicdcDecoder { protocol }
+            .decode(Some(key), Some(value))
+            .unwrap()
+    }
+
+    fn unwrap_row(event: &TicdcEvent) -> (&RowData, u64) {
+        match event {
+            TicdcEvent::Row {
+                row_data,
+                commit_ts,
+            } => (row_data, *commit_ts),
+            _ => panic!("not a row event: {:?}", event),
+        }
+    }
+
+    #[test]
+    fn test_decode_canal_json_rows() {
+        // recorded from TiCDC v7.5 with sink-uri: protocol=canal-json&enable-tidb-extension=true
+        let insert = r#"{"id":0,"database":"test_db_1","table":"tb_1","pkNames":["id"],"isDdl":false,"type":"INSERT","es":1716111749215,"ts":1716111750012,"sql":"","sqlType":{"id":4,"c_tiny":-6,"c_ubig":-5,"c_double":8,"c_decimal":3,"c_datetime":93,"c_varchar":12,"c_blob":2004,"c_json":12,"c_enum":4,"c_bit":-7},"mysqlType":{"id":"int","c_tiny":"tinyint","c_ubig":"bigint unsigned","c_double":"double","c_decimal":"decimal","c_datetime":"datetime","c_varchar":"varchar","c_blob":"blob","c_json":"json","c_enum":"enum","c_bit":"bit"},"old":null,"data":[{"id":"1","c_tiny":"-128","c_ubig":"18446744073709551615","c_double":"1.5","c_decimal":"123.450","c_datetime":"2024-05-19 09:42:29","c_varchar":"abc","c_blob":"\u0000ÿ","c_json":"{\"k\": 1}","c_enum":"2","c_bit":"5"}],"_tidb":{"commitTs":449868398386331649}}"#;
+        let events = decode(TicdcProtocol::CanalJson, &[], insert.as_bytes());
+        assert_eq!(events.len(), 1);
+        let (row_data, commit_ts) = unwrap_row(&events[0]);
+        assert_eq!(commit_ts, 449868398386331649);
+        assert_eq!(row_data.schema, "test_db_1");
+        assert_eq!(row_data.tb, "tb_1");
+        assert_eq!(row_data.row_type, RowType::Insert);
+        assert!(row_data.before.is_none());
+        let after = row_data.after.as_ref().unwrap();
+        assert_eq!(after["id"], ColValue::Long(1));
+        assert_eq!(after["c_tiny"], ColValue::Tiny(-128));
+        assert_eq!(after["c_ubig"], ColValue::UnsignedLongLong(u64::MAX));
+        assert_eq!(after["c_double"], ColValue::Double(1.5));
+        assert_eq!(after["c_decimal"], ColValue::Decimal("123.450".into()));
+        assert_eq!(
+            after["c_datetime"],
+            ColValue::DateTime("2024-05-19 09:42:29".into())
+        );
+        assert_eq!(after["c_varchar"], ColValue::String("abc".into()));
+        assert_eq!(after["c_blob"], ColValue::Blob(vec![0, 255]));
+        assert_eq!(after["c_json"], ColValue::Json2(r#"{"k": 1}"#.into()));
+        assert_eq!(after["c_enum"], ColValue::Enum(2));
+        assert_eq!(after["c_bit"], ColValue::Bit(5));
+
+        let update = r#"{"id":0,"database":"test_db_1","table":"tb_1","pkNames":["id"],"isDdl":false,"type":"UPDATE","es":1716111749215,"ts":1716111750012,"sql":"","sqlType":{"id":4,"c_varchar":12},"mysqlType":{"id":"int","c_varchar":"varchar"},"old":[{"c_varchar":"abc"}],"data":[{"id":"1","c_varchar":null}],"_tidb":{"commitTs":449868398386331650}}"#;
+        let events = decode(TicdcProtocol::CanalJson, &[], update.as_bytes());
+        let (row_data, commit_ts) = unwrap_row(&events[0]);
+        assert_eq!(commit_ts, 449868398386331650);
+        assert_eq!(row_data.row_type, RowType::Update);
+        let before = row_data.before.as_ref().unwrap();
+        let after = row_data.after.as_ref().unwrap();
+        assert_eq!(before["id"], ColValue::Long(1));
+        assert_eq!(before["c_varchar"], ColValue::String("abc".into()));
+        assert_eq!(after["c_varchar"], ColValue::None);
+
+        // without enable-tidb-extension
+        let delete = r#"{"id":0,"database":"test_db_1","table":"tb_1","pkNames":["id"],"isDdl":false,"type":"DELETE","es":1716111749215,"ts":1716111750012,"sql":"","sqlType":{"id":4},"mysqlType":{"id":"int unsigned"},"old":null,"data":[{"id":"1"},{"id":"2"}]}"#;
+        let events = decode(TicdcProtocol::CanalJson, &[], delete.as_bytes());
+        assert_eq!(events.len(), 2);
+        let (row_data, commit_ts) = unwrap_row(&events[1]);
+        assert_eq!(commit_ts, 1716111749215 << 18);
+        assert_eq!(row_data.row_type, RowType::Delete);
+        assert!(row_data.after.is_none());
+        assert_eq!(
+            row_data.before.as_ref().unwrap()["id"],
+            ColValue::UnsignedLong(2)
+        );
+    }
+
+    #[test]
+    fn test_decode_canal_json_ddl_and_watermark() {
+        let ddl = r#"{"id":0,"database":"test_db_1","table":"tb_1","pkNames":null,"isDdl":true,"type":"ALTER","es":1716111749215,"ts":1716111750012,"sql":"ALTER TABLE `tb_1` ADD COLUMN `c_new` INT","sqlType":null,"mysqlType":null,"data":null,"old":null,"_tidb":{"commitTs":449868398386331651}}"#;
+        let watermark = r#"{"id":0,"database":"","table":"","pkNames":null,"isDdl":false,"type":"TIDB_WATERMARK","es":1716111749215,"ts":1716111750012,"sql":"","sqlType":null,"mysqlType":null,"data":null,"old":null,"_tidb":{"watermarkTs":449868398386331652}}"#;
+        let value = format!("{}\n{}", ddl, watermark);
+        let events = decode(TicdcProtocol::CanalJson, &[], value.as_bytes());
+        assert_eq!(events.len(), 2);
+
+        match &events[0] {
+            TicdcEvent::Ddl {
+                schema,
+                query,
+                commit_ts,
+            } => {
+                assert_eq!(schema, "test_db_1");
+                assert_eq!(query, "ALTER TABLE `tb_1` ADD COLUMN `c_new` INT");
+                assert_eq!(*commit_ts, 449868398386331651);
+            }
+            event => panic!("not a ddl event: {:?}", event),
+        }
+        assert_eq!(events[1].get_ts(), 449868398386331652);
+        assert!(matches!(events[1], TicdcEvent::Resolved { .. }));
+
+        let invalid = TicdcDecoder {
+            protocol: TicdcProtocol::CanalJson,
+        }
+        .decode(None, Some(b"{\"type\":\"INSERT\",\"data\":"));
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_decode_open_protocol() {
+        // recorded from TiCDC v7.5 with sink-uri: protocol=open-protocol
+        let (key, value) = open_protocol_message(&[
+            (
+                r#"{"ts":449868398386331649,"scm":"test_db_1","tbl":"tb_1","t":1}"#,
+                r#"{"u":{"id":{"t":3,"h":true,"f":11,"v":1},"c_ubig":{"t":8,"f":129,"v":18446744073709551615},"c_float":{"t":4,"f":64,"v":1.5},"c_varchar":{"t":15,"f":64,"v":"abc"},"c_varbinary":{"t":15,"f":65,"v":"AP8="},"c_year":{"t":13,"f":64,"v":2024},"c_set":{"t":248,"f":64,"v":3},"c_null":{"t":12,"f":64,"v":null},"c_gen":{"t":3,"f":68,"v":2}}}"#,
+            ),
+            (
+                r#"{"ts":449868398386331650,"scm":"test_db_1","tbl":"tb_1","t":1}"#,
+                r#"{"u":{"id":{"t":3,"h":true,"f":11,"v":1},"c_varchar":{"t":15,"f":64,"v":"xyz"}},"p":{"id":{"t":3,"h":true,"f":11,"v":1},"c_varchar":{"t":15,"f":64,"v":"abc"}}}"#,
+            ),
+            (
+                r#"{"ts":449868398386331650,"scm":"test_db_1","tbl":"tb_1","t":1}"#,
+                r#"{"d":{"id":{"t":3,"h":true,"f":11,"v":1}}}"#,
+            ),
+            (
+                r#"{"ts":449868398386331651,"scm":"test_db_1","tbl":"tb_1","t":2}"#,
+                r#"{"q":"TRUNCATE TABLE `tb_1`","t":11}"#,
+            ),
+            (r#"{"ts":449868398386331652,"t":3}"#, ""),
+        ]);
+        let events = decode(TicdcProtocol::OpenProtocol, &key, &value);
+        assert_eq!(events.len(), 5);
+
+        let (row_data, commit_ts) = unwrap_row(&events[0]);
+        assert_eq!(commit_ts, 449868398386331649);
+        assert_eq!(row_data.row_type, RowType::Insert);
+        let after = row_data.after.as_ref().unwrap();
+        assert_eq!(after["id"], ColValue::Long(1));
+        assert_eq!(after["c_ubig"], ColValue::UnsignedLongLong(u64::MAX));
+        assert_eq!(after["c_float"], ColValue::Float(1.5));
+        assert_eq!(after["c_varchar"], ColValue::String("abc".into()));
+        assert_eq!(after["c_varbinary"], ColValue::Blob(vec![0, 255]));
+        assert_eq!(after["c_year"], ColValue::Year(2024));
+        assert_eq!(after["c_set"], ColValue::Set(3));
+        assert_eq!(after["c_null"], ColValue::None);
+        assert!(!after.contains_key("c_gen"));
+
+        let (row_data, _) = unwrap_row(&events[1]);
+        assert_eq!(row_data.row_type, RowType::Update);
+        assert_eq!(
+            row_data.before.as_ref().unwrap()["c_varchar"],
+            ColValue::String("abc".into())
+        );
+        assert_eq!(
+            row_data.after.as_ref().unwrap()["c_varchar"],
+            ColValue::String("xyz".into())
+        );
+
+        let (row_data, _) = unwrap_row(&events[2]);
+        assert_eq!(row_data.row_type, RowType::Delete);
+        assert_eq!(row_data.before.as_ref().unwrap()["id"], ColValue::Long(1));
+
+        match &events[3] {
+            TicdcEvent::Ddl { schema, query, .. } => {
+                assert_eq!(schema, "test_db_1");
+                assert_eq!(query, "TRUNCATE TABLE `tb_1`");
+            }
+            event => panic!("not a ddl event: {:?}", event),
+        }
+        assert!(matches!(
+            events[4],
+            TicdcEvent::Resolved {
+                resolved_ts: 449868398386331652
+            }
+        ));
+
+        // wrong batch version
+        let mut invalid_key = key.clone();
+        invalid_key[7] = 2;
+        let invalid = TicdcDecoder {
+            protocol: TicdcProtocol::OpenProtocol,
+        }
+        .decode(Some(&invalid_key), Some(&value));
+        assert!(invalid.is_err());
+    }
+}
@@ -0,0 +1,234 @@
+use anyhow::Context;
+use async_trait::async_trait;
+use rdkafka::{
+    consumer::{Consumer, StreamConsumer},
+    ClientConfig, Message, Offset, TopicPartitionList,
+};
+
+use crate::{
+    extractor::{
+        base_extractor::BaseExtractor,
+        resumer::cdc_resumer::CdcResumer,
+        tidb::ticdc_decoder::{TicdcDecoder, TicdcEvent},
+    },
+    Extractor,
+};
+use dt_common::{
+    config::config_enums::{DbType, TicdcProtocol},
+    log_info,
+    meta::{dt_data::DtData, position::Position, row_data::RowData},
+    rdb_filter::RdbFilter,
+};
+
+/// extract TiDB changes from the kafka topic which TiCDC sinks to, in canal-json or open-protocol,
+/// rows of the same transaction share the same commit_ts, which is also used to resume
+pub struct TidbCdcExtractor {
+    pub base_extractor: BaseExtractor,
+    pub filter: RdbFilter,
+    pub url: String,
+    pub group: String,
+    pub topic: String,
+    pub partition: i32,
+    pub offset: i64,
+    pub protocol: TicdcProtocol,
+    pub resumer: CdcResumer,
+}
+
+#[async_trait]
+impl Extractor for TidbCdcExtractor {
+    async fn extract(&mut self) -> anyhow::Result<()> {
+        // the message of the checkpoint will be consumed again,
+        // events with ts <= checkpoint commit_ts have been synced
+        let mut resumed_commit_ts = 0;
+        if let Position::TidbCdc {
+            offset, commit_ts, ..
+        } = &self.resumer.checkpoint_position
+        {
+            self.offset = offset.to_owned();
+            resumed_commit_ts = commit_ts.to_owned();
+            log_info!("resume from: {}", self.resumer.checkpoint_position);
+        }
+
+        log_info!(
+            "TidbCdcExtractor starts, topic: {}, partition: {}, offset: {}, protocol: {}",
+            self.topic,
+            self.partition,
+            self.offset,
+            self.protocol
+        );
+        let consumer = self.create_consumer();
+        self.extract_internal(consumer, resumed_commit_ts).await?;
+        self.base_extractor.wait_task_finish().await
+    }
+}
+
+impl TidbCdcExtractor {
+    async fn extract_internal(
+        &mut self,
+        consumer: StreamConsumer,
+        resumed_commit_ts: u64,
+    ) -> anyhow::Result<()> {
+        let decoder = TicdcDecoder {
+            protocol: self.protocol.clone(),
+        };
+        // (commit_ts, position of the last row) of the transaction not committed yet
+        let mut pending_commit = None;
+        // TiCDC is at-least-once, after a changefeed restarts, events already sent may be
+        // sent again at later offsets, so rows / ddls with ts <= the last committed ts are skipped
+        let mut committed_ts = resumed_commit_ts;
+
+        loop {
+            let msg = consumer
+                .recv()
+                .await
+                .with_context(|| format!("TidbCdcExtractor failed, topic: {}", self.topic))?;
+
+            for event in decoder.decode(msg.key(), msg.payload())? {
+                let ts = event.get_ts();
+                if !matches!(event, TicdcEvent::Resolved { .. }) && ts <= committed_ts {
+                    continue;
+                }
+
+                let position = Position::TidbCdc {
+                    topic: self.topic.clone(),
+                    partition: self.partition,
+                    offset: msg.offset(),
+                    commit_ts: ts,
+                    timestamp: Position::format_tidb_tso(ts),
+                };
+                BaseExtractor::update_time_filter(
+                    &mut self.base_extractor.time_filter,
+                    ((ts >> 18) / 1000) as u32,
+                    &position,
+                );
+                if self.base_extractor.time_filter.ended {
+                    self.push_commit(&mut pending_commit, &mut committed_ts)
+                        .await?;
+                    return Ok(());
+                }
+
+                match event {
+                    TicdcEvent::Row {
+                        row_data,
+                        commit_ts,
+                    } => {
+                        if pending_commit
+                            .as_ref()
+                            .is_some_and(|(pending_ts, _)| *pending_ts != commit_ts)
+                        {
+                            self.push_commit(&mut pending_commit, &mut committed_ts)
+                                .await?;
+                        }
+                        pending_commit = Some((commit_ts, position.clone()));
+                        self.push_row(row_data, position).await?;
+                    }
+
+                    TicdcEvent::Ddl { schema, query, .. } => {
+                        self.push_commit(&mut pending_commit, &mut committed_ts)
+                            .await?;
+                        self.push_ddl(&schema, &query, position).await?;
+                        committed_ts = ts;
+                    }
+
+                    TicdcEvent::Resolved { .. } => {
+                        if !self
+                            .push_commit(&mut pending_commit, &mut committed_ts)
+                            .await?
+                        {
+                            self.base_extractor
+                                .push_dt_data(DtData::Heartbeat {}, position)
+                                .await?;
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    /// a transaction ends when rows with another commit_ts, a ddl or a resolved ts comes
+    async fn push_commit(
+        &mut self,
+        pending_commit: &mut Option<(u64, Position)>,
+        committed_ts: &mut u64,
+    ) -> anyhow::Result<bool> {
+        if let Some((commit_ts, position)) = pending_commit.take() {
+            let commit = DtData::Commit {
+                xid: commit_ts.to_string(),
+            };
+            self.base_extractor.push_dt_data(commit, position).await?;
+            *committed_ts = commit_ts;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+
+    async fn push_row(&mut self, mut row_data: RowData, position: Position) -> anyhow::Result<()> {
+        let (schema, tb) = (row_data.schema.clone(), row_data.tb.clone());
+        if self.filter.filter_event(&schema, &tb, &row_data.row_type) {
+            return Ok(());
+        }
+
+        if let Some(ignore_cols) = self.filter.get_ignore_cols(&schema, &tb) {
+            for col_values in [&mut row_data.before, &mut row_data.after]
+                .into_iter()
+                .flatten()
+            {
+                col_values.retain(|col, _| !ignore_cols.contains(col));
+            }
+            row_data.refresh_data_size();
+        }
+
+        if let Some(row_data) = self.filter.filter_row(&schema, &tb, row_data)? {
+            self.base_extractor.push_row(row_data, position).await?;
+        }
+        Ok(())
+    }
+
+    async fn push_ddl(
+        &mut self,
+        schema: &str,
+        query: &str,
+        position: Position,
+    ) -> anyhow::Result<()> {
+        if self.filter.filter_all_ddl() {
+            return Ok(());
+        }
+
+        // ddls of TiDB are in mysql syntax
+        if let Ok(Some(ddl_data)) = self
+            .base_extractor
+            .parse_ddl(&DbType::Mysql, schema, query)
+            .await
+        {
+            for sub_ddl_data in ddl_data.split_to_multi() {
+                let (db, tb) = sub_ddl_data.get_schema_tb();
+                if !self.filter.filter_ddl(&db, &tb, &sub_ddl_data.ddl_type) {
+                    self.base_extractor
+                        .push_ddl(sub_ddl_data, position.clone())
+                        .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn create_consumer(&self) -> StreamConsumer {
+        let mut config = ClientConfig::new();
+        config.set("bootstrap.servers", &self.url);
+        config.set("group.id", &self.group);
+        config.set("auto.offset.reset", "latest");
+        config.set("session.timeout.ms", "10000");
+
+        let consumer: StreamConsumer = config.create().unwrap();
+        // the same as KafkaExtractor, only support extract data from one topic, one partition
+        let mut tpl = TopicPartitionList::new();
+        if self.offset >= 0 {
+            tpl.add_partition_offset(&self.topic, self.partition, Offset::Offset(self.offset))
+                .unwrap();
+        } else {
+            tpl.add_partition(&self.topic, self.partition);
+        }
+        consumer.assign(&tpl).unwrap();
+        consumer
+    }
+}
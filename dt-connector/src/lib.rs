@@ -5,6 +5,7 @@
 pub mod check_log;
 pub mod conn_util;
 pub mod data_marker;
+pub mod dead_letter;
 pub mod extractor;
 pub mod meta_fetcher;
 pub mod rdb_query_builder;
@@ -14,8 +15,8 @@ pub mod sinker;
 use async_trait::async_trait;
 use check_log::check_log::CheckLog;
 use dt_common::meta::{
-    dcl_meta::dcl_data::DclData, ddl_meta::ddl_data::DdlData, dt_data::DtItem, row_data::RowData,
-    struct_meta::struct_data::StructData,
+    dcl_meta::dcl_data::DclData, ddl_meta::ddl_data::DdlData, dt_data::DtItem,
+    message_data::MessageData, row_data::RowData, struct_meta::struct_data::StructData,
 };
 
 #[async_trait]
@@ -24,6 +25,13 @@ pub trait Sinker {
         Ok(())
     }
 
+    // sink rows without taking them, after a failure data keeps the rows not sinked yet,
+    // so that callers (eg: RetrySinker, dead letter) can sink them again without copies,
+    // sinkers not overriding this take all rows and leave data empty
+    async fn sink_dml_ref(&mut self, data: &mut Vec<RowData>, batch: bool) -> anyhow::Result<()> {
+        self.sink_dml(std::mem::take(data), batch).await
+    }
+
     async fn sink_ddl(&mut self, mut _data: Vec<DdlData>, _batch: bool) -> anyhow::Result<()> {
         Ok(())
     }
@@ -32,6 +40,10 @@ pub trait Sinker {
         Ok(())
     }
 
+    async fn sink_message(&mut self, mut _data: Vec<MessageData>) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     async fn close(&mut self) -> anyhow::Result<()> {
         Ok(())
     }
@@ -48,6 +60,12 @@ pub trait Sinker {
         Ok(())
     }
 
+    // called before the pipeline commits a checkpoint, sinkers buffering data
+    // in memory should make it durable here
+    async fn flush(&mut self) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     fn get_id(&self) -> String {
         String::new()
     }
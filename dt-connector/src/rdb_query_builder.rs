@@ -220,7 +220,9 @@ impl RdbQueryBuilder<'_> {
             let after = row_data.after.as_ref().unwrap();
             let mut set_pairs = Vec::new();
             for col in self.rdb_tb_meta.cols.iter() {
-                if self.rdb_tb_meta.id_cols.contains(col) {
+                if self.rdb_tb_meta.id_cols.contains(col)
+                    || (row_data.partial && !after.contains_key(col))
+                {
                     continue;
                 }
                 let sql_value = self.get_sql_value(index, col, &after.get(col), placeholder)?;
@@ -231,13 +233,37 @@ impl RdbQueryBuilder<'_> {
                 index += 1;
             }
 
+            let conflict_action = if set_pairs.is_empty() {
+                "DO NOTHING".to_string()
+            } else {
+                format!("DO UPDATE SET {}", set_pairs.join(","))
+            };
             query_info.sql = format!(
-                "{} ON CONFLICT ({}) DO UPDATE SET {}",
+                "{} ON CONFLICT ({}) {}",
                 query_info.sql,
                 SqlUtil::escape_cols(&self.rdb_tb_meta.id_cols, &self.db_type).join(","),
+                conflict_action
+            );
+        } else if row_data.partial {
+            // REPLACE deletes the existing row, which resets the cols absent in a partial row image,
+            // so only update the present cols of the existing row
+            let mut set_pairs = Vec::new();
+            for col in query_info.cols.iter() {
+                if !self.rdb_tb_meta.id_cols.contains(col) {
+                    let col = self.escape(col);
+                    set_pairs.push(format!("{}=VALUES({})", col, col));
+                }
+            }
+            // no cols to update, keep the existing row
+            if set_pairs.is_empty() {
+                let col = self.escape(&self.rdb_tb_meta.id_cols[0]);
+                set_pairs.push(format!("{}={}", col, col));
+            }
+            query_info.sql = format!(
+                "{} ON DUPLICATE KEY UPDATE {}",
+                query_info.sql,
                 set_pairs.join(",")
             );
-            return Ok(query_info);
         } else {
             query_info.sql = format!("REPLACE{}", query_info.sql.trim_start_matches("INSERT"));
         }
@@ -253,14 +279,17 @@ impl RdbQueryBuilder<'_> {
         let mut binds = Vec::new();
         let after = row_data.after.as_ref().unwrap();
         for col_name in self.rdb_tb_meta.cols.iter() {
+            // cols absent in a partial row image are left to their defaults
+            if row_data.partial && !after.contains_key(col_name) {
+                continue;
+            }
             cols.push(col_name.clone());
             binds.push(after.get(col_name));
         }
 
         let mut col_values = Vec::new();
-        for i in 0..self.rdb_tb_meta.cols.len() {
-            let sql_value =
-                self.get_sql_value(i + 1, &self.rdb_tb_meta.cols[i], &binds[i], placeholder)?;
+        for i in 0..cols.len() {
+            let sql_value = self.get_sql_value(i + 1, &cols[i], &binds[i], placeholder)?;
             col_values.push(sql_value);
         }
 
@@ -268,7 +297,7 @@ impl RdbQueryBuilder<'_> {
             "INSERT INTO {}.{}({}) VALUES({})",
             self.escape(&self.rdb_tb_meta.schema),
             self.escape(&self.rdb_tb_meta.tb),
-            self.escape_cols(&self.rdb_tb_meta.cols).join(","),
+            self.escape_cols(&cols).join(","),
             col_values.join(",")
         );
 
@@ -281,7 +310,8 @@ impl RdbQueryBuilder<'_> {
         placeholder: bool,
     ) -> anyhow::Result<RdbQueryInfo<'a>> {
         let before = row_data.before.as_ref().unwrap();
-        let (where_sql, not_null_cols) = self.get_where_info(1, before, placeholder)?;
+        let (where_sql, not_null_cols) =
+            self.get_where_info(1, before, row_data.partial, placeholder)?;
         let mut sql = format!(
             "DELETE FROM {}.{} WHERE {}",
             self.escape(&self.rdb_tb_meta.schema),
@@ -326,7 +356,8 @@ impl RdbQueryBuilder<'_> {
             ))}
         }
 
-        let (where_sql, not_null_cols) = self.get_where_info(index, before, placeholder)?;
+        let (where_sql, not_null_cols) =
+            self.get_where_info(index, before, row_data.partial, placeholder)?;
         let mut sql = format!(
             "UPDATE {}.{} SET {} WHERE {}",
             self.escape(&self.rdb_tb_meta.schema),
@@ -352,7 +383,7 @@ impl RdbQueryBuilder<'_> {
 
     pub fn get_select_query<'a>(&self, row_data: &'a RowData) -> anyhow::Result<RdbQueryInfo<'a>> {
         let after = row_data.after.as_ref().unwrap();
-        let (where_sql, not_null_cols) = self.get_where_info(1, after, true)?;
+        let (where_sql, not_null_cols) = self.get_where_info(1, after, row_data.partial, true)?;
         let mut sql = format!(
             "SELECT {} FROM {}.{} WHERE {}",
             self.build_extract_cols_str()?,
@@ -435,12 +466,18 @@ impl RdbQueryBuilder<'_> {
         &self,
         mut index: usize,
         col_value_map: &HashMap<String, ColValue>,
+        partial: bool,
         placeholder: bool,
     ) -> anyhow::Result<(String, Vec<String>)> {
         let mut where_sql = String::new();
         let mut not_null_cols = Vec::new();
 
         for col in self.rdb_tb_meta.id_cols.iter() {
+            // for tables without keys, id_cols are all cols, some may be absent in a partial row image
+            if partial && !col_value_map.contains_key(col) {
+                continue;
+            }
+
             if !where_sql.is_empty() {
                 where_sql += " AND";
             }
@@ -174,6 +174,23 @@ impl RdbRouter {
                 );
             }
 
+            DdlStatement::PgTruncateMultiTable(s) => {
+                let dst_schema_tbs = s
+                    .schema_tbs
+                    .iter()
+                    .map(|(schema, tb)| {
+                        let schema = if schema.is_empty() {
+                            &ddl_data.default_schema
+                        } else {
+                            schema
+                        };
+                        let (dst_schema, dst_tb) = self.get_tb_map(schema, tb);
+                        (dst_schema.to_string(), dst_tb.to_string())
+                    })
+                    .collect();
+                s.route(dst_schema_tbs);
+            }
+
             _ => {
                 let (src_schema, src_tb) = ddl_data.get_schema_tb();
                 let (dst_schema, dst_tb) = self.get_tb_map(&src_schema, &src_tb);
@@ -1,6 +1,9 @@
 use std::sync::Arc;
 
+use anyhow::bail;
 use dt_common::{
+    error::Error,
+    meta::row_data::RowData,
     monitor::{counter_type::CounterType, monitor::Monitor},
     utils::limit_queue::LimitedQueue,
 };
@@ -40,6 +43,18 @@ impl BaseSinker {
         monitor.add_multi_counter(CounterType::RtPerQuery, rts);
         Ok(())
     }
+
+    /// sinkers writing whole rows can not apply rows with partial images
+    /// (source binlog_row_image=MINIMAL / NOBLOB), fail instead of writing defaults / NULLs
+    pub fn check_full_row_image(row_data: &RowData, sinker_type: &str) -> anyhow::Result<()> {
+        if row_data.partial {
+            bail! {Error::SinkerError(format!(
+                "{} sinker does not support partial row images, set binlog_row_image=FULL in source, schema: {}, tb: {}",
+                sinker_type, row_data.schema, row_data.tb
+            ))}
+        }
+        Ok(())
+    }
 }
 
 #[macro_export(local_inner_macros)]
@@ -58,7 +73,13 @@ macro_rules! call_batch_fn {
                 break;
             }
 
-            $batch_fn($self, &mut $data, sinked_count, batch_size).await?;
+            if let Err(error) =
+                $batch_fn($self, $data.as_mut_slice(), sinked_count, batch_size).await
+            {
+                // keep rows not sinked yet for retries
+                $data.drain(..sinked_count);
+                return Err(error);
+            }
             sinked_count += batch_size;
         }
     };
@@ -33,11 +33,20 @@ pub struct ClickhouseSinker {
 
 #[async_trait]
 impl Sinker for ClickhouseSinker {
-    async fn sink_dml(&mut self, mut data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
+    async fn sink_dml(&mut self, mut data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
+        self.sink_dml_ref(&mut data, batch).await
+    }
+
+    // rows are converted in place by batch_sink, which is idempotent for retries
+    async fn sink_dml_ref(&mut self, data: &mut Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
         if data.is_empty() {
             return Ok(());
         }
 
+        for row_data in data.iter() {
+            BaseSinker::check_full_row_image(row_data, "clickhouse")?;
+        }
+
         call_batch_fn!(self, data, Self::batch_sink);
         Ok(())
     }
@@ -0,0 +1,453 @@
+use std::sync::Arc;
+
+use parquet::{
+    basic::{Compression, LogicalType, Repetition, Type as PhysicalType},
+    data_type::{
+        BoolType, ByteArray, ByteArrayType, DataType, DoubleType, FloatType, Int32Type, Int64Type,
+    },
+    file::{
+        properties::WriterProperties,
+        writer::{SerializedColumnWriter, SerializedFileWriter},
+    },
+    schema::types::Type,
+};
+use serde_json::{Map, Value};
+
+use dt_common::{config::config_enums::FileFormat, meta::col_value::ColValue};
+
+/// csv / jsonl columns are written as nullable strings, the same text form used by check logs,
+/// parquet columns are typed by their values, see ParquetColType
+pub struct FileEncoder {}
+
+/// the parquet type of a column, decided by the ColValue variant of its first non-null value,
+/// falls back to String if all values are null or values of the column have different types.
+/// unsigned integers are stored as bitcast signed integers annotated with is_signed = false
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParquetColType {
+    Bool,
+    Int32 { bit_width: i8, is_signed: bool },
+    Int64 { is_signed: bool },
+    Float,
+    Double,
+    Binary,
+    String,
+    Json,
+}
+
+impl ParquetColType {
+    fn from_col_value(value: &ColValue) -> Option<Self> {
+        let col_type = match value {
+            ColValue::None => return None,
+            ColValue::Bool(_) => Self::Bool,
+            ColValue::Tiny(_) => Self::Int32 {
+                bit_width: 8,
+                is_signed: true,
+            },
+            ColValue::UnsignedTiny(_) => Self::Int32 {
+                bit_width: 8,
+                is_signed: false,
+            },
+            ColValue::Short(_) => Self::Int32 {
+                bit_width: 16,
+                is_signed: true,
+            },
+            ColValue::UnsignedShort(_) | ColValue::Year(_) => Self::Int32 {
+                bit_width: 16,
+                is_signed: false,
+            },
+            ColValue::Long(_) => Self::Int32 {
+                bit_width: 32,
+                is_signed: true,
+            },
+            ColValue::UnsignedLong(_) => Self::Int32 {
+                bit_width: 32,
+                is_signed: false,
+            },
+            ColValue::LongLong(_) | ColValue::Enum(_) => Self::Int64 { is_signed: true },
+            ColValue::UnsignedLongLong(_) | ColValue::Bit(_) | ColValue::Set(_) => {
+                Self::Int64 { is_signed: false }
+            }
+            ColValue::Float(_) => Self::Float,
+            ColValue::Double(_) => Self::Double,
+            ColValue::Blob(_) | ColValue::RawString(_) => Self::Binary,
+            ColValue::Json2(_) | ColValue::Json3(_) | ColValue::MongoDoc(_) => Self::Json,
+            ColValue::Decimal(_)
+            | ColValue::Time(_)
+            | ColValue::Date(_)
+            | ColValue::DateTime(_)
+            | ColValue::Timestamp(_)
+            | ColValue::String(_)
+            | ColValue::Set2(_)
+            | ColValue::Enum2(_)
+            | ColValue::Json(_) => Self::String,
+        };
+        Some(col_type)
+    }
+
+    fn of_col(rows: &[Vec<ColValue>], col_index: usize) -> Self {
+        let mut col_type = None;
+        for row in rows {
+            match (Self::from_col_value(&row[col_index]), col_type) {
+                (None, _) => {}
+                (Some(value_type), None) => col_type = Some(value_type),
+                (Some(value_type), Some(prev_type)) if value_type != prev_type => {
+                    return Self::String
+                }
+                _ => {}
+            }
+        }
+        col_type.unwrap_or(Self::String)
+    }
+
+    fn to_field(self, col: &str) -> anyhow::Result<Type> {
+        let (physical_type, logical_type) = match self {
+            Self::Bool => (PhysicalType::BOOLEAN, None),
+            Self::Int32 {
+                bit_width,
+                is_signed,
+            } => (
+                PhysicalType::INT32,
+                Some(LogicalType::Integer {
+                    bit_width,
+                    is_signed,
+                }),
+            ),
+            Self::Int64 { is_signed } => (
+                PhysicalType::INT64,
+                Some(LogicalType::Integer {
+                    bit_width: 64,
+                    is_signed,
+                }),
+            ),
+            Self::Float => (PhysicalType::FLOAT, None),
+            Self::Double => (PhysicalType::DOUBLE, None),
+            Self::Binary => (PhysicalType::BYTE_ARRAY, None),
+            Self::String => (PhysicalType::BYTE_ARRAY, Some(LogicalType::String)),
+            Self::Json => (PhysicalType::BYTE_ARRAY, Some(LogicalType::Json)),
+        };
+        Ok(Type::primitive_type_builder(col, physical_type)
+            .with_repetition(Repetition::OPTIONAL)
+            .with_logical_type(logical_type)
+            .build()?)
+    }
+}
+
+impl FileEncoder {
+    pub fn get_file_ext(file_format: &FileFormat) -> &'static str {
+        match file_format {
+            FileFormat::Parquet => "parquet",
+            FileFormat::Csv => "csv",
+            FileFormat::Jsonl => "jsonl",
+        }
+    }
+
+    pub fn encode(
+        file_format: &FileFormat,
+        cols: &[String],
+        rows: &[Vec<ColValue>],
+    ) -> anyhow::Result<Vec<u8>> {
+        match file_format {
+            FileFormat::Parquet => Self::encode_parquet(cols, rows),
+            FileFormat::Csv => Self::encode_csv(cols, rows),
+            FileFormat::Jsonl => Self::encode_jsonl(cols, rows),
+        }
+    }
+
+    /// the first line is the header, NULL is written as an empty field
+    fn encode_csv(cols: &[String], rows: &[Vec<ColValue>]) -> anyhow::Result<Vec<u8>> {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer.write_record(cols)?;
+        for row in rows {
+            writer.write_record(row.iter().map(|v| v.to_option_string().unwrap_or_default()))?;
+        }
+        Ok(writer.into_inner()?)
+    }
+
+    fn encode_jsonl(cols: &[String], rows: &[Vec<ColValue>]) -> anyhow::Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        for row in rows {
+            let mut map = Map::new();
+            for (col, value) in cols.iter().zip(row.iter()) {
+                let value = match value.to_option_string() {
+                    Some(v) => Value::String(v),
+                    None => Value::Null,
+                };
+                map.insert(col.clone(), value);
+            }
+            serde_json::to_writer(&mut buf, &map)?;
+            buf.push(b'\n');
+        }
+        Ok(buf)
+    }
+
+    /// one row group per file, every column is OPTIONAL
+    fn encode_parquet(cols: &[String], rows: &[Vec<ColValue>]) -> anyhow::Result<Vec<u8>> {
+        let mut col_types = Vec::with_capacity(cols.len());
+        let mut fields = Vec::with_capacity(cols.len());
+        for (col_index, col) in cols.iter().enumerate() {
+            let col_type = ParquetColType::of_col(rows, col_index);
+            fields.push(Arc::new(col_type.to_field(col)?));
+            col_types.push(col_type);
+        }
+        let schema = Type::group_type_builder("schema")
+            .with_fields(fields)
+            .build()?;
+        let props = WriterProperties::builder()
+            .set_compression(Compression::SNAPPY)
+            .build();
+
+        let mut writer = SerializedFileWriter::new(Vec::new(), Arc::new(schema), Arc::new(props))?;
+        let mut row_group_writer = writer.next_row_group()?;
+        let mut col_index = 0;
+        while let Some(mut col_writer) = row_group_writer.next_column()? {
+            let values = rows.iter().map(|row| &row[col_index]);
+            match col_types[col_index] {
+                ParquetColType::Bool => {
+                    Self::write_parquet_col::<BoolType, _>(&mut col_writer, values, |v| match v {
+                        ColValue::Bool(v) => Some(*v),
+                        _ => None,
+                    })?
+                }
+                ParquetColType::Int32 { .. } => {
+                    Self::write_parquet_col::<Int32Type, _>(&mut col_writer, values, |v| match v {
+                        ColValue::Tiny(v) => Some(*v as i32),
+                        ColValue::UnsignedTiny(v) => Some(*v as i32),
+                        ColValue::Short(v) => Some(*v as i32),
+                        ColValue::UnsignedShort(v) => Some(*v as i32),
+                        ColValue::Year(v) => Some(*v as i32),
+                        ColValue::Long(v) => Some(*v),
+                        ColValue::UnsignedLong(v) => Some(*v as i32),
+                        _ => None,
+                    })?
+                }
+                ParquetColType::Int64 { .. } => {
+                    Self::write_parquet_col::<Int64Type, _>(&mut col_writer, values, |v| match v {
+                        ColValue::LongLong(v) => Some(*v),
+                        ColValue::Enum(v) => Some(*v as i64),
+                        ColValue::UnsignedLongLong(v) | ColValue::Bit(v) | ColValue::Set(v) => {
+                            Some(*v as i64)
+                        }
+                        _ => None,
+                    })?
+                }
+                ParquetColType::Float => {
+                    Self::write_parquet_col::<FloatType, _>(&mut col_writer, values, |v| match v {
+                        ColValue::Float(v) => Some(*v),
+                        _ => None,
+                    })?
+                }
+                ParquetColType::Double => Self::write_parquet_col::<DoubleType, _>(
+                    &mut col_writer,
+                    values,
+                    |v| match v {
+                        ColValue::Double(v) => Some(*v),
+                        _ => None,
+                    },
+                )?,
+                ParquetColType::Binary => Self::write_parquet_col::<ByteArrayType, _>(
+                    &mut col_writer,
+                    values,
+                    |v| match v {
+                        ColValue::Blob(v) | ColValue::RawString(v) => {
+                            Some(ByteArray::from(v.clone()))
+                        }
+                        _ => None,
+                    },
+                )?,
+                ParquetColType::String | ParquetColType::Json => {
+                    Self::write_parquet_col::<ByteArrayType, _>(&mut col_writer, values, |v| {
+                        v.to_option_string()
+                            .map(|v| ByteArray::from(v.into_bytes()))
+                    })?
+                }
+            }
+            col_writer.close()?;
+            col_index += 1;
+        }
+        row_group_writer.close()?;
+        Ok(writer.into_inner()?)
+    }
+
+    fn write_parquet_col<'a, T: DataType, F>(
+        col_writer: &mut SerializedColumnWriter<'_>,
+        values: impl Iterator<Item = &'a ColValue>,
+        to_parquet_value: F,
+    ) -> anyhow::Result<()>
+    where
+        F: Fn(&ColValue) -> Option<T::T>,
+    {
+        let mut parquet_values = Vec::new();
+        let mut def_levels = Vec::new();
+        for value in values {
+            match to_parquet_value(value) {
+                Some(v) => {
+                    parquet_values.push(v);
+                    def_levels.push(1);
+                }
+                None => def_levels.push(0),
+            }
+        }
+        col_writer
+            .typed::<T>()
+            .write_batch(&parquet_values, Some(&def_levels), None)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use parquet::{
+        file::reader::{FileReader, SerializedFileReader},
+        record::RowAccessor,
+    };
+
+    fn mock_data() -> (Vec<String>, Vec<Vec<ColValue>>) {
+        let cols = vec!["id".to_string(), "name".to_string()];
+        let rows = vec![
+            vec![ColValue::Long(1), ColValue::String("a,\"b\"".to_string())],
+            vec![ColValue::Long(2), ColValue::None],
+        ];
+        (cols, rows)
+    }
+
+    #[test]
+    fn test_encode_csv() {
+        let (cols, rows) = mock_data();
+        let data = FileEncoder::encode(&FileFormat::Csv, &cols, &rows).unwrap();
+        assert_eq!(
+            String::from_utf8(data).unwrap(),
+            "id,name\n1,\"a,\"\"b\"\"\"\n2,\n"
+        );
+    }
+
+    #[test]
+    fn test_encode_jsonl() {
+        let (cols, rows) = mock_data();
+        let data = FileEncoder::encode(&FileFormat::Jsonl, &cols, &rows).unwrap();
+        assert_eq!(
+            String::from_utf8(data).unwrap(),
+            "{\"id\":\"1\",\"name\":\"a,\\\"b\\\"\"}\n{\"id\":\"2\",\"name\":null}\n"
+        );
+    }
+
+    #[test]
+    fn test_encode_parquet() {
+        let (cols, rows) = mock_data();
+        let data = FileEncoder::encode(&FileFormat::Parquet, &cols, &rows).unwrap();
+        assert!(data.starts_with(b"PAR1"));
+        assert!(data.ends_with(b"PAR1"));
+
+        let reader = SerializedFileReader::new(Bytes::from(data)).unwrap();
+        let rows: Vec<_> = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| row.unwrap())
+            .collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(rows[0].get_int(0).unwrap(), 1);
+        assert_eq!(rows[0].get_string(1).unwrap(), "a,\"b\"");
+        assert_eq!(rows[1].get_int(0).unwrap(), 2);
+        assert!(rows[1].get_string(1).is_err());
+    }
+
+    #[test]
+    fn test_encode_parquet_col_types() {
+        let cols: Vec<String> = [
+            "bool",
+            "tiny",
+            "u_long",
+            "long_long",
+            "u_long_long",
+            "float",
+            "double",
+            "decimal",
+            "blob",
+            "json",
+            "mixed",
+            "null",
+        ]
+        .iter()
+        .map(|col| col.to_string())
+        .collect();
+        let rows = vec![
+            vec![
+                ColValue::Bool(true),
+                ColValue::Tiny(-1),
+                ColValue::UnsignedLong(u32::MAX),
+                ColValue::LongLong(i64::MIN),
+                ColValue::UnsignedLongLong(u64::MAX),
+                ColValue::Float(1.5),
+                ColValue::Double(-2.25),
+                ColValue::Decimal("123.456".to_string()),
+                ColValue::Blob(vec![0, 255]),
+                ColValue::Json2("{\"k\": 1}".to_string()),
+                ColValue::Long(1),
+                ColValue::None,
+            ],
+            vec![
+                ColValue::None,
+                ColValue::None,
+                ColValue::None,
+                ColValue::None,
+                ColValue::None,
+                ColValue::None,
+                ColValue::None,
+                ColValue::None,
+                ColValue::None,
+                ColValue::None,
+                ColValue::String("a".to_string()),
+                ColValue::None,
+            ],
+        ];
+        let data = FileEncoder::encode(&FileFormat::Parquet, &cols, &rows).unwrap();
+        let reader = SerializedFileReader::new(Bytes::from(data)).unwrap();
+
+        let fields = reader.metadata().file_metadata().schema().get_fields();
+        let physical_types: Vec<_> = fields.iter().map(|f| f.get_physical_type()).collect();
+        assert_eq!(
+            physical_types,
+            vec![
+                PhysicalType::BOOLEAN,
+                PhysicalType::INT32,
+                PhysicalType::INT32,
+                PhysicalType::INT64,
+                PhysicalType::INT64,
+                PhysicalType::FLOAT,
+                PhysicalType::DOUBLE,
+                PhysicalType::BYTE_ARRAY,
+                PhysicalType::BYTE_ARRAY,
+                PhysicalType::BYTE_ARRAY,
+                PhysicalType::BYTE_ARRAY,
+                PhysicalType::BYTE_ARRAY,
+            ]
+        );
+        assert_eq!(
+            fields[9].get_basic_info().logical_type(),
+            Some(LogicalType::Json)
+        );
+
+        let rows: Vec<_> = reader
+            .get_row_iter(None)
+            .unwrap()
+            .map(|row| row.unwrap())
+            .collect();
+        let row = &rows[0];
+        assert!(row.get_bool(0).unwrap());
+        assert_eq!(row.get_byte(1).unwrap(), -1);
+        assert_eq!(row.get_uint(2).unwrap(), u32::MAX);
+        assert_eq!(row.get_long(3).unwrap(), i64::MIN);
+        assert_eq!(row.get_ulong(4).unwrap(), u64::MAX);
+        assert_eq!(row.get_float(5).unwrap(), 1.5);
+        assert_eq!(row.get_double(6).unwrap(), -2.25);
+        assert_eq!(row.get_string(7).unwrap(), "123.456");
+        assert_eq!(row.get_bytes(8).unwrap().data(), &[0, 255]);
+        assert_eq!(row.get_string(9).unwrap(), "{\"k\": 1}");
+        // values of different types fall back to strings
+        assert_eq!(row.get_string(10).unwrap(), "1");
+        assert_eq!(rows[1].get_string(10).unwrap(), "a");
+        assert!(row.get_string(11).is_err());
+        assert!(rows[1].get_bool(0).is_err());
+    }
+}
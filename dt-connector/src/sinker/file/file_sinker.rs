@@ -0,0 +1,282 @@
+use std::{cmp, collections::HashMap, path::Path, sync::Arc};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use rusoto_core::ByteStream;
+use rusoto_s3::{PutObjectRequest, S3Client, S3};
+use tokio::{fs, time::Instant};
+use uuid::Uuid;
+
+use super::file_encoder::FileEncoder;
+use crate::{rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker};
+use dt_common::{
+    config::{
+        config_enums::{ExtractType, FileFormat},
+        s3_config::S3Config,
+    },
+    log_info,
+    meta::{
+        col_value::ColValue,
+        ddl_meta::ddl_data::DdlData,
+        dt_data::{DtData, DtItem},
+        position::Position,
+        rdb_meta_manager::RdbMetaManager,
+        row_data::RowData,
+        row_type::RowType,
+    },
+    monitor::monitor::Monitor,
+    utils::limit_queue::LimitedQueue,
+};
+
+pub const OP_COL: &str = "_dts_op";
+pub const POSITION_COL: &str = "_dts_position";
+
+/// write rows into files partitioned by: schema/tb/dt=yyyy-mm-dd/part-N-uuid.ext,
+/// a file is rolled when its buffered data reaches file_max_bytes or it has been opened
+/// for file_max_secs, files are written to s3 if s3_client is set, otherwise to root_dir
+pub struct FileSinker {
+    pub root_dir: String,
+    pub file_format: FileFormat,
+    pub file_max_bytes: usize,
+    pub file_max_secs: u64,
+    pub s3_client: Option<S3Client>,
+    pub s3_config: S3Config,
+    pub meta_manager: Option<RdbMetaManager>,
+    pub reverse_router: RdbRouter,
+    pub extract_type: ExtractType,
+    pub monitor: Arc<Monitor>,
+    pub files: HashMap<String, FileBuffer>,
+    pub file_seq: u64,
+}
+
+pub struct FileBuffer {
+    pub dir: String,
+    pub cols: Vec<String>,
+    pub rows: Vec<Vec<ColValue>>,
+    pub data_size: usize,
+    pub open_time: Instant,
+}
+
+#[async_trait]
+impl Sinker for FileSinker {
+    async fn sink_raw(&mut self, data: Vec<DtItem>, _batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let mut data_size = 0;
+        let mut row_count = 0;
+        for item in data {
+            // there may be DtData::Commit items, ignore them
+            if let DtData::Dml { row_data } = item.dt_data {
+                BaseSinker::check_full_row_image(&row_data, "file")?;
+                data_size += row_data.data_size;
+                row_count += 1;
+                self.append(row_data, &item.position).await?;
+            }
+        }
+
+        let rts = self.roll_files(false).await?;
+        BaseSinker::update_batch_monitor(&self.monitor, row_count, data_size as u64).await?;
+        BaseSinker::update_monitor_rt(&self.monitor, &rts).await
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        self.roll_files(true).await?;
+        if let Some(meta_manager) = &self.meta_manager {
+            meta_manager.close().await?;
+        }
+        Ok(())
+    }
+
+    async fn refresh_meta(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
+        if let Some(meta_manager) = self.meta_manager.as_mut() {
+            for ddl_data in data.iter() {
+                meta_manager.invalidate_cache_by_ddl_data(ddl_data);
+            }
+        }
+        Ok(())
+    }
+
+    // positions of buffered rows are committed at checkpoints, write them out first
+    async fn flush(&mut self) -> anyhow::Result<()> {
+        self.roll_files(true).await?;
+        Ok(())
+    }
+}
+
+impl FileSinker {
+    async fn append(&mut self, mut row_data: RowData, position: &Position) -> anyhow::Result<()> {
+        let dir = format!(
+            "{}/{}/dt={}",
+            row_data.schema,
+            row_data.tb,
+            Self::get_partition_date(position)
+        );
+        let cols = self.get_cols(&row_data).await?;
+
+        // roll the current file if table structure changed, eg: after ddl
+        if let Some(buffer) = self.files.get(&dir) {
+            if buffer.cols != cols {
+                let buffer = self.files.remove(&dir).unwrap();
+                self.write_file(buffer).await?;
+            }
+        }
+
+        let with_cdc_cols = self.with_cdc_cols();
+        let (op, mut col_values) = match row_data.row_type {
+            RowType::Insert => ("insert", row_data.after.take()),
+            RowType::Update => ("update", row_data.after.take()),
+            RowType::Delete => ("delete", row_data.before.take()),
+        };
+
+        let buffer = self.files.entry(dir.clone()).or_insert_with(|| FileBuffer {
+            dir,
+            cols,
+            rows: Vec::new(),
+            data_size: 0,
+            open_time: Instant::now(),
+        });
+
+        let mut row = Vec::with_capacity(buffer.cols.len());
+        let data_col_count = if with_cdc_cols {
+            buffer.cols.len() - 2
+        } else {
+            buffer.cols.len()
+        };
+        for col in buffer.cols.iter().take(data_col_count) {
+            let value = col_values
+                .as_mut()
+                .and_then(|values| values.remove(col))
+                .unwrap_or(ColValue::None);
+            row.push(value);
+        }
+        if with_cdc_cols {
+            row.push(ColValue::String(op.to_string()));
+            row.push(ColValue::String(position.to_string()));
+        }
+
+        buffer.data_size += row_data.data_size;
+        buffer.rows.push(row);
+        Ok(())
+    }
+
+    async fn roll_files(&mut self, force: bool) -> anyhow::Result<LimitedQueue<(u64, u64)>> {
+        let max_secs = self.file_max_secs;
+        let max_bytes = self.file_max_bytes;
+        let dirs: Vec<String> = self
+            .files
+            .iter()
+            .filter(|(_, buffer)| {
+                force
+                    || buffer.data_size >= max_bytes
+                    || buffer.open_time.elapsed().as_secs() >= max_secs
+            })
+            .map(|(dir, _)| dir.clone())
+            .collect();
+
+        let mut rts = LimitedQueue::new(cmp::max(1, cmp::min(100, dirs.len())));
+        for dir in dirs {
+            if let Some(buffer) = self.files.remove(&dir) {
+                let start_time = Instant::now();
+                self.write_file(buffer).await?;
+                rts.push((start_time.elapsed().as_millis() as u64, 1));
+            }
+        }
+        Ok(rts)
+    }
+
+    async fn write_file(&mut self, buffer: FileBuffer) -> anyhow::Result<()> {
+        if buffer.rows.is_empty() {
+            return Ok(());
+        }
+
+        self.file_seq += 1;
+        let file_name = format!(
+            "{}/part-{}-{}.{}",
+            buffer.dir,
+            self.file_seq,
+            Uuid::new_v4(),
+            FileEncoder::get_file_ext(&self.file_format)
+        );
+        let data = FileEncoder::encode(&self.file_format, &buffer.cols, &buffer.rows)?;
+
+        if let Some(s3_client) = &self.s3_client {
+            let key = if self.s3_config.root_dir.is_empty() {
+                file_name
+            } else {
+                format!("{}/{}", self.s3_config.root_dir, file_name)
+            };
+            let request = PutObjectRequest {
+                bucket: self.s3_config.bucket.clone(),
+                key: key.clone(),
+                body: Some(ByteStream::from(data)),
+                ..Default::default()
+            };
+            s3_client
+                .put_object(request)
+                .await
+                .with_context(|| format!("failed to put file to s3: {}", key))?;
+            log_info!("file written: {}, rows: {}", key, buffer.rows.len());
+        } else {
+            let path = Path::new(&self.root_dir).join(&file_name);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent).await?;
+            }
+            // write to a temp file first so readers never see a partial file
+            let tmp_path = path.with_extension("tmp");
+            fs::write(&tmp_path, data)
+                .await
+                .with_context(|| format!("failed to write file: {}", tmp_path.display()))?;
+            fs::rename(&tmp_path, &path).await?;
+            log_info!(
+                "file written: {}, rows: {}",
+                path.display(),
+                buffer.rows.len()
+            );
+        }
+        Ok(())
+    }
+
+    async fn get_cols(&mut self, row_data: &RowData) -> anyhow::Result<Vec<String>> {
+        let mut cols = if let Some(meta_manager) = self.meta_manager.as_mut() {
+            let (schema, tb) = self
+                .reverse_router
+                .get_tb_map(&row_data.schema, &row_data.tb);
+            meta_manager.get_tb_meta(schema, tb).await?.cols.clone()
+        } else {
+            // no meta for non-rdb sources (eg: mongo), use the sorted keys of the row
+            let col_values = match row_data.row_type {
+                RowType::Delete => row_data.before.as_ref(),
+                _ => row_data.after.as_ref(),
+            };
+            let mut cols: Vec<String> = col_values
+                .map(|values| values.keys().cloned().collect())
+                .unwrap_or_default();
+            cols.sort();
+            cols
+        };
+
+        if self.with_cdc_cols() {
+            cols.push(OP_COL.to_string());
+            cols.push(POSITION_COL.to_string());
+        }
+        Ok(cols)
+    }
+
+    fn with_cdc_cols(&self) -> bool {
+        !matches!(self.extract_type, ExtractType::Snapshot)
+    }
+
+    /// use the binlog / wal timestamp for cdc rows, otherwise the current date
+    fn get_partition_date(position: &Position) -> String {
+        let timestamp = position.to_timestamp();
+        let date_time = if timestamp > 0 {
+            DateTime::from_timestamp_millis(timestamp as i64).unwrap_or_else(Utc::now)
+        } else {
+            Utc::now()
+        };
+        date_time.format("%Y-%m-%d").to_string()
+    }
+}
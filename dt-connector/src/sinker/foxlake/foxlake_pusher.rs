@@ -126,6 +126,7 @@ impl FoxlakePusher {
 
             // there may be DtData::Commit items, ignore them
             if let DtData::Dml { row_data } = item.dt_data {
+                BaseSinker::check_full_row_image(&row_data, "foxlake")?;
                 batch_data_size += row_data.data_size;
 
                 if row_data.row_type == RowType::Update {
@@ -21,7 +21,11 @@ pub struct KafkaSinker {
 
 #[async_trait]
 impl Sinker for KafkaSinker {
-    async fn sink_dml(&mut self, mut data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
+    async fn sink_dml(&mut self, mut data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
+        self.sink_dml_ref(&mut data, batch).await
+    }
+
+    async fn sink_dml_ref(&mut self, data: &mut Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
         if data.is_empty() {
             return Ok(());
         }
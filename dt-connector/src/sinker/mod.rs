@@ -3,11 +3,13 @@ pub mod base_sinker;
 pub mod base_struct_sinker;
 pub mod clickhouse;
 pub mod dummy_sinker;
+pub mod file;
 pub mod foxlake;
 pub mod kafka;
 pub mod mongo;
 pub mod mysql;
 pub mod pg;
 pub mod redis;
+pub mod retry_sinker;
 pub mod sql_sinker;
 pub mod starrocks;
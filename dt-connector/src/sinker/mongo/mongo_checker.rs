@@ -36,7 +36,11 @@ pub struct MongoChecker {
 
 #[async_trait]
 impl Sinker for MongoChecker {
-    async fn sink_dml(&mut self, mut data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
+    async fn sink_dml(&mut self, mut data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
+        self.sink_dml_ref(&mut data, batch).await
+    }
+
+    async fn sink_dml_ref(&mut self, data: &mut Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
         if data.is_empty() {
             return Ok(());
         }
@@ -3,22 +3,31 @@ use std::{cmp, sync::Arc};
 use async_trait::async_trait;
 use mongodb::{
     bson::{doc, Document},
-    options::UpdateOptions,
+    error::ErrorKind,
+    options::{ReplaceOptions, UpdateOptions},
     Client, Collection,
 };
 use tokio::time::Instant;
 
 use crate::{call_batch_fn, rdb_router::RdbRouter, sinker::base_sinker::BaseSinker, Sinker};
 use dt_common::{
-    log_error,
+    log_error, log_info, log_warn,
     meta::{
-        col_value::ColValue, mongo::mongo_constant::MongoConstants, row_data::RowData,
+        col_value::ColValue,
+        ddl_meta::{ddl_data::DdlData, ddl_type::DdlType},
+        mongo::mongo_constant::MongoConstants,
+        row_data::RowData,
         row_type::RowType,
     },
     monitor::monitor::Monitor,
     utils::limit_queue::LimitedQueue,
 };
 
+// refer: https://www.mongodb.com/docs/manual/reference/error-codes/
+const NAMESPACE_NOT_FOUND: i32 = 26;
+const INDEX_NOT_FOUND: i32 = 27;
+const NAMESPACE_EXISTS: i32 = 48;
+
 #[derive(Clone)]
 pub struct MongoSinker {
     pub router: RdbRouter,
@@ -30,6 +39,10 @@ pub struct MongoSinker {
 #[async_trait]
 impl Sinker for MongoSinker {
     async fn sink_dml(&mut self, mut data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
+        self.sink_dml_ref(&mut data, batch).await
+    }
+
+    async fn sink_dml_ref(&mut self, data: &mut Vec<RowData>, batch: bool) -> anyhow::Result<()> {
         if data.is_empty() {
             return Ok(());
         }
@@ -50,6 +63,43 @@ impl Sinker for MongoSinker {
         Ok(())
     }
 
+    async fn sink_ddl(&mut self, data: Vec<DdlData>, _batch: bool) -> anyhow::Result<()> {
+        let mut rts = LimitedQueue::new(cmp::min(100, data.len()));
+        let mut data_size = 0;
+
+        for ddl_data in data.iter() {
+            data_size += ddl_data.get_data_size();
+            let (db, command) = match ddl_data.statement.to_mongo_command()? {
+                Some(db_command) => db_command,
+                None => {
+                    log_warn!("ddl not supported by mongo, ignore: {}", ddl_data);
+                    continue;
+                }
+            };
+            log_info!("sink ddl, db: {}, command: {}", db, command);
+
+            let start_time = Instant::now();
+            if let Err(error) = self
+                .mongo_client
+                .database(&db)
+                .run_command(command, None)
+                .await
+            {
+                // the target may already be in the expected state, e.g. when events
+                // are replayed after resuming from an earlier position
+                if Self::is_ddl_applied(&ddl_data.ddl_type, &error) {
+                    log_warn!("ddl already applied, ignore error: {}", error);
+                } else {
+                    return Err(error.into());
+                }
+            }
+            rts.push((start_time.elapsed().as_millis() as u64, 1));
+        }
+
+        BaseSinker::update_serial_monitor(&self.monitor, data.len() as u64, data_size).await?;
+        BaseSinker::update_monitor_rt(&self.monitor, &rts).await
+    }
+
     async fn close(&mut self) -> anyhow::Result<()> {
         self.mongo_client.clone().shutdown().await;
         Ok(())
@@ -57,76 +107,95 @@ impl Sinker for MongoSinker {
 }
 
 impl MongoSinker {
-    async fn serial_sink(&mut self, mut data: Vec<RowData>) -> anyhow::Result<()> {
+    async fn serial_sink(&mut self, data: &mut Vec<RowData>) -> anyhow::Result<()> {
         let mut rts = LimitedQueue::new(cmp::min(100, data.len()));
         let mut data_size = 0;
 
-        for row_data in data.iter_mut() {
-            data_size += row_data.data_size;
+        for i in 0..data.len() {
+            data_size += data[i].data_size;
+            if let Err(error) = self.sink_row(&data[i], &mut rts).await {
+                // keep rows not sinked yet for retries
+                data.drain(..i);
+                return Err(error);
+            }
+        }
 
-            let collection = self
-                .mongo_client
-                .database(&row_data.schema)
-                .collection::<Document>(&row_data.tb);
+        BaseSinker::update_serial_monitor(&self.monitor, data.len() as u64, data_size as u64)
+            .await?;
+        BaseSinker::update_monitor_rt(&self.monitor, &rts).await
+    }
 
-            let start_time = Instant::now();
-            match row_data.row_type {
-                RowType::Insert => {
-                    let after = row_data.after.as_mut().unwrap();
-                    if let Some(ColValue::MongoDoc(doc)) = after.remove(MongoConstants::DOC) {
-                        let query_doc =
-                            doc! {MongoConstants::ID: doc.get(MongoConstants::ID).unwrap()};
-                        let update_doc = doc! {MongoConstants::SET: doc};
-                        self.upsert(&collection, query_doc, update_doc).await?;
-                        rts.push((start_time.elapsed().as_millis() as u64, 1));
-                    }
+    async fn sink_row(
+        &mut self,
+        row_data: &RowData,
+        rts: &mut LimitedQueue<(u64, u64)>,
+    ) -> anyhow::Result<()> {
+        let collection = self
+            .mongo_client
+            .database(&row_data.schema)
+            .collection::<Document>(&row_data.tb);
+
+        let start_time = Instant::now();
+        match row_data.row_type {
+            RowType::Insert => {
+                let after = row_data.after.as_ref().unwrap();
+                if let Some(ColValue::MongoDoc(doc)) = after.get(MongoConstants::DOC) {
+                    let query_doc = doc! {MongoConstants::ID: doc.get(MongoConstants::ID).unwrap()};
+                    let update_doc = doc! {MongoConstants::SET: doc};
+                    self.upsert(&collection, query_doc, update_doc).await?;
+                    rts.push((start_time.elapsed().as_millis() as u64, 1));
                 }
+            }
 
-                RowType::Delete => {
-                    let before = row_data.before.as_mut().unwrap();
-                    if let Some(ColValue::MongoDoc(doc)) = before.remove(MongoConstants::DOC) {
-                        let query_doc =
-                            doc! {MongoConstants::ID: doc.get(MongoConstants::ID).unwrap()};
-                        collection.delete_one(query_doc, None).await?;
-                        rts.push((start_time.elapsed().as_millis() as u64, 1));
-                    }
+            RowType::Delete => {
+                let before = row_data.before.as_ref().unwrap();
+                if let Some(ColValue::MongoDoc(doc)) = before.get(MongoConstants::DOC) {
+                    let query_doc = doc! {MongoConstants::ID: doc.get(MongoConstants::ID).unwrap()};
+                    collection.delete_one(query_doc, None).await?;
+                    rts.push((start_time.elapsed().as_millis() as u64, 1));
                 }
+            }
+
+            RowType::Update => {
+                let before = row_data.before.as_ref().unwrap();
+                let after = row_data.after.as_ref().unwrap();
+
+                let Some(ColValue::MongoDoc(before_doc)) = before.get(MongoConstants::DOC) else {
+                    return Ok(());
+                };
+                let query_doc =
+                    doc! {MongoConstants::ID: before_doc.get(MongoConstants::ID).unwrap()};
 
-                RowType::Update => {
-                    let before = row_data.before.as_mut().unwrap();
-                    let after = row_data.after.as_mut().unwrap();
-
-                    let query_doc =
-                        if let Some(ColValue::MongoDoc(doc)) = before.remove(MongoConstants::DOC) {
-                            Some(doc! {MongoConstants::ID: doc.get(MongoConstants::ID).unwrap()})
-                        } else {
-                            None
-                        };
-
-                    let update_doc =
-                        if let Some(ColValue::MongoDoc(doc)) = after.remove(MongoConstants::DOC) {
-                            Some(doc)
-                        } else if let Some(ColValue::MongoDoc(doc)) =
-                            after.remove(MongoConstants::DIFF_DOC)
-                        {
-                            // for Update row_data from oplog (NOT change stream), after contains diff_doc instead of doc
-                            Some(doc)
-                        } else {
-                            None
-                        };
-
-                    if query_doc.is_some() && update_doc.is_some() {
-                        self.upsert(&collection, query_doc.unwrap(), update_doc.unwrap())
+                if let Some(ColValue::MongoDoc(doc)) = after.get(MongoConstants::DOC) {
+                    // the full document, which can not be applied by update_one
+                    let options = ReplaceOptions::builder().upsert(true).build();
+                    collection
+                        .replace_one(query_doc, doc, Some(options))
+                        .await?;
+                    rts.push((start_time.elapsed().as_millis() as u64, 1));
+                } else if let Some(ColValue::MongoDoc(doc)) = after.get(MongoConstants::DIFF_DOC) {
+                    // for Update row_data from oplog (NOT change stream), after contains diff_doc instead of doc,
+                    // pulling elements from a missing document should not create it
+                    if let Some(ColValue::MongoDoc(array_filter)) =
+                        after.get(MongoConstants::ARRAY_FILTER)
+                    {
+                        // updating an element in an array, a missing document should not be created
+                        let options = UpdateOptions::builder()
+                            .array_filters(vec![array_filter.clone()])
+                            .build();
+                        collection
+                            .update_one(query_doc, doc.clone(), Some(options))
                             .await?;
-                        rts.push((start_time.elapsed().as_millis() as u64, 1));
+                    } else if doc.contains_key(MongoConstants::PULL) {
+                        collection.update_one(query_doc, doc.clone(), None).await?;
+                    } else {
+                        self.upsert(&collection, query_doc, doc.clone()).await?;
                     }
+                    rts.push((start_time.elapsed().as_millis() as u64, 1));
                 }
             }
         }
-
-        BaseSinker::update_serial_monitor(&self.monitor, data.len() as u64, data_size as u64)
-            .await?;
-        BaseSinker::update_monitor_rt(&self.monitor, &rts).await
+        Ok(())
     }
 
     async fn batch_delete(
@@ -197,12 +266,25 @@ impl MongoSinker {
                 error.to_string()
             );
             let sub_data = &data[start_index..start_index + batch_size];
-            self.serial_sink(sub_data.to_vec()).await?;
+            self.serial_sink(&mut sub_data.to_vec()).await?;
         }
 
         BaseSinker::update_batch_monitor(&self.monitor, batch_size as u64, data_size as u64).await
     }
 
+    fn is_ddl_applied(ddl_type: &DdlType, error: &mongodb::error::Error) -> bool {
+        let code = match error.kind.as_ref() {
+            ErrorKind::Command(command_error) => command_error.code,
+            _ => return false,
+        };
+        match ddl_type {
+            DdlType::CreateTable => code == NAMESPACE_EXISTS,
+            DdlType::DropTable | DdlType::DropDatabase => code == NAMESPACE_NOT_FOUND,
+            DdlType::DropIndex => code == NAMESPACE_NOT_FOUND || code == INDEX_NOT_FOUND,
+            _ => false,
+        }
+    }
+
     async fn upsert(
         &mut self,
         collection: &Collection<Document>,
@@ -39,6 +39,10 @@ pub struct MysqlChecker {
 #[async_trait]
 impl Sinker for MysqlChecker {
     async fn sink_dml(&mut self, mut data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
+        self.sink_dml_ref(&mut data, batch).await
+    }
+
+    async fn sink_dml_ref(&mut self, data: &mut Vec<RowData>, batch: bool) -> anyhow::Result<()> {
         if data.is_empty() {
             return Ok(());
         }
@@ -68,7 +72,7 @@ impl Sinker for MysqlChecker {
 }
 
 impl MysqlChecker {
-    async fn serial_check(&mut self, data: Vec<RowData>) -> anyhow::Result<()> {
+    async fn serial_check(&mut self, data: &[RowData]) -> anyhow::Result<()> {
         if data.is_empty() {
             return Ok(());
         }
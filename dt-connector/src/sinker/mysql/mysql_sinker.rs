@@ -40,12 +40,17 @@ pub struct MysqlSinker {
 #[async_trait]
 impl Sinker for MysqlSinker {
     async fn sink_dml(&mut self, mut data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
+        self.sink_dml_ref(&mut data, batch).await
+    }
+
+    async fn sink_dml_ref(&mut self, data: &mut Vec<RowData>, batch: bool) -> anyhow::Result<()> {
         if data.is_empty() {
             return Ok(());
         }
 
+        // rows of a serial sink are written in a transaction, all of them are kept after a failure
         if !batch {
-            self.serial_sink(&data).await?;
+            self.serial_sink(data).await?;
         } else {
             match data[0].row_type {
                 RowType::Insert => {
@@ -54,7 +59,7 @@ impl Sinker for MysqlSinker {
                 RowType::Delete => {
                     call_batch_fn!(self, data, Self::batch_delete);
                 }
-                _ => self.serial_sink(&data).await?,
+                _ => self.serial_sink(data).await?,
             }
         }
 
@@ -65,7 +70,7 @@ impl Sinker for MysqlSinker {
         let mut rts = LimitedQueue::new(cmp::min(100, data.len()));
         let mut data_size = 0;
 
-        for ddl_data in data.iter() {
+        for ddl_data in data.iter().flat_map(|ddl_data| ddl_data.to_mysql()) {
             let sql = ddl_data.to_sql();
             data_size += ddl_data.get_data_size();
             let query = sqlx::query(&sql);
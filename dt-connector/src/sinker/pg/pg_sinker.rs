@@ -16,6 +16,7 @@ use dt_common::{
     log_error, log_info,
     meta::{
         ddl_meta::{ddl_data::DdlData, ddl_type::DdlType},
+        message_data::MessageData,
         pg::pg_meta_manager::PgMetaManager,
         row_data::RowData,
         row_type::RowType,
@@ -39,12 +40,17 @@ pub struct PgSinker {
 #[async_trait]
 impl Sinker for PgSinker {
     async fn sink_dml(&mut self, mut data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
+        self.sink_dml_ref(&mut data, batch).await
+    }
+
+    async fn sink_dml_ref(&mut self, data: &mut Vec<RowData>, batch: bool) -> anyhow::Result<()> {
         if data.is_empty() {
             return Ok(());
         }
 
+        // rows of a serial sink are written in a transaction, all of them are kept after a failure
         if !batch {
-            self.serial_sink(&data).await?;
+            self.serial_sink(data).await?;
         } else {
             match data[0].row_type {
                 RowType::Insert => {
@@ -53,7 +59,7 @@ impl Sinker for PgSinker {
                 RowType::Delete => {
                     call_batch_fn!(self, data, Self::batch_delete);
                 }
-                _ => self.serial_sink(&data).await?,
+                _ => self.serial_sink(data).await?,
             }
         }
         Ok(())
@@ -102,6 +108,28 @@ impl Sinker for PgSinker {
         BaseSinker::update_monitor_rt(&self.monitor, &rts).await
     }
 
+    async fn sink_message(&mut self, data: Vec<MessageData>) -> anyhow::Result<()> {
+        let mut rts = LimitedQueue::new(cmp::min(100, data.len()));
+        let mut data_size = 0;
+
+        // re-emit messages into the target wal, so they can be consumed by downstream
+        // subscribers of the target, the same as those of the source
+        for message_data in data.iter() {
+            data_size += message_data.get_malloc_size();
+            let query = sqlx::query("SELECT pg_logical_emit_message($1, $2, $3)")
+                .bind(message_data.transactional)
+                .bind(&message_data.prefix)
+                .bind(&message_data.content);
+
+            let start_time = Instant::now();
+            query.execute(&self.conn_pool).await?;
+            rts.push((start_time.elapsed().as_millis() as u64, 1));
+        }
+
+        BaseSinker::update_serial_monitor(&self.monitor, data.len() as u64, data_size).await?;
+        BaseSinker::update_monitor_rt(&self.monitor, &rts).await
+    }
+
     async fn refresh_meta(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
         for ddl_data in data.iter() {
             self.meta_manager.invalidate_cache_by_ddl_data(ddl_data);
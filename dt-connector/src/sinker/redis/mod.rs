@@ -1,3 +1,4 @@
 pub mod entry_rewriter;
+pub mod redis_checker;
 pub mod redis_sinker;
 pub mod redis_statistic_sinker;
@@ -0,0 +1,437 @@
+use std::{collections::HashMap, sync::Arc};
+
+use anyhow::bail;
+use async_trait::async_trait;
+use redis::{Connection, ConnectionLike, Value};
+use serde_json::json;
+use tokio::time::Instant;
+
+use crate::{
+    call_batch_fn,
+    check_log::{
+        check_log::{CheckLog, DiffColValue},
+        log_type::LogType,
+    },
+    sinker::{base_checker::BaseChecker, base_sinker::BaseSinker},
+    Sinker,
+};
+use dt_common::{
+    error::Error,
+    meta::{
+        dt_data::{DtData, DtItem},
+        redis::{
+            cluster_node::ClusterNode,
+            command::{cmd_encoder::CmdEncoder, key_parser::KeyParser},
+            redis_entry::RedisEntry,
+            redis_object::{RedisCmd, RedisObject},
+        },
+    },
+    monitor::monitor::Monitor,
+    utils::{limit_queue::LimitedQueue, redis_util::DUMP_FOOTER_LEN},
+};
+
+const TYPE_NONE: &str = "none";
+const COL_KEY: &str = "key";
+const COL_TYPE: &str = "type";
+const COL_VALUE: &str = "value";
+const COL_TTL: &str = "ttl";
+
+pub struct RedisChecker {
+    pub cluster_node: Option<ClusterNode>,
+    pub batch_size: usize,
+    pub conn: Connection,
+    pub now_db_id: i64,
+    pub sample_percent: u64,
+    pub ttl_tolerance_ms: i64,
+    pub monitor: Arc<Monitor>,
+}
+
+#[async_trait]
+impl Sinker for RedisChecker {
+    async fn sink_raw(&mut self, mut data: Vec<DtItem>, _batch: bool) -> anyhow::Result<()> {
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        call_batch_fn!(self, data, Self::batch_check);
+        Ok(())
+    }
+
+    fn get_id(&self) -> String {
+        if let Some(node) = &self.cluster_node {
+            node.address.clone()
+        } else {
+            String::new()
+        }
+    }
+}
+
+impl RedisChecker {
+    async fn batch_check(
+        &mut self,
+        data: &mut [DtItem],
+        start_index: usize,
+        batch_size: usize,
+    ) -> anyhow::Result<()> {
+        let mut data_size = 0;
+        let mut miss = Vec::new();
+        let mut diff = Vec::new();
+
+        let start_time = Instant::now();
+        let mut rts = LimitedQueue::new(1);
+        for dt_item in data.iter().skip(start_index).take(batch_size) {
+            data_size += dt_item.dt_data.get_data_size();
+            if let DtData::Redis { entry } = &dt_item.dt_data {
+                // only keys decoded from snapshot (rdb / DUMP) carry full values to compare,
+                // commands from incremental sync are skipped
+                if !entry.is_base || !self.is_sampled(entry.key.as_bytes()) {
+                    continue;
+                }
+
+                if let Some(check_log) = self.check_entry(entry)? {
+                    match check_log.log_type {
+                        LogType::Miss => miss.push(check_log),
+                        _ => diff.push(check_log),
+                    }
+                }
+            }
+        }
+        rts.push((start_time.elapsed().as_millis() as u64, 1));
+        BaseSinker::update_monitor_rt(&self.monitor, &rts).await?;
+
+        BaseChecker::log_dml(miss, diff);
+        BaseSinker::update_batch_monitor(&self.monitor, batch_size as u64, data_size).await
+    }
+
+    fn check_entry(&mut self, entry: &RedisEntry) -> anyhow::Result<Option<CheckLog>> {
+        if matches!(entry.value, RedisObject::Unknown) {
+            return Ok(None);
+        }
+
+        if entry.db_id != self.now_db_id {
+            self.query(vec![
+                b"SELECT".to_vec(),
+                entry.db_id.to_string().into_bytes(),
+            ])?;
+            self.now_db_id = entry.db_id;
+        }
+
+        let key = entry.key.as_bytes().to_vec();
+        let dst_type = Self::value_to_string(self.query(vec![b"TYPE".to_vec(), key.clone()])?)?;
+        if dst_type == TYPE_NONE {
+            return Ok(Some(Self::build_check_log(
+                entry,
+                LogType::Miss,
+                HashMap::new(),
+            )));
+        }
+
+        let mut diff_col_values = HashMap::new();
+        let src_type = entry.get_type();
+        // TYPE returns the module type name (e.g. ReJSON-RL) for module values
+        let is_module = matches!(entry.value, RedisObject::Module(_));
+        if !is_module && src_type != dst_type {
+            diff_col_values.insert(
+                COL_TYPE.to_string(),
+                DiffColValue {
+                    src: Some(src_type),
+                    dst: Some(dst_type),
+                },
+            );
+            return Ok(Some(Self::build_check_log(
+                entry,
+                LogType::Diff,
+                diff_col_values,
+            )));
+        }
+
+        let src_value = Self::get_src_value(entry);
+        let dst_value = self.get_dst_value(entry)?;
+        if src_value != dst_value {
+            diff_col_values.insert(
+                COL_VALUE.to_string(),
+                DiffColValue {
+                    src: Some(Self::format_value(&src_value)),
+                    dst: Some(Self::format_value(&dst_value)),
+                },
+            );
+        }
+
+        // expire_ms of src entry is the remaining ttl when the key was extracted,
+        // keys without expire have expire_ms == 0, and PTTL returns -1 for them
+        let src_ttl = if entry.expire_ms > 0 {
+            entry.expire_ms
+        } else {
+            -1
+        };
+        let dst_ttl = match self.query(vec![b"PTTL".to_vec(), key])? {
+            Value::Int(pttl) => pttl,
+            v => {
+                bail! {Error::RedisResultError(format!(
+                    "unexpected result for PTTL, key: {}, result: {:?}",
+                    entry.key, v
+                ))}
+            }
+        };
+        let ttl_matched = if src_ttl > 0 {
+            dst_ttl > 0 && (src_ttl - dst_ttl).abs() <= self.ttl_tolerance_ms
+        } else {
+            dst_ttl < 0
+        };
+        if !ttl_matched {
+            diff_col_values.insert(
+                COL_TTL.to_string(),
+                DiffColValue {
+                    src: Some(src_ttl.to_string()),
+                    dst: Some(dst_ttl.to_string()),
+                },
+            );
+        }
+
+        if diff_col_values.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(Self::build_check_log(
+            entry,
+            LogType::Diff,
+            diff_col_values,
+        )))
+    }
+
+    /// values are normalized into comparable bytes:
+    /// set members, hash fields and zset members are sorted, zset scores are reformatted
+    fn get_src_value(entry: &RedisEntry) -> Vec<Vec<u8>> {
+        match &entry.value {
+            RedisObject::String(obj) => vec![obj.value.as_bytes().to_vec()],
+
+            RedisObject::List(obj) => obj.elements.iter().map(|i| i.as_bytes().to_vec()).collect(),
+
+            RedisObject::Set(obj) => {
+                let mut members: Vec<Vec<u8>> =
+                    obj.elements.iter().map(|i| i.as_bytes().to_vec()).collect();
+                members.sort();
+                members
+            }
+
+            RedisObject::Hash(obj) => {
+                let pairs = obj
+                    .value
+                    .iter()
+                    .map(|(field, (value, _))| {
+                        (field.as_bytes().to_vec(), value.as_bytes().to_vec())
+                    })
+                    .collect();
+                Self::sort_pairs(pairs)
+            }
+
+            RedisObject::Zset(obj) => {
+                let pairs = obj
+                    .elements
+                    .iter()
+                    .map(|i| {
+                        (
+                            i.member.as_bytes().to_vec(),
+                            Self::normalize_score(i.score.as_bytes()),
+                        )
+                    })
+                    .collect();
+                Self::sort_pairs(pairs)
+            }
+
+            RedisObject::Stream(obj) => {
+                // entries are decoded as: xadd key id field value [field value ...],
+                // other commands (XSETID, XGROUP, XCLAIM, the empty stream trick) are ignored
+                let mut values = Vec::new();
+                for cmd in obj.cmds.iter() {
+                    if cmd.args.len() < 3 || cmd.args[0] != b"xadd" {
+                        continue;
+                    }
+                    values.push(cmd.args[2].clone());
+                    values.push((cmd.args.len() - 3).to_string().into_bytes());
+                    values.extend_from_slice(&cmd.args[3..]);
+                }
+                values
+            }
+
+            RedisObject::Module(_) => vec![entry.raw_bytes.clone()],
+
+            RedisObject::Unknown => vec![],
+        }
+    }
+
+    fn get_dst_value(&mut self, entry: &RedisEntry) -> anyhow::Result<Vec<Vec<u8>>> {
+        let key = entry.key.as_bytes().to_vec();
+        let value = match &entry.value {
+            RedisObject::String(_) => {
+                let result = self.query(vec![b"GET".to_vec(), key])?;
+                vec![Self::value_to_bytes(result)?]
+            }
+
+            RedisObject::List(_) => {
+                let cmd = vec![b"LRANGE".to_vec(), key, b"0".to_vec(), b"-1".to_vec()];
+                Self::value_to_bytes_vec(self.query(cmd)?)?
+            }
+
+            RedisObject::Set(_) => {
+                let mut members =
+                    Self::value_to_bytes_vec(self.query(vec![b"SMEMBERS".to_vec(), key])?)?;
+                members.sort();
+                members
+            }
+
+            RedisObject::Hash(_) => {
+                let result = Self::value_to_bytes_vec(self.query(vec![b"HGETALL".to_vec(), key])?)?;
+                let pairs = result
+                    .chunks(2)
+                    .filter(|i| i.len() == 2)
+                    .map(|i| (i[0].clone(), i[1].clone()))
+                    .collect();
+                Self::sort_pairs(pairs)
+            }
+
+            RedisObject::Zset(_) => {
+                let cmd = vec![
+                    b"ZRANGE".to_vec(),
+                    key,
+                    b"0".to_vec(),
+                    b"-1".to_vec(),
+                    b"WITHSCORES".to_vec(),
+                ];
+                let result = Self::value_to_bytes_vec(self.query(cmd)?)?;
+                let pairs = result
+                    .chunks(2)
+                    .filter(|i| i.len() == 2)
+                    .map(|i| (i[0].clone(), Self::normalize_score(&i[1])))
+                    .collect();
+                Self::sort_pairs(pairs)
+            }
+
+            RedisObject::Stream(_) => {
+                let cmd = vec![b"XRANGE".to_vec(), key, b"-".to_vec(), b"+".to_vec()];
+                let mut values = Vec::new();
+                for stream_entry in Self::value_to_array(self.query(cmd)?)? {
+                    let mut stream_entry = Self::value_to_array(stream_entry)?.into_iter();
+                    if let (Some(id), Some(fields)) = (stream_entry.next(), stream_entry.next()) {
+                        let fields = Self::value_to_bytes_vec(fields)?;
+                        values.push(Self::value_to_bytes(id)?);
+                        values.push(fields.len().to_string().into_bytes());
+                        values.extend(fields);
+                    }
+                }
+                values
+            }
+
+            RedisObject::Module(_) => match self.query(vec![b"DUMP".to_vec(), key])? {
+                Value::BulkString(payload) if payload.len() > DUMP_FOOTER_LEN => {
+                    // strip the type byte and the footer (rdb version + crc64)
+                    vec![payload[1..payload.len() - DUMP_FOOTER_LEN].to_vec()]
+                }
+                _ => vec![],
+            },
+
+            RedisObject::Unknown => vec![],
+        };
+        Ok(value)
+    }
+
+    fn is_sampled(&self, key: &[u8]) -> bool {
+        if self.sample_percent >= 100 {
+            return true;
+        }
+        // hash the key by crc16 (stable, the same as redis slots) instead of random sampling,
+        // so the same keys are picked in every run
+        (KeyParser::crc16(key) as u64) % 100 < self.sample_percent
+    }
+
+    fn build_check_log(
+        entry: &RedisEntry,
+        log_type: LogType,
+        diff_col_values: HashMap<String, DiffColValue>,
+    ) -> CheckLog {
+        let mut id_col_values = HashMap::new();
+        id_col_values.insert(COL_KEY.to_string(), Some(entry.key.to_string()));
+        CheckLog {
+            log_type,
+            schema: entry.db_id.to_string(),
+            tb: String::new(),
+            id_col_values,
+            diff_col_values,
+        }
+    }
+
+    fn sort_pairs(mut pairs: Vec<(Vec<u8>, Vec<u8>)>) -> Vec<Vec<u8>> {
+        pairs.sort();
+        let mut values = Vec::with_capacity(pairs.len() * 2);
+        for (k, v) in pairs {
+            values.push(k);
+            values.push(v);
+        }
+        values
+    }
+
+    fn normalize_score(score: &[u8]) -> Vec<u8> {
+        // rdb may store scores as doubles or strings, while ZRANGE returns redis formatted strings
+        match String::from_utf8_lossy(score).parse::<f64>() {
+            Ok(score) => score.to_string().into_bytes(),
+            Err(_) => score.to_vec(),
+        }
+    }
+
+    fn format_value(value: &[Vec<u8>]) -> String {
+        let value: Vec<String> = value
+            .iter()
+            .map(|i| String::from_utf8_lossy(i).to_string())
+            .collect();
+        json!(value).to_string()
+    }
+
+    fn query(&mut self, args: Vec<Vec<u8>>) -> anyhow::Result<Value> {
+        let cmd = RedisCmd::from_args(args);
+        match self.conn.req_packed_command(&CmdEncoder::encode(&cmd)) {
+            Ok(Value::ServerError(e)) => {
+                bail! {Error::RedisResultError(format!(
+                    "check failed, server error: [{:?}], cmd: [{}]",
+                    e, cmd
+                ))}
+            }
+            Ok(v) => Ok(v),
+            Err(e) => {
+                bail! {Error::SinkerError(format!(
+                    "check failed, error: {:?}, cmd: [{}]",
+                    e, cmd
+                ))}
+            }
+        }
+    }
+
+    fn value_to_string(value: Value) -> anyhow::Result<String> {
+        Ok(String::from_utf8_lossy(&Self::value_to_bytes(value)?).to_string())
+    }
+
+    fn value_to_bytes(value: Value) -> anyhow::Result<Vec<u8>> {
+        match value {
+            Value::BulkString(v) => Ok(v),
+            Value::SimpleString(v) => Ok(v.into_bytes()),
+            Value::Okay => Ok(b"OK".to_vec()),
+            Value::Int(v) => Ok(v.to_string().into_bytes()),
+            Value::Nil => Ok(Vec::new()),
+            v => bail! {Error::RedisResultError(format!("unexpected result: {:?}", v))},
+        }
+    }
+
+    fn value_to_array(value: Value) -> anyhow::Result<Vec<Value>> {
+        match value {
+            Value::Array(v) => Ok(v),
+            Value::Nil => Ok(Vec::new()),
+            v => bail! {Error::RedisResultError(format!("unexpected result: {:?}", v))},
+        }
+    }
+
+    fn value_to_bytes_vec(value: Value) -> anyhow::Result<Vec<Vec<u8>>> {
+        let mut values = Vec::new();
+        for v in Self::value_to_array(value)? {
+            values.push(Self::value_to_bytes(v)?);
+        }
+        Ok(values)
+    }
+}
@@ -57,7 +57,11 @@ impl Sinker for RedisSinker {
         Ok(())
     }
 
-    async fn sink_dml(&mut self, mut data: Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
+    async fn sink_dml(&mut self, mut data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
+        self.sink_dml_ref(&mut data, batch).await
+    }
+
+    async fn sink_dml_ref(&mut self, data: &mut Vec<RowData>, _batch: bool) -> anyhow::Result<()> {
         if data.is_empty() {
             return Ok(());
         }
@@ -65,7 +69,7 @@ impl Sinker for RedisSinker {
         if self.batch_size > 1 {
             call_batch_fn!(self, data, Self::batch_sink_dml);
         } else {
-            self.serial_sink_dml(&mut data).await?;
+            self.serial_sink_dml(data).await?;
         }
         Ok(())
     }
@@ -183,19 +187,28 @@ impl RedisSinker {
         BaseSinker::update_batch_monitor(&self.monitor, cmds.len() as u64, data_size as u64).await
     }
 
-    async fn serial_sink_dml(&mut self, data: &mut [RowData]) -> anyhow::Result<()> {
+    async fn serial_sink_dml(&mut self, data: &mut Vec<RowData>) -> anyhow::Result<()> {
         let mut data_size = 0;
 
-        for row_data in data.iter_mut() {
-            data_size += row_data.data_size;
-            if let Some(cmd) = self.dml_to_redis_cmd(row_data).await? {
-                self.batch_sink(&[cmd]).await?
+        for i in 0..data.len() {
+            data_size += data[i].data_size;
+            if let Err(error) = self.serial_sink_row(&data[i]).await {
+                // keep rows not sinked yet for retries
+                data.drain(..i);
+                return Err(error);
             }
         }
 
         BaseSinker::update_serial_monitor(&self.monitor, data.len() as u64, data_size as u64).await
     }
 
+    async fn serial_sink_row(&mut self, row_data: &RowData) -> anyhow::Result<()> {
+        if let Some(cmd) = self.dml_to_redis_cmd(row_data).await? {
+            self.batch_sink(&[cmd]).await?
+        }
+        Ok(())
+    }
+
     async fn dml_to_redis_cmd(&mut self, row_data: &RowData) -> anyhow::Result<Option<RedisCmd>> {
         if self.meta_manager.is_none() {
             return Ok(None);
@@ -0,0 +1,291 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use mongodb::error::{ErrorKind, WriteFailure, RETRYABLE_WRITE_ERROR, TRANSIENT_TRANSACTION_ERROR};
+use sqlx::{mysql::MySqlDatabaseError, postgres::PgDatabaseError};
+
+use crate::Sinker;
+use dt_common::{
+    log_warn,
+    meta::{
+        dcl_meta::dcl_data::DclData, ddl_meta::ddl_data::DdlData, dt_data::DtItem,
+        message_data::MessageData, row_data::RowData, struct_meta::struct_data::StructData,
+    },
+    monitor::{counter_type::CounterType, monitor::Monitor},
+    utils::time_util::TimeUtil,
+};
+
+// lock wait timeout, deadlock, too many connections, server shutdown, connection killed,
+// network read/write errors, read only after failover (--read-only, super_read_only)
+const MYSQL_RETRIABLE_ERRORS: [u16; 12] = [
+    1205, 1213, 1040, 1053, 1927, 1158, 1159, 1160, 1161, 1290, 1836, 1792,
+];
+// serialization failure, deadlock, lock not available, too many connections,
+// admin shutdown, crash shutdown, cannot connect now, read only transaction (standby)
+const PG_RETRIABLE_STATES: [&str; 8] = [
+    "40001", "40P01", "55P03", "53300", "57P01", "57P02", "57P03", "25006",
+];
+// class 08: connection exception
+const PG_RETRIABLE_STATE_CLASS: &str = "08";
+// not writable primary, interrupted at shutdown, host unreachable, network timeout, etc.
+const MONGO_RETRIABLE_CODES: [i32; 12] = [
+    11600, 11602, 10107, 13435, 13436, 189, 91, 7, 6, 89, 9001, 262,
+];
+
+/// wrap a sinker and retry failed calls with exponential backoff and jitter,
+/// only transient errors (connection lost, failover, deadlock, lock wait timeout, etc.)
+/// are retried. Rows are sinked by sink_dml_ref without copies and only rows not sinked yet
+/// are retried, rows taken by the wrapped sinker are not retried. A retry may re-sink rows
+/// of a failed batch, so the wrapped sinker should be idempotent (eg: replace=true for rdb sinkers).
+/// ddl / dcl / raw / message / struct data are taken by sinkers, so they are copied for each attempt.
+/// Connections are re-established by conn pools / clients of the wrapped sinker,
+/// broken connections are dropped from the pool once an io error occurs.
+pub struct RetrySinker {
+    pub inner: Arc<async_mutex::Mutex<Box<dyn Sinker + Send>>>,
+    pub max_retries: u64,
+    pub retry_interval_ms: u64,
+    pub retry_max_interval_ms: u64,
+    pub monitor: Arc<Monitor>,
+}
+
+macro_rules! call_with_retry {
+    ($self:ident, $data:ident, $method:ident $(, $args:expr)*) => {{
+        let mut retries = 0;
+        loop {
+            // the last attempt takes the data
+            let data = if retries < $self.max_retries {
+                $data.clone()
+            } else {
+                std::mem::take(&mut $data)
+            };
+            match $self.inner.lock().await.$method(data $(, $args)*).await {
+                Ok(()) => break Ok(()),
+                Err(error) => {
+                    if retries >= $self.max_retries || !RetrySinker::is_retriable(&error) {
+                        break Err(error);
+                    }
+                    retries += 1;
+                    $self.wait_for_retry(retries, &error).await;
+                }
+            }
+        }
+    }};
+}
+
+#[async_trait]
+impl Sinker for RetrySinker {
+    async fn sink_dml(&mut self, mut data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
+        self.sink_dml_ref(&mut data, batch).await
+    }
+
+    async fn sink_dml_ref(&mut self, data: &mut Vec<RowData>, batch: bool) -> anyhow::Result<()> {
+        let mut retries = 0;
+        loop {
+            let error = match self.inner.lock().await.sink_dml_ref(data, batch).await {
+                Ok(()) => return Ok(()),
+                Err(error) => error,
+            };
+            // data is empty if the wrapped sinker took the rows
+            if retries >= self.max_retries || data.is_empty() || !Self::is_retriable(&error) {
+                return Err(error);
+            }
+            retries += 1;
+            self.wait_for_retry(retries, &error).await;
+        }
+    }
+
+    async fn sink_ddl(&mut self, mut data: Vec<DdlData>, batch: bool) -> anyhow::Result<()> {
+        call_with_retry!(self, data, sink_ddl, batch)
+    }
+
+    async fn sink_dcl(&mut self, mut data: Vec<DclData>, batch: bool) -> anyhow::Result<()> {
+        call_with_retry!(self, data, sink_dcl, batch)
+    }
+
+    async fn sink_raw(&mut self, mut data: Vec<DtItem>, batch: bool) -> anyhow::Result<()> {
+        call_with_retry!(self, data, sink_raw, batch)
+    }
+
+    async fn sink_message(&mut self, mut data: Vec<MessageData>) -> anyhow::Result<()> {
+        call_with_retry!(self, data, sink_message)
+    }
+
+    async fn sink_struct(&mut self, mut data: Vec<StructData>) -> anyhow::Result<()> {
+        call_with_retry!(self, data, sink_struct)
+    }
+
+    async fn close(&mut self) -> anyhow::Result<()> {
+        self.inner.lock().await.close().await
+    }
+
+    async fn refresh_meta(&mut self, data: Vec<DdlData>) -> anyhow::Result<()> {
+        self.inner.lock().await.refresh_meta(data).await
+    }
+
+    async fn flush(&mut self) -> anyhow::Result<()> {
+        self.inner.lock().await.flush().await
+    }
+
+    fn get_id(&self) -> String {
+        match self.inner.try_lock() {
+            Some(inner) => inner.get_id(),
+            None => String::new(),
+        }
+    }
+}
+
+impl RetrySinker {
+    async fn wait_for_retry(&self, retries: u64, error: &anyhow::Error) {
+        let interval_ms = self.get_retry_interval_ms(retries);
+        log_warn!(
+            "sink failed, retry: {}/{} after {} ms, error: {:?}",
+            retries,
+            self.max_retries,
+            interval_ms,
+            error
+        );
+        self.monitor.add_counter(CounterType::SinkRetries, 1);
+        TimeUtil::sleep_millis(interval_ms).await;
+    }
+
+    fn get_retry_interval_ms(&self, retries: u64) -> u64 {
+        TimeUtil::get_backoff_millis(self.retry_interval_ms, self.retry_max_interval_ms, retries)
+    }
+
+    pub fn is_retriable(error: &anyhow::Error) -> bool {
+        for cause in error.chain() {
+            if let Some(err) = cause.downcast_ref::<sqlx::Error>() {
+                return Self::is_sqlx_retriable(err);
+            }
+            if let Some(err) = cause.downcast_ref::<mongodb::error::Error>() {
+                return Self::is_mongo_retriable(err);
+            }
+            if let Some(err) = cause.downcast_ref::<reqwest::Error>() {
+                return err.is_connect() || err.is_timeout();
+            }
+            if let Some(err) = cause.downcast_ref::<std::io::Error>() {
+                return Self::is_io_retriable(err);
+            }
+        }
+        false
+    }
+
+    // connection and timeout errors only, others (eg: permission denied, disk full) are not transient
+    fn is_io_retriable(error: &std::io::Error) -> bool {
+        matches!(
+            error.kind(),
+            std::io::ErrorKind::ConnectionRefused
+                | std::io::ErrorKind::ConnectionReset
+                | std::io::ErrorKind::ConnectionAborted
+                | std::io::ErrorKind::NotConnected
+                | std::io::ErrorKind::BrokenPipe
+                | std::io::ErrorKind::TimedOut
+                | std::io::ErrorKind::UnexpectedEof
+        )
+    }
+
+    fn is_sqlx_retriable(error: &sqlx::Error) -> bool {
+        match error {
+            sqlx::Error::Io(err) => Self::is_io_retriable(err),
+            sqlx::Error::PoolTimedOut | sqlx::Error::WorkerCrashed => true,
+            sqlx::Error::Database(db_error) => {
+                if let Some(err) = db_error.try_downcast_ref::<MySqlDatabaseError>() {
+                    return MYSQL_RETRIABLE_ERRORS.contains(&err.number());
+                }
+                if let Some(err) = db_error.try_downcast_ref::<PgDatabaseError>() {
+                    let code = err.code();
+                    return PG_RETRIABLE_STATES.contains(&code)
+                        || code.starts_with(PG_RETRIABLE_STATE_CLASS);
+                }
+                false
+            }
+            _ => false,
+        }
+    }
+
+    fn is_mongo_retriable(error: &mongodb::error::Error) -> bool {
+        if error.contains_label(RETRYABLE_WRITE_ERROR)
+            || error.contains_label(TRANSIENT_TRANSACTION_ERROR)
+        {
+            return true;
+        }
+
+        match error.kind.as_ref() {
+            ErrorKind::Io(err) => Self::is_io_retriable(err),
+            ErrorKind::ConnectionPoolCleared { .. } | ErrorKind::ServerSelection { .. } => true,
+            ErrorKind::Command(err) => MONGO_RETRIABLE_CODES.contains(&err.code),
+            ErrorKind::Write(WriteFailure::WriteConcernError(err)) => {
+                MONGO_RETRIABLE_CODES.contains(&err.code)
+            }
+            ErrorKind::BulkWrite(err) => err
+                .write_concern_error
+                .as_ref()
+                .is_some_and(|err| MONGO_RETRIABLE_CODES.contains(&err.code)),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sinker::dummy_sinker::DummySinker;
+    use anyhow::Context;
+
+    fn mock_sinker() -> RetrySinker {
+        let inner: Box<dyn Sinker + Send> = Box::new(DummySinker {});
+        RetrySinker {
+            inner: Arc::new(async_mutex::Mutex::new(inner)),
+            max_retries: 10,
+            retry_interval_ms: 100,
+            retry_max_interval_ms: 1000,
+            monitor: Arc::new(Monitor::new("sinker", "", 1, 100, 1)),
+        }
+    }
+
+    #[test]
+    fn test_get_retry_interval_ms() {
+        let sinker = mock_sinker();
+        let expects = [(1, 50, 100), (2, 100, 200), (3, 200, 400), (4, 400, 800)];
+        for (retries, min, max) in expects {
+            let interval_ms = sinker.get_retry_interval_ms(retries);
+            assert!(interval_ms >= min && interval_ms <= max);
+        }
+        // capped by retry_max_interval_ms
+        for retries in 5..100 {
+            let interval_ms = sinker.get_retry_interval_ms(retries);
+            assert!((500..=1000).contains(&interval_ms));
+        }
+    }
+
+    #[test]
+    fn test_is_retriable() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::ConnectionReset, "reset");
+        let error = Err::<(), _>(sqlx::Error::Io(io_error))
+            .context("serial sink failed")
+            .unwrap_err();
+        assert!(RetrySinker::is_retriable(&error));
+
+        let error = Err::<(), _>(sqlx::Error::RowNotFound)
+            .context("serial sink failed")
+            .unwrap_err();
+        assert!(!RetrySinker::is_retriable(&error));
+
+        let error = anyhow::anyhow!("unknown error");
+        assert!(!RetrySinker::is_retriable(&error));
+
+        let io_error = std::io::Error::new(std::io::ErrorKind::TimedOut, "timed out");
+        let error = anyhow::Error::new(io_error).context("write failed");
+        assert!(RetrySinker::is_retriable(&error));
+
+        let io_error = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "denied");
+        let error = anyhow::Error::new(io_error).context("write failed");
+        assert!(!RetrySinker::is_retriable(&error));
+
+        let io_error = std::io::Error::new(std::io::ErrorKind::InvalidData, "bad packet");
+        let error = Err::<(), _>(sqlx::Error::Io(io_error))
+            .context("serial sink failed")
+            .unwrap_err();
+        assert!(!RetrySinker::is_retriable(&error));
+    }
+}
@@ -46,10 +46,19 @@ pub struct StarRocksSinker {
 #[async_trait]
 impl Sinker for StarRocksSinker {
     async fn sink_dml(&mut self, mut data: Vec<RowData>, batch: bool) -> anyhow::Result<()> {
+        self.sink_dml_ref(&mut data, batch).await
+    }
+
+    // rows are converted in place by send_data, which is idempotent for retries
+    async fn sink_dml_ref(&mut self, data: &mut Vec<RowData>, batch: bool) -> anyhow::Result<()> {
         if data.is_empty() {
             return Ok(());
         }
 
+        for row_data in data.iter() {
+            BaseSinker::check_full_row_image(row_data, "starrocks")?;
+        }
+
         if !batch {
             self.serial_sink(data).await?;
         } else {
@@ -64,13 +73,16 @@ impl Sinker for StarRocksSinker {
 }
 
 impl StarRocksSinker {
-    async fn serial_sink(&mut self, mut data: Vec<RowData>) -> anyhow::Result<()> {
+    async fn serial_sink(&mut self, data: &mut Vec<RowData>) -> anyhow::Result<()> {
         let mut data_size = 0;
 
-        let data = data.as_mut_slice();
         for i in 0..data.len() {
             data_size += data[i].data_size;
-            self.send_data(data, i, 1).await?;
+            if let Err(error) = self.send_data(data, i, 1).await {
+                // keep rows not sinked yet for retries
+                data.drain(..i);
+                return Err(error);
+            }
         }
 
         BaseSinker::update_serial_monitor(&self.monitor, data.len() as u64, data_size as u64).await
@@ -4,6 +4,8 @@ use dt_precheck::{config::task_config::PrecheckTaskConfig, do_precheck};
 use dt_task::task_runner::TaskRunner;
 
 const ENV_SHUTDOWN_TIMEOUT_SECS: &str = "SHUTDOWN_TIMEOUT_SECS";
+const SETUP_DDL_CAPTURE: &str = "setup_ddl_capture";
+const TEARDOWN_DDL_CAPTURE: &str = "teardown_ddl_capture";
 
 #[tokio::main]
 async fn main() {
@@ -21,6 +23,18 @@ async fn main() {
         std::process::exit(0);
     });
 
+    // ape_dts setup_ddl_capture / teardown_ddl_capture task_config.ini
+    if let Some(cmd @ (SETUP_DDL_CAPTURE | TEARDOWN_DDL_CAPTURE)) = env::args().nth(1).as_deref() {
+        let task_config = env::args().nth(2).expect("no task_config provided in args");
+        let runner = TaskRunner::new(&task_config).unwrap();
+        runner
+            .setup_ddl_capture(cmd == TEARDOWN_DDL_CAPTURE)
+            .await
+            .unwrap();
+        println!("{} finished.", cmd);
+        return;
+    }
+
     let task_config = env::args().nth(1).expect("no task_config provided in args");
     if PrecheckTaskConfig::new(&task_config).is_ok() {
         do_precheck(&task_config).await;
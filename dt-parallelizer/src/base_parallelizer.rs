@@ -12,6 +12,11 @@ use dt_common::monitor::counter_type::CounterType;
 use dt_common::{error::Error, monitor::monitor::Monitor};
 use dt_connector::Sinker;
 
+use crate::DmlSinkError;
+
+// rows not sinked and the error if a sub sinker fails
+pub type DmlSinkResult = Result<(), (anyhow::Error, Vec<RowData>)>;
+
 #[derive(Default)]
 pub struct BaseParallelizer {
     pub poped_data: VecDeque<DtItem>,
@@ -27,12 +32,13 @@ impl BaseParallelizer {
         }
 
         let mut record_size_counter = Counter::new(0, 0);
-        // ddls and dmls should be drained seperately
+        // ddls, messages and dmls should be drained seperately
         while let Ok(item) = self.pop(buffer, &mut record_size_counter).await {
             if data.is_empty()
-                || (data[0].is_ddl() == item.is_ddl()
-                    && data[0].data_origin_node == item.data_origin_node)
-                || data[0].is_dcl() == item.is_dcl()
+                || (data[0].is_message() == item.is_message()
+                    && ((data[0].is_ddl() == item.is_ddl()
+                        && data[0].data_origin_node == item.data_origin_node)
+                        || data[0].is_dcl() == item.is_dcl()))
             {
                 data.push(item);
             } else {
@@ -110,14 +116,38 @@ impl BaseParallelizer {
     ) -> anyhow::Result<()> {
         let mut join_set = tokio::task::JoinSet::new();
         for i in 0..sub_datas.len() {
-            let data = sub_datas.remove(0);
+            let mut data = sub_datas.remove(0);
             let sinker = sinkers[i % parallel_size].clone();
-            join_set.spawn(async move { sinker.lock().await.sink_dml(data, batch).await });
+            join_set.spawn(async move {
+                let result = sinker.lock().await.sink_dml_ref(&mut data, batch).await;
+                (i, result.map_err(|error| (error, data)))
+            });
         }
+        Self::join_dml_sinkers(join_set).await
+    }
+
+    /// wait for all sub sinkers, if any fails, return DmlSinkError with the first error and
+    /// rows not sinked by the failed sub sinkers, in the order of sub datas
+    pub async fn join_dml_sinkers(
+        mut join_set: tokio::task::JoinSet<(usize, DmlSinkResult)>,
+    ) -> anyhow::Result<()> {
+        let mut failures = Vec::new();
         while let Some(result) = join_set.join_next().await {
-            result??;
+            if let (i, Err((error, rows))) = result? {
+                failures.push((i, error, rows));
+            }
         }
-        Ok(())
+        if failures.is_empty() {
+            return Ok(());
+        }
+
+        failures.sort_by_key(|(i, _, _)| *i);
+        let mut failures = failures.into_iter();
+        let (_, error, mut rows) = failures.next().unwrap();
+        for (_, _, sub_rows) in failures {
+            rows.extend(sub_rows);
+        }
+        Err(DmlSinkError { rows, error }.into())
     }
 
     pub async fn sink_ddl(
@@ -11,7 +11,7 @@ pub mod serial_parallelizer;
 pub mod snapshot_parallelizer;
 pub mod table_parallelizer;
 
-use std::sync::Arc;
+use std::{fmt, sync::Arc};
 
 use async_trait::async_trait;
 use dt_common::meta::{
@@ -105,3 +105,25 @@ impl DataSize {
         self
     }
 }
+
+/// returned by sink_dml of parallelizers if any sub sinker fails, rows are those not sinked
+/// by the failed sub sinkers (kept by Sinker::sink_dml_ref, empty if taken by the sinkers),
+/// so that they can be written again without copying all rows before sinking
+#[derive(Debug)]
+pub struct DmlSinkError {
+    pub rows: Vec<RowData>,
+    pub error: anyhow::Error,
+}
+
+impl fmt::Display for DmlSinkError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)
+    }
+}
+
+impl std::error::Error for DmlSinkError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        // the chain continues with the error of the failed sub sinker, eg: for RetrySinker::is_retriable
+        Some(&*self.error)
+    }
+}
@@ -1,7 +1,7 @@
 use std::{cmp, sync::Arc};
 
-use super::base_parallelizer::BaseParallelizer;
-use crate::{DataSize, Merger, Parallelizer};
+use super::base_parallelizer::{BaseParallelizer, DmlSinkResult};
+use crate::{DataSize, DmlSinkError, Merger, Parallelizer};
 use async_trait::async_trait;
 use dt_common::config::sinker_config::BasicSinkerConfig;
 use dt_common::meta::dcl_meta::dcl_data::DclData;
@@ -58,18 +58,15 @@ impl Parallelizer for MergeParallelizer {
         let mut data_size = DataSize::default();
         // no need to check foreign key since foreign key checks were disabled in MySQL/Postgres connections
         let mut tb_merged_datas = self.merger.merge(data).await?;
-        data_size.add(
-            self.sink_dml_internal(&mut tb_merged_datas, sinkers, MergeType::Delete)
-                .await?,
-        );
-        data_size.add(
-            self.sink_dml_internal(&mut tb_merged_datas, sinkers, MergeType::Insert)
-                .await?,
-        );
-        data_size.add(
-            self.sink_dml_internal(&mut tb_merged_datas, sinkers, MergeType::Unmerged)
-                .await?,
-        );
+        for merge_type in [MergeType::Delete, MergeType::Insert, MergeType::Unmerged] {
+            match self
+                .sink_dml_internal(&mut tb_merged_datas, sinkers, merge_type)
+                .await
+            {
+                Ok(size) => data_size.add(size),
+                Err(error) => return Err(Self::append_unsinked_rows(error, tb_merged_datas)),
+            }
+        }
         Ok(data_size)
     }
 
@@ -116,10 +113,10 @@ impl MergeParallelizer {
         sinkers: &[Arc<async_mutex::Mutex<Box<dyn Sinker + Send>>>],
         merge_type: MergeType,
     ) -> anyhow::Result<DataSize> {
-        let mut futures = Vec::new();
+        let mut join_set = tokio::task::JoinSet::new();
         let mut data_size = DataSize::default();
         for tb_merged_data in tb_merged_datas.iter_mut() {
-            let data: Vec<RowData> = match merge_type {
+            let mut data: Vec<RowData> = match merge_type {
                 MergeType::Delete => tb_merged_data.delete_rows.drain(..).collect(),
                 MergeType::Insert => tb_merged_data.insert_rows.drain(..).collect(),
                 MergeType::Unmerged => tb_merged_data.unmerged_rows.drain(..).collect(),
@@ -140,53 +137,69 @@ impl MergeParallelizer {
 
             match merge_type {
                 MergeType::Insert | MergeType::Delete => {
-                    let mut i = 0;
-                    while i < data.len() {
-                        let sub_size = cmp::min(batch_size, data.len() - i);
-                        let sub_data = data[i..i + sub_size].to_vec();
-                        let sinker = sinkers[futures.len() % self.parallel_size].clone();
-                        let future = tokio::spawn(async move {
-                            sinker.lock().await.sink_dml(sub_data, true).await.unwrap();
+                    while !data.is_empty() {
+                        let sub_size = cmp::min(batch_size, data.len());
+                        let mut sub_data: Vec<RowData> = data.drain(..sub_size).collect();
+                        let i = join_set.len();
+                        let sinker = sinkers[i % self.parallel_size].clone();
+                        join_set.spawn(async move {
+                            let result =
+                                sinker.lock().await.sink_dml_ref(&mut sub_data, true).await;
+                            (i, result.map_err(|error| (error, sub_data)))
                         });
-                        futures.push(future);
-                        i += batch_size;
                     }
                 }
 
                 MergeType::Unmerged => {
-                    let sinker = sinkers[futures.len() % self.parallel_size].clone();
-                    let future = tokio::spawn(async move {
-                        Self::sink_unmerged_rows(sinker, data).await.unwrap();
-                    });
-                    futures.push(future);
+                    let i = join_set.len();
+                    let sinker = sinkers[i % self.parallel_size].clone();
+                    join_set
+                        .spawn(async move { (i, Self::sink_unmerged_rows(sinker, data).await) });
                 }
             }
         }
 
-        // wait for sub sinkers to finish and unwrap errors
-        for future in futures {
-            future.await.unwrap();
-        }
+        // wait for sub sinkers to finish, rows not sinked are returned by DmlSinkError
+        BaseParallelizer::join_dml_sinkers(join_set).await?;
         Ok(data_size)
     }
 
     async fn sink_unmerged_rows(
         sinker: Arc<async_mutex::Mutex<Box<dyn Sinker + Send>>>,
-        data: Vec<RowData>,
-    ) -> anyhow::Result<()> {
-        let mut start = 0;
-        for i in 1..=data.len() {
-            if i == data.len() || data[i].row_type != data[start].row_type {
-                let sub_data = data[start..i].to_vec();
-                if data[start].row_type == RowType::Insert {
-                    sinker.lock().await.sink_dml(sub_data, true).await?;
-                } else {
-                    // for Delete / Update, the safest way is serial
-                    sinker.lock().await.sink_dml(sub_data, false).await?;
-                }
-                start = i;
+        mut data: Vec<RowData>,
+    ) -> DmlSinkResult {
+        while !data.is_empty() {
+            let end = data
+                .iter()
+                .position(|row_data| row_data.row_type != data[0].row_type)
+                .unwrap_or(data.len());
+            let mut sub_data: Vec<RowData> = data.drain(..end).collect();
+            // for Delete / Update, the safest way is serial
+            let batch = sub_data[0].row_type == RowType::Insert;
+            if let Err(error) = sinker.lock().await.sink_dml_ref(&mut sub_data, batch).await {
+                // rows after the failed ones are not sinked either
+                sub_data.append(&mut data);
+                return Err((error, sub_data));
             }
         }
         Ok(())
     }
+
+    // rows of later merge types are not sinked if a former one fails
+    fn append_unsinked_rows(
+        error: anyhow::Error,
+        tb_merged_datas: Vec<TbMergedData>,
+    ) -> anyhow::Error {
+        match error.downcast::<DmlSinkError>() {
+            Ok(mut error) => {
+                for tb_merged_data in tb_merged_datas {
+                    error.rows.extend(tb_merged_data.delete_rows);
+                    error.rows.extend(tb_merged_data.insert_rows);
+                    error.rows.extend(tb_merged_data.unmerged_rows);
+                }
+                error.into()
+            }
+            Err(error) => error,
+        }
+    }
 }
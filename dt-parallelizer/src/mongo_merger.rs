@@ -70,22 +70,24 @@ impl MongoMerger {
                 RowType::Update => {
                     let before = row_data.before.unwrap();
                     let after: HashMap<String, ColValue> = row_data.after.unwrap();
-                    let delete_row = RowData::new(
+                    let mut delete_row = RowData::new(
                         row_data.schema.clone(),
                         row_data.tb.clone(),
                         RowType::Delete,
                         Some(before),
                         None,
                     );
+                    delete_row.position = row_data.position.clone();
                     delete_map.insert(id.clone(), delete_row);
 
-                    let insert_row = RowData::new(
+                    let mut insert_row = RowData::new(
                         row_data.schema,
                         row_data.tb,
                         RowType::Insert,
                         Option::None,
                         Some(after),
                     );
+                    insert_row.position = row_data.position;
                     insert_map.insert(id, insert_row);
                 }
             }
@@ -65,8 +65,9 @@ impl RdbMerger {
 
         // case 1: table has no primary/unique key
         // case 2: any key col value is NULL
+        // case 3: partial row image, which can not be batch inserted or split into delete + insert
         let hash_code = Self::get_hash_code(&row_data, tb_meta).await?;
-        if hash_code == 0 {
+        if hash_code == 0 || row_data.partial {
             merged.unmerged_rows.push(row_data);
             return Ok(());
         }
@@ -96,13 +97,15 @@ impl RdbMerger {
                 if Self::check_collision(&merged.insert_rows, tb_meta, &insert, insert_hash_code)
                     || Self::check_collision(&merged.delete_rows, tb_meta, &delete, hash_code)
                 {
-                    let row_data = RowData::new(
+                    let mut row_data = RowData::new(
                         delete.schema,
                         delete.tb,
                         RowType::Update,
                         delete.before,
                         insert.after,
                     );
+                    row_data.partial = insert.partial;
+                    row_data.position = insert.position;
                     merged.unmerged_rows.push(row_data);
                     return Ok(());
                 }
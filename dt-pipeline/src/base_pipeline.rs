@@ -6,15 +6,19 @@ use std::sync::{
 use async_trait::async_trait;
 use tokio::{sync::Mutex, sync::RwLock, time::Instant};
 
-use crate::{lua_processor::LuaProcessor, Pipeline};
+use crate::{
+    ddl_policy::DdlPolicy, lua_processor::LuaProcessor, mongo_flattener::MongoFlattener,
+    mongo_shaper::MongoShaper, rdb_transformer::RdbTransformer, Pipeline,
+};
 use dt_common::{
-    config::sinker_config::SinkerConfig,
-    log_info, log_position,
+    config::{config_enums::DdlPolicyType, sinker_config::SinkerConfig},
+    log_info, log_position, log_sql, log_warn,
     meta::{
         dcl_meta::dcl_data::DclData,
         ddl_meta::ddl_data::DdlData,
         dt_data::{DtData, DtItem},
         dt_queue::DtQueue,
+        message_data::MessageData,
         position::Position,
         row_data::RowData,
         syncer::Syncer,
@@ -22,8 +26,11 @@ use dt_common::{
     monitor::{counter_type::CounterType, monitor::Monitor},
     utils::time_util::TimeUtil,
 };
-use dt_connector::{data_marker::DataMarker, Sinker};
-use dt_parallelizer::{DataSize, Parallelizer};
+use dt_connector::{
+    data_marker::DataMarker, dead_letter::dead_letter_writer::DeadLetterWriter,
+    sinker::retry_sinker::RetrySinker, Sinker,
+};
+use dt_parallelizer::{DataSize, DmlSinkError, Parallelizer};
 
 pub struct BasePipeline {
     pub buffer: Arc<DtQueue>,
@@ -37,6 +44,11 @@ pub struct BasePipeline {
     pub monitor: Arc<Monitor>,
     pub data_marker: Option<Arc<RwLock<DataMarker>>>,
     pub lua_processor: Option<LuaProcessor>,
+    pub dead_letter_writer: Option<DeadLetterWriter>,
+    pub ddl_policy: DdlPolicy,
+    pub transformer: RdbTransformer,
+    pub flattener: Option<MongoFlattener>,
+    pub shaper: Option<MongoShaper>,
 }
 
 enum SinkMethod {
@@ -44,6 +56,7 @@ enum SinkMethod {
     Ddl,
     Dcl,
     Dml,
+    Message,
     Struct,
 }
 
@@ -53,6 +66,13 @@ impl Pipeline for BasePipeline {
         for sinker in self.sinkers.iter_mut() {
             sinker.lock().await.close().await?;
         }
+        if let Some(dead_letter_writer) = &mut self.dead_letter_writer {
+            dead_letter_writer.close().await?;
+        }
+        if let Some(shaper) = &self.shaper {
+            shaper.close().await?;
+        }
+        self.ddl_policy.stop_approval_server().await;
         self.parallelizer.close().await
     }
 
@@ -63,6 +83,7 @@ impl Pipeline for BasePipeline {
             self.sinkers.len(),
             self.checkpoint_interval_secs
         );
+        self.ddl_policy.start_approval_server()?;
 
         let mut last_sink_time = Instant::now();
         let mut last_checkpoint_time = Instant::now();
@@ -108,6 +129,7 @@ impl Pipeline for BasePipeline {
                 SinkMethod::Dcl => self.sink_dcl(data).await?,
                 SinkMethod::Dml => self.sink_dml(data).await?,
                 SinkMethod::Raw => self.sink_raw(data).await?,
+                SinkMethod::Message => self.sink_message(data).await?,
                 SinkMethod::Struct => self.sink_struct(data).await?,
             };
 
@@ -125,7 +147,7 @@ impl Pipeline for BasePipeline {
                     &last_received_position,
                     &last_commit_position,
                 )
-                .await;
+                .await?;
 
             self.monitor
                 .add_counter(CounterType::SinkedRecordTotal, data_size.count)
@@ -136,7 +158,7 @@ impl Pipeline for BasePipeline {
         }
 
         self.record_checkpoint(None, &last_received_position, &last_commit_position)
-            .await;
+            .await?;
         Ok(())
     }
 }
@@ -144,10 +166,18 @@ impl Pipeline for BasePipeline {
 impl BasePipeline {
     async fn sink_raw(
         &mut self,
-        all_data: Vec<DtItem>,
+        mut all_data: Vec<DtItem>,
     ) -> anyhow::Result<(DataSize, Option<Position>, Option<Position>)> {
         let (data_count, last_received_position, last_commit_position) = Self::fetch_raw(&all_data);
         if data_count > 0 {
+            if !self.transformer.is_empty() {
+                for item in all_data.iter_mut() {
+                    if let DtData::Dml { row_data } = &mut item.dt_data {
+                        self.transformer.transform(row_data, &item.position)?;
+                    }
+                }
+            }
+
             let data_size = self.parallelizer.sink_raw(all_data, &self.sinkers).await?;
             Ok((data_size, last_received_position, last_commit_position))
         } else {
@@ -177,14 +207,44 @@ impl BasePipeline {
         &mut self,
         all_data: Vec<DtItem>,
     ) -> anyhow::Result<(DataSize, Option<Position>, Option<Position>)> {
-        let (mut data, last_received_position, last_commit_position) = Self::fetch_dml(all_data);
+        let with_positions = self.lua_processor.is_some()
+            || !self.transformer.is_empty()
+            || self.dead_letter_writer.is_some();
+        let (mut data, mut positions, last_received_position, last_commit_position) =
+            Self::fetch_dml(all_data, with_positions);
         if !data.is_empty() {
+            // flatten mongo documents into rows of relational tables
+            if let Some(flattener) = &self.flattener {
+                (data, positions) = flattener.process(data, positions)?;
+            }
+
+            // execute column transforms
+            if !self.transformer.is_empty() {
+                for (row_data, position) in data.iter_mut().zip(positions.iter()) {
+                    self.transformer.transform(row_data, position)?;
+                }
+            }
+
             // execute lua processor
             if let Some(lua_processor) = &self.lua_processor {
-                data = lua_processor.process(data)?;
+                (data, positions) = lua_processor.process(data, positions)?;
+            }
+
+            // shape rows of relational tables into mongo documents
+            if let Some(shaper) = &mut self.shaper {
+                (data, positions) = shaper.process(data, positions).await?;
             }
 
-            let data_size = self.parallelizer.sink_dml(data, &self.sinkers).await?;
+            let data_size = if self.dead_letter_writer.is_some() {
+                // positions go along with rows which may be split or reordered by sinkers
+                for (row_data, position) in data.iter_mut().zip(positions) {
+                    row_data.position = Some(position);
+                }
+                self.sink_dml_with_dead_letter(data, &last_received_position)
+                    .await?
+            } else {
+                self.parallelizer.sink_dml(data, &self.sinkers).await?
+            };
             Ok((data_size, last_received_position, last_commit_position))
         } else {
             Ok((
@@ -195,30 +255,137 @@ impl BasePipeline {
         }
     }
 
+    /// if the batch fails with a non-retriable error, sink rows not sinked by the failed sub sinkers
+    /// one by one, rows still failing are written to dead letter with their own positions
+    async fn sink_dml_with_dead_letter(
+        &mut self,
+        data: Vec<RowData>,
+        last_received_position: &Option<Position>,
+    ) -> anyhow::Result<DataSize> {
+        let mut data_size = DataSize::default();
+        data_size
+            .add_count(data.len() as u64)
+            .add_bytes(data.iter().map(|v| v.get_data_size()).sum());
+
+        let (error, rows) = match self.parallelizer.sink_dml(data, &self.sinkers).await {
+            Ok(size) => return Ok(size),
+            Err(error) => Self::split_dml_sink_error(error)?,
+        };
+        // rows were taken by the sinker, can not be sinked again
+        if rows.is_empty() {
+            return Err(error);
+        }
+        log_warn!(
+            "batch sink failed, will sink {} rows not sinked one by one, error: {:#}",
+            rows.len(),
+            error
+        );
+
+        for row_data in rows {
+            let error = match self
+                .parallelizer
+                .sink_dml(vec![row_data], &self.sinkers)
+                .await
+            {
+                Ok(_) => continue,
+                Err(error) => error,
+            };
+            let (error, failed_rows) = Self::split_dml_sink_error(error)?;
+            if failed_rows.is_empty() {
+                return Err(error);
+            }
+            for mut row_data in failed_rows {
+                data_size.count -= 1;
+                data_size.bytes -= row_data.get_data_size();
+                let position = row_data
+                    .position
+                    .take()
+                    .or_else(|| last_received_position.clone())
+                    .unwrap_or(Position::None);
+                if let Some(dead_letter_writer) = &mut self.dead_letter_writer {
+                    dead_letter_writer
+                        .write(row_data, &position, &error)
+                        .await?;
+                }
+            }
+        }
+        Ok(data_size)
+    }
+
+    // retriable errors and errors not returned by sub sinkers are returned directly
+    fn split_dml_sink_error(error: anyhow::Error) -> anyhow::Result<(anyhow::Error, Vec<RowData>)> {
+        if RetrySinker::is_retriable(&error) {
+            return Err(error);
+        }
+        match error.downcast::<DmlSinkError>() {
+            Ok(DmlSinkError { rows, error }) => Ok((error, rows)),
+            Err(error) => Err(error),
+        }
+    }
+
     async fn sink_ddl(
         &mut self,
         all_data: Vec<DtItem>,
     ) -> anyhow::Result<(DataSize, Option<Position>, Option<Position>)> {
         let (data, last_received_position, last_commit_position) = Self::fetch_ddl(all_data);
-        if !data.is_empty() {
-            let data_size = self
+        let mut data_size = DataSize::default();
+        for (ddl_data, position) in data {
+            // the source table has changed whether or not the ddl is applied to the target
+            if let Some(shaper) = &mut self.shaper {
+                shaper.invalidate_cache_by_ddl_data(&ddl_data);
+            }
+
+            let policy = self.ddl_policy.get_policy(&ddl_data);
+            let apply = match policy {
+                DdlPolicyType::Apply | DdlPolicyType::ApplyIgnoreErrors => true,
+                DdlPolicyType::Skip => {
+                    log_info!("ddl skipped, position: {}, ddl: {}", position, ddl_data);
+                    false
+                }
+                DdlPolicyType::LogOnly => {
+                    log_sql!("{}", ddl_data.to_sql());
+                    false
+                }
+                DdlPolicyType::PauseForApproval => {
+                    self.ddl_policy
+                        .wait_for_approval(&ddl_data, &position)
+                        .await?
+                }
+            };
+            if !apply {
+                continue;
+            }
+
+            match self
                 .parallelizer
-                .sink_ddl(data.clone(), &self.sinkers)
-                .await?;
+                .sink_ddl(vec![ddl_data.clone()], &self.sinkers)
+                .await
+            {
+                Ok(size) => data_size.add(size),
+                Err(error) if policy == DdlPolicyType::ApplyIgnoreErrors => {
+                    log_warn!(
+                        "ddl failed and ignored, position: {}, ddl: {}, error: {:#}",
+                        position,
+                        ddl_data,
+                        error
+                    );
+                }
+                Err(error) => return Err(error),
+            }
             // only part of sinkers will execute sink_ddl, but all sinkers should refresh metadata
             for sinker in self.sinkers.iter_mut() {
-                sinker.lock().await.refresh_meta(data.clone()).await?;
+                sinker
+                    .lock()
+                    .await
+                    .refresh_meta(vec![ddl_data.clone()])
+                    .await?;
             }
+        }
+        if data_size.count > 0 {
             self.monitor
                 .add_counter(CounterType::DDLRecordTotal, data_size.count);
-            Ok((data_size, last_received_position, last_commit_position))
-        } else {
-            Ok((
-                DataSize::default(),
-                last_received_position,
-                last_commit_position,
-            ))
         }
+        Ok((data_size, last_received_position, last_commit_position))
     }
 
     async fn sink_dcl(
@@ -236,6 +403,27 @@ impl BasePipeline {
         Ok((data_size, last_received_position, last_commit_position))
     }
 
+    async fn sink_message(
+        &mut self,
+        all_data: Vec<DtItem>,
+    ) -> anyhow::Result<(DataSize, Option<Position>, Option<Position>)> {
+        let (data, last_received_position, last_commit_position) = Self::fetch_message(all_data);
+        let data_size = DataSize {
+            count: data.len() as u64,
+            bytes: data.iter().map(|i| i.get_malloc_size()).sum(),
+        };
+        for message_data in data.iter() {
+            log_info!("sink message: {}", message_data);
+        }
+        // messages should keep their order, so they are sinked serially by one sinker
+        if data_size.count > 0 {
+            if let Some(sinker) = self.sinkers.first() {
+                sinker.lock().await.sink_message(data).await?;
+            }
+        }
+        Ok((data_size, last_received_position, last_commit_position))
+    }
+
     pub fn fetch_raw(data: &[DtItem]) -> (u64, Option<Position>, Option<Position>) {
         let mut data_count = 0;
         let mut last_received_position = Option::None;
@@ -267,8 +455,17 @@ impl BasePipeline {
         (data_count, last_received_position, last_commit_position)
     }
 
-    fn fetch_dml(mut data: Vec<DtItem>) -> (Vec<RowData>, Option<Position>, Option<Position>) {
+    fn fetch_dml(
+        mut data: Vec<DtItem>,
+        with_positions: bool,
+    ) -> (
+        Vec<RowData>,
+        Vec<Position>,
+        Option<Position>,
+        Option<Position>,
+    ) {
         let mut dml_data = Vec::new();
+        let mut positions = Vec::new();
         let mut last_received_position = Option::None;
         let mut last_commit_position = Option::None;
         for i in data.drain(..) {
@@ -280,6 +477,9 @@ impl BasePipeline {
                 }
 
                 DtData::Dml { row_data } => {
+                    if with_positions {
+                        positions.push(i.position.clone());
+                    }
                     last_received_position = Some(i.position);
                     dml_data.push(row_data);
                 }
@@ -288,10 +488,17 @@ impl BasePipeline {
             }
         }
 
-        (dml_data, last_received_position, last_commit_position)
+        (
+            dml_data,
+            positions,
+            last_received_position,
+            last_commit_position,
+        )
     }
 
-    fn fetch_ddl(mut data: Vec<DtItem>) -> (Vec<DdlData>, Option<Position>, Option<Position>) {
+    fn fetch_ddl(
+        mut data: Vec<DtItem>,
+    ) -> (Vec<(DdlData, Position)>, Option<Position>, Option<Position>) {
         let mut result = Vec::new();
         let mut last_received_position = Option::None;
         let mut last_commit_position = Option::None;
@@ -304,9 +511,9 @@ impl BasePipeline {
                 }
 
                 DtData::Ddl { ddl_data } => {
-                    last_commit_position = Some(i.position);
+                    last_commit_position = Some(i.position.clone());
                     last_received_position = last_commit_position.clone();
-                    result.push(ddl_data);
+                    result.push((ddl_data, i.position));
                 }
 
                 _ => {}
@@ -340,17 +547,44 @@ impl BasePipeline {
         (result, last_received_position, last_commit_position)
     }
 
+    fn fetch_message(
+        mut data: Vec<DtItem>,
+    ) -> (Vec<MessageData>, Option<Position>, Option<Position>) {
+        let mut result = Vec::new();
+        let mut last_received_position = Option::None;
+        let mut last_commit_position = Option::None;
+        for i in data.drain(..) {
+            match i.dt_data {
+                DtData::Commit { .. } | DtData::Heartbeat {} => {
+                    last_commit_position = Some(i.position);
+                    last_received_position = last_commit_position.clone();
+                }
+
+                DtData::Message { message_data } => {
+                    last_received_position = Some(i.position);
+                    result.push(message_data);
+                }
+
+                _ => {}
+            }
+        }
+
+        (result, last_received_position, last_commit_position)
+    }
+
     fn get_sink_method(&self, data: &Vec<DtItem>) -> SinkMethod {
         for i in data {
             match i.dt_data {
                 DtData::Struct { .. } => return SinkMethod::Struct,
                 DtData::Ddl { .. } => return SinkMethod::Ddl,
                 DtData::Dcl { .. } => return SinkMethod::Dcl,
+                DtData::Message { .. } => return SinkMethod::Message,
                 DtData::Dml { .. } => match self.sinker_config {
                     SinkerConfig::FoxlakePush { .. }
                     | SinkerConfig::FoxlakeMerge { .. }
                     | SinkerConfig::Foxlake { .. }
-                    | SinkerConfig::Redis { .. } => return SinkMethod::Raw,
+                    | SinkerConfig::Redis { .. }
+                    | SinkerConfig::File { .. } => return SinkMethod::Raw,
                     _ => return SinkMethod::Dml,
                 },
                 DtData::Redis { .. } | DtData::Foxlake { .. } => return SinkMethod::Raw,
@@ -367,13 +601,19 @@ impl BasePipeline {
         last_checkpoint_time: Option<Instant>,
         last_received_position: &Position,
         last_commit_position: &Position,
-    ) -> Instant {
+    ) -> anyhow::Result<Instant> {
         if let Some(last) = last_checkpoint_time {
             if last.elapsed().as_secs() < self.checkpoint_interval_secs {
-                return last;
+                return Ok(last);
             }
         }
 
+        // data buffered by sinkers must be durable before its position is committed,
+        // this also flushes sinkers periodically when no more data arrives
+        for sinker in self.sinkers.iter() {
+            sinker.lock().await.flush().await?;
+        }
+
         log_position!("current_position | {}", last_received_position.to_string());
         log_position!("checkpoint_position | {}", last_commit_position.to_string());
 
@@ -386,6 +626,6 @@ impl BasePipeline {
             last_received_position.to_timestamp(),
         );
 
-        Instant::now()
+        Ok(Instant::now())
     }
 }
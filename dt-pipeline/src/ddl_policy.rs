@@ -0,0 +1,391 @@
+use std::{fs, path::Path, str::FromStr};
+
+use actix_web::{
+    dev::ServerHandle, http::header, web, App, HttpRequest, HttpResponse, HttpServer, Responder,
+};
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::time::Instant;
+
+use dt_common::{
+    config::{
+        config_enums::{DbType, DdlPolicyType},
+        ddl_policy_config::DdlPolicyConfig,
+        filter_config::FilterConfig,
+    },
+    error::Error,
+    log_error, log_info, log_warn,
+    meta::{ddl_meta::ddl_data::DdlData, position::Position},
+    rdb_filter::RdbFilter,
+    utils::time_util::TimeUtil,
+};
+
+const JSON_PREFIX: &str = "json:";
+const PENDING: &str = "pending";
+const APPROVE: &str = "approve";
+const REJECT: &str = "reject";
+const WAITING_LOG_INTERVAL_SECS: u64 = 60;
+
+/// decide how a ddl is handled by the sinker side, rules are matched in order
+/// by ddl type and the routed (target) schema / tb, the first matched one wins
+#[derive(Default)]
+pub struct DdlPolicy {
+    pub default_policy: DdlPolicyType,
+    pub rules: Vec<(RdbFilter, DdlPolicyType)>,
+    pub approval_dir: String,
+    pub approval_http_host: String,
+    pub approval_http_port: u64,
+    pub approval_http_token: String,
+    server_handle: Option<ServerHandle>,
+}
+
+#[derive(Clone)]
+struct ApprovalServerState {
+    approval_dir: String,
+    approval_http_token: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct PendingDdl {
+    id: String,
+    schema: String,
+    tb: String,
+    ddl_type: String,
+    sql: String,
+    position: String,
+}
+
+impl DdlPolicy {
+    pub fn from_config(config: &DdlPolicyConfig, db_type: &DbType) -> anyhow::Result<Self> {
+        Ok(Self {
+            default_policy: config.default_policy.clone(),
+            rules: Self::parse_rules(&config.policies, db_type)?,
+            approval_dir: config.approval_dir.clone(),
+            approval_http_host: config.approval_http_host.clone(),
+            approval_http_port: config.approval_http_port,
+            approval_http_token: config.approval_http_token.clone(),
+            server_handle: None,
+        })
+    }
+
+    pub fn get_policy(&self, ddl_data: &DdlData) -> DdlPolicyType {
+        let (schema, tb) = ddl_data.get_schema_tb();
+        for (filter, policy) in self.rules.iter() {
+            if !filter.filter_ddl(&schema, &tb, &ddl_data.ddl_type) {
+                return policy.clone();
+            }
+        }
+        self.default_policy.clone()
+    }
+
+    /// block until the ddl is approved (true) or rejected (false) by creating
+    /// [approval_dir]/[id].approve or [approval_dir]/[id].reject.
+    /// the whole pipeline pauses meanwhile, including dmls of other tables and checkpoints,
+    /// the extractor keeps buffering until [pipeline] buffer_size is reached and then stops
+    /// reading from source, the source may close the idle connection if it waits too long
+    pub async fn wait_for_approval(
+        &self,
+        ddl_data: &DdlData,
+        position: &Position,
+    ) -> anyhow::Result<bool> {
+        let id = Self::get_approval_id(ddl_data, position);
+        let pending_file = Self::get_marker_file(&self.approval_dir, &id, PENDING);
+        let approve_file = Self::get_marker_file(&self.approval_dir, &id, APPROVE);
+        let reject_file = Self::get_marker_file(&self.approval_dir, &id, REJECT);
+
+        // the ddl may have been decided before the task restarted
+        if !Path::new(&approve_file).exists() && !Path::new(&reject_file).exists() {
+            let (schema, tb) = ddl_data.get_schema_tb();
+            let pending = PendingDdl {
+                id: id.clone(),
+                schema,
+                tb,
+                ddl_type: ddl_data.ddl_type.to_string(),
+                sql: ddl_data.to_sql(),
+                position: position.to_string(),
+            };
+            fs::create_dir_all(&self.approval_dir)?;
+            fs::write(&pending_file, serde_json::to_string(&pending)?)
+                .with_context(|| format!("failed to write pending ddl: {}", pending_file))?;
+            log_warn!(
+                "ddl paused for approval, create {} to apply or {} to skip, ddl: {}",
+                approve_file,
+                reject_file,
+                pending.sql
+            );
+        }
+
+        let start_time = Instant::now();
+        let mut log_time = Instant::now();
+        loop {
+            let approved = Path::new(&approve_file).exists();
+            if approved || Path::new(&reject_file).exists() {
+                let _ = fs::remove_file(&pending_file);
+                log_info!("ddl approval: {}, approved: {}", id, approved);
+                return Ok(approved);
+            }
+
+            if log_time.elapsed().as_secs() >= WAITING_LOG_INTERVAL_SECS {
+                log_warn!(
+                    "pipeline paused for {} secs, waiting for approval of ddl: {}",
+                    start_time.elapsed().as_secs(),
+                    id
+                );
+                log_time = Instant::now();
+            }
+            TimeUtil::sleep_millis(1000).await;
+        }
+    }
+
+    pub fn start_approval_server(&mut self) -> anyhow::Result<()> {
+        if self.approval_http_port == 0 {
+            return Ok(());
+        }
+
+        let state = ApprovalServerState {
+            approval_dir: self.approval_dir.clone(),
+            approval_http_token: self.approval_http_token.clone(),
+        };
+        let addr = format!("{}:{}", self.approval_http_host, self.approval_http_port);
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(web::Data::new(state.clone()))
+                .service(web::resource("/ddl/pending").route(web::get().to(list_pending_handler)))
+                .service(
+                    web::resource("/ddl/{id}/{decision}").route(web::post().to(decide_handler)),
+                )
+        })
+        .workers(1)
+        .bind(&addr)
+        .with_context(|| format!("failed to bind ddl approval server: {}", addr))?
+        .run();
+
+        self.server_handle = Some(server.handle());
+        tokio::spawn(server);
+        log_info!("ddl approval server started: {}", addr);
+        Ok(())
+    }
+
+    pub async fn stop_approval_server(&mut self) {
+        if let Some(handle) = self.server_handle.take() {
+            handle.stop(true).await;
+        }
+    }
+
+    fn get_approval_id(ddl_data: &DdlData, position: &Position) -> String {
+        // stable across restarts and rust releases, so the decision made before still works
+        let mut hasher = Sha256::new();
+        hasher.update(position.to_string().as_bytes());
+        hasher.update([0u8]);
+        hasher.update(ddl_data.to_sql().as_bytes());
+        format!("{:x}", hasher.finalize())[..16].to_string()
+    }
+
+    fn get_marker_file(approval_dir: &str, id: &str, suffix: &str) -> String {
+        format!("{}/{}.{}", approval_dir, id, suffix)
+    }
+
+    fn list_pending(approval_dir: &str) -> anyhow::Result<Vec<PendingDdl>> {
+        let mut result = Vec::new();
+        if !Path::new(approval_dir).exists() {
+            return Ok(result);
+        }
+        for entry in fs::read_dir(approval_dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|i| i.to_str()) != Some(PENDING) {
+                continue;
+            }
+            let content = fs::read_to_string(&path)?;
+            result.push(serde_json::from_str(&content)?);
+        }
+        Ok(result)
+    }
+
+    fn parse_rules(
+        config_str: &str,
+        db_type: &DbType,
+    ) -> anyhow::Result<Vec<(RdbFilter, DdlPolicyType)>> {
+        let mut results = Vec::new();
+        if config_str.trim().is_empty() {
+            return Ok(results);
+        }
+        // policies=json:[{"ddl_types":"drop_table,truncate_table","tbs":"db_1.*","policy":"skip"}]
+        #[derive(Deserialize)]
+        struct Rule {
+            #[serde(default = "default_ddl_types")]
+            ddl_types: String,
+            #[serde(default = "default_tbs")]
+            tbs: String,
+            policy: String,
+        }
+        fn default_ddl_types() -> String {
+            "*".into()
+        }
+        fn default_tbs() -> String {
+            "*.*".into()
+        }
+
+        let rules: Vec<Rule> = serde_json::from_str(config_str.trim_start_matches(JSON_PREFIX))?;
+        for rule in rules {
+            let policy = match DdlPolicyType::from_str(&rule.policy) {
+                Ok(policy) => policy,
+                Err(_) => bail! {Error::ConfigError(format!(
+                    "invalid ddl policy: {}",
+                    rule.policy
+                ))},
+            };
+            let filter_config = FilterConfig {
+                do_tbs: rule.tbs,
+                do_ddls: rule.ddl_types,
+                ..Default::default()
+            };
+            results.push((RdbFilter::from_config(&filter_config, db_type)?, policy));
+        }
+        Ok(results)
+    }
+}
+
+fn check_token(req: &HttpRequest, state: &ApprovalServerState) -> Option<HttpResponse> {
+    let expected = format!("Bearer {}", state.approval_http_token);
+    let authorized = req
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        // compare digests to avoid leaking the token by timing
+        .is_some_and(|v| Sha256::digest(v.as_bytes()) == Sha256::digest(expected.as_bytes()));
+    if authorized {
+        None
+    } else {
+        Some(HttpResponse::Unauthorized().body("invalid approval token"))
+    }
+}
+
+async fn list_pending_handler(
+    req: HttpRequest,
+    state: web::Data<ApprovalServerState>,
+) -> impl Responder {
+    if let Some(response) = check_token(&req, &state) {
+        return response;
+    }
+
+    match DdlPolicy::list_pending(&state.approval_dir) {
+        Ok(pending) => HttpResponse::Ok().json(pending),
+        Err(err) => {
+            log_error!("failed to list pending ddls: {:?}", err);
+            HttpResponse::InternalServerError().body(err.to_string())
+        }
+    }
+}
+
+async fn decide_handler(
+    req: HttpRequest,
+    state: web::Data<ApprovalServerState>,
+    path: web::Path<(String, String)>,
+) -> impl Responder {
+    if let Some(response) = check_token(&req, &state) {
+        return response;
+    }
+
+    let (id, decision) = path.into_inner();
+    if decision != APPROVE && decision != REJECT {
+        return HttpResponse::BadRequest().body(format!("invalid decision: {}", decision));
+    }
+
+    let pending_file = DdlPolicy::get_marker_file(&state.approval_dir, &id, PENDING);
+    if !id.chars().all(|c| c.is_ascii_hexdigit()) || !Path::new(&pending_file).exists() {
+        return HttpResponse::NotFound().body(format!("pending ddl not found: {}", id));
+    }
+
+    let marker_file = DdlPolicy::get_marker_file(&state.approval_dir, &id, &decision);
+    match fs::write(&marker_file, "") {
+        Ok(_) => HttpResponse::Ok().body(format!("{}: {}", id, decision)),
+        Err(err) => HttpResponse::InternalServerError().body(err.to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test::TestRequest;
+    use dt_common::meta::ddl_meta::{
+        ddl_statement::{DdlStatement, MysqlAlterTableStatement},
+        ddl_type::DdlType,
+    };
+
+    #[test]
+    fn test_get_policy() {
+        let config = DdlPolicyConfig {
+            default_policy: DdlPolicyType::Apply,
+            policies: r#"json:[{"ddl_types":"drop_table,truncate_table","tbs":"db_1.*","policy":"skip"},{"ddl_types":"alter_table","policy":"pause_for_approval"},{"tbs":"db_2.tb_1","policy":"log_only"}]"#.into(),
+            ..Default::default()
+        };
+        let ddl_policy = DdlPolicy::from_config(&config, &DbType::Mysql).unwrap();
+
+        let build_ddl = |ddl_type: DdlType, db: &str, tb: &str| DdlData {
+            ddl_type,
+            db_type: DbType::Mysql,
+            statement: DdlStatement::MysqlAlterTable(MysqlAlterTableStatement {
+                db: db.into(),
+                tb: tb.into(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let ddl = build_ddl(DdlType::DropTable, "db_1", "tb_1");
+        assert_eq!(ddl_policy.get_policy(&ddl), DdlPolicyType::Skip);
+        let ddl = build_ddl(DdlType::DropTable, "db_3", "tb_1");
+        assert_eq!(ddl_policy.get_policy(&ddl), DdlPolicyType::Apply);
+        let ddl = build_ddl(DdlType::AlterTable, "db_1", "tb_1");
+        assert_eq!(ddl_policy.get_policy(&ddl), DdlPolicyType::PauseForApproval);
+        let ddl = build_ddl(DdlType::CreateIndex, "db_2", "tb_1");
+        assert_eq!(ddl_policy.get_policy(&ddl), DdlPolicyType::LogOnly);
+        let ddl = build_ddl(DdlType::CreateIndex, "db_2", "tb_2");
+        assert_eq!(ddl_policy.get_policy(&ddl), DdlPolicyType::Apply);
+    }
+
+    #[test]
+    fn test_get_approval_id() {
+        let ddl = DdlData {
+            db_type: DbType::Mysql,
+            statement: DdlStatement::MysqlAlterTable(MysqlAlterTableStatement {
+                db: "db_1".into(),
+                tb: "tb_1".into(),
+                unparsed: "ADD COLUMN c_1 INT".into(),
+            }),
+            ..Default::default()
+        };
+        let position = Position::RdbSnapshotFinished {
+            db_type: "mysql".into(),
+            schema: "db_1".into(),
+            tb: "tb_1".into(),
+        };
+
+        let id = DdlPolicy::get_approval_id(&ddl, &position);
+        assert_eq!(id.len(), 16);
+        assert!(id.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(id, DdlPolicy::get_approval_id(&ddl, &position));
+        assert_ne!(id, DdlPolicy::get_approval_id(&ddl, &Position::None));
+    }
+
+    #[test]
+    fn test_check_token() {
+        let state = ApprovalServerState {
+            approval_dir: String::new(),
+            approval_http_token: "abc".into(),
+        };
+        let req = TestRequest::default()
+            .insert_header((header::AUTHORIZATION, "Bearer abc"))
+            .to_http_request();
+        assert!(check_token(&req, &state).is_none());
+
+        let req = TestRequest::default()
+            .insert_header((header::AUTHORIZATION, "Bearer abd"))
+            .to_http_request();
+        assert!(check_token(&req, &state).is_some());
+
+        let req = TestRequest::default().to_http_request();
+        assert!(check_token(&req, &state).is_some());
+    }
+}
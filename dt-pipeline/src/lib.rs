@@ -1,6 +1,10 @@
 pub mod base_pipeline;
+pub mod ddl_policy;
 pub mod http_server_pipeline;
 pub mod lua_processor;
+pub mod mongo_flattener;
+pub mod mongo_shaper;
+pub mod rdb_transformer;
 
 use async_trait::async_trait;
 
@@ -1,57 +1,207 @@
 use std::collections::HashMap;
 use std::str::FromStr;
 
+use dt_common::config::config_enums::DbType;
+use dt_common::config::filter_config::FilterConfig;
+use dt_common::config::processor_config::ProcessorConfig;
 use dt_common::meta::col_value::ColValue;
+use dt_common::meta::position::Position;
 use dt_common::meta::row_data::RowData;
 use dt_common::meta::row_type::RowType;
-use mlua::{IntoLua, Lua};
+use dt_common::rdb_filter::RdbFilter;
+use mlua::{IntoLua, Lua, RegistryKey};
 
+/// the lua VM lives as long as the task, so globals set by the init code or by scripts
+/// are kept across rows and batches, scripts are compiled only once
 pub struct LuaProcessor {
-    pub lua_code: String,
+    lua: Lua,
+    default_code: Option<RegistryKey>,
+    // scripts for specific tables, matched by the schema / tb passed into lua, the first matched one wins
+    tb_codes: Vec<(RdbFilter, RegistryKey)>,
 }
 
 impl LuaProcessor {
-    pub fn process(&self, data: Vec<RowData>) -> anyhow::Result<Vec<RowData>> {
-        let mut new_data = Vec::new();
+    pub fn from_config(config: &ProcessorConfig, db_type: &DbType) -> anyhow::Result<Self> {
         let lua = Lua::new();
+        if !config.lua_init_code.is_empty() {
+            lua.load(&config.lua_init_code)
+                .set_name("lua_init_code")
+                .exec()?;
+        }
+
+        let default_code = if config.lua_code.is_empty() {
+            None
+        } else {
+            Some(Self::compile(&lua, &config.lua_code, "lua_code")?)
+        };
+
+        let mut tb_codes = Vec::new();
+        for (tbs, code) in config.lua_tb_codes.iter() {
+            let filter_config = FilterConfig {
+                do_tbs: tbs.clone(),
+                ..Default::default()
+            };
+            let filter = RdbFilter::from_config(&filter_config, db_type)?;
+            tb_codes.push((filter, Self::compile(&lua, code, tbs)?));
+        }
+
+        Ok(Self {
+            lua,
+            default_code,
+            tb_codes,
+        })
+    }
+
+    /// positions should be either empty or matched with data one by one,
+    /// the returned positions are matched with the returned rows
+    pub fn process(
+        &self,
+        data: Vec<RowData>,
+        positions: Vec<Position>,
+    ) -> anyhow::Result<(Vec<RowData>, Vec<Position>)> {
+        let mut new_data = Vec::new();
+        let mut new_positions = Vec::new();
+        let mut positions = positions.into_iter();
 
         for row_data in data {
-            // to lua
-            let (lua_before, blob_before) = self.col_values_to_lua_table(row_data.before, &lua)?;
-            let (lua_after, blob_after) = self.col_values_to_lua_table(row_data.after, &lua)?;
-
-            lua.globals().set("before", lua_before)?;
-            lua.globals().set("after", lua_after)?;
-            lua.globals().set("schema", row_data.schema)?;
-            lua.globals().set("tb", row_data.tb)?;
-            lua.globals()
-                .set("row_type", row_data.row_type.to_string())?;
-
-            // execute lua
-            lua.load(&self.lua_code).exec()?;
-
-            // row filtered
-            let row_type: String = lua.globals().get("row_type")?;
-            if row_type.is_empty() {
+            let position = positions.next().unwrap_or(Position::None);
+            let Some(code) = self.get_code(&row_data.schema, &row_data.tb) else {
+                new_data.push(row_data);
+                new_positions.push(position);
                 continue;
+            };
+
+            let partial = row_data.partial;
+            for mut new_row_data in self.process_row(code, row_data, &position)? {
+                // cols absent in the row image are still absent after processed
+                new_row_data.partial = partial;
+                new_data.push(new_row_data);
+                new_positions.push(position.clone());
             }
+        }
 
-            // from lua
-            let lua_before: mlua::Table = lua.globals().get("before")?;
-            let lua_after: mlua::Table = lua.globals().get("after")?;
-            let before = self.lua_table_to_col_values(lua_before, blob_before)?;
-            let after = self.lua_table_to_col_values(lua_after, blob_after)?;
+        Ok((new_data, new_positions))
+    }
 
-            let schema = lua.globals().get("schema")?;
-            let tb = lua.globals().get("tb")?;
-            let row_type = RowType::from_str(&row_type)?;
-            let new_row_data = RowData::new(schema, tb, row_type, before, after);
-            new_data.push(new_row_data);
+    fn process_row(
+        &self,
+        code: &RegistryKey,
+        row_data: RowData,
+        position: &Position,
+    ) -> anyhow::Result<Vec<RowData>> {
+        let lua = &self.lua;
+        let globals = lua.globals();
+
+        // to lua
+        let (lua_before, blob_before) = self.col_values_to_lua_table(row_data.before, lua)?;
+        let (lua_after, blob_after) = self.col_values_to_lua_table(row_data.after, lua)?;
+
+        globals.set("before", lua_before)?;
+        globals.set("after", lua_after)?;
+        globals.set("schema", row_data.schema)?;
+        globals.set("tb", row_data.tb)?;
+        globals.set("row_type", row_data.row_type.to_string())?;
+        globals.set("position", position.to_string())?;
+        globals.set("timestamp", position.to_timestamp() as i64)?;
+        globals.set("rows", mlua::Value::Nil)?;
+
+        // execute lua
+        let func: mlua::Function = lua.registry_value(code)?;
+        func.call::<_, ()>(())?;
+
+        // from lua
+        let schema: String = globals.get("schema")?;
+        let tb: String = globals.get("tb")?;
+        let row_type: String = globals.get("row_type")?;
+        let lua_before: mlua::Table = globals.get("before")?;
+        let lua_after: mlua::Table = globals.get("after")?;
+
+        // fan-out: rows = { { schema = .., tb = .., row_type = .., before = {..}, after = {..} }, .. },
+        // fields not set in an item are taken from the globals
+        let mut new_data = Vec::new();
+        if let mlua::Value::Table(rows) = globals.get::<_, mlua::Value>("rows")? {
+            for item in rows.sequence_values::<mlua::Table>() {
+                let item = item?;
+                let item_schema = item
+                    .get::<_, Option<String>>("schema")?
+                    .unwrap_or_else(|| schema.clone());
+                let item_tb = item
+                    .get::<_, Option<String>>("tb")?
+                    .unwrap_or_else(|| tb.clone());
+                let item_row_type = item
+                    .get::<_, Option<String>>("row_type")?
+                    .unwrap_or_else(|| row_type.clone());
+                let item_before = item
+                    .get::<_, Option<mlua::Table>>("before")?
+                    .unwrap_or_else(|| lua_before.clone());
+                let item_after = item
+                    .get::<_, Option<mlua::Table>>("after")?
+                    .unwrap_or_else(|| lua_after.clone());
+                if let Some(new_row_data) = self.build_row_data(
+                    item_schema,
+                    item_tb,
+                    &item_row_type,
+                    item_before,
+                    item_after,
+                    &blob_before,
+                    &blob_after,
+                )? {
+                    new_data.push(new_row_data);
+                }
+            }
+            return Ok(new_data);
         }
 
+        if let Some(new_row_data) = self.build_row_data(
+            schema,
+            tb,
+            &row_type,
+            lua_before,
+            lua_after,
+            &blob_before,
+            &blob_after,
+        )? {
+            new_data.push(new_row_data);
+        }
         Ok(new_data)
     }
 
+    #[allow(clippy::too_many_arguments)]
+    fn build_row_data(
+        &self,
+        schema: String,
+        tb: String,
+        row_type: &str,
+        lua_before: mlua::Table,
+        lua_after: mlua::Table,
+        blob_before: &HashMap<String, ColValue>,
+        blob_after: &HashMap<String, ColValue>,
+    ) -> anyhow::Result<Option<RowData>> {
+        // row filtered
+        if row_type.is_empty() {
+            return Ok(None);
+        }
+
+        let before = self.lua_table_to_col_values(lua_before, blob_before)?;
+        let after = self.lua_table_to_col_values(lua_after, blob_after)?;
+        let row_type = RowType::from_str(row_type)?;
+        Ok(Some(RowData::new(schema, tb, row_type, before, after)))
+    }
+
+    fn get_code(&self, schema: &str, tb: &str) -> Option<&RegistryKey> {
+        for (filter, code) in self.tb_codes.iter() {
+            if !filter.filter_tb(schema, tb) {
+                return Some(code);
+            }
+        }
+        self.default_code.as_ref()
+    }
+
+    fn compile(lua: &Lua, code: &str, name: &str) -> anyhow::Result<RegistryKey> {
+        let func = lua.load(code).set_name(name).into_function()?;
+        Ok(lua.create_registry_value(func)?)
+    }
+
     fn col_values_to_lua_table<'lua>(
         &'lua self,
         col_values: Option<HashMap<String, ColValue>>,
@@ -63,10 +213,12 @@ impl LuaProcessor {
         if let Some(map) = col_values {
             for (key, col_value) in map {
                 let lua_value = match col_value {
-                    // do not support editing Blob columns in lua, pass empty values into lua
-                    ColValue::Blob(_) => {
-                        blob_col_values.insert(key.clone(), col_value);
-                        self.col_value_to_lua_value(ColValue::Blob(Vec::new()), lua)?
+                    // Blob columns are read-only in lua, they are passed as byte strings
+                    // and the original values are set back
+                    ColValue::Blob(v) => {
+                        let lua_value = mlua::Value::String(lua.create_string(&v)?);
+                        blob_col_values.insert(key.clone(), ColValue::Blob(v));
+                        lua_value
                     }
                     _ => self.col_value_to_lua_value(col_value, lua)?,
                 };
@@ -80,7 +232,7 @@ impl LuaProcessor {
     fn lua_table_to_col_values(
         &self,
         lua_table: mlua::Table,
-        blob_col_values: HashMap<String, ColValue>,
+        blob_col_values: &HashMap<String, ColValue>,
     ) -> anyhow::Result<Option<HashMap<String, ColValue>>> {
         if lua_table.is_empty() {
             return Ok(None);
@@ -95,15 +247,10 @@ impl LuaProcessor {
         }
 
         for (col, blob_col_value) in blob_col_values {
-            // if some col was removed(set to nil) in lua, the col should not exist in map
-            // Some(col_value) = map.get(&col) means: col was NOT removed in lua
-            if let Some(col_value) = map.get(&col) {
-                // since we passed mlua::Value::NULL into lua for blob columns,
-                // *col_value == ColValue::None means: column value was not removed and not changed in lua,
-                // in this case, set the original blob_col_value back
-                if *col_value == ColValue::None {
-                    map.insert(col, blob_col_value);
-                }
+            // if some col was removed(set to nil) in lua, the col should not exist in map,
+            // otherwise set the original blob_col_value back since Blob columns are read-only
+            if map.contains_key(col) {
+                map.insert(col.clone(), blob_col_value.clone());
             }
         }
 
@@ -159,7 +306,11 @@ impl LuaProcessor {
             mlua::Value::Boolean(v) => ColValue::Bool(v),
             mlua::Value::Integer(v) => ColValue::LongLong(v),
             mlua::Value::Number(v) => ColValue::Double(v),
-            mlua::Value::String(v) => ColValue::String(v.to_str()?.to_string()),
+            mlua::Value::String(v) => match v.to_str() {
+                Ok(s) => ColValue::String(s.to_string()),
+                // not utf-8, e.g. Blob values copied into another column
+                Err(_) => ColValue::Blob(v.as_bytes().to_vec()),
+            },
             _ => ColValue::None,
         };
         Ok(col_value)
@@ -0,0 +1,602 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use mongodb::bson::{Bson, Document};
+use serde::Deserialize;
+
+use dt_common::{
+    config::flattener_config::FlattenerConfig,
+    error::Error,
+    meta::{
+        col_value::ColValue, mongo::mongo_constant::MongoConstants, position::Position,
+        row_data::RowData, row_type::RowType,
+    },
+};
+
+const JSON_PREFIX: &str = "json:";
+
+/// flattens mongo documents into rows of relational tables, configured per collection and
+/// matched by the routed (target) db / tb. Fields are picked by dotted paths, arrays are kept
+/// as json columns or flattened into child tables keyed by (parent _id, array index).
+/// Inserts and updates are turned into full-row inserts, which are upserts with [sinker] replace=true
+#[derive(Default)]
+pub struct MongoFlattener {
+    tbs: HashMap<(String, String), FlattenTb>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct FlattenTb {
+    db: String,
+    tb: String,
+    // target column of _id
+    #[serde(default = "default_id_col")]
+    id_col: String,
+    #[serde(default)]
+    cols: Vec<FlattenCol>,
+    #[serde(default)]
+    child_tbs: Vec<ChildTb>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct ChildTb {
+    // in the same db as the parent table
+    tb: String,
+    // path of the array in the parent document
+    path: String,
+    // target columns of the parent _id and the array index, primary key of the child table
+    #[serde(default = "default_id_col")]
+    parent_col: String,
+    #[serde(default = "default_index_col")]
+    index_col: String,
+    #[serde(default)]
+    cols: Vec<FlattenCol>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct FlattenCol {
+    col: String,
+    // dotted path, the same as col if not set, for child tables it is relative to
+    // the array element, and an empty path refers to the element itself
+    path: Option<String>,
+    // the value is converted by its bson type if not set
+    #[serde(rename = "type")]
+    col_type: Option<ColType>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum ColType {
+    String,
+    Int,
+    Float,
+    Bool,
+    Decimal,
+    Datetime,
+    Json,
+}
+
+fn default_id_col() -> String {
+    MongoConstants::ID.to_string()
+}
+
+fn default_index_col() -> String {
+    "_idx".to_string()
+}
+
+impl FlattenCol {
+    fn path(&self) -> &str {
+        self.path.as_deref().unwrap_or(&self.col)
+    }
+}
+
+impl MongoFlattener {
+    pub fn from_config(config: &FlattenerConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            tbs: Self::parse_tbs(&config.tbs)?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tbs.is_empty()
+    }
+
+    /// positions should be either empty or matched with data one by one,
+    /// the returned positions are matched with the returned rows
+    pub fn process(
+        &self,
+        data: Vec<RowData>,
+        positions: Vec<Position>,
+    ) -> anyhow::Result<(Vec<RowData>, Vec<Position>)> {
+        let with_positions = !positions.is_empty();
+        let mut new_data = Vec::new();
+        let mut new_positions = Vec::new();
+        let mut positions = positions.into_iter();
+
+        for row_data in data {
+            let position = positions.next().unwrap_or(Position::None);
+            let rows = match self
+                .tbs
+                .get(&(row_data.schema.clone(), row_data.tb.clone()))
+            {
+                Some(flatten_tb) => Self::flatten(flatten_tb, &row_data)?,
+                None => vec![row_data],
+            };
+
+            for row in rows {
+                new_data.push(row);
+                if with_positions {
+                    new_positions.push(position.clone());
+                }
+            }
+        }
+        Ok((new_data, new_positions))
+    }
+
+    fn flatten(flatten_tb: &FlattenTb, row_data: &RowData) -> anyhow::Result<Vec<RowData>> {
+        let get_doc = |col_values: &Option<HashMap<String, ColValue>>| match col_values
+            .as_ref()
+            .and_then(|v| v.get(MongoConstants::DOC))
+        {
+            Some(ColValue::MongoDoc(doc)) => Some(doc.clone()),
+            _ => None,
+        };
+
+        if let Some(after) = &row_data.after {
+            if after.contains_key(MongoConstants::DIFF_DOC) {
+                bail! {Error::PipelineError(format!(
+                    "updates from op_log only contain diffs and can not be flattened, use [extractor] source=change_stream, db: {}, tb: {}",
+                    row_data.schema, row_data.tb
+                ))}
+            }
+        }
+
+        let before = get_doc(&row_data.before);
+        let after = get_doc(&row_data.after);
+        let mut rows = Vec::new();
+        match row_data.row_type {
+            RowType::Insert | RowType::Update => {
+                // for change stream updates, after is empty if the document was deleted
+                // before being looked up, the delete will follow
+                let Some(after) = after else {
+                    return Ok(rows);
+                };
+                let id = Self::get_id(&after)?;
+                rows.push(RowData::new(
+                    row_data.schema.clone(),
+                    flatten_tb.tb.clone(),
+                    RowType::Insert,
+                    None,
+                    Some(Self::build_cols(flatten_tb, &id, &after)?),
+                ));
+
+                for child_tb in flatten_tb.child_tbs.iter() {
+                    // the pre-image may be unavailable, so all child rows of the document are
+                    // deleted before re-inserting the array, which removes the shrunk elements
+                    if row_data.row_type == RowType::Update {
+                        rows.push(Self::build_child_delete(&row_data.schema, child_tb, &id));
+                    }
+                    for cols in Self::build_child_cols(child_tb, &id, &after)? {
+                        rows.push(RowData::new(
+                            row_data.schema.clone(),
+                            child_tb.tb.clone(),
+                            RowType::Insert,
+                            None,
+                            Some(cols),
+                        ));
+                    }
+                }
+            }
+
+            RowType::Delete => {
+                let Some(before) = before else {
+                    return Ok(rows);
+                };
+                let id = Self::get_id(&before)?;
+                for child_tb in flatten_tb.child_tbs.iter() {
+                    rows.push(Self::build_child_delete(&row_data.schema, child_tb, &id));
+                }
+                rows.push(RowData::new(
+                    row_data.schema.clone(),
+                    flatten_tb.tb.clone(),
+                    RowType::Delete,
+                    Some(HashMap::from([(flatten_tb.id_col.clone(), id)])),
+                    None,
+                ));
+            }
+        }
+        Ok(rows)
+    }
+
+    fn get_id(doc: &Document) -> anyhow::Result<ColValue> {
+        match doc.get(MongoConstants::ID) {
+            Some(id) => Self::to_col_value(MongoConstants::ID, Some(id), &None),
+            None => bail! {Error::PipelineError(format!(
+                "document without _id can not be flattened: {}",
+                doc
+            ))},
+        }
+    }
+
+    fn build_cols(
+        flatten_tb: &FlattenTb,
+        id: &ColValue,
+        doc: &Document,
+    ) -> anyhow::Result<HashMap<String, ColValue>> {
+        let mut cols = HashMap::from([(flatten_tb.id_col.clone(), id.clone())]);
+        for col in flatten_tb.cols.iter() {
+            let value = Self::get_doc_path(doc, col.path());
+            cols.insert(
+                col.col.clone(),
+                Self::to_col_value(&col.col, value, &col.col_type)?,
+            );
+        }
+        Ok(cols)
+    }
+
+    fn build_child_cols(
+        child_tb: &ChildTb,
+        id: &ColValue,
+        doc: &Document,
+    ) -> anyhow::Result<Vec<HashMap<String, ColValue>>> {
+        let mut rows = Vec::new();
+        let Some(Bson::Array(items)) = Self::get_doc_path(doc, &child_tb.path) else {
+            return Ok(rows);
+        };
+
+        for (i, item) in items.iter().enumerate() {
+            let mut cols = HashMap::from([
+                (child_tb.parent_col.clone(), id.clone()),
+                (child_tb.index_col.clone(), ColValue::LongLong(i as i64)),
+            ]);
+            for col in child_tb.cols.iter() {
+                let value = Self::get_path(item, col.path());
+                cols.insert(
+                    col.col.clone(),
+                    Self::to_col_value(&col.col, value, &col.col_type)?,
+                );
+            }
+            rows.push(cols);
+        }
+        Ok(rows)
+    }
+
+    /// DELETE FROM child WHERE parent_col = _id, the row is partial without index_col
+    fn build_child_delete(db: &str, child_tb: &ChildTb, id: &ColValue) -> RowData {
+        let before = HashMap::from([(child_tb.parent_col.clone(), id.clone())]);
+        let mut row_data = RowData::new(
+            db.into(),
+            child_tb.tb.clone(),
+            RowType::Delete,
+            Some(before),
+            None,
+        );
+        row_data.partial = true;
+        row_data
+    }
+
+    fn get_doc_path<'a>(doc: &'a Document, path: &str) -> Option<&'a Bson> {
+        let (key, sub_path) = path.split_once('.').unwrap_or((path, ""));
+        Self::get_path(doc.get(key)?, sub_path)
+    }
+
+    /// path segments are document keys or array indexes, e.g. items.0.sku
+    fn get_path<'a>(value: &'a Bson, path: &str) -> Option<&'a Bson> {
+        if path.is_empty() {
+            return Some(value);
+        }
+
+        let mut current = value;
+        for key in path.split('.') {
+            current = match current {
+                Bson::Document(doc) => doc.get(key)?,
+                Bson::Array(items) => items.get(key.parse::<usize>().ok()?)?,
+                _ => return None,
+            };
+        }
+        Some(current)
+    }
+
+    fn to_col_value(
+        col: &str,
+        value: Option<&Bson>,
+        col_type: &Option<ColType>,
+    ) -> anyhow::Result<ColValue> {
+        let value = match value {
+            None | Some(Bson::Null) | Some(Bson::Undefined) => return Ok(ColValue::None),
+            Some(value) => value,
+        };
+
+        let Some(col_type) = col_type else {
+            return Self::bson_to_col_value(value);
+        };
+
+        let col_value = match (col_type, value) {
+            (ColType::Json, _) => ColValue::Json2(Self::to_json(value)),
+
+            (ColType::String, Bson::String(v)) => ColValue::String(v.clone()),
+            (ColType::String, Bson::Document(_) | Bson::Array(_)) => {
+                ColValue::String(Self::to_json(value))
+            }
+            (ColType::String, _) => match Self::bson_to_col_value(value)?.to_option_string() {
+                Some(v) => ColValue::String(v),
+                None => ColValue::String(Self::to_json(value)),
+            },
+
+            (ColType::Int, Bson::Int32(v)) => ColValue::LongLong(*v as i64),
+            (ColType::Int, Bson::Int64(v)) => ColValue::LongLong(*v),
+            (ColType::Int, Bson::Double(v)) => ColValue::LongLong(*v as i64),
+            (ColType::Int, Bson::Boolean(v)) => ColValue::LongLong(*v as i64),
+            (ColType::Int, Bson::DateTime(v)) => ColValue::LongLong(v.timestamp_millis()),
+            (ColType::Int, Bson::String(v)) if v.trim().parse::<i64>().is_ok() => {
+                ColValue::LongLong(v.trim().parse()?)
+            }
+
+            (ColType::Float, Bson::Int32(v)) => ColValue::Double(*v as f64),
+            (ColType::Float, Bson::Int64(v)) => ColValue::Double(*v as f64),
+            (ColType::Float, Bson::Double(v)) => ColValue::Double(*v),
+            (ColType::Float, Bson::Decimal128(v)) if v.to_string().parse::<f64>().is_ok() => {
+                ColValue::Double(v.to_string().parse()?)
+            }
+            (ColType::Float, Bson::String(v)) if v.trim().parse::<f64>().is_ok() => {
+                ColValue::Double(v.trim().parse()?)
+            }
+
+            (ColType::Bool, Bson::Boolean(v)) => ColValue::Bool(*v),
+            (ColType::Bool, Bson::Int32(v)) => ColValue::Bool(*v != 0),
+            (ColType::Bool, Bson::Int64(v)) => ColValue::Bool(*v != 0),
+            (ColType::Bool, Bson::String(v)) => match v.trim().to_lowercase().as_str() {
+                "1" | "true" => ColValue::Bool(true),
+                "0" | "false" => ColValue::Bool(false),
+                _ => return Self::cast_error(col, col_type, value),
+            },
+
+            (ColType::Decimal, Bson::Int32(v)) => ColValue::Decimal(v.to_string()),
+            (ColType::Decimal, Bson::Int64(v)) => ColValue::Decimal(v.to_string()),
+            (ColType::Decimal, Bson::Double(v)) => ColValue::Decimal(v.to_string()),
+            (ColType::Decimal, Bson::Decimal128(v)) => ColValue::Decimal(v.to_string()),
+            (ColType::Decimal, Bson::String(v)) => ColValue::Decimal(v.trim().to_string()),
+
+            (ColType::Datetime, Bson::DateTime(_)) => Self::bson_to_col_value(value)?,
+            (ColType::Datetime, Bson::Timestamp(v)) => Self::bson_to_col_value(&Bson::DateTime(
+                mongodb::bson::DateTime::from_millis(v.time as i64 * 1000),
+            ))?,
+            (ColType::Datetime, Bson::Int64(v)) => {
+                Self::bson_to_col_value(&Bson::DateTime(mongodb::bson::DateTime::from_millis(*v)))?
+            }
+            (ColType::Datetime, Bson::String(v)) => ColValue::DateTime(v.clone()),
+
+            _ => return Self::cast_error(col, col_type, value),
+        };
+        Ok(col_value)
+    }
+
+    fn bson_to_col_value(value: &Bson) -> anyhow::Result<ColValue> {
+        let col_value = match value {
+            Bson::Null | Bson::Undefined => ColValue::None,
+            Bson::Boolean(v) => ColValue::Bool(*v),
+            Bson::Int32(v) => ColValue::Long(*v),
+            Bson::Int64(v) => ColValue::LongLong(*v),
+            Bson::Double(v) => ColValue::Double(*v),
+            Bson::String(v) | Bson::Symbol(v) => ColValue::String(v.clone()),
+            Bson::ObjectId(v) => ColValue::String(v.to_hex()),
+            Bson::Decimal128(v) => ColValue::Decimal(v.to_string()),
+            Bson::Binary(v) => ColValue::Blob(v.bytes.clone()),
+            // yyyy-MM-dd HH:mm:ss.SSS in UTC
+            Bson::DateTime(v) => {
+                let datetime = v.try_to_rfc3339_string()?;
+                ColValue::DateTime(datetime.replacen('T', " ", 1).trim_end_matches('Z').into())
+            }
+            _ => ColValue::Json2(Self::to_json(value)),
+        };
+        Ok(col_value)
+    }
+
+    fn to_json(value: &Bson) -> String {
+        value.clone().into_relaxed_extjson().to_string()
+    }
+
+    fn cast_error(col: &str, col_type: &ColType, value: &Bson) -> anyhow::Result<ColValue> {
+        bail! {Error::PipelineError(format!(
+            "failed to cast column: {} to {:?}, value: {}",
+            col, col_type, value
+        ))}
+    }
+
+    fn parse_tbs(config_str: &str) -> anyhow::Result<HashMap<(String, String), FlattenTb>> {
+        let mut results = HashMap::new();
+        if config_str.trim().is_empty() {
+            return Ok(results);
+        }
+
+        let items: Vec<FlattenTb> =
+            match serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX)) {
+                Ok(items) => items,
+                Err(err) => bail! {Error::ConfigError(format!(
+                    "invalid [flattener].tbs: {}",
+                    err
+                ))},
+            };
+
+        for item in items {
+            for child_tb in item.child_tbs.iter() {
+                if child_tb.path.is_empty() || child_tb.tb == item.tb {
+                    bail! {Error::ConfigError(format!(
+                        "[flattener].tbs, child_tbs should have a path and a tb different from the parent, db: {}, tb: {}",
+                        item.db, item.tb
+                    ))}
+                }
+            }
+            results.insert((item.db.clone(), item.tb.clone()), item);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use mongodb::bson::{doc, oid::ObjectId};
+
+    fn build_flattener() -> MongoFlattener {
+        let config = FlattenerConfig {
+            tbs: r#"json:[{
+                "db":"db_1","tb":"orders",
+                "cols":[
+                    {"col":"name"},
+                    {"col":"city","path":"address.city"},
+                    {"col":"amount","type":"decimal"},
+                    {"col":"tags","type":"json"}
+                ],
+                "child_tbs":[{
+                    "tb":"order_items","path":"items","parent_col":"order_id",
+                    "cols":[{"col":"sku"},{"col":"qty","type":"int"}]
+                }]
+            }]"#
+            .into(),
+        };
+        MongoFlattener::from_config(&config).unwrap()
+    }
+
+    fn build_row_data(
+        row_type: RowType,
+        before: Option<Document>,
+        after: Option<Document>,
+    ) -> RowData {
+        let to_col_values = |doc: Option<Document>| {
+            doc.map(|doc| {
+                HashMap::from([(MongoConstants::DOC.to_string(), ColValue::MongoDoc(doc))])
+            })
+        };
+        RowData::new(
+            "db_1".into(),
+            "orders".into(),
+            row_type,
+            to_col_values(before),
+            to_col_values(after),
+        )
+    }
+
+    #[test]
+    fn test_flatten_insert() {
+        let flattener = build_flattener();
+        let id = ObjectId::new();
+        let doc = doc! {
+            "_id": id,
+            "name": "a",
+            "address": {"city": "hz", "street": "s1"},
+            "amount": 10.5,
+            "tags": ["x", "y"],
+            "items": [{"sku": "s_1", "qty": "2"}, {"sku": "s_2", "qty": 3}],
+        };
+        let row_data = build_row_data(RowType::Insert, None, Some(doc));
+        let (data, positions) = flattener.process(vec![row_data], vec![]).unwrap();
+        assert!(positions.is_empty());
+        assert_eq!(data.len(), 3);
+
+        let after = data[0].after.as_ref().unwrap();
+        assert_eq!(data[0].tb, "orders");
+        assert_eq!(after["_id"], ColValue::String(id.to_hex()));
+        assert_eq!(after["name"], ColValue::String("a".into()));
+        assert_eq!(after["city"], ColValue::String("hz".into()));
+        assert_eq!(after["amount"], ColValue::Decimal("10.5".into()));
+        assert_eq!(after["tags"], ColValue::Json2(r#"["x","y"]"#.into()));
+
+        for (i, row_data) in data[1..].iter().enumerate() {
+            let after = row_data.after.as_ref().unwrap();
+            assert_eq!(row_data.tb, "order_items");
+            assert_eq!(row_data.row_type, RowType::Insert);
+            assert_eq!(after["order_id"], ColValue::String(id.to_hex()));
+            assert_eq!(after["_idx"], ColValue::LongLong(i as i64));
+            assert_eq!(after["sku"], ColValue::String(format!("s_{}", i + 1)));
+            assert_eq!(after["qty"], ColValue::LongLong(i as i64 + 2));
+        }
+    }
+
+    #[test]
+    fn test_flatten_update_and_delete() {
+        let flattener = build_flattener();
+        let before = doc! {"_id": 1, "items": [{"sku": "s_1"}, {"sku": "s_2"}, {"sku": "s_3"}]};
+        let after = doc! {"_id": 1, "name": "b", "items": [{"sku": "s_1"}]};
+
+        // update, child rows are deleted by the parent _id before re-inserting the array
+        let row_data = build_row_data(RowType::Update, Some(doc! {"_id": 1}), Some(after));
+        let (data, positions) = flattener
+            .process(vec![row_data], vec![Position::None])
+            .unwrap();
+        assert_eq!(positions.len(), data.len());
+        let row_types: Vec<(&str, RowType)> = data
+            .iter()
+            .map(|r| (r.tb.as_str(), r.row_type.clone()))
+            .collect();
+        assert_eq!(
+            row_types,
+            vec![
+                ("orders", RowType::Insert),
+                ("order_items", RowType::Delete),
+                ("order_items", RowType::Insert),
+            ]
+        );
+        assert_eq!(data[0].after.as_ref().unwrap()["city"], ColValue::None);
+        assert!(data[1].partial);
+        assert_eq!(
+            data[1].before,
+            Some(HashMap::from([("order_id".to_string(), ColValue::Long(1))]))
+        );
+        assert!(!data[2].partial);
+
+        // update whose document has been deleted before lookup
+        let row_data = build_row_data(RowType::Update, Some(doc! {"_id": 1}), None);
+        let (data, _) = flattener.process(vec![row_data], vec![]).unwrap();
+        assert!(data.is_empty());
+
+        // delete with or without pre-image
+        for before in [before, doc! {"_id": 1}] {
+            let row_data = build_row_data(RowType::Delete, Some(before), None);
+            let (data, _) = flattener.process(vec![row_data], vec![]).unwrap();
+            assert_eq!(data.len(), 2);
+            assert_eq!(data[0].tb, "order_items");
+            assert!(data[0].partial);
+            assert_eq!(
+                data[0].before,
+                Some(HashMap::from([("order_id".to_string(), ColValue::Long(1))]))
+            );
+            assert_eq!(data[1].tb, "orders");
+            assert!(!data[1].partial);
+            assert_eq!(
+                data[1].before,
+                Some(HashMap::from([("_id".to_string(), ColValue::Long(1))]))
+            );
+        }
+    }
+
+    #[test]
+    fn test_flatten_errors() {
+        let flattener = build_flattener();
+
+        // diffs from op_log
+        let mut row_data = build_row_data(RowType::Update, Some(doc! {"_id": 1}), None);
+        row_data.after = Some(HashMap::from([(
+            MongoConstants::DIFF_DOC.to_string(),
+            ColValue::MongoDoc(doc! {"$set": {"name": "c"}}),
+        )]));
+        assert!(flattener.process(vec![row_data], vec![]).is_err());
+
+        // cast failure
+        let doc = doc! {"_id": 1, "items": [{"qty": "abc"}]};
+        let row_data = build_row_data(RowType::Insert, None, Some(doc));
+        assert!(flattener.process(vec![row_data], vec![]).is_err());
+
+        // rows of other tables are kept
+        let mut row_data = build_row_data(RowType::Insert, None, Some(doc! {"_id": 1}));
+        row_data.tb = "other".into();
+        let (data, _) = flattener.process(vec![row_data.clone()], vec![]).unwrap();
+        assert_eq!(data, vec![row_data]);
+
+        let config = FlattenerConfig {
+            tbs:
+                r#"json:[{"db":"db_1","tb":"orders","child_tbs":[{"tb":"orders","path":"items"}]}]"#
+                    .into(),
+        };
+        assert!(MongoFlattener::from_config(&config).is_err());
+    }
+}
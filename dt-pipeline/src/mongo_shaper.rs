@@ -0,0 +1,884 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use chrono::{DateTime, NaiveDate, NaiveDateTime};
+use mongodb::bson::{doc, spec::BinarySubtype, Binary, Bson, Decimal128, Document};
+use serde::Deserialize;
+
+use dt_common::{
+    config::shaper_config::ShaperConfig,
+    error::Error,
+    meta::{
+        col_value::ColValue, ddl_meta::ddl_data::DdlData, mongo::mongo_constant::MongoConstants,
+        position::Position, rdb_meta_manager::RdbMetaManager, row_data::RowData, row_type::RowType,
+    },
+};
+use dt_connector::rdb_router::RdbRouter;
+
+const JSON_PREFIX: &str = "json:";
+// identifier of the embedded element in arrayFilters
+const ELEMENT_IDENTIFIER: &str = "e";
+
+/// shapes rows of relational tables into mongo documents, the routed (target) db / tb of a row
+/// are the db / collection in mongo. _id is derived from the primary key: the value itself for
+/// a single column key, or a sub document for a composite key. Rows of a child table can be
+/// embedded into an array field of the parent document, located by foreign key columns.
+pub struct MongoShaper {
+    tbs: HashMap<(String, String), ShapeTb>,
+    // (db, child tb) -> (parent tb, embed)
+    embeds: HashMap<(String, String), (String, EmbedTb)>,
+    // to get the cols and the primary key, None if only configured tables are shaped
+    meta_manager: Option<RdbMetaManager>,
+    router: RdbRouter,
+    reverse_router: RdbRouter,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+struct ShapeTb {
+    db: String,
+    tb: String,
+    // derive _id from these cols instead of the primary key
+    #[serde(default)]
+    id_cols: Vec<String>,
+    // also keep id cols as fields of the document besides _id
+    #[serde(default = "default_true")]
+    keep_id_cols: bool,
+    #[serde(default)]
+    embeds: Vec<EmbedTb>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct EmbedTb {
+    // in the same db as the parent table
+    tb: String,
+    // array field in the parent document
+    field: String,
+    // referencing the id cols of the parent table, in the same order
+    fk_cols: Vec<String>,
+    // to identify an element in the array, the primary key of the child table if not set
+    #[serde(default)]
+    id_cols: Vec<String>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl MongoShaper {
+    pub fn from_config(
+        config: &ShaperConfig,
+        router: RdbRouter,
+        meta_manager: Option<RdbMetaManager>,
+    ) -> anyhow::Result<Self> {
+        let tbs = Self::parse_tbs(&config.tbs)?;
+        let mut embeds = HashMap::new();
+        for shape_tb in tbs.values() {
+            for embed in shape_tb.embeds.iter() {
+                embeds.insert(
+                    (shape_tb.db.clone(), embed.tb.clone()),
+                    (shape_tb.tb.clone(), embed.clone()),
+                );
+            }
+        }
+
+        Ok(Self {
+            tbs,
+            embeds,
+            meta_manager,
+            reverse_router: router.reverse(),
+            router,
+        })
+    }
+
+    pub async fn close(&self) -> anyhow::Result<()> {
+        if let Some(meta_manager) = &self.meta_manager {
+            meta_manager.close().await?;
+        }
+        Ok(())
+    }
+
+    pub fn invalidate_cache_by_ddl_data(&mut self, ddl_data: &DdlData) {
+        if let Some(meta_manager) = self.meta_manager.as_mut() {
+            meta_manager.invalidate_cache_by_ddl_data(ddl_data);
+        }
+    }
+
+    /// positions should be either empty or matched with data one by one,
+    /// the returned positions are matched with the returned rows
+    pub async fn process(
+        &mut self,
+        data: Vec<RowData>,
+        positions: Vec<Position>,
+    ) -> anyhow::Result<(Vec<RowData>, Vec<Position>)> {
+        let with_positions = !positions.is_empty();
+        let mut new_data = Vec::new();
+        let mut new_positions = Vec::new();
+        let mut positions = positions.into_iter();
+
+        for row_data in data {
+            let position = positions.next().unwrap_or(Position::None);
+            let key = (row_data.schema.clone(), row_data.tb.clone());
+            let rows = match self.embeds.get(&key).cloned() {
+                Some((parent_tb, embed)) => self.embed(&parent_tb, &embed, &row_data).await?,
+                None => self.shape(&row_data).await?,
+            };
+
+            for row in rows {
+                new_data.push(row);
+                if with_positions {
+                    new_positions.push(position.clone());
+                }
+            }
+        }
+        Ok((new_data, new_positions))
+    }
+
+    async fn shape(&mut self, row_data: &RowData) -> anyhow::Result<Vec<RowData>> {
+        let (db, tb) = (&row_data.schema, &row_data.tb);
+        let shape_tb = self
+            .tbs
+            .get(&(db.clone(), tb.clone()))
+            .cloned()
+            .unwrap_or_default();
+        let (cols, id_cols) = self.get_cols(db, tb, &shape_tb.id_cols).await?;
+
+        let before_id = Self::get_id(&id_cols, &row_data.before);
+        let after_id = Self::get_id(&id_cols, &row_data.after);
+        let after_doc = match &row_data.after {
+            Some(after) => {
+                let id = after_id.clone().unwrap_or(Bson::Null);
+                Some(Self::build_doc(&shape_tb, &cols, &id_cols, id, after))
+            }
+            None => None,
+        };
+
+        let build_row = |row_type: RowType, before: Option<Document>, after: Option<Document>| {
+            let to_col_values = |doc: Option<Document>, key: &str| {
+                doc.map(|doc| HashMap::from([(key.to_string(), ColValue::MongoDoc(doc))]))
+            };
+            // a parent document is updated by $set, otherwise the embedded arrays are lost,
+            // so is a partial row image, otherwise the absent fields are lost
+            let set_fields = !shape_tb.embeds.is_empty() || row_data.partial;
+            let after_key = if row_type == RowType::Update && set_fields {
+                MongoConstants::DIFF_DOC
+            } else {
+                MongoConstants::DOC
+            };
+            RowData::new(
+                db.clone(),
+                tb.clone(),
+                row_type,
+                to_col_values(before, MongoConstants::DOC),
+                to_col_values(after, after_key),
+            )
+        };
+
+        let mut rows = Vec::new();
+        match row_data.row_type {
+            RowType::Insert => {
+                Self::check_id(&after_id, row_data)?;
+                rows.push(build_row(RowType::Insert, None, after_doc));
+            }
+
+            RowType::Delete => {
+                Self::check_id(&before_id, row_data)?;
+                let before = doc! {MongoConstants::ID: before_id.unwrap()};
+                rows.push(build_row(RowType::Delete, Some(before), None));
+            }
+
+            RowType::Update => {
+                Self::check_id(&after_id, row_data)?;
+                let (after_id, mut after_doc) = (after_id.unwrap(), after_doc.unwrap());
+                // the before image may not contain the key if it is unchanged, e.g. postgres
+                let before_id = before_id.unwrap_or_else(|| after_id.clone());
+                let before = doc! {MongoConstants::ID: before_id.clone()};
+                if before_id != after_id {
+                    // _id is immutable in mongo
+                    rows.push(build_row(RowType::Delete, Some(before), None));
+                    rows.push(build_row(RowType::Insert, None, Some(after_doc)));
+                } else if !shape_tb.embeds.is_empty() || row_data.partial {
+                    after_doc.remove(MongoConstants::ID);
+                    let after = doc! {MongoConstants::SET: after_doc};
+                    rows.push(build_row(RowType::Update, Some(before), Some(after)));
+                } else {
+                    rows.push(build_row(RowType::Update, Some(before), Some(after_doc)));
+                }
+            }
+        }
+        Ok(rows)
+    }
+
+    /// rows of a child table are turned into updates of the parent document. An element is updated
+    /// in place by $set with arrayFilters if it stays in the same parent with the same id cols,
+    /// otherwise it is moved by $pull + $push, to the end of the array of the new parent
+    async fn embed(
+        &mut self,
+        parent_tb: &str,
+        embed: &EmbedTb,
+        row_data: &RowData,
+    ) -> anyhow::Result<Vec<RowData>> {
+        let db = &row_data.schema;
+        let parent_id_cols = match self.tbs.get(&(db.clone(), parent_tb.to_string())) {
+            Some(shape_tb) => shape_tb.id_cols.clone(),
+            None => Vec::new(),
+        };
+        let (_, parent_id_cols) = self.get_cols(db, parent_tb, &parent_id_cols).await?;
+        if parent_id_cols.len() != embed.fk_cols.len() {
+            bail! {Error::ConfigError(format!(
+                "[shaper].tbs, fk_cols: {:?} of: {}.{} do not match the id cols: {:?} of: {}.{}",
+                embed.fk_cols, db, embed.tb, parent_id_cols, db, parent_tb
+            ))}
+        }
+        let (cols, id_cols) = self.get_cols(db, &embed.tb, &embed.id_cols).await?;
+
+        // the parent id built from the fk cols, in the same way as _id of the parent document
+        let get_parent_id = |col_values: &Option<HashMap<String, ColValue>>| {
+            let fk_id = Self::get_id(&embed.fk_cols, col_values)?;
+            match fk_id {
+                Bson::Document(fk_doc) => {
+                    let mut id = Document::new();
+                    for (col, fk_col) in parent_id_cols.iter().zip(embed.fk_cols.iter()) {
+                        id.insert(col, fk_doc.get(fk_col).cloned().unwrap_or(Bson::Null));
+                    }
+                    Some(Bson::Document(id))
+                }
+                fk_id => Some(fk_id),
+            }
+        };
+        let get_element_filter = |col_values: &Option<HashMap<String, ColValue>>| {
+            let mut filter = Document::new();
+            let col_values = col_values.as_ref()?;
+            for col in id_cols.iter() {
+                filter.insert(col, Self::col_value_to_bson(col_values.get(col)?));
+            }
+            Some(filter)
+        };
+        let build_row = |parent_id: Bson, update: Document, array_filter: Option<Document>| {
+            let before = doc! {MongoConstants::ID: parent_id};
+            let mut after = HashMap::from([(
+                MongoConstants::DIFF_DOC.to_string(),
+                ColValue::MongoDoc(update),
+            )]);
+            if let Some(array_filter) = array_filter {
+                after.insert(
+                    MongoConstants::ARRAY_FILTER.to_string(),
+                    ColValue::MongoDoc(array_filter),
+                );
+            }
+            RowData::new(
+                db.clone(),
+                parent_tb.to_string(),
+                RowType::Update,
+                Some(HashMap::from([(
+                    MongoConstants::DOC.to_string(),
+                    ColValue::MongoDoc(before),
+                )])),
+                Some(after),
+            )
+        };
+        let build_field_row = |parent_id: Bson, operator: &str, value: Document| {
+            let mut update = Document::new();
+            update.insert(operator, doc! {embed.field.as_str(): value});
+            build_row(parent_id, update, None)
+        };
+
+        let mut rows = Vec::new();
+        let mut old_element = None;
+        if row_data.row_type != RowType::Insert {
+            let (Some(parent_id), Some(filter)) = (
+                get_parent_id(&row_data.before).or_else(|| get_parent_id(&row_data.after)),
+                get_element_filter(&row_data.before)
+                    .or_else(|| get_element_filter(&row_data.after)),
+            ) else {
+                bail! {Error::PipelineError(format!(
+                    "the before image of: {}.{} should contain fk_cols: {:?} and id cols: {:?} to locate the embedded element",
+                    db, embed.tb, embed.fk_cols, id_cols
+                ))}
+            };
+            old_element = Some((parent_id, filter));
+        }
+
+        // update the element in place, only the cols in the after image are set
+        if let (Some((parent_id, filter)), Some(after)) = (&old_element, &row_data.after) {
+            let unmoved = get_parent_id(&row_data.after).as_ref() == Some(parent_id)
+                && get_element_filter(&row_data.after).as_ref() == Some(filter);
+            if unmoved {
+                if !Self::is_null_id(parent_id) {
+                    let mut fields = Document::new();
+                    for (col, value) in Self::build_cols_doc(&cols, after, &[]) {
+                        let path = format!("{}.$[{}].{}", embed.field, ELEMENT_IDENTIFIER, col);
+                        fields.insert(path, value);
+                    }
+                    let mut array_filter = Document::new();
+                    for (col, value) in filter {
+                        array_filter.insert(format!("{}.{}", ELEMENT_IDENTIFIER, col), value);
+                    }
+                    rows.push(build_row(
+                        parent_id.clone(),
+                        doc! {MongoConstants::SET: fields},
+                        Some(array_filter),
+                    ));
+                }
+                return Ok(rows);
+            }
+        }
+
+        // pull the old element
+        if let Some((parent_id, filter)) = old_element {
+            if !Self::is_null_id(&parent_id) {
+                rows.push(build_field_row(parent_id, MongoConstants::PULL, filter));
+            }
+        }
+
+        // push the new element, pull it first to make replays idempotent
+        if let Some(after) = &row_data.after {
+            let (Some(parent_id), Some(filter)) = (
+                get_parent_id(&row_data.after),
+                get_element_filter(&row_data.after),
+            ) else {
+                bail! {Error::PipelineError(format!(
+                    "fk_cols: {:?} and id cols: {:?} not found in: {}.{}",
+                    embed.fk_cols, id_cols, db, embed.tb
+                ))}
+            };
+            // rows not referencing any parent are not embedded
+            if !Self::is_null_id(&parent_id) {
+                let element = Self::build_cols_doc(&cols, after, &[]);
+                if row_data.row_type == RowType::Insert {
+                    rows.push(build_field_row(
+                        parent_id.clone(),
+                        MongoConstants::PULL,
+                        filter,
+                    ));
+                }
+                rows.push(build_field_row(parent_id, MongoConstants::PUSH, element));
+            }
+        }
+        Ok(rows)
+    }
+
+    /// returns (cols, id cols) with routed names, cols are empty if there is no meta_manager,
+    /// in which case the cols of the row are used
+    async fn get_cols(
+        &mut self,
+        db: &str,
+        tb: &str,
+        id_cols: &[String],
+    ) -> anyhow::Result<(Vec<String>, Vec<String>)> {
+        let Some(meta_manager) = self.meta_manager.as_mut() else {
+            if id_cols.is_empty() {
+                bail! {Error::ConfigError(format!(
+                    "[shaper].tbs, id_cols should be set for: {}.{} since no meta is available",
+                    db, tb
+                ))}
+            }
+            return Ok((Vec::new(), id_cols.to_vec()));
+        };
+
+        let (src_db, src_tb) = self.reverse_router.get_tb_map(db, tb);
+        let tb_meta = meta_manager.get_tb_meta(src_db, src_tb).await?;
+        let col_map = self.router.get_col_map(src_db, src_tb);
+        let route = |cols: &[String]| -> Vec<String> {
+            cols.iter()
+                .map(|col| col_map.and_then(|m| m.get(col)).unwrap_or(col).clone())
+                .collect()
+        };
+
+        let cols = route(&tb_meta.cols);
+        if id_cols.is_empty() {
+            Ok((cols, route(&tb_meta.id_cols)))
+        } else {
+            Ok((cols, id_cols.to_vec()))
+        }
+    }
+
+    fn get_id(id_cols: &[String], col_values: &Option<HashMap<String, ColValue>>) -> Option<Bson> {
+        let col_values = col_values.as_ref()?;
+        if id_cols.len() == 1 {
+            return Some(Self::col_value_to_bson(col_values.get(&id_cols[0])?));
+        }
+
+        let mut id = Document::new();
+        for col in id_cols.iter() {
+            id.insert(col, Self::col_value_to_bson(col_values.get(col)?));
+        }
+        Some(Bson::Document(id))
+    }
+
+    fn is_null_id(id: &Bson) -> bool {
+        match id {
+            Bson::Null => true,
+            Bson::Document(doc) => doc.values().all(|v| *v == Bson::Null),
+            _ => false,
+        }
+    }
+
+    fn check_id(id: &Option<Bson>, row_data: &RowData) -> anyhow::Result<()> {
+        if id.is_none() {
+            bail! {Error::PipelineError(format!(
+                "id cols not found, can not derive _id for: {}.{}",
+                row_data.schema, row_data.tb
+            ))}
+        }
+        Ok(())
+    }
+
+    fn build_doc(
+        shape_tb: &ShapeTb,
+        cols: &[String],
+        id_cols: &[String],
+        id: Bson,
+        col_values: &HashMap<String, ColValue>,
+    ) -> Document {
+        let skip_cols: &[String] = if shape_tb.keep_id_cols { &[] } else { id_cols };
+        let mut doc = doc! {MongoConstants::ID: id};
+        doc.extend(Self::build_cols_doc(cols, col_values, skip_cols));
+        doc
+    }
+
+    /// fields are in the order of cols, or sorted by name if cols are empty
+    fn build_cols_doc(
+        cols: &[String],
+        col_values: &HashMap<String, ColValue>,
+        skip_cols: &[String],
+    ) -> Document {
+        let mut sorted_cols: Vec<&String> = if cols.is_empty() {
+            col_values.keys().collect()
+        } else {
+            cols.iter()
+                .filter(|c| col_values.contains_key(*c))
+                .collect()
+        };
+        if cols.is_empty() {
+            sorted_cols.sort();
+        }
+
+        let mut doc = Document::new();
+        for col in sorted_cols {
+            if !skip_cols.contains(col) {
+                doc.insert(col, Self::col_value_to_bson(&col_values[col]));
+            }
+        }
+        doc
+    }
+
+    fn col_value_to_bson(col_value: &ColValue) -> Bson {
+        match col_value {
+            ColValue::None => Bson::Null,
+            ColValue::Bool(v) => Bson::Boolean(*v),
+            ColValue::Tiny(v) => Bson::Int32(*v as i32),
+            ColValue::UnsignedTiny(v) => Bson::Int32(*v as i32),
+            ColValue::Short(v) => Bson::Int32(*v as i32),
+            ColValue::UnsignedShort(v) => Bson::Int32(*v as i32),
+            ColValue::Long(v) => Bson::Int32(*v),
+            ColValue::UnsignedLong(v) => Bson::Int64(*v as i64),
+            ColValue::LongLong(v) => Bson::Int64(*v),
+            ColValue::UnsignedLongLong(v) => match i64::try_from(*v) {
+                Ok(v) => Bson::Int64(v),
+                Err(_) => Self::decimal_to_bson(&v.to_string()),
+            },
+            ColValue::Float(v) => Bson::Double(*v as f64),
+            ColValue::Double(v) => Bson::Double(*v),
+            ColValue::Decimal(v) => Self::decimal_to_bson(v),
+            ColValue::Date(v) | ColValue::DateTime(v) | ColValue::Timestamp(v) => {
+                Self::datetime_to_bson(v)
+            }
+            ColValue::Year(v) => Bson::Int32(*v as i32),
+            ColValue::Bit(v) | ColValue::Set(v) => Bson::Int64(*v as i64),
+            ColValue::Enum(v) => Bson::Int64(*v as i64),
+            ColValue::Time(v) | ColValue::String(v) | ColValue::Set2(v) | ColValue::Enum2(v) => {
+                Bson::String(v.clone())
+            }
+            ColValue::RawString(v) | ColValue::Blob(v) => Bson::Binary(Binary {
+                subtype: BinarySubtype::Generic,
+                bytes: v.clone(),
+            }),
+            ColValue::Json(v) => match serde_json::from_slice(v) {
+                Ok(json) => Self::json_to_bson(json),
+                Err(_) => Bson::String(String::from_utf8_lossy(v).into()),
+            },
+            ColValue::Json2(v) => match serde_json::from_str(v) {
+                Ok(json) => Self::json_to_bson(json),
+                Err(_) => Bson::String(v.clone()),
+            },
+            ColValue::Json3(v) => Self::json_to_bson(v.clone()),
+            ColValue::MongoDoc(v) => Bson::Document(v.clone()),
+        }
+    }
+
+    /// values beyond the precision of Decimal128 (34 digits) are kept as strings
+    fn decimal_to_bson(v: &str) -> Bson {
+        match v.parse::<Decimal128>() {
+            Ok(decimal) => Bson::Decimal128(decimal),
+            Err(_) => Bson::String(v.to_string()),
+        }
+    }
+
+    /// values without time zone are taken as UTC, invalid values (e.g. 0000-00-00) are kept as strings
+    fn datetime_to_bson(v: &str) -> Bson {
+        let millis = if let Ok(dt) = DateTime::parse_from_str(v, "%Y-%m-%d %H:%M:%S%.f%#z") {
+            Some(dt.timestamp_millis())
+        } else if let Ok(dt) = NaiveDateTime::parse_from_str(v, "%Y-%m-%d %H:%M:%S%.f") {
+            Some(dt.and_utc().timestamp_millis())
+        } else if let Ok(date) = NaiveDate::parse_from_str(v, "%Y-%m-%d") {
+            date.and_hms_opt(0, 0, 0)
+                .map(|dt| dt.and_utc().timestamp_millis())
+        } else {
+            None
+        };
+
+        match millis {
+            Some(millis) => Bson::DateTime(mongodb::bson::DateTime::from_millis(millis)),
+            None => Bson::String(v.to_string()),
+        }
+    }
+
+    fn json_to_bson(json: serde_json::Value) -> Bson {
+        match Bson::try_from(json.clone()) {
+            Ok(bson) => bson,
+            // invalid extended json, e.g. {"$oid": 1}
+            Err(_) => Bson::String(json.to_string()),
+        }
+    }
+
+    fn parse_tbs(config_str: &str) -> anyhow::Result<HashMap<(String, String), ShapeTb>> {
+        let mut results = HashMap::new();
+        if config_str.trim().is_empty() {
+            return Ok(results);
+        }
+
+        let items: Vec<ShapeTb> =
+            match serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX)) {
+                Ok(items) => items,
+                Err(err) => bail! {Error::ConfigError(format!(
+                    "invalid [shaper].tbs: {}",
+                    err
+                ))},
+            };
+
+        for item in items {
+            for embed in item.embeds.iter() {
+                if embed.field.is_empty() || embed.fk_cols.is_empty() || embed.tb == item.tb {
+                    bail! {Error::ConfigError(format!(
+                        "[shaper].tbs, embeds should have a field, fk_cols and a tb different from the parent, db: {}, tb: {}",
+                        item.db, item.tb
+                    ))}
+                }
+            }
+            results.insert((item.db.clone(), item.tb.clone()), item);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn build_shaper() -> MongoShaper {
+        let config = ShaperConfig {
+            tbs: r#"json:[
+                {"db":"db_1","tb":"tb_1","id_cols":["id"]},
+                {"db":"db_1","tb":"tb_2","id_cols":["k1","k2"],"keep_id_cols":false},
+                {"db":"db_1","tb":"orders","id_cols":["id"],"embeds":[
+                    {"tb":"order_items","field":"items","fk_cols":["order_id"],"id_cols":["id"]}
+                ]}
+            ]"#
+            .into(),
+        };
+        let router = RdbRouter {
+            schema_map: HashMap::new(),
+            tb_map: HashMap::new(),
+            col_map: HashMap::new(),
+            topic_map: HashMap::new(),
+        };
+        MongoShaper::from_config(&config, router, None).unwrap()
+    }
+
+    fn build_row_data(
+        tb: &str,
+        row_type: RowType,
+        before: Option<Vec<(&str, ColValue)>>,
+        after: Option<Vec<(&str, ColValue)>>,
+    ) -> RowData {
+        let to_col_values = |cols: Option<Vec<(&str, ColValue)>>| {
+            cols.map(|cols| {
+                cols.into_iter()
+                    .map(|(col, value)| (col.to_string(), value))
+                    .collect::<HashMap<_, _>>()
+            })
+        };
+        RowData::new(
+            "db_1".into(),
+            tb.into(),
+            row_type,
+            to_col_values(before),
+            to_col_values(after),
+        )
+    }
+
+    fn get_doc(col_values: &Option<HashMap<String, ColValue>>, key: &str) -> Document {
+        match col_values.as_ref().unwrap().get(key) {
+            Some(ColValue::MongoDoc(doc)) => doc.clone(),
+            _ => panic!("{} not found", key),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shape_col_types() {
+        let mut shaper = build_shaper();
+        let after = vec![
+            ("id", ColValue::Long(1)),
+            ("amount", ColValue::Decimal("10.50".into())),
+            (
+                "created_at",
+                ColValue::DateTime("2024-01-02 03:04:05.678".into()),
+            ),
+            (
+                "updated_at",
+                ColValue::Timestamp("2024-01-02 03:04:05+08".into()),
+            ),
+            ("birthday", ColValue::Date("2024-01-02".into())),
+            (
+                "zero_date",
+                ColValue::DateTime("0000-00-00 00:00:00".into()),
+            ),
+            ("extra", ColValue::Json2(r#"{"a":[1,"b"]}"#.into())),
+            ("name", ColValue::String("a".into())),
+            ("big", ColValue::UnsignedLongLong(u64::MAX)),
+            ("flag", ColValue::Bool(true)),
+            ("note", ColValue::None),
+        ];
+        let row_data = build_row_data("tb_1", RowType::Insert, None, Some(after));
+        let (data, positions) = shaper.process(vec![row_data], vec![]).await.unwrap();
+        assert!(positions.is_empty());
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].row_type, RowType::Insert);
+
+        let doc = get_doc(&data[0].after, MongoConstants::DOC);
+        let millis = |v: &str| {
+            NaiveDateTime::parse_from_str(v, "%Y-%m-%d %H:%M:%S%.f")
+                .unwrap()
+                .and_utc()
+                .timestamp_millis()
+        };
+        assert_eq!(doc.keys().next().unwrap(), "_id");
+        assert_eq!(doc.get("_id"), Some(&Bson::Int32(1)));
+        assert_eq!(doc.get("id"), Some(&Bson::Int32(1)));
+        assert_eq!(
+            doc.get("amount"),
+            Some(&Bson::Decimal128("10.50".parse().unwrap()))
+        );
+        assert_eq!(
+            doc.get_datetime("created_at").unwrap().timestamp_millis(),
+            millis("2024-01-02 03:04:05.678")
+        );
+        assert_eq!(
+            doc.get_datetime("updated_at").unwrap().timestamp_millis(),
+            millis("2024-01-01 19:04:05")
+        );
+        assert_eq!(
+            doc.get_datetime("birthday").unwrap().timestamp_millis(),
+            millis("2024-01-02 00:00:00")
+        );
+        assert_eq!(doc.get_str("zero_date").unwrap(), "0000-00-00 00:00:00");
+        assert_eq!(doc.get_document("extra").unwrap(), &doc! {"a": [1, "b"]});
+        assert_eq!(doc.get_str("name").unwrap(), "a");
+        assert_eq!(
+            doc.get("big"),
+            Some(&Bson::Decimal128(u64::MAX.to_string().parse().unwrap()))
+        );
+        assert_eq!(doc.get("flag"), Some(&Bson::Boolean(true)));
+        assert_eq!(doc.get("note"), Some(&Bson::Null));
+    }
+
+    #[tokio::test]
+    async fn test_shape_update_and_delete() {
+        let mut shaper = build_shaper();
+
+        // composite id, id cols are not kept
+        let before = vec![
+            ("k1", ColValue::Long(1)),
+            ("k2", ColValue::String("a".into())),
+        ];
+        let after = vec![
+            ("k1", ColValue::Long(1)),
+            ("k2", ColValue::String("a".into())),
+            ("v", ColValue::Long(2)),
+        ];
+        let row_data = build_row_data("tb_2", RowType::Update, Some(before), Some(after));
+        let (data, positions) = shaper
+            .process(vec![row_data], vec![Position::None])
+            .await
+            .unwrap();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(data.len(), 1);
+        assert_eq!(data[0].row_type, RowType::Update);
+        assert_eq!(
+            get_doc(&data[0].before, MongoConstants::DOC),
+            doc! {"_id": {"k1": 1, "k2": "a"}}
+        );
+        assert_eq!(
+            get_doc(&data[0].after, MongoConstants::DOC),
+            doc! {"_id": {"k1": 1, "k2": "a"}, "v": 2}
+        );
+
+        // id changed
+        let before = vec![("id", ColValue::Long(1))];
+        let after = vec![("id", ColValue::Long(2)), ("v", ColValue::Long(2))];
+        let row_data = build_row_data("tb_1", RowType::Update, Some(before), Some(after));
+        let (data, _) = shaper.process(vec![row_data], vec![]).await.unwrap();
+        assert_eq!(data.len(), 2);
+        assert_eq!(data[0].row_type, RowType::Delete);
+        assert_eq!(
+            get_doc(&data[0].before, MongoConstants::DOC),
+            doc! {"_id": 1}
+        );
+        assert_eq!(data[1].row_type, RowType::Insert);
+        assert_eq!(
+            get_doc(&data[1].after, MongoConstants::DOC),
+            doc! {"_id": 2, "id": 2, "v": 2}
+        );
+
+        // delete
+        let before = vec![("id", ColValue::Long(1)), ("v", ColValue::Long(2))];
+        let row_data = build_row_data("tb_1", RowType::Delete, Some(before), None);
+        let (data, _) = shaper.process(vec![row_data], vec![]).await.unwrap();
+        assert_eq!(data.len(), 1);
+        assert_eq!(
+            get_doc(&data[0].before, MongoConstants::DOC),
+            doc! {"_id": 1}
+        );
+
+        // parent documents are updated by $set to keep the embedded arrays
+        let before = vec![("id", ColValue::Long(1))];
+        let after = vec![("id", ColValue::Long(1)), ("v", ColValue::Long(2))];
+        let row_data = build_row_data("orders", RowType::Update, Some(before), Some(after));
+        let (data, _) = shaper.process(vec![row_data], vec![]).await.unwrap();
+        assert_eq!(data.len(), 1);
+        assert_eq!(
+            get_doc(&data[0].after, MongoConstants::DIFF_DOC),
+            doc! {"$set": {"id": 1, "v": 2}}
+        );
+
+        // id cols should be configured without meta
+        let row_data = build_row_data(
+            "tb_3",
+            RowType::Insert,
+            None,
+            Some(vec![("id", ColValue::Long(1))]),
+        );
+        assert!(shaper.process(vec![row_data], vec![]).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_embed() {
+        let mut shaper = build_shaper();
+        let item = |order_id: i32, qty: i32| {
+            vec![
+                ("id", ColValue::Long(10)),
+                ("order_id", ColValue::Long(order_id)),
+                ("qty", ColValue::Long(qty)),
+            ]
+        };
+        let get_ops = |data: &[RowData]| -> Vec<(String, Document, Document)> {
+            data.iter()
+                .map(|row_data| {
+                    assert_eq!(row_data.tb, "orders");
+                    assert_eq!(row_data.row_type, RowType::Update);
+                    (
+                        row_data.tb.clone(),
+                        get_doc(&row_data.before, MongoConstants::DOC),
+                        get_doc(&row_data.after, MongoConstants::DIFF_DOC),
+                    )
+                })
+                .collect()
+        };
+
+        // insert
+        let row_data = build_row_data("order_items", RowType::Insert, None, Some(item(1, 2)));
+        let (data, _) = shaper.process(vec![row_data], vec![]).await.unwrap();
+        let ops = get_ops(&data);
+        assert_eq!(ops.len(), 2);
+        assert_eq!(ops[0].1, doc! {"_id": 1});
+        assert_eq!(ops[0].2, doc! {"$pull": {"items": {"id": 10}}});
+        assert_eq!(
+            ops[1].2,
+            doc! {"$push": {"items": {"id": 10, "order_id": 1, "qty": 2}}}
+        );
+
+        // update in place, the element keeps its position in the array
+        let row_data = build_row_data(
+            "order_items",
+            RowType::Update,
+            Some(item(1, 2)),
+            Some(item(1, 5)),
+        );
+        let (data, _) = shaper.process(vec![row_data], vec![]).await.unwrap();
+        let ops = get_ops(&data);
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].1, doc! {"_id": 1});
+        assert_eq!(
+            ops[0].2,
+            doc! {"$set": {"items.$[e].id": 10, "items.$[e].order_id": 1, "items.$[e].qty": 5}}
+        );
+        assert_eq!(
+            get_doc(&data[0].after, MongoConstants::ARRAY_FILTER),
+            doc! {"e.id": 10}
+        );
+
+        // update moving the element to another parent
+        let row_data = build_row_data(
+            "order_items",
+            RowType::Update,
+            Some(item(1, 2)),
+            Some(item(2, 3)),
+        );
+        let (data, _) = shaper.process(vec![row_data], vec![]).await.unwrap();
+        let ops = get_ops(&data);
+        assert_eq!(ops.len(), 2);
+        assert_eq!(ops[0].1, doc! {"_id": 1});
+        assert_eq!(ops[0].2, doc! {"$pull": {"items": {"id": 10}}});
+        assert_eq!(ops[1].1, doc! {"_id": 2});
+        assert_eq!(
+            ops[1].2,
+            doc! {"$push": {"items": {"id": 10, "order_id": 2, "qty": 3}}}
+        );
+
+        // delete
+        let row_data = build_row_data("order_items", RowType::Delete, Some(item(2, 3)), None);
+        let (data, _) = shaper.process(vec![row_data], vec![]).await.unwrap();
+        let ops = get_ops(&data);
+        assert_eq!(ops.len(), 1);
+        assert_eq!(ops[0].1, doc! {"_id": 2});
+        assert_eq!(ops[0].2, doc! {"$pull": {"items": {"id": 10}}});
+
+        // rows without a parent are not embedded
+        let row_data = build_row_data(
+            "order_items",
+            RowType::Insert,
+            None,
+            Some(vec![
+                ("id", ColValue::Long(11)),
+                ("order_id", ColValue::None),
+            ]),
+        );
+        let (data, _) = shaper.process(vec![row_data], vec![]).await.unwrap();
+        assert!(data.is_empty());
+
+        // the before image without fk_cols, e.g. from postgres without REPLICA IDENTITY FULL
+        let row_data = build_row_data(
+            "order_items",
+            RowType::Delete,
+            Some(vec![("id", ColValue::Long(10))]),
+            None,
+        );
+        assert!(shaper.process(vec![row_data], vec![]).await.is_err());
+
+        let config = ShaperConfig {
+            tbs: r#"json:[{"db":"db_1","tb":"orders","embeds":[{"tb":"orders","field":"items","fk_cols":["order_id"]}]}]"#.into(),
+        };
+        let router = shaper.router.clone();
+        assert!(MongoShaper::from_config(&config, router, None).is_err());
+    }
+}
@@ -0,0 +1,448 @@
+use std::collections::HashMap;
+
+use anyhow::bail;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use dt_common::{
+    config::transformer_config::TransformerConfig,
+    error::Error,
+    meta::{col_value::ColValue, position::Position, row_data::RowData},
+    utils::time_util::TimeUtil,
+};
+
+const JSON_PREFIX: &str = "json:";
+
+/// declarative column transforms, matched by the routed (target) db / tb,
+/// transforms of a table are applied in the configured order
+#[derive(Default)]
+pub struct RdbTransformer {
+    // db -> tb -> [(col, transform)], looked up by &str without allocating for each row
+    transforms: HashMap<String, HashMap<String, Vec<(String, Transform)>>>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Transform {
+    // keep the first / last N characters, mask the others
+    Mask {
+        #[serde(default)]
+        keep_first: usize,
+        #[serde(default)]
+        keep_last: usize,
+        #[serde(default = "default_mask_char")]
+        mask_char: char,
+    },
+    // hex of sha256(salt + value)
+    Hash {
+        #[serde(default)]
+        salt: String,
+    },
+    // null if value is not set
+    Constant {
+        #[serde(default)]
+        value: Option<String>,
+    },
+    // add a column computed from row metadata, only set in after images
+    Computed {
+        source: ComputedSource,
+        // for pk_hash
+        #[serde(default)]
+        cols: Vec<String>,
+        #[serde(default)]
+        salt: String,
+    },
+    Cast {
+        to: CastType,
+    },
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum ComputedSource {
+    // commit time of the source transaction in millis
+    CommitTimestamp,
+    // commit time of the source transaction, yyyy-MM-dd HH:mm:ss.SSS in UTC
+    CommitTime,
+    Position,
+    PkHash,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+enum CastType {
+    String,
+    Int,
+    Float,
+    Bool,
+}
+
+fn default_mask_char() -> char {
+    '*'
+}
+
+impl RdbTransformer {
+    pub fn from_config(config: &TransformerConfig) -> anyhow::Result<Self> {
+        Ok(Self {
+            transforms: Self::parse_transforms(&config.transforms)?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.transforms.is_empty()
+    }
+
+    pub fn transform(&self, row_data: &mut RowData, position: &Position) -> anyhow::Result<()> {
+        let Some(transforms) = self
+            .transforms
+            .get(&row_data.schema)
+            .and_then(|tbs| tbs.get(&row_data.tb))
+        else {
+            return Ok(());
+        };
+
+        for (col, transform) in transforms.iter() {
+            match transform {
+                Transform::Computed { source, cols, salt } => {
+                    if let Some(after) = row_data.after.as_mut() {
+                        let col_value = Self::compute(source, cols, salt, after, position)?;
+                        after.insert(col.clone(), col_value);
+                    }
+                }
+
+                Transform::Constant { value } => {
+                    let col_value = match value {
+                        Some(v) => ColValue::String(v.clone()),
+                        None => ColValue::None,
+                    };
+                    for col_values in [row_data.before.as_mut(), row_data.after.as_mut()]
+                        .into_iter()
+                        .flatten()
+                    {
+                        col_values.insert(col.clone(), col_value.clone());
+                    }
+                }
+
+                _ => {
+                    // before images should be transformed in the same way,
+                    // since they may be used to locate the rows in target
+                    for col_values in [row_data.before.as_mut(), row_data.after.as_mut()]
+                        .into_iter()
+                        .flatten()
+                    {
+                        if let Some(col_value) = col_values.get_mut(col) {
+                            if *col_value != ColValue::None {
+                                *col_value = Self::transform_value(col, transform, col_value)?;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn transform_value(
+        col: &str,
+        transform: &Transform,
+        col_value: &ColValue,
+    ) -> anyhow::Result<ColValue> {
+        let str = col_value.to_option_string().unwrap_or_default();
+        let new_col_value = match transform {
+            Transform::Mask {
+                keep_first,
+                keep_last,
+                mask_char,
+            } => ColValue::String(Self::mask(&str, *keep_first, *keep_last, *mask_char)),
+
+            Transform::Hash { salt } => ColValue::String(Self::sha256(salt, &[&str])),
+
+            Transform::Cast { to } => match to {
+                CastType::String => ColValue::String(str),
+                CastType::Int => match str.trim().parse::<i64>() {
+                    Ok(v) => ColValue::LongLong(v),
+                    Err(_) => match str.trim().parse::<f64>() {
+                        Ok(v) => ColValue::LongLong(v as i64),
+                        Err(_) => bail! {Error::PipelineError(format!(
+                            "failed to cast column: {} to int, value: {}",
+                            col, str
+                        ))},
+                    },
+                },
+                CastType::Float => match str.trim().parse::<f64>() {
+                    Ok(v) => ColValue::Double(v),
+                    Err(_) => bail! {Error::PipelineError(format!(
+                        "failed to cast column: {} to float, value: {}",
+                        col, str
+                    ))},
+                },
+                CastType::Bool => match str.trim().to_lowercase().as_str() {
+                    "1" | "true" => ColValue::Bool(true),
+                    "0" | "false" => ColValue::Bool(false),
+                    _ => bail! {Error::PipelineError(format!(
+                        "failed to cast column: {} to bool, value: {}",
+                        col, str
+                    ))},
+                },
+            },
+
+            Transform::Constant { .. } | Transform::Computed { .. } => col_value.clone(),
+        };
+        Ok(new_col_value)
+    }
+
+    fn compute(
+        source: &ComputedSource,
+        cols: &[String],
+        salt: &str,
+        after: &HashMap<String, ColValue>,
+        position: &Position,
+    ) -> anyhow::Result<ColValue> {
+        let col_value = match source {
+            ComputedSource::CommitTimestamp => match position.to_timestamp() {
+                0 => ColValue::None,
+                timestamp => ColValue::LongLong(timestamp as i64),
+            },
+            ComputedSource::CommitTime => match position.to_timestamp() {
+                0 => ColValue::None,
+                timestamp => ColValue::String(TimeUtil::millis_to_str(timestamp)?),
+            },
+            ComputedSource::Position => ColValue::String(position.to_string()),
+            ComputedSource::PkHash => {
+                let values: Vec<String> = cols
+                    .iter()
+                    .map(|col| {
+                        after
+                            .get(col)
+                            .and_then(|v| v.to_option_string())
+                            .unwrap_or_default()
+                    })
+                    .collect();
+                let values: Vec<&str> = values.iter().map(|v| v.as_str()).collect();
+                ColValue::String(Self::sha256(salt, &values))
+            }
+        };
+        Ok(col_value)
+    }
+
+    fn mask(str: &str, keep_first: usize, keep_last: usize, mask_char: char) -> String {
+        let chars: Vec<char> = str.chars().collect();
+        let len = chars.len();
+        chars
+            .iter()
+            .enumerate()
+            .map(|(i, c)| {
+                if i < keep_first || i + keep_last >= len {
+                    *c
+                } else {
+                    mask_char
+                }
+            })
+            .collect()
+    }
+
+    fn sha256(salt: &str, values: &[&str]) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(salt.as_bytes());
+        for (i, value) in values.iter().enumerate() {
+            // separate values so that ("a", "bc") and ("ab", "c") differ
+            if i > 0 {
+                hasher.update([0u8]);
+            }
+            hasher.update(value.as_bytes());
+        }
+        format!("{:x}", hasher.finalize())
+    }
+
+    fn parse_transforms(
+        config_str: &str,
+    ) -> anyhow::Result<HashMap<String, HashMap<String, Vec<(String, Transform)>>>> {
+        let mut results: HashMap<String, HashMap<String, Vec<(String, Transform)>>> =
+            HashMap::new();
+        if config_str.trim().is_empty() {
+            return Ok(results);
+        }
+
+        // transforms=json:[{"db":"db_1","tb":"tb_1","col":"phone","type":"mask","keep_first":3,"keep_last":4}]
+        #[derive(Deserialize)]
+        struct Item {
+            db: String,
+            tb: String,
+            col: String,
+            #[serde(flatten)]
+            transform: Transform,
+        }
+
+        let items: Vec<Item> =
+            match serde_json::from_str(config_str.trim().trim_start_matches(JSON_PREFIX)) {
+                Ok(items) => items,
+                Err(err) => bail! {Error::ConfigError(format!(
+                    "invalid [transformer].transforms: {}",
+                    err
+                ))},
+            };
+
+        for item in items {
+            if let Transform::Computed {
+                source: ComputedSource::PkHash,
+                cols,
+                ..
+            } = &item.transform
+            {
+                if cols.is_empty() {
+                    bail! {Error::ConfigError(format!(
+                        "[transformer].transforms, cols required for pk_hash, db: {}, tb: {}, col: {}",
+                        item.db, item.tb, item.col
+                    ))}
+                }
+            }
+            results
+                .entry(item.db)
+                .or_default()
+                .entry(item.tb)
+                .or_default()
+                .push((item.col, item.transform));
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dt_common::meta::row_type::RowType;
+
+    #[test]
+    fn test_transform() {
+        let config = TransformerConfig {
+            transforms: r#"json:[
+                {"db":"db_1","tb":"tb_1","col":"phone","type":"mask","keep_first":3,"keep_last":4},
+                {"db":"db_1","tb":"tb_1","col":"email","type":"hash","salt":"s"},
+                {"db":"db_1","tb":"tb_1","col":"region","type":"constant","value":"cn"},
+                {"db":"db_1","tb":"tb_1","col":"age","type":"cast","to":"string"},
+                {"db":"db_1","tb":"tb_1","col":"commit_ts","type":"computed","source":"commit_timestamp"},
+                {"db":"db_1","tb":"tb_1","col":"row_key","type":"computed","source":"pk_hash","cols":["id"]}
+            ]"#
+            .into(),
+        };
+        let transformer = RdbTransformer::from_config(&config).unwrap();
+
+        let before = HashMap::from([
+            ("id".to_string(), ColValue::Long(1)),
+            ("phone".to_string(), ColValue::String("13812345678".into())),
+            ("email".to_string(), ColValue::None),
+            ("age".to_string(), ColValue::Long(20)),
+        ]);
+        let mut after = before.clone();
+        after.insert("email".into(), ColValue::String("a@b.c".into()));
+        let mut row_data = RowData::new(
+            "db_1".into(),
+            "tb_1".into(),
+            RowType::Update,
+            Some(before),
+            Some(after),
+        );
+        let position = Position::MysqlCdc {
+            server_id: String::new(),
+            binlog_filename: String::new(),
+            next_event_position: 0,
+            gtid_set: String::new(),
+            timestamp: "2024-01-01 00:00:00".into(),
+        };
+        transformer.transform(&mut row_data, &position).unwrap();
+
+        let before = row_data.before.unwrap();
+        let after = row_data.after.unwrap();
+        for col_values in [&before, &after] {
+            assert_eq!(col_values["phone"], ColValue::String("138****5678".into()));
+            assert_eq!(col_values["region"], ColValue::String("cn".into()));
+            assert_eq!(col_values["age"], ColValue::String("20".into()));
+        }
+        assert_eq!(before["email"], ColValue::None);
+        assert_eq!(
+            after["email"],
+            ColValue::String(RdbTransformer::sha256("s", &["a@b.c"]))
+        );
+        assert!(!before.contains_key("commit_ts"));
+        assert_eq!(after["commit_ts"], ColValue::LongLong(1704067200000));
+        assert_eq!(
+            after["row_key"],
+            ColValue::String(RdbTransformer::sha256("", &["1"]))
+        );
+
+        // other tables are not touched
+        let mut row_data = RowData::new(
+            "db_1".into(),
+            "tb_2".into(),
+            RowType::Insert,
+            None,
+            Some(HashMap::from([(
+                "phone".to_string(),
+                ColValue::String("13812345678".into()),
+            )])),
+        );
+        transformer.transform(&mut row_data, &position).unwrap();
+        assert_eq!(
+            row_data.after.unwrap()["phone"],
+            ColValue::String("13812345678".into())
+        );
+    }
+
+    /// cargo test --release -p dt-pipeline test_transform_throughput -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn test_transform_throughput() {
+        let config = TransformerConfig {
+            transforms: r#"json:[
+                {"db":"db_1","tb":"tb_1","col":"phone","type":"mask","keep_first":3,"keep_last":4},
+                {"db":"db_1","tb":"tb_1","col":"email","type":"hash","salt":"s"},
+                {"db":"db_1","tb":"tb_1","col":"region","type":"constant","value":"cn"},
+                {"db":"db_1","tb":"tb_1","col":"commit_ts","type":"computed","source":"commit_timestamp"},
+                {"db":"db_1","tb":"tb_1","col":"row_key","type":"computed","source":"pk_hash","cols":["id"]}
+            ]"#
+            .into(),
+        };
+        let transformer = RdbTransformer::from_config(&config).unwrap();
+        let position = Position::MysqlCdc {
+            server_id: String::new(),
+            binlog_filename: String::new(),
+            next_event_position: 0,
+            gtid_set: String::new(),
+            timestamp: "2024-01-01 00:00:00".into(),
+        };
+
+        let row_count = 100_000;
+        let mut rows: Vec<RowData> = (0..row_count)
+            .map(|i| {
+                let after = HashMap::from([
+                    ("id".to_string(), ColValue::Long(i)),
+                    ("phone".to_string(), ColValue::String("13812345678".into())),
+                    ("email".to_string(), ColValue::String(format!("{}@b.c", i))),
+                    ("name".to_string(), ColValue::String("abc".into())),
+                ]);
+                RowData::new(
+                    "db_1".into(),
+                    "tb_1".into(),
+                    RowType::Insert,
+                    None,
+                    Some(after),
+                )
+            })
+            .collect();
+
+        let start_time = std::time::Instant::now();
+        for row_data in rows.iter_mut() {
+            transformer.transform(row_data, &position).unwrap();
+        }
+        let rows_per_sec = row_count as f64 / start_time.elapsed().as_secs_f64();
+        println!("transformed {} rows, {:.0} rows/s", row_count, rows_per_sec);
+        assert!(rows_per_sec >= 100_000.0);
+    }
+
+    #[test]
+    fn test_mask() {
+        assert_eq!(RdbTransformer::mask("abcdef", 1, 2, '*'), "a***ef");
+        assert_eq!(RdbTransformer::mask("abc", 2, 2, '*'), "abc");
+        assert_eq!(RdbTransformer::mask("中文字符", 0, 1, '#'), "###符");
+    }
+}
@@ -12,7 +12,7 @@ pub struct PrecheckTaskConfig {
 
 impl PrecheckTaskConfig {
     pub fn new(task_config_file: &str) -> anyhow::Result<Self> {
-        let ini = IniLoader::new(task_config_file).ini;
+        let ini = IniLoader::load(task_config_file)?.ini;
         let precheck_config = Self::load_precheck_config(&ini)?;
         Ok(Self {
             precheck: precheck_config,
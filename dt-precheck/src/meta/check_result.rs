@@ -51,7 +51,7 @@ impl CheckResult {
                     source_or_sink
                 );
                 match db_type {
-                    DbType::Mysql => advise_msg = "(1)open 'log_bin' configuration. (2)set 'binlog_format' configuration to 'row'. (3)set 'binlog_row_image' configuration to 'full'.".to_string(),
+                    DbType::Mysql => advise_msg = "(1)open 'log_bin' configuration. (2)set 'binlog_format' configuration to 'row'. (3)set 'binlog_row_image' configuration to 'full'. (4)set 'binlog_row_value_options' configuration to ''.".to_string(),
                     DbType::Pg => advise_msg = "(1)set 'wal_level' configuration to 'logical'. (2)make sure that the number of 'max_replication_slots' configured is sufficient. (3)make sure that the number of 'max_wal_senders' configured is sufficient.".to_string(),
                     DbType::Mongo => advise_msg = "make sure that the configured link address is the master node under a replica set architecture.".to_string(),
                     _ => {}
@@ -89,10 +89,15 @@ impl Prechecker for MySqlPrechecker {
         }
 
         let mut errs: Vec<String> = vec![];
-        let cdc_configs = ["log_bin", "binlog_format", "binlog_row_image"]
-            .iter()
-            .map(|c| c.to_string())
-            .collect::<Vec<String>>();
+        let cdc_configs = [
+            "log_bin",
+            "binlog_format",
+            "binlog_row_image",
+            "binlog_row_value_options",
+        ]
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<String>>();
         let result = self.fetcher.fetch_configuration(cdc_configs).await;
         match result {
             Ok(configs) => {
@@ -122,6 +127,15 @@ impl Prechecker for MySqlPrechecker {
                                 ));
                             }
                         }
+                        // not exists before mysql 8.0, PARTIAL_UPDATE_ROWS_EVENT can not be parsed
+                        "binlog_row_value_options" => {
+                            if v.to_lowercase().contains("partial_json") {
+                                errs.push(format!(
+                                    "binlog_row_value_options setting:[{}] is not ''.",
+                                    v.to_lowercase()
+                                ));
+                            }
+                        }
                         _ => {
                             bail! {
                             "find database cdc settings meet unknown error"}
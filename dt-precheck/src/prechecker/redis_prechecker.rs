@@ -24,6 +24,7 @@ use dt_connector::{
     extractor::{
         base_extractor::BaseExtractor,
         extractor_monitor::ExtractorMonitor,
+        reconnector::Reconnector,
         redis::{redis_client::RedisClient, redis_psync_extractor::RedisPsyncExtractor},
         resumer::cdc_resumer::CdcResumer,
     },
@@ -104,6 +105,7 @@ impl Prechecker for RedisPrechecker {
             extract_type: ExtractType::Snapshot,
             syncer: Arc::new(Mutex::new(Syncer::default())),
             resumer: CdcResumer::default(),
+            reconnector: Reconnector::default(),
             keepalive_interval_secs: 0,
             heartbeat_interval_secs: 0,
             heartbeat_key: String::new(),
@@ -4,6 +4,7 @@ use std::{
     sync::{atomic::AtomicBool, Arc},
 };
 
+use anyhow::bail;
 use tokio::sync::Mutex;
 
 use dt_common::{
@@ -12,6 +13,7 @@ use dt_common::{
         extractor_config::ExtractorConfig,
         task_config::TaskConfig,
     },
+    error::Error,
     meta::{
         avro::avro_converter::AvroConverter, mongo::mongo_cdc_source::MongoCdcSource,
         pg::pg_meta_manager::PgMetaManager, redis::redis_statistic_type::RedisStatisticType,
@@ -38,21 +40,28 @@ use dt_connector::{
             mongo_snapshot_extractor::MongoSnapshotExtractor,
         },
         mysql::{
-            mysql_cdc_extractor::MysqlCdcExtractor, mysql_check_extractor::MysqlCheckExtractor,
+            mysql_cdc_extractor::MysqlCdcExtractor,
+            mysql_check_extractor::MysqlCheckExtractor,
+            mysql_multi_cdc_extractor::{MysqlCdcSource, MysqlMultiCdcExtractor},
             mysql_snapshot_extractor::MysqlSnapshotExtractor,
             mysql_struct_extractor::MysqlStructExtractor,
+            xa_buffer::XaBuffer,
         },
         pg::{
             pg_cdc_extractor::PgCdcExtractor, pg_check_extractor::PgCheckExtractor,
             pg_snapshot_extractor::PgSnapshotExtractor, pg_struct_extractor::PgStructExtractor,
         },
+        reconnector::Reconnector,
         redis::{
-            redis_client::RedisClient, redis_psync_extractor::RedisPsyncExtractor,
+            redis_aof_file_extractor::RedisAofFileExtractor, redis_client::RedisClient,
+            redis_cluster_psync_extractor::RedisClusterPsyncExtractor,
+            redis_psync_extractor::RedisPsyncExtractor,
             redis_reshard_extractor::RedisReshardExtractor,
             redis_scan_extractor::RedisScanExtractor,
             redis_snapshot_file_extractor::RedisSnapshotFileExtractor,
         },
         resumer::{cdc_resumer::CdcResumer, snapshot_resumer::SnapshotResumer},
+        tidb::tidb_cdc_extractor::TidbCdcExtractor,
     },
     rdb_router::RdbRouter,
     Extractor,
@@ -161,6 +170,84 @@ impl ExtractorUtil {
                 heartbeat_tb,
                 start_time_utc,
                 end_time_utc,
+                osc_enabled,
+                sources,
+                source_col,
+            } if !sources.is_empty() => {
+                // positions of sources are resumed by cdc_resumer
+                let mut cdc_sources = Vec::new();
+                for (i, source) in MysqlCdcSource::parse(&sources)?.into_iter().enumerate() {
+                    let conn_pool =
+                        TaskUtil::create_mysql_conn_pool(&source.url, 2, enable_sqlx_log, false)
+                            .await?;
+                    let meta_manager = TaskUtil::create_mysql_meta_manager(
+                        &source.url,
+                        &config.runtime.log_level,
+                        DbType::Mysql,
+                        config.meta_center.clone(),
+                    )
+                    .await?;
+                    let source_base_extractor =
+                        MysqlMultiCdcExtractor::create_source_base_extractor(
+                            &source.name,
+                            base_extractor.router.clone(),
+                            TimeFilter::new(&start_time_utc, &end_time_utc)?,
+                        )
+                        .await;
+                    let server_id = if source.server_id > 0 {
+                        source.server_id
+                    } else {
+                        server_id + i as u64
+                    };
+                    let extractor = MysqlCdcExtractor {
+                        meta_manager,
+                        filter: filter.clone(),
+                        conn_pool,
+                        url: source.url,
+                        binlog_filename: source.binlog_filename,
+                        binlog_position: source.binlog_position,
+                        server_id,
+                        binlog_heartbeat_interval_secs,
+                        binlog_timeout_secs,
+                        heartbeat_interval_secs,
+                        heartbeat_tb: heartbeat_tb.clone(),
+                        osc_enabled,
+                        syncer: Arc::new(Mutex::new(Syncer::default())),
+                        base_extractor: source_base_extractor,
+                        resumer: CdcResumer::default(),
+                        reconnector: Reconnector::from_config(&config.extractor_basic),
+                        gtid_enabled,
+                        gtid_set: source.gtid_set,
+                        xa_buffer: XaBuffer::default(),
+                    };
+                    cdc_sources.push((source.name, extractor));
+                }
+
+                let extractor = MysqlMultiCdcExtractor {
+                    base_extractor,
+                    sources: cdc_sources,
+                    source_col,
+                    syncer,
+                    resumer: cdc_resumer,
+                };
+                Box::new(extractor)
+            }
+
+            ExtractorConfig::MysqlCdc {
+                url,
+                binlog_filename,
+                binlog_position,
+                server_id,
+                gtid_enabled,
+                gtid_set,
+                binlog_heartbeat_interval_secs,
+                binlog_timeout_secs,
+                heartbeat_interval_secs,
+                heartbeat_tb,
+                start_time_utc,
+                end_time_utc,
+                osc_enabled,
+                ..
             } => {
                 let conn_pool =
                     TaskUtil::create_mysql_conn_pool(&url, 2, enable_sqlx_log, false).await?;
@@ -184,11 +271,14 @@ impl ExtractorUtil {
                     binlog_timeout_secs,
                     heartbeat_interval_secs,
                     heartbeat_tb,
+                    osc_enabled,
                     syncer,
                     base_extractor,
                     resumer: cdc_resumer,
+                    reconnector: Reconnector::from_config(&config.extractor_basic),
                     gtid_enabled,
                     gtid_set,
+                    xa_buffer: XaBuffer::default(),
                 };
                 Box::new(extractor)
             }
@@ -199,6 +289,7 @@ impl ExtractorUtil {
                 tb,
                 sample_interval,
                 batch_size,
+                ..
             } => {
                 let conn_pool =
                     TaskUtil::create_pg_conn_pool(&url, 2, enable_sqlx_log, false).await?;
@@ -248,6 +339,7 @@ impl ExtractorUtil {
                 ddl_meta_tb,
                 start_time_utc,
                 end_time_utc,
+                partition_root,
             } => {
                 let conn_pool =
                     TaskUtil::create_pg_conn_pool(&url, 2, enable_sqlx_log, false).await?;
@@ -267,7 +359,9 @@ impl ExtractorUtil {
                     heartbeat_interval_secs,
                     heartbeat_tb,
                     ddl_meta_tb,
+                    partition_root,
                     resumer: cdc_resumer,
+                    reconnector: Reconnector::from_config(&config.extractor_basic),
                     base_extractor,
                 };
                 Box::new(extractor)
@@ -312,6 +406,7 @@ impl ExtractorUtil {
                     heartbeat_tb,
                     syncer,
                     resumer: cdc_resumer,
+                    reconnector: Reconnector::from_config(&config.extractor_basic),
                 };
                 Box::new(extractor)
             }
@@ -363,27 +458,57 @@ impl ExtractorUtil {
                 Box::new(extractor)
             }
 
-            ExtractorConfig::RedisSnapshot { url, repl_port } => {
-                let extractor = RedisPsyncExtractor {
-                    conn: RedisClient::new(&url).await?,
-                    syncer,
-                    repl_port,
+            ExtractorConfig::RedisSnapshot {
+                url,
+                repl_port,
+                is_cluster,
+            } => {
+                if is_cluster {
+                    let extractor = RedisClusterPsyncExtractor {
+                        url,
+                        syncer,
+                        repl_port,
+                        filter,
+                        resumer: cdc_resumer,
+                        base_extractor,
+                        extract_type: ExtractType::Snapshot,
+                        keepalive_interval_secs: 0,
+                        heartbeat_interval_secs: 0,
+                        heartbeat_key: String::new(),
+                    };
+                    Box::new(extractor)
+                } else {
+                    let extractor = RedisPsyncExtractor {
+                        conn: RedisClient::new(&url).await?,
+                        syncer,
+                        repl_port,
+                        filter,
+                        resumer: cdc_resumer,
+                        base_extractor,
+                        extract_type: ExtractType::Snapshot,
+                        reconnector: Reconnector::default(),
+                        repl_id: String::new(),
+                        repl_offset: 0,
+                        now_db_id: 0,
+                        keepalive_interval_secs: 0,
+                        heartbeat_interval_secs: 0,
+                        heartbeat_key: String::new(),
+                    };
+                    Box::new(extractor)
+                }
+            }
+
+            ExtractorConfig::RedisSnapshotFile { file_path } => {
+                let extractor = RedisSnapshotFileExtractor {
+                    file_path,
                     filter,
-                    resumer: cdc_resumer,
                     base_extractor,
-                    extract_type: ExtractType::Snapshot,
-                    repl_id: String::new(),
-                    repl_offset: 0,
-                    now_db_id: 0,
-                    keepalive_interval_secs: 0,
-                    heartbeat_interval_secs: 0,
-                    heartbeat_key: String::new(),
                 };
                 Box::new(extractor)
             }
 
-            ExtractorConfig::RedisSnapshotFile { file_path } => {
-                let extractor = RedisSnapshotFileExtractor {
+            ExtractorConfig::RedisAofFile { file_path } => {
+                let extractor = RedisAofFileExtractor {
                     file_path,
                     filter,
                     base_extractor,
@@ -395,13 +520,20 @@ impl ExtractorUtil {
                 url,
                 scan_count,
                 statistic_type,
+                keyspace_events,
             } => {
                 let conn = RedisUtil::create_redis_conn(&url).await?;
-                let statistic_type = RedisStatisticType::from_str(&statistic_type)?;
+                let statistic_type = if statistic_type.is_empty() {
+                    None
+                } else {
+                    Some(RedisStatisticType::from_str(&statistic_type)?)
+                };
                 let extractor = RedisScanExtractor {
                     conn,
+                    url,
                     statistic_type,
                     scan_count,
+                    keyspace_events,
                     filter,
                     base_extractor,
                 };
@@ -417,23 +549,42 @@ impl ExtractorUtil {
                 keepalive_interval_secs,
                 heartbeat_interval_secs,
                 heartbeat_key,
+                is_cluster,
             } => {
-                let extractor = RedisPsyncExtractor {
-                    conn: RedisClient::new(&url).await?,
-                    repl_id,
-                    repl_offset,
-                    keepalive_interval_secs,
-                    heartbeat_interval_secs,
-                    heartbeat_key,
-                    syncer,
-                    repl_port,
-                    now_db_id,
-                    filter,
-                    resumer: cdc_resumer,
-                    base_extractor,
-                    extract_type: ExtractType::Cdc,
-                };
-                Box::new(extractor)
+                if is_cluster {
+                    // positions of shards are resumed by cdc_resumer
+                    let extractor = RedisClusterPsyncExtractor {
+                        url,
+                        keepalive_interval_secs,
+                        heartbeat_interval_secs,
+                        heartbeat_key,
+                        syncer,
+                        repl_port,
+                        filter,
+                        resumer: cdc_resumer,
+                        base_extractor,
+                        extract_type: ExtractType::Cdc,
+                    };
+                    Box::new(extractor)
+                } else {
+                    let extractor = RedisPsyncExtractor {
+                        conn: RedisClient::new(&url).await?,
+                        repl_id,
+                        repl_offset,
+                        keepalive_interval_secs,
+                        heartbeat_interval_secs,
+                        heartbeat_key,
+                        syncer,
+                        repl_port,
+                        now_db_id,
+                        filter,
+                        resumer: cdc_resumer,
+                        base_extractor,
+                        extract_type: ExtractType::Cdc,
+                        reconnector: Reconnector::from_config(&config.extractor_basic),
+                    };
+                    Box::new(extractor)
+                }
             }
 
             ExtractorConfig::RedisSnapshotAndCdc {
@@ -443,23 +594,41 @@ impl ExtractorUtil {
                 keepalive_interval_secs,
                 heartbeat_interval_secs,
                 heartbeat_key,
+                is_cluster,
             } => {
-                let extractor = RedisPsyncExtractor {
-                    conn: RedisClient::new(&url).await?,
-                    syncer,
-                    repl_port,
-                    filter,
-                    resumer: cdc_resumer,
-                    base_extractor,
-                    extract_type: ExtractType::SnapshotAndCdc,
-                    repl_id,
-                    repl_offset: 0,
-                    now_db_id: 0,
-                    keepalive_interval_secs,
-                    heartbeat_interval_secs,
-                    heartbeat_key,
-                };
-                Box::new(extractor)
+                if is_cluster {
+                    let extractor = RedisClusterPsyncExtractor {
+                        url,
+                        keepalive_interval_secs,
+                        heartbeat_interval_secs,
+                        heartbeat_key,
+                        syncer,
+                        repl_port,
+                        filter,
+                        resumer: cdc_resumer,
+                        base_extractor,
+                        extract_type: ExtractType::SnapshotAndCdc,
+                    };
+                    Box::new(extractor)
+                } else {
+                    let extractor = RedisPsyncExtractor {
+                        conn: RedisClient::new(&url).await?,
+                        syncer,
+                        repl_port,
+                        filter,
+                        resumer: cdc_resumer,
+                        base_extractor,
+                        extract_type: ExtractType::SnapshotAndCdc,
+                        reconnector: Reconnector::from_config(&config.extractor_basic),
+                        repl_id,
+                        repl_offset: 0,
+                        now_db_id: 0,
+                        keepalive_interval_secs,
+                        heartbeat_interval_secs,
+                        heartbeat_key,
+                    };
+                    Box::new(extractor)
+                }
             }
 
             ExtractorConfig::RedisReshard { url } => {
@@ -495,6 +664,28 @@ impl ExtractorUtil {
                 Box::new(extractor)
             }
 
+            ExtractorConfig::TidbCdc {
+                url,
+                group,
+                topic,
+                partition,
+                offset,
+                protocol,
+            } => {
+                let extractor = TidbCdcExtractor {
+                    url,
+                    group,
+                    topic,
+                    partition,
+                    offset,
+                    protocol,
+                    filter,
+                    resumer: cdc_resumer,
+                    base_extractor,
+                };
+                Box::new(extractor)
+            }
+
             ExtractorConfig::FoxlakeS3 {
                 schema,
                 tb,
@@ -514,6 +705,13 @@ impl ExtractorUtil {
                 };
                 Box::new(extractor)
             }
+
+            ExtractorConfig::SnapshotAndCdc { .. } => {
+                // TaskRunner starts the snapshot and the cdc extractors one after another
+                bail! {Error::ConfigError(
+                    "snapshot_and_cdc extractor should be started in phases".into()
+                )}
+            }
         };
         Ok(extractor)
     }
@@ -100,7 +100,9 @@ impl ParallelizerUtil {
 
             ParallelType::Redis => {
                 let mut slot_node_map = HashMap::new();
-                if let SinkerConfig::Redis { is_cluster, .. } = config.sinker {
+                if let SinkerConfig::Redis { is_cluster, .. }
+                | SinkerConfig::RedisCheck { is_cluster, .. } = config.sinker
+                {
                     let mut conn = RedisUtil::create_redis_conn(&config.sinker_basic.url).await?;
                     if is_cluster {
                         let nodes = RedisUtil::get_cluster_master_nodes(&mut conn)?;
@@ -1,4 +1,8 @@
-use std::{str::FromStr, sync::Arc};
+use std::{
+    collections::{HashMap, VecDeque},
+    str::FromStr,
+    sync::Arc,
+};
 
 use anyhow::Context;
 use kafka::producer::{Producer, RequiredAcks};
@@ -9,7 +13,9 @@ use tokio::sync::{Mutex, RwLock};
 
 use dt_common::{
     config::{
-        config_enums::DbType, extractor_config::ExtractorConfig, sinker_config::SinkerConfig,
+        config_enums::{DbType, DeadLetterType},
+        extractor_config::ExtractorConfig,
+        sinker_config::SinkerConfig,
         task_config::TaskConfig,
     },
     meta::redis::command::key_parser::KeyParser,
@@ -28,12 +34,14 @@ use super::task_util::TaskUtil;
 use crate::extractor_util::ExtractorUtil;
 use dt_connector::{
     data_marker::DataMarker,
+    dead_letter::dead_letter_writer::{DeadLetterTarget, DeadLetterWriter},
     rdb_router::RdbRouter,
     sinker::{
         clickhouse::{
             clickhouse_sinker::ClickhouseSinker, clickhouse_struct_sinker::ClickhouseStructSinker,
         },
         dummy_sinker::DummySinker,
+        file::file_sinker::FileSinker,
         foxlake::{
             foxlake_merger::FoxlakeMerger, foxlake_pusher::FoxlakePusher,
             foxlake_sinker::FoxlakeSinker, foxlake_struct_sinker::FoxlakeStructSinker,
@@ -46,7 +54,11 @@ use dt_connector::{
             mysql_struct_sinker::MysqlStructSinker,
         },
         pg::{pg_checker::PgChecker, pg_sinker::PgSinker, pg_struct_sinker::PgStructSinker},
-        redis::{redis_sinker::RedisSinker, redis_statistic_sinker::RedisStatisticSinker},
+        redis::{
+            redis_checker::RedisChecker, redis_sinker::RedisSinker,
+            redis_statistic_sinker::RedisStatisticSinker,
+        },
+        retry_sinker::RetrySinker,
         sql_sinker::SqlSinker,
         starrocks::{
             starrocks_sinker::StarRocksSinker, starrocks_struct_sinker::StarrocksStructSinker,
@@ -399,6 +411,57 @@ impl SinkerUtil {
                 }
             }
 
+            SinkerConfig::RedisCheck {
+                url,
+                batch_size,
+                is_cluster,
+                sample_percent,
+                ttl_tolerance_secs,
+                ..
+            } => {
+                let ttl_tolerance_ms = ttl_tolerance_secs as i64 * 1000;
+                if is_cluster {
+                    let url_info = Url::parse(&url)?;
+                    let username = url_info.username();
+                    let password = url_info.password().unwrap_or("").to_string();
+
+                    let mut conn = RedisUtil::create_redis_conn(&url).await?;
+                    let nodes = RedisUtil::get_cluster_master_nodes(&mut conn)?;
+                    for node in nodes.iter() {
+                        if !node.is_master {
+                            continue;
+                        }
+
+                        let new_url = format!("redis://{}:{}@{}", username, password, node.address);
+                        let conn = RedisUtil::create_redis_conn(&new_url).await?;
+                        let sinker = RedisChecker {
+                            cluster_node: Some(node.clone()),
+                            batch_size,
+                            conn,
+                            now_db_id: -1,
+                            sample_percent,
+                            ttl_tolerance_ms,
+                            monitor: monitor.clone(),
+                        };
+                        sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+                    }
+                } else {
+                    for _ in 0..parallel_size {
+                        let conn = RedisUtil::create_redis_conn(&url).await?;
+                        let sinker = RedisChecker {
+                            cluster_node: None,
+                            batch_size,
+                            conn,
+                            now_db_id: -1,
+                            sample_percent,
+                            ttl_tolerance_ms,
+                            monitor: monitor.clone(),
+                        };
+                        sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+                    }
+                }
+            }
+
             SinkerConfig::RedisStatistic {
                 statistic_type,
                 data_size_threshold,
@@ -737,7 +800,131 @@ impl SinkerUtil {
                 };
                 sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
             }
+
+            SinkerConfig::File {
+                root_dir,
+                file_format,
+                file_max_mb,
+                file_max_secs,
+                s3_config,
+                ..
+            } => {
+                let s3_client = if s3_config.bucket.is_empty() {
+                    None
+                } else {
+                    Some(TaskUtil::create_s3_client(&s3_config))
+                };
+                let reverse_router = RdbRouter::from_config(
+                    &task_config.router,
+                    &task_config.extractor_basic.db_type,
+                )?
+                .reverse();
+
+                for _ in 0..parallel_size {
+                    // column order comes from the meta of RDB extractor
+                    let meta_manager =
+                        ExtractorUtil::get_extractor_meta_manager(task_config).await?;
+                    let sinker = FileSinker {
+                        root_dir: root_dir.clone(),
+                        file_format: file_format.clone(),
+                        file_max_bytes: file_max_mb * 1024 * 1024,
+                        file_max_secs,
+                        s3_client: s3_client.clone(),
+                        s3_config: s3_config.clone(),
+                        meta_manager,
+                        reverse_router: reverse_router.clone(),
+                        extract_type: task_config.extractor_basic.extract_type.clone(),
+                        monitor: monitor.clone(),
+                        files: HashMap::new(),
+                        file_seq: 0,
+                    };
+                    sub_sinkers.push(Arc::new(async_mutex::Mutex::new(Box::new(sinker))));
+                }
+            }
         };
+
+        let sinker_basic = &task_config.sinker_basic;
+        if sinker_basic.max_retries > 0 {
+            sub_sinkers = sub_sinkers
+                .into_iter()
+                .map(|inner| {
+                    let sinker = RetrySinker {
+                        inner,
+                        max_retries: sinker_basic.max_retries,
+                        retry_interval_ms: sinker_basic.retry_interval_ms,
+                        retry_max_interval_ms: sinker_basic.retry_max_interval_ms,
+                        monitor: monitor.clone(),
+                    };
+                    let sinker: Box<dyn Sinker + Send> = Box::new(sinker);
+                    Arc::new(async_mutex::Mutex::new(sinker))
+                })
+                .collect();
+        }
         Ok(sub_sinkers)
     }
+
+    pub async fn create_dead_letter_writer(
+        task_config: &TaskConfig,
+        monitor: Arc<Monitor>,
+    ) -> anyhow::Result<Option<DeadLetterWriter>> {
+        let config = match &task_config.dead_letter {
+            Some(config) => config,
+            None => return Ok(None),
+        };
+
+        let enable_sqlx_log = TaskUtil::check_enable_sqlx_log(&task_config.runtime.log_level);
+        let target = match config.dead_letter_type {
+            DeadLetterType::Log => DeadLetterTarget::Log,
+
+            DeadLetterType::Kafka => {
+                let producer = Producer::from_hosts(vec![config.url.clone()])
+                    .create()
+                    .with_context(|| {
+                        format!("failed to create kafka producer, url: [{}]", config.url)
+                    })?;
+                DeadLetterTarget::Kafka {
+                    producer: Arc::new(std::sync::Mutex::new(producer)),
+                    topic: config.topic.clone(),
+                }
+            }
+
+            DeadLetterType::Table => match config.db_type {
+                DbType::Mysql => {
+                    let conn_pool =
+                        TaskUtil::create_mysql_conn_pool(&config.url, 1, enable_sqlx_log, false)
+                            .await?;
+                    DeadLetterTarget::MysqlTable {
+                        conn_pool,
+                        tb: config.tb.clone(),
+                    }
+                }
+                _ => {
+                    let conn_pool =
+                        TaskUtil::create_pg_conn_pool(&config.url, 1, enable_sqlx_log, false)
+                            .await?;
+                    DeadLetterTarget::PgTable {
+                        conn_pool,
+                        tb: config.tb.clone(),
+                    }
+                }
+            },
+        };
+
+        // dead letters are logged with source schema / tb / id cols, so they can be replayed
+        let reverse_router =
+            RdbRouter::from_config(&task_config.router, &task_config.extractor_basic.db_type)?
+                .reverse();
+        let extractor_meta_manager = ExtractorUtil::get_extractor_meta_manager(task_config).await?;
+
+        Ok(Some(DeadLetterWriter {
+            target,
+            extractor_db_type: task_config.extractor_basic.db_type.clone(),
+            extractor_meta_manager,
+            reverse_router,
+            threshold: config.threshold,
+            window_secs: config.window_secs,
+            monitor,
+            write_times: VecDeque::new(),
+        }))
+    }
 }
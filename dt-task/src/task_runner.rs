@@ -1,6 +1,8 @@
 use std::{
     collections::VecDeque,
+    io::{BufRead, BufReader},
     panic,
+    path::Path,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc,
@@ -21,7 +23,7 @@ use super::{
 use crate::task_util::TaskUtil;
 use dt_common::{
     config::{
-        config_enums::{build_task_type, DbType, PipelineType},
+        config_enums::{build_task_type, DbType, ExtractType, ParallelType, PipelineType},
         config_token_parser::ConfigTokenParser,
         extractor_config::ExtractorConfig,
         sinker_config::SinkerConfig,
@@ -45,13 +47,17 @@ use dt_common::{
 };
 use dt_connector::{
     data_marker::DataMarker,
-    extractor::resumer::{cdc_resumer::CdcResumer, snapshot_resumer::SnapshotResumer},
+    extractor::{
+        pg::pg_ddl_capture::PgDdlCapture,
+        resumer::{cdc_resumer::CdcResumer, snapshot_resumer::SnapshotResumer},
+    },
     rdb_router::RdbRouter,
     Sinker,
 };
 use dt_pipeline::{
-    base_pipeline::BasePipeline, http_server_pipeline::HttpServerPipeline,
-    lua_processor::LuaProcessor, Pipeline,
+    base_pipeline::BasePipeline, ddl_policy::DdlPolicy, http_server_pipeline::HttpServerPipeline,
+    lua_processor::LuaProcessor, mongo_flattener::MongoFlattener, mongo_shaper::MongoShaper,
+    rdb_transformer::RdbTransformer, Pipeline,
 };
 
 #[cfg(feature = "metrics")]
@@ -74,6 +80,7 @@ const LOG_LEVEL_PLACEHODLER: &str = "LOG_LEVEL_PLACEHODLER";
 const LOG_DIR_PLACEHODLER: &str = "LOG_DIR_PLACEHODLER";
 const DEFAULT_CHECK_LOG_DIR_PLACEHODLER: &str = "LOG_DIR_PLACEHODLER/check";
 const DEFAULT_STATISTIC_LOG_DIR_PLACEHODLER: &str = "LOG_DIR_PLACEHODLER/statistic";
+const CDC_START_POSITION_LOG_FLAG: &str = "cdc_start_position";
 
 impl TaskRunner {
     pub fn new(task_config_file: &str) -> anyhow::Result<Self> {
@@ -141,6 +148,10 @@ impl TaskRunner {
                     .await?
             }
 
+            ExtractorConfig::SnapshotAndCdc { snapshot, cdc } => {
+                self.start_snapshot_and_cdc(snapshot, cdc, &router).await?
+            }
+
             _ => {
                 self.clone()
                     .start_single_task(
@@ -158,6 +169,51 @@ impl TaskRunner {
         Ok(())
     }
 
+    /// install (or uninstall if teardown) the event trigger, function and ddl_meta_tb
+    /// used by pg cdc tasks to capture ddls, the installation is verified by a probe ddl
+    pub async fn setup_ddl_capture(&self, teardown: bool) -> anyhow::Result<()> {
+        let cdc_config = match &self.config.extractor {
+            ExtractorConfig::SnapshotAndCdc { cdc, .. } => cdc.as_ref(),
+            config => config,
+        };
+        let ExtractorConfig::PgCdc {
+            url,
+            slot_name,
+            pub_name,
+            ddl_meta_tb,
+            ..
+        } = cdc_config
+        else {
+            bail! {Error::ConfigError(
+                "ddl capture setup is only supported for pg cdc tasks".into()
+            )}
+        };
+        if ddl_meta_tb.is_empty() {
+            bail! {Error::ConfigError(
+                "[extractor] ddl_meta_tb is required for ddl capture setup".into()
+            )}
+        }
+
+        // the same as PgCdcClient
+        let pub_name = if pub_name.is_empty() {
+            format!("{}_publication_for_all_tables", slot_name)
+        } else {
+            pub_name.to_owned()
+        };
+        let ddl_capture = PgDdlCapture {
+            conn_pool: TaskUtil::create_pg_conn_pool(url, 1, false, false).await?,
+            ddl_meta_tb: ddl_meta_tb.to_owned(),
+            pub_name,
+        };
+        let result = if teardown {
+            ddl_capture.teardown().await
+        } else {
+            ddl_capture.setup().await
+        };
+        ddl_capture.conn_pool.close().await;
+        result
+    }
+
     async fn start_multi_task(
         &self,
         url: &str,
@@ -222,7 +278,23 @@ impl TaskRunner {
 
             // find pending tables
             let tbs = TaskUtil::list_tbs(url, schema, db_type).await?;
+            // rows of partitions are extracted once by their partition roots
+            let partitions = match &self.config.extractor {
+                ExtractorConfig::PgSnapshot {
+                    partition_root: true,
+                    ..
+                } => TaskUtil::list_pg_partitions(url, schema).await?,
+                _ => Vec::new(),
+            };
             for tb in tbs.iter() {
+                if partitions.contains(tb) {
+                    log_info!(
+                        "schema: {}, tb: {}, extracted by partition root",
+                        schema,
+                        tb
+                    );
+                    continue;
+                }
                 if snapshot_resumer.check_finished(schema, tb) {
                     log_info!("schema: {}, tb: {}, already finished", schema, tb);
                     continue;
@@ -307,6 +379,156 @@ impl TaskRunner {
         Ok(())
     }
 
+    async fn start_snapshot_and_cdc(
+        &self,
+        snapshot_extractor_config: &ExtractorConfig,
+        cdc_extractor_config: &ExtractorConfig,
+        router: &RdbRouter,
+    ) -> anyhow::Result<()> {
+        let snapshot_runner =
+            self.clone_for_phase(ExtractType::Snapshot, snapshot_extractor_config);
+        let cdc_runner = self.clone_for_phase(ExtractType::Cdc, cdc_extractor_config);
+        let cdc_resumer = CdcResumer::from_config(&cdc_runner.config)?;
+
+        // if cdc had started before the task restarted, the snapshot must have finished
+        let cdc_started = matches!(
+            cdc_resumer.checkpoint_position,
+            Position::MysqlCdc { .. } | Position::PgCdc { .. } | Position::MongoCdc { .. }
+        );
+
+        let mut cdc_extractor_config = cdc_extractor_config.clone();
+        if !cdc_started {
+            // the position must be fetched before the snapshot starts, changes during the snapshot
+            // will be extracted again by cdc, which is idempotent since sinkers apply rows by replace
+            let start_position = match self.load_cdc_start_position()? {
+                Some(position) => {
+                    log_info!("resume cdc start position: {}", position);
+                    position
+                }
+                None => {
+                    let position =
+                        TaskUtil::fetch_cdc_start_position(&cdc_extractor_config).await?;
+                    log_finished!("{} | {}", CDC_START_POSITION_LOG_FLAG, position);
+                    position
+                }
+            };
+            Self::set_cdc_start_position(&mut cdc_extractor_config, &start_position);
+
+            let url = &self.config.extractor_basic.url;
+            let snapshot_resumer = SnapshotResumer::from_config(&snapshot_runner.config)?;
+            snapshot_runner
+                .start_multi_task(url, router, &snapshot_resumer, &CdcResumer::default())
+                .await?;
+            log_info!("snapshot finished, start cdc from: {}", start_position);
+        }
+
+        cdc_runner
+            .start_single_task(
+                &cdc_extractor_config,
+                router,
+                &SnapshotResumer::default(),
+                &cdc_resumer,
+                false,
+            )
+            .await
+    }
+
+    fn clone_for_phase(
+        &self,
+        extract_type: ExtractType,
+        extractor_config: &ExtractorConfig,
+    ) -> Self {
+        let mut me = self.clone();
+        if let ExtractType::Snapshot = extract_type {
+            // row based parallelizers for cdc are not needed for inserts
+            if matches!(
+                me.config.parallelizer.parallel_type,
+                ParallelType::RdbMerge | ParallelType::RdbPartition
+            ) {
+                me.config.parallelizer.parallel_type = ParallelType::Snapshot;
+            }
+        }
+        me.config.extractor_basic.extract_type = extract_type;
+        me.config.extractor = extractor_config.clone();
+        me
+    }
+
+    fn load_cdc_start_position(&self) -> anyhow::Result<Option<Position>> {
+        let config = &self.config.resumer;
+        let finished_log = format!("{}/finished.log", config.resume_log_dir);
+        if !config.resume_from_log || !Path::new(&finished_log).exists() {
+            return Ok(None);
+        }
+
+        let file = std::fs::File::open(&finished_log).with_context(|| {
+            format!(
+                "failed to open finished.log: [{}] while it exists",
+                finished_log
+            )
+        })?;
+        let position = BufReader::new(file)
+            .lines()
+            .map_while(Result::ok)
+            .filter(|line| line.contains(CDC_START_POSITION_LOG_FLAG))
+            .map(|line| Position::from_log(&line))
+            .find(|position| *position != Position::None);
+        Ok(position)
+    }
+
+    fn set_cdc_start_position(extractor_config: &mut ExtractorConfig, position: &Position) {
+        match (extractor_config, position) {
+            (
+                ExtractorConfig::MysqlCdc {
+                    binlog_filename,
+                    binlog_position,
+                    gtid_set,
+                    start_time_utc,
+                    ..
+                },
+                Position::MysqlCdc {
+                    binlog_filename: position_binlog_filename,
+                    next_event_position,
+                    gtid_set: position_gtid_set,
+                    ..
+                },
+            ) => {
+                *binlog_filename = position_binlog_filename.clone();
+                *binlog_position = *next_event_position;
+                *gtid_set = position_gtid_set.clone();
+                start_time_utc.clear();
+            }
+
+            (
+                ExtractorConfig::PgCdc {
+                    start_lsn,
+                    recreate_slot_if_exists,
+                    start_time_utc,
+                    ..
+                },
+                Position::PgCdc { lsn, .. },
+            ) => {
+                *start_lsn = lsn.clone();
+                // the slot was created before the snapshot and holds the wal since then
+                *recreate_slot_if_exists = false;
+                start_time_utc.clear();
+            }
+
+            (
+                ExtractorConfig::MongoCdc {
+                    resume_token,
+                    start_timestamp,
+                    ..
+                },
+                Position::MongoCdc { operation_time, .. },
+            ) => {
+                resume_token.clear();
+                *start_timestamp = *operation_time;
+            }
+
+            _ => {}
+        }
+    }
+
     async fn spawn_single_task(
         self,
         schema: &str,
@@ -337,6 +559,7 @@ impl TaskRunner {
                 url,
                 sample_interval,
                 batch_size,
+                partition_root,
                 ..
             } => ExtractorConfig::PgSnapshot {
                 url: url.clone(),
@@ -344,6 +567,7 @@ impl TaskRunner {
                 tb: tb.into(),
                 sample_interval: *sample_interval,
                 batch_size: *batch_size,
+                partition_root: *partition_root,
             },
 
             ExtractorConfig::MongoSnapshot { url, app_name, .. } => {
@@ -623,13 +847,13 @@ impl TaskRunner {
                     None
                 };
 
-                let lua_processor =
-                    self.config
-                        .processor
-                        .as_ref()
-                        .map(|processor_config| LuaProcessor {
-                            lua_code: processor_config.lua_code.clone(),
-                        });
+                let lua_processor = match &self.config.processor {
+                    Some(processor_config) => Some(LuaProcessor::from_config(
+                        processor_config,
+                        &self.config.extractor_basic.db_type,
+                    )?),
+                    None => None,
+                };
 
                 let parallelizer = ParallelizerUtil::create_parallelizer(
                     &self.config,
@@ -637,6 +861,23 @@ impl TaskRunner {
                     rps_limiter,
                 )
                 .await?;
+                let dead_letter_writer =
+                    SinkerUtil::create_dead_letter_writer(&self.config, monitor.clone()).await?;
+                let ddl_policy = match &self.config.ddl_policy {
+                    Some(config) => {
+                        DdlPolicy::from_config(config, &self.config.sinker_basic.db_type)?
+                    }
+                    None => DdlPolicy::default(),
+                };
+                let transformer = match &self.config.transformer {
+                    Some(config) => RdbTransformer::from_config(config)?,
+                    None => RdbTransformer::default(),
+                };
+                let flattener = match &self.config.flattener {
+                    Some(config) => Some(MongoFlattener::from_config(config)?),
+                    None => None,
+                };
+                let shaper = self.create_shaper().await?;
 
                 let pipeline = BasePipeline {
                     buffer,
@@ -650,6 +891,11 @@ impl TaskRunner {
                     monitor,
                     data_marker,
                     lua_processor,
+                    dead_letter_writer,
+                    ddl_policy,
+                    transformer,
+                    flattener,
+                    shaper,
                 };
                 Ok(Box::new(pipeline))
             }
@@ -673,6 +919,25 @@ impl TaskRunner {
         }
     }
 
+    /// rows from mysql / pg are shaped into documents before being sinked to mongo
+    async fn create_shaper(&self) -> anyhow::Result<Option<MongoShaper>> {
+        let extractor_db_type = &self.config.extractor_basic.db_type;
+        if self.config.sinker_basic.db_type != DbType::Mongo
+            || !matches!(extractor_db_type, DbType::Mysql | DbType::Pg)
+        {
+            return Ok(None);
+        }
+
+        let config = self.config.shaper.clone().unwrap_or_default();
+        let router = RdbRouter::from_config(&self.config.router, extractor_db_type)?;
+        let meta_manager = ExtractorUtil::get_extractor_meta_manager(&self.config).await?;
+        Ok(Some(MongoShaper::from_config(
+            &config,
+            router,
+            meta_manager,
+        )?))
+    }
+
     async fn init_log4rs(&self) -> anyhow::Result<()> {
         let log4rs_file = &self.config.runtime.log4rs_file;
         if metadata(log4rs_file).await.is_err() {
@@ -685,7 +950,8 @@ impl TaskRunner {
 
         match &self.config.sinker {
             SinkerConfig::MysqlCheck { check_log_dir, .. }
-            | SinkerConfig::PgCheck { check_log_dir, .. } => {
+            | SinkerConfig::PgCheck { check_log_dir, .. }
+            | SinkerConfig::RedisCheck { check_log_dir, .. } => {
                 if !check_log_dir.is_empty() {
                     config_str = config_str.replace(CHECK_LOG_DIR_PLACEHODLER, check_log_dir);
                 }
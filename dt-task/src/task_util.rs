@@ -1,5 +1,6 @@
 use std::{str::FromStr, time::Duration};
 
+use anyhow::bail;
 use dt_common::config::config_enums::TaskType;
 use dt_common::config::extractor_config::ExtractorConfig;
 use dt_common::config::s3_config::S3Config;
@@ -7,8 +8,10 @@ use dt_common::config::{
     config_enums::DbType, meta_center_config::MetaCenterConfig, sinker_config::SinkerConfig,
     task_config::TaskConfig,
 };
+use dt_common::error::Error;
 use dt_common::log_info;
 use dt_common::meta::mysql::mysql_dbengine_meta_center::MysqlDbEngineMetaCenter;
+use dt_common::meta::position::Position;
 use dt_common::meta::{
     mysql::mysql_meta_manager::MysqlMetaManager, pg::pg_meta_manager::PgMetaManager,
     rdb_meta_manager::RdbMetaManager,
@@ -320,6 +323,174 @@ WHERE
         Ok(total_length)
     }
 
+    /// the position for cdc to start from, fetched before snapshot so that
+    /// no changes will be lost during the snapshot
+    pub async fn fetch_cdc_start_position(
+        cdc_extractor_config: &ExtractorConfig,
+    ) -> anyhow::Result<Position> {
+        let position = match cdc_extractor_config {
+            ExtractorConfig::MysqlCdc { url, server_id, .. } => {
+                Self::fetch_mysql_cdc_start_position(url, *server_id).await?
+            }
+
+            ExtractorConfig::PgCdc {
+                url,
+                slot_name,
+                pub_name,
+                recreate_slot_if_exists,
+                partition_root,
+                ..
+            } => {
+                Self::fetch_pg_cdc_start_position(
+                    url,
+                    slot_name,
+                    pub_name,
+                    *recreate_slot_if_exists,
+                    *partition_root,
+                )
+                .await?
+            }
+
+            ExtractorConfig::MongoCdc { url, app_name, .. } => {
+                Self::fetch_mongo_cdc_start_position(url, app_name).await?
+            }
+
+            _ => bail! {Error::ConfigError(
+                "fetching cdc start position is only supported for mysql / pg / mongo".into()
+            )},
+        };
+        log_info!("fetched cdc start position: {}", position);
+        Ok(position)
+    }
+
+    async fn fetch_mysql_cdc_start_position(url: &str, server_id: u64) -> anyhow::Result<Position> {
+        let conn_pool = Self::create_mysql_conn_pool(url, 1, false, false).await?;
+        // SHOW MASTER STATUS was renamed to SHOW BINARY LOG STATUS since mysql 8.4
+        let row = match sqlx::query("SHOW MASTER STATUS")
+            .fetch_optional(&conn_pool)
+            .await
+        {
+            Ok(row) => row,
+            Err(_) => {
+                sqlx::query("SHOW BINARY LOG STATUS")
+                    .fetch_optional(&conn_pool)
+                    .await?
+            }
+        };
+        let Some(row) = row else {
+            bail! {Error::ExtractorError("binlog is not enabled in source mysql".into())}
+        };
+
+        let binlog_filename: String = row.try_get("File")?;
+        let next_event_position: u64 = row.try_get("Position")?;
+        // multiple gtid sets are separated by ",\n"
+        let gtid_set: String = row.try_get("Executed_Gtid_Set")?;
+        conn_pool.close().await;
+
+        Ok(Position::MysqlCdc {
+            server_id: server_id.to_string(),
+            binlog_filename,
+            next_event_position: next_event_position as u32,
+            gtid_set: gtid_set.replace('\n', ""),
+            timestamp: String::new(),
+        })
+    }
+
+    async fn fetch_pg_cdc_start_position(
+        url: &str,
+        slot_name: &str,
+        pub_name: &str,
+        recreate_slot_if_exists: bool,
+        partition_root: bool,
+    ) -> anyhow::Result<Position> {
+        let conn_pool = Self::create_pg_conn_pool(url, 1, false, false).await?;
+
+        // the publication should exist before the slot starts, same as PgCdcClient
+        let pub_name = if pub_name.is_empty() {
+            format!("{}_publication_for_all_tables", slot_name)
+        } else {
+            pub_name.to_string()
+        };
+        let sql = format!(
+            "SELECT pubname FROM pg_catalog.pg_publication WHERE pubname = '{}'",
+            pub_name
+        );
+        if sqlx::query(&sql)
+            .fetch_optional(&conn_pool)
+            .await?
+            .is_none()
+        {
+            let mut sql = format!("CREATE PUBLICATION {} FOR ALL TABLES", pub_name);
+            if partition_root {
+                let version: String = sqlx::query("SHOW server_version_num")
+                    .fetch_one(&conn_pool)
+                    .await?
+                    .try_get(0)?;
+                if version.parse::<u32>()? >= 130000 {
+                    sql = format!("{} WITH (publish_via_partition_root = true)", sql);
+                }
+            }
+            log_info!("execute: {}", sql);
+            sqlx::query(&sql).execute(&conn_pool).await?;
+        }
+
+        let sql = format!(
+            "SELECT confirmed_flush_lsn::text FROM pg_catalog.pg_replication_slots WHERE slot_name = '{}'",
+            slot_name
+        );
+        let confirmed_flush_lsn: Option<Option<String>> =
+            match sqlx::query(&sql).fetch_optional(&conn_pool).await? {
+                Some(row) => Some(row.try_get(0)?),
+                None => None,
+            };
+
+        let lsn = match confirmed_flush_lsn {
+            // the slot holds all wal after confirmed_flush_lsn
+            Some(Some(lsn)) if !recreate_slot_if_exists => lsn,
+            slot => {
+                if slot.is_some() {
+                    let sql = format!("SELECT pg_drop_replication_slot('{}')", slot_name);
+                    log_info!("execute: {}", sql);
+                    sqlx::query(&sql).execute(&conn_pool).await?;
+                }
+                let sql = format!(
+                    "SELECT lsn::text FROM pg_create_logical_replication_slot('{}', 'pgoutput')",
+                    slot_name
+                );
+                log_info!("execute: {}", sql);
+                let row = sqlx::query(&sql).fetch_one(&conn_pool).await?;
+                row.try_get(0)?
+            }
+        };
+        conn_pool.close().await;
+
+        Ok(Position::PgCdc {
+            lsn,
+            timestamp: String::new(),
+        })
+    }
+
+    async fn fetch_mongo_cdc_start_position(url: &str, app_name: &str) -> anyhow::Result<Position> {
+        let client = Self::create_mongo_client(url, app_name).await?;
+        // operationTime is returned by replica sets / sharded clusters
+        let result = client
+            .database("admin")
+            .run_command(doc! { "ping": 1 }, None)
+            .await?;
+        client.shutdown().await;
+
+        let Ok(operation_time) = result.get_timestamp("operationTime") else {
+            bail! {Error::ExtractorError(
+                "failed to get operationTime, source mongo should be a replica set".into()
+            )}
+        };
+        Ok(Position::MongoCdc {
+            resume_token: String::new(),
+            operation_time: operation_time.time,
+            timestamp: String::new(),
+        })
+    }
+
     pub async fn check_tb_exist(
         url: &str,
         schema: &str,
@@ -414,6 +585,28 @@ WHERE
         Ok(tbs)
     }
 
+    /// tables attached as partitions to a partitioned table, including sub-partitioned ones
+    pub async fn list_pg_partitions(url: &str, schema: &str) -> anyhow::Result<Vec<String>> {
+        let mut tbs = Vec::new();
+        let conn_pool = TaskUtil::create_pg_conn_pool(url, 1, false, false).await?;
+
+        let sql = format!(
+            "SELECT c.relname
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE n.nspname = '{}'
+            AND c.relispartition",
+            schema
+        );
+        let mut rows = sqlx::query(&sql).fetch(&conn_pool);
+        while let Some(row) = rows.try_next().await.unwrap() {
+            let tb: String = row.try_get(0)?;
+            tbs.push(tb);
+        }
+        conn_pool.close().await;
+        Ok(tbs)
+    }
+
     async fn list_mysql_dbs(url: &str) -> anyhow::Result<Vec<String>> {
         let mut dbs = Vec::new();
         let conn_pool = TaskUtil::create_mysql_conn_pool(url, 1, false, false).await?;
@@ -1,9 +1,12 @@
 mod log_reader;
 mod mongo_to_mongo;
+mod mongo_to_mysql;
 mod mysql_to_clickhouse;
 mod mysql_to_doris;
+mod mysql_to_file;
 mod mysql_to_foxlake;
 mod mysql_to_kafka_to_mysql;
+mod mysql_to_mongo;
 mod mysql_to_mysql;
 mod mysql_to_mysql_case_sensitive;
 mod mysql_to_mysql_lua;
@@ -19,3 +22,4 @@ mod pg_to_starrocks;
 mod redis_to_redis;
 mod test_config_util;
 mod test_runner;
+mod tidb_to_mysql;
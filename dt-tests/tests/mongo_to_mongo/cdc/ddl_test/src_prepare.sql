@@ -0,0 +1,14 @@
+use test_db_1
+
+db.dropDatabase();
+
+db.createCollection("tb_1");
+db.createCollection("tb_2");
+db.tb_2.createIndex({ "age": 1 }, { "name": "idx_age" });
+db.createCollection("tb_4");
+
+use test_db_2
+
+db.dropDatabase();
+
+db.createCollection("tb_1");
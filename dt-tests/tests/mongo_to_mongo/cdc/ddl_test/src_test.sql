@@ -0,0 +1,21 @@
+use test_db_1
+
+-- create
+db.createCollection("tb_3");
+db.tb_1.createIndex({ "name": 1 }, { "name": "idx_name", "unique": true });
+
+-- drop index
+db.tb_2.dropIndex("idx_age");
+
+-- rename
+db.tb_1.renameCollection("tb_1_new");
+
+-- rename to an existing collection
+db.tb_2.renameCollection("tb_4", true);
+
+db.tb_3.insertOne({ "name": "a", "age": "1" });
+db.tb_3.insertOne({ "name": "b", "age": "2" });
+
+use test_db_2
+
+db.dropDatabase();
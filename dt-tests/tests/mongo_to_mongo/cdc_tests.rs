@@ -40,6 +40,12 @@ mod test {
         TestBase::run_mongo_cdc_test("mongo_to_mongo/cdc/route_test", 3000, 3000).await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn cdc_ddl_test() {
+        TestBase::run_mongo_cdc_ddl_test("mongo_to_mongo/cdc/ddl_test", 3000, 3000).await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn cdc_heartbeat_test() {
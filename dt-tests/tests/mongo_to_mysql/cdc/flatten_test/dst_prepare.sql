@@ -0,0 +1,7 @@
+DROP DATABASE IF EXISTS test_db_1;
+
+CREATE DATABASE test_db_1;
+
+CREATE TABLE test_db_1.orders(`_id` varchar(64) PRIMARY KEY, name varchar(64), city varchar(64));
+
+CREATE TABLE test_db_1.order_items(order_id varchar(64), `_idx` int, sku varchar(64), qty int, PRIMARY KEY(order_id, `_idx`));
@@ -0,0 +1,5 @@
+use test_db_1
+
+db.dropDatabase();
+
+db.createCollection("orders");
@@ -0,0 +1,12 @@
+use test_db_1
+
+db.orders.insertOne({ "_id": "o_1", "name": "a", "address": { "city": "hz" }, "items": [{ "sku": "s_1", "qty": 1 }, { "sku": "s_2", "qty": 2 }, { "sku": "s_3", "qty": 3 }] });
+db.orders.insertOne({ "_id": "o_2", "name": "b", "address": { "city": "sh" }, "items": [{ "sku": "s_4", "qty": 4 }] });
+db.orders.insertOne({ "_id": "o_3", "name": "c", "items": [{ "sku": "s_5", "qty": 5 }, { "sku": "s_6", "qty": 6 }] });
+
+-- pre-images are not enabled, shrunk arrays and deleted documents leave no child rows
+db.orders.updateOne({ "_id": "o_1" }, { "$pop": { "items": 1 } });
+db.orders.updateOne({ "_id": "o_1" }, { "$pop": { "items": 1 } });
+db.orders.updateOne({ "_id": "o_1" }, { "$set": { "name": "a_1" } });
+db.orders.updateOne({ "_id": "o_2" }, { "$unset": { "items": "" } });
+db.orders.deleteOne({ "_id": "o_3" });
@@ -0,0 +1,12 @@
+#[cfg(test)]
+mod test {
+    use serial_test::serial;
+
+    use crate::test_runner::test_base::TestBase;
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_flatten_test() {
+        TestBase::run_mongo_rdb_cdc_test("mongo_to_mysql/cdc/flatten_test", 3000, 3000).await;
+    }
+}
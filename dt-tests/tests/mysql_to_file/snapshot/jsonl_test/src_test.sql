@@ -0,0 +1,3 @@
+INSERT INTO test_db_1.tb_1 VALUES (1, 'a', 10), (2, 'b,"c"', NULL), (3, NULL, 30);
+
+INSERT INTO test_db_1.tb_2 VALUES (1, 1.10), (2, NULL);
@@ -0,0 +1,7 @@
+DROP DATABASE IF EXISTS test_db_1;
+
+CREATE DATABASE test_db_1;
+
+CREATE TABLE test_db_1.tb_1 ( id int, f_1 varchar(255) DEFAULT NULL, f_2 bigint DEFAULT NULL, PRIMARY KEY (id) ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE test_db_1.tb_2 ( id int, f_1 decimal(10,2) DEFAULT NULL, PRIMARY KEY (id) ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
@@ -0,0 +1,19 @@
+#[cfg(test)]
+mod test {
+
+    use serial_test::serial;
+
+    use crate::test_runner::test_base::TestBase;
+
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_jsonl_test() {
+        TestBase::run_rdb_file_snapshot_test("mysql_to_file/snapshot/jsonl_test").await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_s3_jsonl_test() {
+        TestBase::run_rdb_file_snapshot_test("mysql_to_file/snapshot/s3_jsonl_test").await;
+    }
+}
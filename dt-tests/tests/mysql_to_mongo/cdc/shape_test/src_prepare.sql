@@ -0,0 +1,9 @@
+DROP DATABASE IF EXISTS test_db_1;
+
+CREATE DATABASE test_db_1;
+
+CREATE TABLE test_db_1.users(id int PRIMARY KEY, name varchar(64), age int);
+
+CREATE TABLE test_db_1.orders(id int PRIMARY KEY, user_id int, city varchar(64));
+
+CREATE TABLE test_db_1.order_items(id int PRIMARY KEY, order_id int, sku varchar(64), qty int);
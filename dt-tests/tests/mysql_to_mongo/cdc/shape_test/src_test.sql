@@ -0,0 +1,15 @@
+INSERT INTO test_db_1.users VALUES (1, 'a', 10), (2, 'b', 20);
+INSERT INTO test_db_1.orders VALUES (1, 1, 'hz'), (2, 2, 'sh');
+INSERT INTO test_db_1.order_items VALUES (1, 1, 's_1', 1), (2, 1, 's_2', 2), (3, 1, 's_3', 3), (4, 2, 's_4', 4);
+
+UPDATE test_db_1.users SET age = 11 WHERE id = 1;
+DELETE FROM test_db_1.users WHERE id = 2;
+UPDATE test_db_1.orders SET city = 'bj' WHERE id = 1;
+
+-- updated in place, keeps its position in the array
+UPDATE test_db_1.order_items SET qty = 10 WHERE id = 1;
+DELETE FROM test_db_1.order_items WHERE id = 3;
+-- moved to the end of the array of another order
+UPDATE test_db_1.order_items SET order_id = 1 WHERE id = 4;
+-- not referencing any order, not embedded
+INSERT INTO test_db_1.order_items VALUES (5, NULL, 's_5', 5);
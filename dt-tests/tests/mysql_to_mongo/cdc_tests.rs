@@ -0,0 +1,12 @@
+#[cfg(test)]
+mod test {
+    use serial_test::serial;
+
+    use crate::test_runner::test_base::TestBase;
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_shape_test() {
+        TestBase::run_rdb_mongo_cdc_test("mysql_to_mongo/cdc/shape_test", 3000, 3000).await;
+    }
+}
@@ -0,0 +1,7 @@
+DROP DATABASE IF EXISTS test_db_1;
+
+CREATE DATABASE test_db_1;
+
+CREATE TABLE test_db_1.one_pk ( id int, f_1 int DEFAULT NULL, f_2 varchar(255) NOT NULL DEFAULT 'abc', f_3 datetime DEFAULT '2024-01-01 00:00:00', f_4 text, f_5 blob, PRIMARY KEY (id) ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE test_db_1.no_pk ( id int, f_1 int DEFAULT NULL, f_2 varchar(255) NOT NULL DEFAULT 'abc', f_3 text, f_4 blob ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
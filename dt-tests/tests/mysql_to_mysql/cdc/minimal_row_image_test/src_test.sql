@@ -0,0 +1,33 @@
+-- sqls are executed in order by the same connection, so the session binlog_row_image applies
+SET SESSION binlog_row_image = 'MINIMAL';
+
+-- cols not specified are absent in MINIMAL row images, the target should use their defaults
+INSERT INTO test_db_1.one_pk (id, f_1) VALUES (1, 1);
+INSERT INTO test_db_1.one_pk (id, f_1, f_4, f_5) VALUES (2, 2, 'text_2', x'0123456789ABCDEF');
+INSERT INTO test_db_1.one_pk VALUES (3, 3, 'def', '2024-05-01 10:00:00', 'text_3', x'ABCDEF0123456789');
+INSERT INTO test_db_1.no_pk (id, f_1) VALUES (1, 1);
+INSERT INTO test_db_1.no_pk VALUES (2, 2, 'def', 'text_2', x'0123456789ABCDEF');
+
+-- only changed cols are in MINIMAL after images, other cols should not be touched
+UPDATE test_db_1.one_pk SET f_1 = 10 WHERE id = 1;
+UPDATE test_db_1.one_pk SET f_2 = 'xyz', f_5 = NULL WHERE id = 2;
+UPDATE test_db_1.one_pk SET id = 30 WHERE id = 3;
+UPDATE test_db_1.no_pk SET f_1 = 20 WHERE id = 2;
+
+DELETE FROM test_db_1.one_pk WHERE id = 1;
+DELETE FROM test_db_1.no_pk WHERE id = 2;
+
+SET SESSION binlog_row_image = 'NOBLOB';
+
+-- unchanged text / blob cols are absent in NOBLOB row images, they should not be touched
+INSERT INTO test_db_1.one_pk (id, f_1) VALUES (4, 4);
+INSERT INTO test_db_1.one_pk VALUES (5, 5, 'def', '2024-05-01 10:00:00', 'text_5', x'0123456789ABCDEF');
+UPDATE test_db_1.one_pk SET f_1 = 50 WHERE id = 5;
+UPDATE test_db_1.one_pk SET f_4 = 'text_5_1' WHERE id = 5;
+UPDATE test_db_1.one_pk SET f_2 = 'uvw' WHERE id = 2;
+UPDATE test_db_1.one_pk SET id = 40 WHERE id = 4;
+INSERT INTO test_db_1.no_pk VALUES (3, 3, 'def', 'text_3', x'ABCDEF0123456789');
+UPDATE test_db_1.no_pk SET f_1 = 30 WHERE id = 3;
+DELETE FROM test_db_1.one_pk WHERE id = 30;
+
+SET SESSION binlog_row_image = 'FULL';
@@ -0,0 +1,5 @@
+DROP DATABASE IF EXISTS test_db_1;
+
+CREATE DATABASE test_db_1;
+
+CREATE TABLE test_db_1.tb_1(id INT, value VARCHAR(64), _source_shard VARCHAR(64), PRIMARY KEY(id, _source_shard));
@@ -0,0 +1,2 @@
+-- executed before the task starts, synced by resuming from the position of shard_01
+INSERT INTO test_db_1.tb_1 VALUES(1, 'a_1'), (2, 'a_2');
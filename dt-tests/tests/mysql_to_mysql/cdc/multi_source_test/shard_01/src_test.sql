@@ -0,0 +1,7 @@
+INSERT INTO test_db_1.tb_1 VALUES(5, 'a_5');
+UPDATE test_db_1.tb_1 SET value = 'a_1_1' WHERE id = 1;
+DELETE FROM test_db_1.tb_1 WHERE id = 2;
+
+-- the same ddl from both sources is applied only once
+ALTER TABLE test_db_1.tb_1 ADD COLUMN c_1 INT;
+INSERT INTO test_db_1.tb_1 VALUES(6, 'a_6', 6);
@@ -0,0 +1,2 @@
+-- executed before the task starts, synced by resuming from the position of shard_02
+INSERT INTO test_db_1.tb_1 VALUES(1, 'b_1'), (3, 'b_3'), (4, 'b_4');
@@ -0,0 +1,6 @@
+UPDATE test_db_1.tb_1 SET value = 'b_3_1' WHERE id = 3;
+DELETE FROM test_db_1.tb_1 WHERE id = 1;
+
+-- the same ddl from both sources is applied only once
+ALTER TABLE test_db_1.tb_1 ADD COLUMN c_1 INT;
+INSERT INTO test_db_1.tb_1 VALUES(7, 'b_7', 7);
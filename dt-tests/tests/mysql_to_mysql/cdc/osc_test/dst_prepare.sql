@@ -0,0 +1,7 @@
+DROP DATABASE IF EXISTS dst_test_db_1;
+
+CREATE DATABASE dst_test_db_1;
+
+CREATE TABLE dst_test_db_1.dst_tb_1 ( id int, f_1 int DEFAULT NULL, PRIMARY KEY (id) );
+
+INSERT INTO dst_test_db_1.dst_tb_1 VALUES (1, 1), (2, 2);
@@ -0,0 +1,25 @@
+-- gh-ost: changelog table, ignored
+CREATE TABLE test_db_1._tb_1_ghc ( id bigint AUTO_INCREMENT, hint varchar(64), value varchar(255), PRIMARY KEY (id) );
+INSERT INTO test_db_1._tb_1_ghc (hint, value) VALUES ('state', 'GhostTableMigrated');
+
+-- gh-ost: ghost table with the new structure, routed as _dst_tb_1_gho
+CREATE TABLE test_db_1._tb_1_gho ( id int, f_1 int DEFAULT NULL, PRIMARY KEY (id) );
+ALTER TABLE test_db_1._tb_1_gho ADD COLUMN f_2 int DEFAULT NULL;
+
+-- gh-ost: row copy
+INSERT INTO test_db_1._tb_1_gho (id, f_1) SELECT id, f_1 FROM test_db_1.tb_1;
+
+-- gh-ost: changes of the original table applied to the ghost table
+INSERT INTO test_db_1.tb_1 VALUES (3, 3);
+INSERT INTO test_db_1._tb_1_gho (id, f_1) VALUES (3, 3);
+UPDATE test_db_1.tb_1 SET f_1 = 10 WHERE id = 1;
+UPDATE test_db_1._tb_1_gho SET f_1 = 10 WHERE id = 1;
+DELETE FROM test_db_1.tb_1 WHERE id = 2;
+DELETE FROM test_db_1._tb_1_gho WHERE id = 2;
+
+-- gh-ost: cut-over, the renamed-to _tb_1_del is routed as _dst_tb_1_del
+RENAME TABLE test_db_1.tb_1 TO test_db_1._tb_1_del, test_db_1._tb_1_gho TO test_db_1.tb_1;
+DROP TABLE test_db_1._tb_1_ghc;
+
+INSERT INTO test_db_1.tb_1 VALUES (4, 4, 4);
+UPDATE test_db_1.tb_1 SET f_2 = 1 WHERE id = 1;
@@ -0,0 +1,7 @@
+DROP DATABASE IF EXISTS test_db_1;
+
+CREATE DATABASE test_db_1;
+
+CREATE TABLE test_db_1.one_pk ( id int, f_1 int DEFAULT NULL, f_2 varchar(255) DEFAULT NULL, f_3 text, PRIMARY KEY (id) ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE test_db_1.no_pk ( id int, f_1 int DEFAULT NULL, f_2 varchar(255) DEFAULT NULL, f_3 text ) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
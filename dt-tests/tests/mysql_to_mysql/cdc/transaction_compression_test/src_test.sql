@@ -0,0 +1,10 @@
+-- each transaction is written as a zstd compressed TRANSACTION_PAYLOAD_EVENT
+INSERT INTO test_db_1.one_pk VALUES (1, 1, 'abc', 'text_1'), (2, 2, 'def', REPEAT('text_2', 1000)), (3, 3, NULL, NULL);
+INSERT INTO test_db_1.no_pk VALUES (1, 1, 'abc', 'text_1'), (2, 2, 'def', REPEAT('text_2', 1000));
+
+UPDATE test_db_1.one_pk SET f_1 = 10, f_3 = REPEAT('text_1', 1000) WHERE id = 1;
+UPDATE test_db_1.one_pk SET id = 30 WHERE id = 3;
+UPDATE test_db_1.no_pk SET f_2 = 'xyz' WHERE id = 2;
+
+DELETE FROM test_db_1.one_pk WHERE id = 2;
+DELETE FROM test_db_1.no_pk WHERE id = 1;
@@ -0,0 +1,32 @@
+-- two phase commit
+XA START 'xa_1';
+INSERT INTO test_db_1.tb_1 VALUES (1, 1), (2, 2);
+INSERT INTO test_db_1.tb_2 VALUES (1, 'a');
+XA END 'xa_1';
+XA PREPARE 'xa_1';
+XA COMMIT 'xa_1';
+
+-- one phase commit
+XA START 'xa_2';
+UPDATE test_db_1.tb_1 SET f_1 = 10 WHERE id = 1;
+INSERT INTO test_db_1.tb_2 VALUES (2, 'b');
+XA END 'xa_2';
+XA COMMIT 'xa_2' ONE PHASE;
+
+-- rolled back after prepared, rows should not be synced
+XA START 'xa_3', 'branch_1', 2;
+INSERT INTO test_db_1.tb_1 VALUES (3, 3);
+DELETE FROM test_db_1.tb_2 WHERE id = 1;
+XA END 'xa_3', 'branch_1', 2;
+XA PREPARE 'xa_3', 'branch_1', 2;
+XA ROLLBACK 'xa_3', 'branch_1', 2;
+
+-- normal transactions between XA PREPARE and XA COMMIT
+XA START 'xa_4';
+DELETE FROM test_db_1.tb_1 WHERE id = 2;
+INSERT INTO test_db_1.tb_2 VALUES (4, 'd');
+XA END 'xa_4';
+XA PREPARE 'xa_4';
+INSERT INTO test_db_1.tb_1 VALUES (5, 5);
+UPDATE test_db_1.tb_2 SET f_1 = 'aa' WHERE id = 1;
+XA COMMIT 'xa_4';
@@ -11,6 +11,12 @@ mod test {
         TestBase::run_cdc_test("mysql_to_mysql/cdc/basic_test", 3000, 4000).await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn cdc_snapshot_and_cdc_test() {
+        TestBase::run_cdc_test("mysql_to_mysql/cdc/snapshot_and_cdc_test", 3000, 4000).await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn cdc_uk_changed_test() {
@@ -41,6 +47,12 @@ mod test {
         TestBase::run_ddl_test("mysql_to_mysql/cdc/ddl_route_test", 3000, 5000).await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn cdc_osc_test() {
+        TestBase::run_ddl_test("mysql_to_mysql/cdc/osc_test", 3000, 5000).await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn cdc_ddl_meta_center_test() {
@@ -167,9 +179,44 @@ mod test {
         TestBase::run_cdc_test("mysql_to_mysql/cdc/gtid_test", 3000, 2000).await;
     }
 
+    /// binlog_row_image is set to MINIMAL / NOBLOB by sessions in src_test.sql,
+    /// sqls are executed in order by the same connection
+    #[tokio::test]
+    #[serial]
+    async fn cdc_minimal_row_image_test() {
+        TestBase::run_ddl_test("mysql_to_mysql/cdc/minimal_row_image_test", 3000, 5000).await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn cdc_dcl_test() {
         TestBase::run_dcl_test("mysql_to_mysql/cdc/dcl_test", 3000, 5000).await;
     }
+
+    /// sqls are executed in order by the same connection
+    #[tokio::test]
+    #[serial]
+    async fn cdc_xa_test() {
+        TestBase::run_ddl_test("mysql_to_mysql/cdc/xa_test", 3000, 5000).await;
+    }
+
+    /// sources: mysql_extractor_url and mysql_extractor_url_8_0
+    #[tokio::test]
+    #[serial]
+    async fn cdc_multi_source_test() {
+        TestBase::run_rdb_multi_source_cdc_test("mysql_to_mysql/cdc/multi_source_test", 3000, 3000)
+            .await;
+    }
+
+    /// need mysql config: binlog_transaction_compression=ON
+    #[tokio::test]
+    #[serial]
+    async fn cdc_transaction_compression_test() {
+        TestBase::run_cdc_test(
+            "mysql_to_mysql/cdc/transaction_compression_test",
+            3000,
+            2000,
+        )
+        .await;
+    }
 }
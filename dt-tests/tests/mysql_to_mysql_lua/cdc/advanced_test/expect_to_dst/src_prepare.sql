@@ -0,0 +1,11 @@
+DROP DATABASE IF EXISTS lua_test_advanced_expect;
+
+CREATE DATABASE lua_test_advanced_expect;
+
+CREATE TABLE `lua_test_advanced_expect`.`init_test` ( `id` int(11) NOT NULL, `f_1` int(11) DEFAULT NULL, PRIMARY KEY (`id`) ) ENGINE = InnoDB CHARSET = utf8;
+CREATE TABLE `lua_test_advanced_expect`.`fan_out_test` ( `id` int(11) NOT NULL, `f_1` int(11) DEFAULT NULL, PRIMARY KEY (`id`) ) ENGINE = InnoDB CHARSET = utf8;
+CREATE TABLE `lua_test_advanced_expect`.`fan_out_test_copy` ( `id` int(11) NOT NULL, `f_1` int(11) DEFAULT NULL, PRIMARY KEY (`id`) ) ENGINE = InnoDB CHARSET = utf8;
+CREATE TABLE `lua_test_advanced_expect`.`tb_script_test` ( `id` int(11) NOT NULL, `f_1` int(11) DEFAULT NULL, PRIMARY KEY (`id`) ) ENGINE = InnoDB CHARSET = utf8;
+CREATE TABLE `lua_test_advanced_expect`.`metadata_test` ( `id` int(11) NOT NULL, `f_1` int(11) DEFAULT NULL, `f_2` int(11) DEFAULT NULL, PRIMARY KEY (`id`) ) ENGINE = InnoDB CHARSET = utf8;
+
+CREATE TABLE `lua_test_advanced_expect`.`read_blob_test` ( `id` int(11) NOT NULL, `f_1` varbinary(255) DEFAULT NULL, `f_2` blob DEFAULT NULL, `f_3` int(11) DEFAULT NULL, PRIMARY KEY (`id`) ) ENGINE = InnoDB CHARSET = utf8mb4;
@@ -0,0 +1,24 @@
+INSERT INTO lua_test_advanced_expect.init_test VALUES(1,1);
+INSERT INTO lua_test_advanced_expect.init_test VALUES(2,2);
+INSERT INTO lua_test_advanced_expect.init_test VALUES(3,3);
+INSERT INTO lua_test_advanced_expect.fan_out_test VALUES(1,1),(2,2);
+INSERT INTO lua_test_advanced_expect.fan_out_test_copy VALUES(1,1),(2,2);
+INSERT INTO lua_test_advanced_expect.tb_script_test VALUES(1,100);
+INSERT INTO lua_test_advanced_expect.metadata_test VALUES(1,1,1);
+
+INSERT INTO lua_test_advanced_expect.read_blob_test VALUES(1, x'ABCDEF0123456789', x'ABCDEF0123456789', 8);
+
+UPDATE lua_test_advanced_expect.init_test SET f_1 = 2000;
+UPDATE lua_test_advanced_expect.fan_out_test SET f_1 = 2000;
+UPDATE lua_test_advanced_expect.fan_out_test_copy SET f_1 = 2000;
+UPDATE lua_test_advanced_expect.tb_script_test SET f_1 = 100;
+UPDATE lua_test_advanced_expect.metadata_test SET f_1 = 1, f_2 = 1;
+
+UPDATE lua_test_advanced_expect.read_blob_test SET f_1 = x'0123', f_3 = 2;
+
+DELETE FROM lua_test_advanced_expect.init_test;
+DELETE FROM lua_test_advanced_expect.fan_out_test;
+DELETE FROM lua_test_advanced_expect.fan_out_test_copy;
+DELETE FROM lua_test_advanced_expect.tb_script_test;
+DELETE FROM lua_test_advanced_expect.metadata_test;
+DELETE FROM lua_test_advanced_expect.read_blob_test;
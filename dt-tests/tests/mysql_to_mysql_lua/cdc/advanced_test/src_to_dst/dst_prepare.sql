@@ -0,0 +1,11 @@
+DROP DATABASE IF EXISTS lua_test_advanced;
+
+CREATE DATABASE lua_test_advanced;
+
+CREATE TABLE `lua_test_advanced`.`init_test` ( `id` int(11) NOT NULL, `f_1` int(11) DEFAULT NULL, PRIMARY KEY (`id`) ) ENGINE = InnoDB CHARSET = utf8;
+CREATE TABLE `lua_test_advanced`.`fan_out_test` ( `id` int(11) NOT NULL, `f_1` int(11) DEFAULT NULL, PRIMARY KEY (`id`) ) ENGINE = InnoDB CHARSET = utf8;
+CREATE TABLE `lua_test_advanced`.`fan_out_test_copy` ( `id` int(11) NOT NULL, `f_1` int(11) DEFAULT NULL, PRIMARY KEY (`id`) ) ENGINE = InnoDB CHARSET = utf8;
+CREATE TABLE `lua_test_advanced`.`tb_script_test` ( `id` int(11) NOT NULL, `f_1` int(11) DEFAULT NULL, PRIMARY KEY (`id`) ) ENGINE = InnoDB CHARSET = utf8;
+CREATE TABLE `lua_test_advanced`.`metadata_test` ( `id` int(11) NOT NULL, `f_1` int(11) DEFAULT NULL, `f_2` int(11) DEFAULT NULL, PRIMARY KEY (`id`) ) ENGINE = InnoDB CHARSET = utf8;
+
+CREATE TABLE `lua_test_advanced`.`read_blob_test` ( `id` int(11) NOT NULL, `f_1` varbinary(255) DEFAULT NULL, `f_2` blob DEFAULT NULL, `f_3` int(11) DEFAULT NULL, PRIMARY KEY (`id`) ) ENGINE = InnoDB CHARSET = utf8mb4;
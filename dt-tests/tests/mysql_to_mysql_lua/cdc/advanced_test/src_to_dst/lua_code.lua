@@ -0,0 +1,35 @@
+-- globals set in lua_init_code_file persist
+if (schema == "lua_test_advanced" and tb == "init_test" and row_type == "insert")
+then
+    insert_count = insert_count + 1
+    after.f_1 = insert_count
+end
+
+-- fan out each row to fan_out_test and fan_out_test_copy
+if (schema == "lua_test_advanced" and tb == "fan_out_test")
+then
+    rows = {
+        {},
+        { tb = "fan_out_test_copy" },
+    }
+end
+
+-- should never be executed since lua_tb_code_files matches this table
+if (schema == "lua_test_advanced" and tb == "tb_script_test" and row_type ~= "delete")
+then
+    after.f_1 = 200
+end
+
+-- position and timestamp
+if (schema == "lua_test_advanced" and tb == "metadata_test" and row_type ~= "delete")
+then
+    after.f_1 = (timestamp > 0) and 1 or 0
+    after.f_2 = string.find(position, "MysqlCdc", 1, true) and 1 or 0
+end
+
+-- read blob columns, changes to them are ignored
+if (schema == "lua_test_advanced" and tb == "read_blob_test" and row_type ~= "delete")
+then
+    after.f_3 = string.len(after.f_1)
+    after.f_1 = "changed"
+end
@@ -0,0 +1,2 @@
+-- executed once when the task starts, globals are kept across rows and batches
+insert_count = 0
@@ -0,0 +1,5 @@
+-- only for lua_test_advanced.tb_script_test, lua_code_file is not executed for it
+if (row_type ~= "delete")
+then
+    after.f_1 = 100
+end
@@ -0,0 +1,21 @@
+INSERT INTO lua_test_advanced.init_test VALUES(1,0);
+INSERT INTO lua_test_advanced.init_test VALUES(2,0);
+INSERT INTO lua_test_advanced.init_test VALUES(3,0);
+INSERT INTO lua_test_advanced.fan_out_test VALUES(1,1),(2,2);
+INSERT INTO lua_test_advanced.tb_script_test VALUES(1,1);
+INSERT INTO lua_test_advanced.metadata_test VALUES(1,0,0);
+
+INSERT INTO lua_test_advanced.read_blob_test VALUES(1, x'ABCDEF0123456789', x'ABCDEF0123456789', 0);
+
+UPDATE lua_test_advanced.init_test SET f_1 = 2000;
+UPDATE lua_test_advanced.fan_out_test SET f_1 = 2000;
+UPDATE lua_test_advanced.tb_script_test SET f_1 = 2000;
+UPDATE lua_test_advanced.metadata_test SET f_1 = 2000, f_2 = 2000;
+
+UPDATE lua_test_advanced.read_blob_test SET f_1 = x'0123';
+
+DELETE FROM lua_test_advanced.init_test;
+DELETE FROM lua_test_advanced.fan_out_test;
+DELETE FROM lua_test_advanced.tb_script_test;
+DELETE FROM lua_test_advanced.metadata_test;
+DELETE FROM lua_test_advanced.read_blob_test;
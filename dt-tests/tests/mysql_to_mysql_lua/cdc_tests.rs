@@ -16,4 +16,10 @@ mod test {
     async fn cdc_basic_test() {
         TestBase::run_cdc_lua_test("mysql_to_mysql_lua/cdc/basic_test", 3000, 2000).await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_advanced_test() {
+        TestBase::run_cdc_lua_test("mysql_to_mysql_lua/cdc/advanced_test", 3000, 2000).await;
+    }
 }
@@ -0,0 +1,12 @@
+DROP SCHEMA IF EXISTS partition_test_db CASCADE;
+CREATE SCHEMA partition_test_db;
+
+-- partitions are created as plain tables in target, they should be empty since
+-- changes of partitions are replicated as changes of the partition root
+CREATE TABLE partition_test_db.part_tb (id int, region varchar(16), value int, PRIMARY KEY(id, region));
+CREATE TABLE partition_test_db.part_tb_east (id int, region varchar(16), value int, PRIMARY KEY(id, region));
+CREATE TABLE partition_test_db.part_tb_west (id int, region varchar(16), value int, PRIMARY KEY(id, region));
+CREATE TABLE partition_test_db.part_tb_west_1 (id int, region varchar(16), value int, PRIMARY KEY(id, region));
+CREATE TABLE partition_test_db.part_tb_west_2 (id int, region varchar(16), value int, PRIMARY KEY(id, region));
+
+CREATE TABLE partition_test_db.tb_1 (id int, value int, PRIMARY KEY(id));
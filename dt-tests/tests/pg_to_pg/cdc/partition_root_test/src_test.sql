@@ -0,0 +1,13 @@
+INSERT INTO partition_test_db.part_tb VALUES (1, 'east', 1), (2, 'east', 2), (1, 'west', 1), (100, 'west', 100);
+INSERT INTO partition_test_db.part_tb_west_2 VALUES (200, 'west', 200);
+INSERT INTO partition_test_db.tb_1 VALUES (1, 1), (2, 2);
+
+UPDATE partition_test_db.part_tb SET value = 10 WHERE id = 1;
+-- moved across partitions
+UPDATE partition_test_db.part_tb SET region = 'west' WHERE id = 2 AND region = 'east';
+UPDATE partition_test_db.part_tb_west_1 SET value = 20 WHERE id = 2;
+UPDATE partition_test_db.tb_1 SET value = 10 WHERE id = 1;
+
+DELETE FROM partition_test_db.part_tb WHERE id = 100;
+DELETE FROM partition_test_db.part_tb_east;
+DELETE FROM partition_test_db.tb_1 WHERE id = 2;
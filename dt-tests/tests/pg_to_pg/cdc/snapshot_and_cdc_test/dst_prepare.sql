@@ -0,0 +1,5 @@
+DROP SCHEMA IF EXISTS snapshot_and_cdc_test_db CASCADE;
+CREATE SCHEMA snapshot_and_cdc_test_db;
+
+CREATE TABLE snapshot_and_cdc_test_db.tb_1 (id int, value int, PRIMARY KEY(id));
+CREATE TABLE snapshot_and_cdc_test_db.tb_2 (id int, name varchar(255), created_at timestamp, PRIMARY KEY(id));
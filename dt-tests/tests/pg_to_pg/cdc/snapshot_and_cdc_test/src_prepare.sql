@@ -0,0 +1,9 @@
+DROP SCHEMA IF EXISTS snapshot_and_cdc_test_db CASCADE;
+CREATE SCHEMA snapshot_and_cdc_test_db;
+
+CREATE TABLE snapshot_and_cdc_test_db.tb_1 (id int, value int, PRIMARY KEY(id));
+CREATE TABLE snapshot_and_cdc_test_db.tb_2 (id int, name varchar(255), created_at timestamp, PRIMARY KEY(id));
+
+-- rows before the task starts, synced by snapshot
+INSERT INTO snapshot_and_cdc_test_db.tb_1 VALUES (1, 1), (2, 2), (3, 3), (4, 4), (5, 5);
+INSERT INTO snapshot_and_cdc_test_db.tb_2 VALUES (1, 'a', '2024-01-01 00:00:00.123456'), (2, 'b', NULL), (3, NULL, '2024-01-03 00:00:00');
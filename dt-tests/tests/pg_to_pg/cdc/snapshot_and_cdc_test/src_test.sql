@@ -0,0 +1,11 @@
+-- changes after the snapshot, synced by cdc
+INSERT INTO snapshot_and_cdc_test_db.tb_1 VALUES (6, 6), (7, 7);
+INSERT INTO snapshot_and_cdc_test_db.tb_2 VALUES (4, 'd', '2024-01-04 00:00:00');
+
+UPDATE snapshot_and_cdc_test_db.tb_1 SET value = 10 WHERE id = 1;
+UPDATE snapshot_and_cdc_test_db.tb_1 SET value = 60 WHERE id = 6;
+UPDATE snapshot_and_cdc_test_db.tb_2 SET name = 'c' WHERE id = 3;
+
+DELETE FROM snapshot_and_cdc_test_db.tb_1 WHERE id = 2;
+DELETE FROM snapshot_and_cdc_test_db.tb_1 WHERE id = 7;
+DELETE FROM snapshot_and_cdc_test_db.tb_2 WHERE id = 1;
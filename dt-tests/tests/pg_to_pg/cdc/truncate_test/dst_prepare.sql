@@ -0,0 +1,11 @@
+DROP SCHEMA IF EXISTS truncate_test_db CASCADE;
+CREATE SCHEMA truncate_test_db;
+
+CREATE TABLE truncate_test_db.tb_1 (id int, value int, PRIMARY KEY(id));
+CREATE TABLE truncate_test_db.tb_2 (id serial, value int, PRIMARY KEY(id));
+CREATE TABLE truncate_test_db.tb_3 (id int, tb_2_id int REFERENCES truncate_test_db.tb_2(id), PRIMARY KEY(id));
+CREATE TABLE truncate_test_db.tb_4 (id int, value int, PRIMARY KEY(id));
+
+-- rows only in target, should be removed by truncate
+INSERT INTO truncate_test_db.tb_1 VALUES (100, 100);
+INSERT INTO truncate_test_db.tb_4 VALUES (100, 100);
@@ -0,0 +1,22 @@
+INSERT INTO truncate_test_db.tb_1 VALUES (1, 1), (2, 2);
+INSERT INTO truncate_test_db.tb_2 (value) VALUES (1), (2);
+INSERT INTO truncate_test_db.tb_3 VALUES (1, 1), (2, 2);
+INSERT INTO truncate_test_db.tb_4 VALUES (1, 1), (2, 2);
+
+-- single table
+TRUNCATE TABLE truncate_test_db.tb_1;
+INSERT INTO truncate_test_db.tb_1 VALUES (3, 3);
+
+-- RESTART IDENTITY CASCADE, tb_3 is truncated together
+TRUNCATE TABLE truncate_test_db.tb_2 RESTART IDENTITY CASCADE;
+INSERT INTO truncate_test_db.tb_2 (value) VALUES (3);
+INSERT INTO truncate_test_db.tb_3 VALUES (3, 1);
+
+-- multiple relations
+TRUNCATE TABLE truncate_test_db.tb_1, truncate_test_db.tb_4;
+INSERT INTO truncate_test_db.tb_4 VALUES (4, 4);
+
+-- logical decoding messages, transactional and non-transactional
+SELECT pg_logical_emit_message(true, 'truncate_test', 'transactional message');
+SELECT pg_logical_emit_message(false, 'truncate_test', 'non-transactional message');
+INSERT INTO truncate_test_db.tb_1 VALUES (5, 5);
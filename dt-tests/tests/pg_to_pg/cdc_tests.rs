@@ -11,6 +11,12 @@ mod test {
         TestBase::run_cdc_test("pg_to_pg/cdc/basic_test", 3000, 9000).await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn cdc_snapshot_and_cdc_test() {
+        TestBase::run_cdc_test("pg_to_pg/cdc/snapshot_and_cdc_test", 3000, 9000).await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn cdc_postgis_test() {
@@ -71,6 +77,18 @@ mod test {
         TestBase::run_ddl_test("pg_to_pg/cdc/ddl_test", 3000, 5000).await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn cdc_truncate_test() {
+        TestBase::run_cdc_test("pg_to_pg/cdc/truncate_test", 3000, 5000).await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn cdc_partition_root_test() {
+        TestBase::run_cdc_test("pg_to_pg/cdc/partition_root_test", 3000, 5000).await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn cycle_basic_test() {
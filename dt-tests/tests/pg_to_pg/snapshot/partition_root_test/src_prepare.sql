@@ -0,0 +1,10 @@
+DROP SCHEMA IF EXISTS partition_test_db CASCADE;
+CREATE SCHEMA partition_test_db;
+
+CREATE TABLE partition_test_db.part_tb (id int, region varchar(16), value int, PRIMARY KEY(id, region)) PARTITION BY LIST (region);
+CREATE TABLE partition_test_db.part_tb_east PARTITION OF partition_test_db.part_tb FOR VALUES IN ('east');
+CREATE TABLE partition_test_db.part_tb_west PARTITION OF partition_test_db.part_tb FOR VALUES IN ('west') PARTITION BY RANGE (id);
+CREATE TABLE partition_test_db.part_tb_west_1 PARTITION OF partition_test_db.part_tb_west FOR VALUES FROM (MINVALUE) TO (100);
+CREATE TABLE partition_test_db.part_tb_west_2 PARTITION OF partition_test_db.part_tb_west FOR VALUES FROM (100) TO (MAXVALUE);
+
+CREATE TABLE partition_test_db.tb_1 (id int, value int, PRIMARY KEY(id));
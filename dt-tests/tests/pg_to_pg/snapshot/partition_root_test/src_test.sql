@@ -0,0 +1,4 @@
+INSERT INTO partition_test_db.part_tb VALUES (1, 'east', 1), (2, 'east', 2), (1, 'west', 1), (100, 'west', 100);
+INSERT INTO partition_test_db.part_tb_west_2 VALUES (200, 'west', 200);
+
+INSERT INTO partition_test_db.tb_1 VALUES (1, 1), (2, 2);
@@ -92,4 +92,23 @@ mod test {
     async fn snapshot_route_test() {
         TestBase::run_snapshot_test("pg_to_pg/snapshot/route_test").await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_partition_root_test() {
+        let mut dst_expected_counts = HashMap::new();
+        dst_expected_counts.insert("partition_test_db.part_tb", 5);
+        dst_expected_counts.insert("partition_test_db.part_tb_east", 0);
+        dst_expected_counts.insert("partition_test_db.part_tb_west", 0);
+        dst_expected_counts.insert("partition_test_db.part_tb_west_1", 0);
+        dst_expected_counts.insert("partition_test_db.part_tb_west_2", 0);
+        dst_expected_counts.insert("partition_test_db.tb_1", 2);
+
+        TestBase::run_snapshot_test_and_check_dst_count(
+            "pg_to_pg/snapshot/partition_root_test",
+            &DbType::Pg,
+            dst_expected_counts,
+        )
+        .await;
+    }
 }
@@ -0,0 +1,2 @@
+flushall
+CONFIG SET notify-keyspace-events KEA
\ No newline at end of file
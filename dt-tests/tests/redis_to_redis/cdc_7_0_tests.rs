@@ -40,6 +40,24 @@ mod test {
         TestBase::run_redis_cdc_test("redis_to_redis/cdc/7_0/to_cluster_test", 2000, 3000).await;
     }
 
+    #[tokio::test]
+    #[serial]
+    async fn cdc_from_cluster_test() {
+        TestBase::run_redis_cdc_test("redis_to_redis/cdc/7_0/from_cluster_test", 5000, 3000).await;
+    }
+
+    // sync incremental data by keyspace notifications instead of PSYNC
+    #[tokio::test]
+    #[serial]
+    async fn cdc_scan_keyspace_events_test() {
+        TestBase::run_redis_cdc_test(
+            "redis_to_redis/cdc/7_0/scan_keyspace_events_test",
+            2000,
+            3000,
+        )
+        .await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn cdc_heartbeat_test() {
@@ -0,0 +1 @@
+flushall
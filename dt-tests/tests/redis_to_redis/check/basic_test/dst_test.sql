@@ -0,0 +1,12 @@
+-- same
+SET str_same val_1
+HSET hash_same f_2 v_2 f_1 v_1
+SADD set_same m_3 m_2 m_1
+ZADD zset_same 2.5 m_2 1 m_1
+
+-- diff
+SET str_diff val_2
+HSET hash_diff f_1 v_1 f_2 v_3
+RPUSH list_diff v_1 v_3 v_2
+ZADD zset_diff 1 m_1 3 m_2
+LPUSH type_diff v_1
@@ -0,0 +1,15 @@
+-- same
+SET str_same val_1
+HSET hash_same f_1 v_1 f_2 v_2
+SADD set_same m_1 m_2 m_3
+ZADD zset_same 1 m_1 2.5 m_2
+
+-- miss
+SET str_miss val_1
+
+-- diff
+SET str_diff val_1
+HSET hash_diff f_1 v_1 f_2 v_2
+RPUSH list_diff v_1 v_2 v_3
+ZADD zset_diff 1 m_1 2.5 m_2
+SET type_diff val_1
@@ -0,0 +1,12 @@
+#[cfg(test)]
+mod test {
+    use serial_test::serial;
+
+    use crate::test_runner::test_base::TestBase;
+
+    #[tokio::test]
+    #[serial]
+    async fn check_basic_test() {
+        TestBase::run_redis_check_test("redis_to_redis/check/basic_test").await;
+    }
+}
@@ -0,0 +1,15 @@
+-- same data as the aof file(s), to compare with dst
+SELECT 0
+SET set_key_1 val_1
+HSET hset_key_1 field_1 val_1 field_2 val_2
+RPUSH list_key_1 val_1 val_2 val_3
+SELECT 0
+SET set_key_1 val_1_new
+HDEL hset_key_1 field_2
+SELECT 1
+SADD sadd_key_1 val_1 val_2
+SELECT 1
+SREM sadd_key_1 val_1
+ZADD zadd_key_1 1 val_1 2.5 val_2
+SELECT 0
+RPOP list_key_1
@@ -0,0 +1,14 @@
+-- same data as the aof file(s), to compare with dst
+SELECT 0
+SET set_key_1 val_1
+SET set_key_2 val_2
+HSET hset_key_1 field_1 val_1 field_2 val_2
+RPUSH list_key_1 val_1 val_2 val_3
+LPOP list_key_1
+SADD sadd_key_1 val_1 val_2
+INCR incr_key_1
+INCR incr_key_1
+SELECT 1
+ZADD zadd_key_1 1 val_1 2.5 val_2
+DEL set_key_1
+SET set_key_3 val_3
@@ -83,6 +83,27 @@ mod test {
         TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/7_0/filter_db_test").await;
     }
 
+    // migrate data by SCAN + DUMP instead of PSYNC
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_scan_test() {
+        TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/7_0/scan_test").await;
+    }
+
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_aof_file_test() {
+        TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/7_0/aof_file_test").await;
+    }
+
+    // redis 7.0+ multi-part aof: base + incr files listed in manifest
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_aof_file_multi_part_test() {
+        TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/7_0/aof_file_multi_part_test")
+            .await;
+    }
+
     #[tokio::test]
     #[serial]
     async fn snapshot_statistic_big_key_rdb_test() {
@@ -112,4 +133,10 @@ mod test {
     async fn snapshot_to_cluster_test() {
         TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/7_0/to_cluster_test").await;
     }
+
+    #[tokio::test]
+    #[serial]
+    async fn snapshot_from_cluster_test() {
+        TestBase::run_redis_snapshot_test("redis_to_redis/snapshot/7_0/from_cluster_test").await;
+    }
 }
@@ -1,4 +1,5 @@
 use std::{
+    collections::HashMap,
     env,
     fs::{self, File},
     path::{Path, PathBuf},
@@ -54,7 +55,10 @@ impl TestConfigUtil {
         result_dir
     }
 
-    pub fn update_task_config_from_env(src_task_config_file: &str, dst_task_config_file: &str) {
+    pub fn update_task_config_from_env(
+        src_task_config_file: &str,
+        dst_task_config_file: &str,
+    ) -> anyhow::Result<()> {
         let env_local_file = TestConfigUtil::get_absolute_path(".env.local");
         let env_file = TestConfigUtil::get_absolute_path(".env");
 
@@ -65,17 +69,19 @@ impl TestConfigUtil {
         dotenv::from_path(&env_file).unwrap();
 
         let mut update_configs = Vec::new();
-        let ini = IniLoader::new(src_task_config_file).ini;
+        let ini = IniLoader::load(src_task_config_file)?.ini;
         for (section, kvs) in ini.get_map().unwrap() {
             for (k, v) in kvs.iter() {
-                if v.is_none() {
+                let Some(v) = v else {
                     continue;
-                }
+                };
+                // placeholders may also be embedded, eg: sources=json:[{"url":"{mysql_extractor_url}"}]
+                let mut new_v = v.clone();
                 for (env_k, env_v) in env::vars() {
-                    if *v.as_ref().unwrap() == format!("{{{}}}", env_k) {
-                        update_configs.push((section.clone(), k.clone(), env_v.clone()));
-                        break;
-                    }
+                    new_v = new_v.replace(&format!("{{{}}}", env_k), &env_v);
+                }
+                if new_v != *v {
+                    update_configs.push((section.clone(), k.clone(), new_v));
                 }
             }
         }
@@ -84,15 +90,15 @@ impl TestConfigUtil {
             src_task_config_file,
             dst_task_config_file,
             &update_configs,
-        );
+        )
     }
 
     pub fn update_file_paths_in_task_config(
         src_task_config_file: &str,
         dst_task_config_file: &str,
         project_root: &str,
-    ) {
-        let config = TaskConfig::new(src_task_config_file).unwrap();
+    ) -> anyhow::Result<()> {
+        let config = TaskConfig::new(src_task_config_file)?;
         let mut update_configs = Vec::new();
 
         // runtime/log4rs_file
@@ -132,7 +138,8 @@ impl TestConfigUtil {
                 ));
             }
 
-            ExtractorConfig::RedisSnapshotFile { file_path } => {
+            ExtractorConfig::RedisSnapshotFile { file_path }
+            | ExtractorConfig::RedisAofFile { file_path } => {
                 let file_path = format!("{}/{}", project_root, file_path);
                 update_configs.push((EXTRACTOR.to_string(), "file_path".to_string(), file_path));
             }
@@ -144,7 +151,8 @@ impl TestConfigUtil {
             // sinker/check_log_dir
             SinkerConfig::MysqlCheck { check_log_dir, .. }
             | SinkerConfig::PgCheck { check_log_dir, .. }
-            | SinkerConfig::MongoCheck { check_log_dir, .. } => {
+            | SinkerConfig::MongoCheck { check_log_dir, .. }
+            | SinkerConfig::RedisCheck { check_log_dir, .. } => {
                 let sinker_check_log_dir = if !check_log_dir.is_empty() {
                     format!("{}/{}", project_root, check_log_dir)
                 } else {
@@ -183,21 +191,47 @@ impl TestConfigUtil {
                 "lua_code_file".to_string(),
                 lua_code_file,
             ));
+
+            if !processor.lua_init_code_file.is_empty() {
+                let lua_init_code_file =
+                    format!("{}/{}", project_root, processor.lua_init_code_file);
+                update_configs.push((
+                    "processor".to_string(),
+                    "lua_init_code_file".to_string(),
+                    lua_init_code_file,
+                ));
+            }
+
+            if !processor.lua_tb_code_files.is_empty() {
+                let mut items: Vec<HashMap<String, String>> =
+                    serde_json::from_str(processor.lua_tb_code_files.trim_start_matches("json:"))
+                        .unwrap();
+                for item in items.iter_mut() {
+                    if let Some(file) = item.get_mut("lua_code_file") {
+                        *file = format!("{}/{}", project_root, file);
+                    }
+                }
+                update_configs.push((
+                    "processor".to_string(),
+                    "lua_tb_code_files".to_string(),
+                    format!("json:{}", serde_json::to_string(&items).unwrap()),
+                ));
+            }
         }
 
         TestConfigUtil::update_task_config(
             src_task_config_file,
             dst_task_config_file,
             &update_configs,
-        );
+        )
     }
 
     pub fn update_task_config(
         src_task_config_file: &str,
         dst_task_config_file: &str,
         config: &[(String, String, String)],
-    ) {
-        let mut ini = IniLoader::new(src_task_config_file).ini;
+    ) -> anyhow::Result<()> {
+        let mut ini = IniLoader::load(src_task_config_file)?.ini;
         for (section, key, value) in config.iter() {
             ini.set(section, key, Some(value.to_string()));
         }
@@ -209,17 +243,18 @@ impl TestConfigUtil {
             .set_len(0)
             .unwrap();
         ini.write(dst_task_config_file).unwrap();
+        Ok(())
     }
 
     pub fn update_task_config_2<'a>(
         src_task_config_file: &str,
         dst_task_config_file: &str,
         config: &[(&'a str, &'a str, &'a str)],
-    ) {
+    ) -> anyhow::Result<()> {
         let config: Vec<(String, String, String)> = config
             .iter()
             .map(|i| (i.0.to_string(), i.1.to_string(), i.2.to_string()))
             .collect();
-        Self::update_task_config(src_task_config_file, dst_task_config_file, &config);
+        Self::update_task_config(src_task_config_file, dst_task_config_file, &config)
     }
 }
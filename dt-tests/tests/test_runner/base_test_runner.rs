@@ -31,9 +31,9 @@ impl BaseTestRunner {
         let test_dir = TestConfigUtil::get_absolute_path(relative_test_dir);
 
         let dst_task_config_file =
-            Self::generate_tmp_task_config_file(relative_test_dir, "task_config.ini");
+            Self::generate_tmp_task_config_file(relative_test_dir, "task_config.ini")?;
         let dst_struct_task_config_file =
-            Self::generate_tmp_task_config_file(relative_test_dir, "struct_task_config.ini");
+            Self::generate_tmp_task_config_file(relative_test_dir, "struct_task_config.ini")?;
 
         let (
             src_test_sqls,
@@ -62,13 +62,13 @@ impl BaseTestRunner {
     pub fn generate_tmp_task_config_file(
         relative_test_dir: &str,
         task_config_file: &str,
-    ) -> String {
+    ) -> anyhow::Result<String> {
         let project_root = TestConfigUtil::get_project_root();
         let test_dir = TestConfigUtil::get_absolute_path(relative_test_dir);
         let src_task_config_file = format!("{}/{}", test_dir, task_config_file);
 
         if !Self::check_path_exists(&src_task_config_file) {
-            return String::new();
+            return Ok(String::new());
         }
 
         let tmp_dir = format!("{}/tmp/{}", project_root, relative_test_dir);
@@ -79,11 +79,11 @@ impl BaseTestRunner {
             &src_task_config_file,
             &dst_task_config_file,
             &project_root,
-        );
+        )?;
 
         // update extractor / sinker urls from .env
-        TestConfigUtil::update_task_config_from_env(&dst_task_config_file, &dst_task_config_file);
-        dst_task_config_file
+        TestConfigUtil::update_task_config_from_env(&dst_task_config_file, &dst_task_config_file)?;
+        Ok(dst_task_config_file)
     }
 
     pub fn get_config(&self) -> TaskConfig {
@@ -176,7 +176,7 @@ impl BaseTestRunner {
         )
     }
 
-    fn load_sql_file(sql_file: &str) -> Vec<String> {
+    pub fn load_sql_file(sql_file: &str) -> Vec<String> {
         /* sqls content E.g. :
         -- this is comment
 
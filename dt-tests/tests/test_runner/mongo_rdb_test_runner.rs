@@ -0,0 +1,95 @@
+use dt_common::utils::time_util::TimeUtil;
+use serde_json::{Map, Value};
+
+use super::{
+    base_test_runner::BaseTestRunner,
+    mongo_test_runner::MongoTestRunner,
+    rdb_test_runner::{RdbTestRunner, DST},
+};
+
+/// This is used for test cases: mongo(src) -> mysql / pg(dst) with [flattener].
+/// After src_test.sql is executed, dst tables are compared with expect_data.jsonl in test dir,
+/// each line is a table: {"db":"test_db_1","tb":"orders","rows":[{"_id":"o_1","name":"a"}]},
+/// column values are compared as strings and the order of rows is ignored.
+pub struct MongoRdbTestRunner {
+    src_runner: MongoTestRunner,
+    dst_runner: RdbTestRunner,
+}
+
+impl MongoRdbTestRunner {
+    pub async fn new(relative_test_dir: &str) -> anyhow::Result<Self> {
+        let src_runner = MongoTestRunner::new(relative_test_dir).await?;
+        let dst_runner = RdbTestRunner::new(relative_test_dir).await?;
+        Ok(Self {
+            src_runner,
+            dst_runner,
+        })
+    }
+
+    pub async fn close(&self) -> anyhow::Result<()> {
+        self.dst_runner.close().await
+    }
+
+    pub async fn run_cdc_test(&self, start_millis: u64, parse_millis: u64) -> anyhow::Result<()> {
+        let base = &self.dst_runner.base;
+        self.src_runner
+            .execute_src_sqls(&base.src_prepare_sqls)
+            .await?;
+        self.dst_runner
+            .execute_dst_sqls(&base.dst_prepare_sqls)
+            .await?;
+
+        let task = base.spawn_task().await?;
+        TimeUtil::sleep_millis(start_millis).await;
+
+        self.src_runner
+            .execute_src_sqls(&base.src_test_sqls)
+            .await?;
+        TimeUtil::sleep_millis(parse_millis).await;
+        self.compare_expect_data().await?;
+
+        base.abort_task(&task).await
+    }
+
+    async fn compare_expect_data(&self) -> anyhow::Result<()> {
+        let expect_file = format!("{}/expect_data.jsonl", self.dst_runner.base.test_dir);
+        for line in BaseTestRunner::load_file(&expect_file) {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let expect: Value = serde_json::from_str(&line)?;
+            let db_tb = (
+                expect["db"].as_str().unwrap().to_string(),
+                expect["tb"].as_str().unwrap().to_string(),
+            );
+            let mut expect_rows: Vec<String> = expect["rows"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|row| row.to_string())
+                .collect();
+
+            let mut dst_rows = Vec::new();
+            for row_data in self.dst_runner.fetch_data(&db_tb, DST).await? {
+                let mut row = Map::new();
+                for (col, col_value) in row_data.after.unwrap() {
+                    let value = col_value
+                        .to_option_string()
+                        .map_or(Value::Null, Value::String);
+                    row.insert(col, value);
+                }
+                dst_rows.push(Value::Object(row).to_string());
+            }
+
+            expect_rows.sort();
+            dst_rows.sort();
+            println!(
+                "compare tb data, db: {}, tb: {}, expect_rows: {:?}, dst_rows: {:?}",
+                db_tb.0, db_tb.1, expect_rows, dst_rows
+            );
+            assert_eq!(expect_rows, dst_rows);
+        }
+        Ok(())
+    }
+}
@@ -93,7 +93,7 @@ impl MongoTestRunner {
             &self.base.task_config_file,
             &self.base.task_config_file,
             &config,
-        );
+        )?;
 
         // execute sqls in src before cdc task starts
         let src_mongo_client = self.src_mongo_client.as_ref().unwrap();
@@ -173,6 +173,31 @@ impl MongoTestRunner {
         self.base.abort_task(&task).await
     }
 
+    pub async fn run_cdc_ddl_test(
+        &self,
+        start_millis: u64,
+        parse_millis: u64,
+    ) -> anyhow::Result<()> {
+        self.execute_prepare_sqls().await?;
+
+        let task = self.base.spawn_task().await?;
+        TimeUtil::sleep_millis(start_millis).await;
+
+        let src_mongo_client = self.src_mongo_client.as_ref().unwrap();
+        let src_sqls = Self::slice_sqls_by_db(&self.base.src_test_sqls);
+        for (db, sqls) in src_sqls.iter() {
+            self.execute_ddls(src_mongo_client, db, sqls).await?;
+            self.execute_dmls(src_mongo_client, db, sqls).await?;
+        }
+        TimeUtil::sleep_millis(parse_millis).await;
+
+        for (db, _) in src_sqls.iter() {
+            self.compare_db_struct(db).await;
+            self.compare_db_data(db).await;
+        }
+        self.base.abort_task(&task).await
+    }
+
     pub async fn run_snapshot_test(&self, compare_data: bool) -> anyhow::Result<()> {
         self.execute_prepare_sqls().await?;
         self.execute_test_sqls().await?;
@@ -251,14 +276,38 @@ impl MongoTestRunner {
         Ok(())
     }
 
+    pub async fn execute_src_sqls(&self, sqls: &[String]) -> anyhow::Result<()> {
+        let src_mongo_client = self.src_mongo_client.as_ref().unwrap();
+        for (db, sqls) in Self::slice_sqls_by_db(sqls).iter() {
+            self.execute_ddls(src_mongo_client, db, sqls).await?;
+            self.execute_dmls(src_mongo_client, db, sqls).await?;
+        }
+        Ok(())
+    }
+
+    pub async fn execute_dst_sqls(&self, sqls: &[String]) -> anyhow::Result<()> {
+        let dst_mongo_client = self.dst_mongo_client.as_ref().unwrap();
+        for (db, sqls) in Self::slice_sqls_by_db(sqls).iter() {
+            self.execute_ddls(dst_mongo_client, db, sqls).await?;
+            self.execute_dmls(dst_mongo_client, db, sqls).await?;
+        }
+        Ok(())
+    }
+
     async fn execute_ddls(&self, client: &Client, db: &str, sqls: &[String]) -> anyhow::Result<()> {
         for sql in sqls.iter() {
             if sql.contains("dropDatabase") {
                 self.execute_drop_database(client, db).await.unwrap();
+            } else if sql.contains("dropIndex") {
+                self.execute_drop_index(client, db, sql).await.unwrap();
             } else if sql.contains("drop") {
                 self.execute_drop(client, db, sql).await.unwrap();
             } else if sql.contains("createCollection") {
                 self.execute_create(client, db, sql).await.unwrap();
+            } else if sql.contains("createIndex") {
+                self.execute_create_index(client, db, sql).await.unwrap();
+            } else if sql.contains("renameCollection") {
+                self.execute_rename(client, db, sql).await.unwrap();
             }
         }
         Ok(())
@@ -317,6 +366,64 @@ impl MongoTestRunner {
         Ok(())
     }
 
+    async fn execute_create_index(
+        &self,
+        client: &Client,
+        db: &str,
+        sql: &str,
+    ) -> anyhow::Result<()> {
+        // example: db.tb_1.createIndex({ "name": 1 }, { "name": "idx_name" })
+        let re = Regex::new(r"db.(\w+).createIndex\((\{[^}]*\}),([^)]+)\)").unwrap();
+        let cap = re.captures(sql).unwrap();
+        let tb = cap.get(1).unwrap().as_str();
+        let keys: Document = serde_json::from_str(cap.get(2).unwrap().as_str()).unwrap();
+        let mut index: Document = serde_json::from_str(cap.get(3).unwrap().as_str()).unwrap();
+        index.insert("key", keys);
+
+        client
+            .database(db)
+            .run_command(doc! {"createIndexes": tb, "indexes": [index]}, None)
+            .await
+            .unwrap();
+        Ok(())
+    }
+
+    async fn execute_drop_index(&self, client: &Client, db: &str, sql: &str) -> anyhow::Result<()> {
+        let re = Regex::new(r#"db.(\w+).dropIndex\("(\w+)"\)"#).unwrap();
+        let cap = re.captures(sql).unwrap();
+        let tb = cap.get(1).unwrap().as_str();
+        let index_name = cap.get(2).unwrap().as_str();
+
+        client
+            .database(db)
+            .collection::<Document>(tb)
+            .drop_index(index_name, None)
+            .await
+            .unwrap();
+        Ok(())
+    }
+
+    async fn execute_rename(&self, client: &Client, db: &str, sql: &str) -> anyhow::Result<()> {
+        // example: db.tb_1.renameCollection("tb_1_new") / db.tb_1.renameCollection("tb_1_new", true)
+        let re = Regex::new(r#"db.(\w+).renameCollection\("(\w+)"(,[ ]*true)?\)"#).unwrap();
+        let cap = re.captures(sql).unwrap();
+        let tb = cap.get(1).unwrap().as_str();
+        let new_tb = cap.get(2).unwrap().as_str();
+        let drop_target = cap.get(3).is_some();
+
+        let command = doc! {
+            "renameCollection": format!("{}.{}", db, tb),
+            "to": format!("{}.{}", db, new_tb),
+            "dropTarget": drop_target,
+        };
+        client
+            .database("admin")
+            .run_command(command, None)
+            .await
+            .unwrap();
+        Ok(())
+    }
+
     async fn execute_insert(&self, client: &Client, db: &str, sql: &str) -> anyhow::Result<()> {
         // example: db.tb_2.insertOne({ "name": "a", "age": "1" })
         let re = Regex::new(r"db.(\w+).insert(One|Many)\(([\w\W]+)\)").unwrap();
@@ -376,6 +483,32 @@ impl MongoTestRunner {
         }
     }
 
+    async fn compare_db_struct(&self, db: &str) {
+        let mut src_tbs = self.list_tb(db, SRC).await;
+        let dst_db = self.router.get_schema_map(db);
+        let mut dst_tbs = self.list_tb(dst_db, DST).await;
+        src_tbs.sort();
+        dst_tbs.sort();
+        println!(
+            "compare db struct, db: {}, src_tbs: {:?}, dst_tbs: {:?}",
+            db, src_tbs, dst_tbs
+        );
+        assert_eq!(src_tbs, dst_tbs);
+
+        for tb in src_tbs.iter() {
+            let mut src_indexes = self.list_index(db, tb, SRC).await;
+            let (dst_db, dst_tb) = self.router.get_tb_map(db, tb);
+            let mut dst_indexes = self.list_index(dst_db, dst_tb, DST).await;
+            src_indexes.sort();
+            dst_indexes.sort();
+            println!(
+                "compare tb indexes, db: {}, tb: {}, src_indexes: {:?}, dst_indexes: {:?}",
+                db, tb, src_indexes, dst_indexes
+            );
+            assert_eq!(src_indexes, dst_indexes);
+        }
+    }
+
     async fn compare_tb_data(&self, db: &str, tb: &str) {
         println!("compare tb data, db: {}, tb: {}", db, tb);
         let src_data = self.fetch_data(db, tb, SRC).await;
@@ -408,6 +541,20 @@ impl MongoTestRunner {
             .unwrap()
     }
 
+    pub async fn list_index(&self, db: &str, tb: &str, from: &str) -> Vec<String> {
+        let client = if from == SRC {
+            self.src_mongo_client.as_ref().unwrap()
+        } else {
+            self.dst_mongo_client.as_ref().unwrap()
+        };
+        client
+            .database(db)
+            .collection::<Document>(tb)
+            .list_index_names()
+            .await
+            .unwrap()
+    }
+
     pub async fn fetch_data(&self, db: &str, tb: &str, from: &str) -> HashMap<MongoKey, Document> {
         let client = if from == SRC {
             self.src_mongo_client.as_ref().unwrap()
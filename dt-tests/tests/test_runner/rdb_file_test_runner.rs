@@ -0,0 +1,150 @@
+use std::{fs, path::Path};
+
+use dt_common::config::{s3_config::S3Config, sinker_config::SinkerConfig};
+use dt_task::task_util::TaskUtil;
+use rusoto_s3::{DeleteObjectRequest, GetObjectRequest, ListObjectsV2Request, S3Client, S3};
+use serde_json::Value;
+use tokio::io::AsyncReadExt;
+
+use super::{base_test_runner::BaseTestRunner, rdb_test_runner::RdbTestRunner};
+
+/// This is used for test cases: rdb(src) -> jsonl files, on local disk or s3 if s3_bucket is set.
+/// Rows in all generated files are compared with expect_data.jsonl in test dir,
+/// the order of rows and files is ignored.
+pub struct RdbFileTestRunner {
+    src_runner: RdbTestRunner,
+}
+
+impl RdbFileTestRunner {
+    pub async fn new(relative_test_dir: &str) -> anyhow::Result<Self> {
+        let src_runner = RdbTestRunner::new(relative_test_dir).await?;
+        Ok(Self { src_runner })
+    }
+
+    pub async fn close(&self) -> anyhow::Result<()> {
+        self.src_runner.close().await
+    }
+
+    pub async fn run_snapshot_test(&self) -> anyhow::Result<()> {
+        let (root_dir, s3_config) = match self.src_runner.base.get_config().sinker {
+            SinkerConfig::File {
+                root_dir,
+                s3_config,
+                ..
+            } => (root_dir, Some(s3_config)),
+            _ => (String::new(), None),
+        };
+        let s3 = s3_config
+            .filter(|s3_config| !s3_config.bucket.is_empty())
+            .map(|s3_config| (TaskUtil::create_s3_client(&s3_config), s3_config));
+
+        match &s3 {
+            Some((s3_client, s3_config)) => {
+                for key in Self::list_s3_keys(s3_client, s3_config).await? {
+                    let request = DeleteObjectRequest {
+                        bucket: s3_config.bucket.clone(),
+                        key,
+                        ..Default::default()
+                    };
+                    s3_client.delete_object(request).await?;
+                }
+            }
+            None => {
+                if BaseTestRunner::check_path_exists(&root_dir) {
+                    fs::remove_dir_all(&root_dir)?;
+                }
+            }
+        }
+
+        self.src_runner.execute_prepare_sqls().await?;
+        self.src_runner.execute_test_sqls().await?;
+        self.src_runner.base.start_task().await?;
+
+        let expect_file = format!("{}/expect_data.jsonl", self.src_runner.base.test_dir);
+        let mut expect_rows = Self::parse_rows(BaseTestRunner::load_file(&expect_file));
+        let mut actual_rows = Vec::new();
+        match &s3 {
+            Some((s3_client, s3_config)) => {
+                Self::load_s3_rows(s3_client, s3_config, &mut actual_rows).await?
+            }
+            None => Self::load_dir_rows(Path::new(&root_dir), &mut actual_rows)?,
+        }
+
+        expect_rows.sort();
+        actual_rows.sort();
+        assert_eq!(expect_rows, actual_rows);
+        Ok(())
+    }
+
+    async fn list_s3_keys(
+        s3_client: &S3Client,
+        s3_config: &S3Config,
+    ) -> anyhow::Result<Vec<String>> {
+        let mut keys = Vec::new();
+        let mut continuation_token = None;
+        loop {
+            let request = ListObjectsV2Request {
+                bucket: s3_config.bucket.clone(),
+                prefix: Some(format!("{}/", s3_config.root_dir)),
+                continuation_token,
+                ..Default::default()
+            };
+            let output = s3_client.list_objects_v2(request).await?;
+            for object in output.contents.unwrap_or_default() {
+                if let Some(key) = object.key {
+                    keys.push(key);
+                }
+            }
+            continuation_token = output.next_continuation_token;
+            if continuation_token.is_none() {
+                return Ok(keys);
+            }
+        }
+    }
+
+    async fn load_s3_rows(
+        s3_client: &S3Client,
+        s3_config: &S3Config,
+        rows: &mut Vec<String>,
+    ) -> anyhow::Result<()> {
+        for key in Self::list_s3_keys(s3_client, s3_config).await? {
+            let request = GetObjectRequest {
+                bucket: s3_config.bucket.clone(),
+                key,
+                ..Default::default()
+            };
+            let output = s3_client.get_object(request).await?;
+            let mut content = String::new();
+            if let Some(body) = output.body {
+                body.into_async_read().read_to_string(&mut content).await?;
+            }
+            let lines = content.lines().map(|line| line.to_string()).collect();
+            rows.extend(Self::parse_rows(lines));
+        }
+        Ok(())
+    }
+
+    fn load_dir_rows(dir: &Path, rows: &mut Vec<String>) -> anyhow::Result<()> {
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                Self::load_dir_rows(&path, rows)?;
+            } else {
+                let lines = BaseTestRunner::load_file(path.to_str().unwrap());
+                rows.extend(Self::parse_rows(lines));
+            }
+        }
+        Ok(())
+    }
+
+    fn parse_rows(lines: Vec<String>) -> Vec<String> {
+        lines
+            .iter()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| {
+                let value: Value = serde_json::from_str(line).unwrap();
+                value.to_string()
+            })
+            .collect()
+    }
+}
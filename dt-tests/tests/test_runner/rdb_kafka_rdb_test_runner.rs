@@ -137,33 +137,39 @@ impl RdbKafkaRdbTestRunner {
 
         let config = TaskConfig::new(&self.src_to_kafka_runner.task_config_file).unwrap();
         if let SinkerConfig::Kafka { url, .. } = config.sinker {
-            let check_topic_exist = |meta: &Metadata, topic: &str| -> bool {
-                for exist_topic in meta.topics() {
-                    if exist_topic.name() == topic && !exist_topic.partitions().is_empty() {
-                        return true;
-                    }
-                }
-                false
-            };
-
-            let admin_client = Self::create_kafka_admin_client(&url);
-            let consumer: BaseConsumer = Self::create_kafka_base_consumer(&url);
             for topic in topics.iter() {
-                // delete_topic/create_topic may fail
-                let mut meta = consumer.fetch_metadata(Some(topic), Duration::from_secs(10))?;
-                while check_topic_exist(&meta, topic) {
-                    Self::delete_topic(&admin_client, topic).await;
-                    meta = consumer.fetch_metadata(Some(topic), Duration::from_secs(10))?;
-                    TimeUtil::sleep_millis(100).await;
-                }
+                Self::prepare_topic(&url, topic).await?;
+            }
+        }
+        Ok(())
+    }
 
-                while !check_topic_exist(&meta, topic) {
-                    Self::create_topic(&admin_client, topic).await;
-                    meta = consumer.fetch_metadata(Some(topic), Duration::from_secs(10))?;
-                    TimeUtil::sleep_millis(100).await;
-                    println!("kafka topic: [{}] is NOT ready", topic);
+    /// recreate the topic to clear the messages of previous tests
+    pub async fn prepare_topic(url: &str, topic: &str) -> anyhow::Result<()> {
+        let check_topic_exist = |meta: &Metadata, topic: &str| -> bool {
+            for exist_topic in meta.topics() {
+                if exist_topic.name() == topic && !exist_topic.partitions().is_empty() {
+                    return true;
                 }
             }
+            false
+        };
+
+        let admin_client = Self::create_kafka_admin_client(url);
+        let consumer: BaseConsumer = Self::create_kafka_base_consumer(url);
+        // delete_topic/create_topic may fail
+        let mut meta = consumer.fetch_metadata(Some(topic), Duration::from_secs(10))?;
+        while check_topic_exist(&meta, topic) {
+            Self::delete_topic(&admin_client, topic).await;
+            meta = consumer.fetch_metadata(Some(topic), Duration::from_secs(10))?;
+            TimeUtil::sleep_millis(100).await;
+        }
+
+        while !check_topic_exist(&meta, topic) {
+            Self::create_topic(&admin_client, topic).await;
+            meta = consumer.fetch_metadata(Some(topic), Duration::from_secs(10))?;
+            TimeUtil::sleep_millis(100).await;
+            println!("kafka topic: [{}] is NOT ready", topic);
         }
         Ok(())
     }
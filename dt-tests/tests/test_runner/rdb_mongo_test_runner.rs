@@ -0,0 +1,86 @@
+use dt_common::utils::time_util::TimeUtil;
+use mongodb::bson::Bson;
+use serde_json::Value;
+
+use super::{
+    base_test_runner::BaseTestRunner,
+    mongo_test_runner::{MongoTestRunner, DST},
+    rdb_test_runner::RdbTestRunner,
+};
+
+/// This is used for test cases: mysql / pg(src) -> mongo(dst) with [shaper].
+/// After src_test.sql is executed, dst collections are compared with expect_data.jsonl in test dir,
+/// each line is a collection: {"db":"test_db_1","tb":"orders","docs":[{"_id":1,"items":[]}]},
+/// documents are compared as relaxed extended json, the order of documents is ignored
+/// but the order of array elements is not.
+pub struct RdbMongoTestRunner {
+    src_runner: RdbTestRunner,
+    dst_runner: MongoTestRunner,
+}
+
+impl RdbMongoTestRunner {
+    pub async fn new(relative_test_dir: &str) -> anyhow::Result<Self> {
+        let src_runner = RdbTestRunner::new(relative_test_dir).await?;
+        let dst_runner = MongoTestRunner::new(relative_test_dir).await?;
+        Ok(Self {
+            src_runner,
+            dst_runner,
+        })
+    }
+
+    pub async fn close(&self) -> anyhow::Result<()> {
+        self.src_runner.close().await
+    }
+
+    pub async fn run_cdc_test(&self, start_millis: u64, parse_millis: u64) -> anyhow::Result<()> {
+        let base = &self.src_runner.base;
+        self.src_runner
+            .execute_src_sqls(&base.src_prepare_sqls)
+            .await?;
+        self.dst_runner
+            .execute_dst_sqls(&base.dst_prepare_sqls)
+            .await?;
+
+        let task = base.spawn_task().await?;
+        TimeUtil::sleep_millis(start_millis).await;
+
+        self.src_runner
+            .execute_src_sqls(&base.src_test_sqls)
+            .await?;
+        TimeUtil::sleep_millis(parse_millis).await;
+        self.compare_expect_data().await?;
+
+        base.abort_task(&task).await
+    }
+
+    async fn compare_expect_data(&self) -> anyhow::Result<()> {
+        let expect_file = format!("{}/expect_data.jsonl", self.src_runner.base.test_dir);
+        for line in BaseTestRunner::load_file(&expect_file) {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let expect: Value = serde_json::from_str(&line)?;
+            let db = expect["db"].as_str().unwrap();
+            let tb = expect["tb"].as_str().unwrap();
+            let mut expect_docs = expect["docs"].as_array().unwrap().clone();
+
+            let mut dst_docs: Vec<Value> = self
+                .dst_runner
+                .fetch_data(db, tb, DST)
+                .await
+                .into_values()
+                .map(|doc| Bson::Document(doc).into_relaxed_extjson())
+                .collect();
+
+            expect_docs.sort_by_key(|doc| doc["_id"].to_string());
+            dst_docs.sort_by_key(|doc| doc["_id"].to_string());
+            println!(
+                "compare tb data, db: {}, tb: {}, expect_docs: {:?}, dst_docs: {:?}",
+                db, tb, expect_docs, dst_docs
+            );
+            assert_eq!(expect_docs, dst_docs);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,155 @@
+use std::{collections::BTreeMap, fs, path::Path, sync::Arc};
+
+use dt_common::{
+    config::extractor_config::ExtractorConfig, meta::position::Position, utils::time_util::TimeUtil,
+};
+use dt_connector::extractor::mysql::mysql_multi_cdc_extractor::MysqlCdcSource;
+use dt_task::task_util::TaskUtil;
+use sqlx::{MySql, Pool, Row};
+
+use super::{
+    base_test_runner::BaseTestRunner,
+    rdb_test_runner::{RdbTestRunner, DST},
+    rdb_util::RdbUtil,
+};
+
+/// This is used for test cases: multiple mysql sources([extractor] sources) -> mysql.
+/// src_prepare.sql is executed in every source, [source name]/src_resume.sql is executed in the source
+/// after its binlog position is written into [resumer] resume_config_file and before the task starts,
+/// [source name]/src_test.sql is executed in the source after the task starts.
+/// Rows of each source are compared with dst rows whose source_col is the source name.
+pub struct RdbMultiSourceTestRunner {
+    dst_runner: RdbTestRunner,
+    // (name, conn_pool) of each source
+    src_conn_pools: Vec<(String, Pool<MySql>)>,
+    source_col: String,
+}
+
+impl RdbMultiSourceTestRunner {
+    pub async fn new(relative_test_dir: &str) -> anyhow::Result<Self> {
+        let dst_runner = RdbTestRunner::new(relative_test_dir).await?;
+        let (sources, source_col) = match &dst_runner.config.extractor {
+            ExtractorConfig::MysqlCdc {
+                sources,
+                source_col,
+                ..
+            } => (MysqlCdcSource::parse(sources)?, source_col.clone()),
+            _ => (Vec::new(), String::new()),
+        };
+
+        let mut src_conn_pools = Vec::new();
+        for source in sources {
+            let conn_pool = TaskUtil::create_mysql_conn_pool(&source.url, 1, false, true).await?;
+            src_conn_pools.push((source.name, conn_pool));
+        }
+
+        Ok(Self {
+            dst_runner,
+            src_conn_pools,
+            source_col,
+        })
+    }
+
+    pub async fn close(&self) -> anyhow::Result<()> {
+        for (_, conn_pool) in self.src_conn_pools.iter() {
+            conn_pool.close().await;
+        }
+        self.dst_runner.close().await
+    }
+
+    pub async fn run_cdc_test(&self, start_millis: u64, parse_millis: u64) -> anyhow::Result<()> {
+        let base = &self.dst_runner.base;
+        for (_, conn_pool) in self.src_conn_pools.iter() {
+            RdbUtil::execute_sqls_mysql(conn_pool, &base.src_prepare_sqls).await?;
+        }
+        self.dst_runner
+            .execute_dst_sqls(&base.dst_prepare_sqls)
+            .await?;
+
+        // each source resumes from its own position and gets rows of src_resume.sql
+        self.write_resume_config().await?;
+        self.execute_source_sqls("src_resume.sql").await?;
+
+        let task = base.spawn_task().await?;
+        TimeUtil::sleep_millis(start_millis).await;
+        self.compare_data().await?;
+
+        self.execute_source_sqls("src_test.sql").await?;
+        TimeUtil::sleep_millis(parse_millis).await;
+        self.compare_data().await?;
+
+        base.abort_task(&task).await
+    }
+
+    async fn write_resume_config(&self) -> anyhow::Result<()> {
+        let mut sources = BTreeMap::new();
+        for (name, conn_pool) in self.src_conn_pools.iter() {
+            let row = sqlx::query("SHOW MASTER STATUS")
+                .fetch_one(conn_pool)
+                .await?;
+            let binlog_filename: String = row.try_get("File")?;
+            let next_event_position: u64 = row.try_get("Position")?;
+            let position = Position::MysqlCdc {
+                server_id: String::new(),
+                binlog_filename,
+                next_event_position: next_event_position as u32,
+                gtid_set: String::new(),
+                timestamp: String::new(),
+            };
+            sources.insert(name.clone(), position);
+        }
+
+        let position = Position::MysqlMultiCdc {
+            sources: Arc::new(sources),
+        };
+        let resume_config_file = &self.dst_runner.config.resumer.resume_config_file;
+        fs::create_dir_all(Path::new(resume_config_file).parent().unwrap())?;
+        fs::write(resume_config_file, position.to_string())?;
+        Ok(())
+    }
+
+    async fn execute_source_sqls(&self, sql_file: &str) -> anyhow::Result<()> {
+        for (name, conn_pool) in self.src_conn_pools.iter() {
+            let sql_file = format!("{}/{}/{}", self.dst_runner.base.test_dir, name, sql_file);
+            let sqls = BaseTestRunner::load_sql_file(&sql_file);
+            RdbUtil::execute_sqls_mysql(conn_pool, &sqls).await?;
+        }
+        Ok(())
+    }
+
+    async fn compare_data(&self) -> anyhow::Result<()> {
+        let base = &self.dst_runner.base;
+        let db_tbs = RdbTestRunner::get_compare_db_tbs_from_sqls(
+            &self.dst_runner.get_db_type(DST),
+            &base.src_prepare_sqls,
+        )?;
+
+        for db_tb in db_tbs.iter() {
+            for (name, conn_pool) in self.src_conn_pools.iter() {
+                let src_data = RdbUtil::fetch_data_mysql(conn_pool, None, db_tb, "").await?;
+                let condition = format!("`{}` = '{}'", self.source_col, name);
+                let dst_data = self
+                    .dst_runner
+                    .fetch_data_with_condition(db_tb, DST, &condition)
+                    .await?;
+                println!(
+                    "compare tb data, db: {}, tb: {}, source: {}, src_count: {}, dst_count: {}",
+                    db_tb.0,
+                    db_tb.1,
+                    name,
+                    src_data.len(),
+                    dst_data.len()
+                );
+                assert_eq!(src_data.len(), dst_data.len());
+
+                for (src_row, dst_row) in src_data.iter().zip(dst_data.iter()) {
+                    let mut dst_after = dst_row.after.clone().unwrap();
+                    let dst_source = dst_after.remove(&self.source_col).unwrap();
+                    assert_eq!(dst_source.to_option_string(), Some(name.clone()));
+                    assert_eq!(src_row.after.as_ref().unwrap(), &dst_after);
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -183,7 +183,7 @@ impl RdbSqlTestRunner {
         TimeUtil::sleep_millis(start_millis).await;
 
         let end_time_utc = Utc::now().format(UTC_FORMAT).to_string();
-        self.update_task_config(self.reverse, &start_time_utc, &end_time_utc);
+        self.update_task_config(self.reverse, &start_time_utc, &end_time_utc)?;
 
         // start task to generate sql file
         self.src_to_sql_runner.base.start_task().await
@@ -192,7 +192,7 @@ impl RdbSqlTestRunner {
     async fn start_pg_task(&self, start_millis: u64, parse_millis: u64) -> anyhow::Result<()> {
         let duration = Duration::try_milliseconds((start_millis + parse_millis) as i64).unwrap();
         let end_time_utc = (Utc::now() + duration).format(UTC_FORMAT).to_string();
-        self.update_task_config(self.reverse, "", &end_time_utc);
+        self.update_task_config(self.reverse, "", &end_time_utc)?;
 
         let task = self.src_to_sql_runner.base.spawn_task().await?;
         TimeUtil::sleep_millis(start_millis).await;
@@ -204,7 +204,12 @@ impl RdbSqlTestRunner {
         self.src_to_sql_runner.base.wait_task_finish(&task).await
     }
 
-    fn update_task_config(&self, reverse: bool, start_time_utc: &str, end_time_utc: &str) {
+    fn update_task_config(
+        &self,
+        reverse: bool,
+        start_time_utc: &str,
+        end_time_utc: &str,
+    ) -> anyhow::Result<()> {
         let reverse = if reverse { "true" } else { "false" };
         let update_configs = vec![
             ("sinker", "reverse", reverse),
@@ -216,6 +221,6 @@ impl RdbSqlTestRunner {
             &self.src_to_sql_runner.base.task_config_file,
             &self.src_to_sql_runner.base.task_config_file,
             &update_configs,
-        );
+        )
     }
 }
@@ -432,8 +432,7 @@ impl RdbTestRunner {
             &self.base.task_config_file,
             &self.base.task_config_file,
             &update_configs,
-        );
-        Ok(())
+        )
     }
 
     pub async fn spawn_cdc_task(
@@ -0,0 +1,50 @@
+use dt_common::{
+    config::{extractor_config::ExtractorConfig, sinker_config::SinkerConfig},
+    utils::redis_util::RedisUtil,
+};
+
+use crate::test_runner::redis_test_util::RedisTestUtil;
+
+use super::{base_test_runner::BaseTestRunner, check_util::CheckUtil};
+
+pub struct RedisCheckTestRunner {
+    pub base: BaseTestRunner,
+    dst_check_log_dir: String,
+    expect_check_log_dir: String,
+}
+
+impl RedisCheckTestRunner {
+    pub async fn new(relative_test_dir: &str) -> anyhow::Result<Self> {
+        let base = BaseTestRunner::new(relative_test_dir).await.unwrap();
+        let (expect_check_log_dir, dst_check_log_dir) = CheckUtil::get_check_log_dir(&base, "");
+        Ok(Self {
+            base,
+            dst_check_log_dir,
+            expect_check_log_dir,
+        })
+    }
+
+    pub async fn run_check_test(&self) -> anyhow::Result<()> {
+        CheckUtil::clear_check_log(&self.dst_check_log_dir);
+
+        let config = self.base.get_config();
+        let redis_util = RedisTestUtil::new_default();
+        match config.extractor {
+            ExtractorConfig::RedisSnapshot { url, .. } | ExtractorConfig::RedisScan { url, .. } => {
+                let mut src_conn = RedisUtil::create_redis_conn(&url).await?;
+                redis_util.execute_cmds(&mut src_conn, &self.base.src_prepare_sqls);
+                redis_util.execute_cmds(&mut src_conn, &self.base.src_test_sqls);
+            }
+            _ => {}
+        }
+
+        if let SinkerConfig::RedisCheck { url, .. } = config.sinker {
+            let mut dst_conn = RedisUtil::create_redis_conn(&url).await?;
+            redis_util.execute_cmds(&mut dst_conn, &self.base.dst_prepare_sqls);
+            redis_util.execute_cmds(&mut dst_conn, &self.base.dst_test_sqls);
+        }
+
+        self.base.start_task().await?;
+        CheckUtil::validate_check_log(&self.expect_check_log_dir, &self.dst_check_log_dir)
+    }
+}
@@ -7,13 +7,17 @@ use futures::executor::block_on;
 use crate::test_runner::rdb_test_runner::DST;
 
 use super::{
-    mongo_check_test_runner::MongoCheckTestRunner, mongo_test_runner::MongoTestRunner,
-    precheck_test_runner::PrecheckTestRunner, rdb_check_test_runner::RdbCheckTestRunner,
+    mongo_check_test_runner::MongoCheckTestRunner, mongo_rdb_test_runner::MongoRdbTestRunner,
+    mongo_test_runner::MongoTestRunner, precheck_test_runner::PrecheckTestRunner,
+    rdb_check_test_runner::RdbCheckTestRunner, rdb_file_test_runner::RdbFileTestRunner,
     rdb_kafka_rdb_test_runner::RdbKafkaRdbTestRunner, rdb_lua_test_runner::RdbLuaTestRunner,
+    rdb_mongo_test_runner::RdbMongoTestRunner,
+    rdb_multi_source_test_runner::RdbMultiSourceTestRunner,
     rdb_redis_test_runner::RdbRedisTestRunner, rdb_sql_test_runner::RdbSqlTestRunner,
     rdb_starrocks_test_runner::RdbStarRocksTestRunner, rdb_struct_test_runner::RdbStructTestRunner,
-    rdb_test_runner::RdbTestRunner, redis_statistic_runner::RedisStatisticTestRunner,
-    redis_test_runner::RedisTestRunner,
+    rdb_test_runner::RdbTestRunner, redis_check_test_runner::RedisCheckTestRunner,
+    redis_statistic_runner::RedisStatisticTestRunner, redis_test_runner::RedisTestRunner,
+    tidb_cdc_test_runner::TidbCdcTestRunner,
 };
 
 pub struct TestBase {}
@@ -163,6 +167,24 @@ impl TestBase {
             .unwrap();
     }
 
+    pub async fn run_mongo_rdb_cdc_test(test_dir: &str, start_millis: u64, parse_millis: u64) {
+        let runner = MongoRdbTestRunner::new(test_dir).await.unwrap();
+        runner
+            .run_cdc_test(start_millis, parse_millis)
+            .await
+            .unwrap();
+        runner.close().await.unwrap();
+    }
+
+    pub async fn run_rdb_mongo_cdc_test(test_dir: &str, start_millis: u64, parse_millis: u64) {
+        let runner = RdbMongoTestRunner::new(test_dir).await.unwrap();
+        runner
+            .run_cdc_test(start_millis, parse_millis)
+            .await
+            .unwrap();
+        runner.close().await.unwrap();
+    }
+
     pub async fn run_mongo_cdc_resume_test(test_dir: &str, start_millis: u64, parse_millis: u64) {
         let runner = MongoTestRunner::new(test_dir).await.unwrap();
         runner
@@ -171,6 +193,14 @@ impl TestBase {
             .unwrap();
     }
 
+    pub async fn run_mongo_cdc_ddl_test(test_dir: &str, start_millis: u64, parse_millis: u64) {
+        let runner = MongoTestRunner::new(test_dir).await.unwrap();
+        runner
+            .run_cdc_ddl_test(start_millis, parse_millis)
+            .await
+            .unwrap();
+    }
+
     pub async fn run_mongo_heartbeat_test(test_dir: &str, start_millis: u64, parse_millis: u64) {
         let runner = MongoTestRunner::new(test_dir).await.unwrap();
         runner
@@ -239,6 +269,11 @@ impl TestBase {
             .unwrap();
     }
 
+    pub async fn run_redis_check_test(test_dir: &str) {
+        let runner = RedisCheckTestRunner::new(test_dir).await.unwrap();
+        runner.run_check_test().await.unwrap();
+    }
+
     pub async fn run_redis_statistic_test(test_dir: &str) {
         let mut runner = RedisStatisticTestRunner::new(test_dir).await.unwrap();
         runner.run_statistic_test().await.unwrap();
@@ -283,6 +318,14 @@ impl TestBase {
             .unwrap();
     }
 
+    pub async fn run_tidb_cdc_test(test_dir: &str, start_millis: u64, parse_millis: u64) {
+        let runner = TidbCdcTestRunner::new(test_dir).await.unwrap();
+        runner
+            .run_cdc_test(start_millis, parse_millis)
+            .await
+            .unwrap();
+    }
+
     pub async fn run_rdb_kafka_rdb_snapshot_test(
         test_dir: &str,
         start_millis: u64,
@@ -310,6 +353,25 @@ impl TestBase {
         runner.close().await.unwrap();
     }
 
+    pub async fn run_rdb_multi_source_cdc_test(
+        test_dir: &str,
+        start_millis: u64,
+        parse_millis: u64,
+    ) {
+        let runner = RdbMultiSourceTestRunner::new(test_dir).await.unwrap();
+        runner
+            .run_cdc_test(start_millis, parse_millis)
+            .await
+            .unwrap();
+        runner.close().await.unwrap();
+    }
+
+    pub async fn run_rdb_file_snapshot_test(test_dir: &str) {
+        let runner = RdbFileTestRunner::new(test_dir).await.unwrap();
+        runner.run_snapshot_test().await.unwrap();
+        runner.close().await.unwrap();
+    }
+
     pub async fn run_rdb_starrocks_cdc_test(test_dir: &str, start_millis: u64, parse_millis: u64) {
         let runner = RdbStarRocksTestRunner::new(test_dir).await.unwrap();
         runner
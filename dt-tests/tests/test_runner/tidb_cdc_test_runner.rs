@@ -0,0 +1,134 @@
+use std::fs;
+use std::time::Duration;
+
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::ClientConfig;
+
+use crate::test_config_util::TestConfigUtil;
+
+use super::base_test_runner::BaseTestRunner;
+use super::rdb_kafka_rdb_test_runner::RdbKafkaRdbTestRunner;
+use super::rdb_test_runner::RdbTestRunner;
+use dt_common::config::config_enums::TicdcProtocol;
+use dt_common::config::extractor_config::ExtractorConfig;
+use dt_common::utils::time_util::TimeUtil;
+use serde_json::Value;
+
+const TICDC_MESSAGES_FILE: &str = "ticdc_messages.jsonl";
+const OPEN_PROTOCOL_BATCH_VERSION: u64 = 1;
+
+/// This is used for test cases: tidb -> TiCDC -> kafka -> rdb(dst).
+/// Messages recorded from TiCDC are produced to kafka, and there is 1 task running:
+///     kafka -> rdb(dst)
+/// And we need another dummy task runner to compare rdb(src) and rdb(dst),
+/// src_test.sql of it should be the sqls executed in tidb when recording the messages
+///     rdb(src) -> rdb(dst)
+/// Each line of ticdc_messages.jsonl is a kafka message, for canal-json it is the payload,
+/// for open-protocol it is a json array of [key, value] pairs which are encoded into a batch
+pub struct TidbCdcTestRunner {
+    src_to_dst_runner: RdbTestRunner,
+    kafka_to_dst_runner: BaseTestRunner,
+    ticdc_messages: Vec<String>,
+}
+
+#[allow(dead_code)]
+impl TidbCdcTestRunner {
+    pub async fn new(relative_test_dir: &str) -> anyhow::Result<Self> {
+        let src_to_dst_runner =
+            RdbTestRunner::new(&format!("{}/src_to_dst", relative_test_dir)).await?;
+        let kafka_to_dst_runner =
+            BaseTestRunner::new(&format!("{}/kafka_to_dst", relative_test_dir)).await?;
+
+        let test_dir = TestConfigUtil::get_absolute_path(relative_test_dir);
+        let ticdc_messages = fs::read_to_string(format!("{}/{}", test_dir, TICDC_MESSAGES_FILE))?
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.to_string())
+            .collect();
+
+        Ok(Self {
+            src_to_dst_runner,
+            kafka_to_dst_runner,
+            ticdc_messages,
+        })
+    }
+
+    pub async fn run_cdc_test(&self, start_millis: u64, parse_millis: u64) -> anyhow::Result<()> {
+        self.src_to_dst_runner.execute_prepare_sqls().await?;
+        self.produce_ticdc_messages().await?;
+
+        // prepare expected data in src
+        self.src_to_dst_runner.execute_test_sqls().await?;
+
+        // kafka -> dst
+        let kafka_to_dst_task = self.kafka_to_dst_runner.spawn_task().await?;
+        TimeUtil::sleep_millis(start_millis + parse_millis).await;
+
+        // compare data
+        let (src_db_tbs, dst_db_tbs) = self.src_to_dst_runner.get_compare_db_tbs()?;
+        assert!(
+            self.src_to_dst_runner
+                .compare_data_for_tbs(&src_db_tbs, &dst_db_tbs)
+                .await?
+        );
+
+        self.kafka_to_dst_runner
+            .abort_task(&kafka_to_dst_task)
+            .await?;
+        Ok(())
+    }
+
+    async fn produce_ticdc_messages(&self) -> anyhow::Result<()> {
+        let config = self.kafka_to_dst_runner.get_config();
+        let ExtractorConfig::TidbCdc {
+            url,
+            topic,
+            partition,
+            protocol,
+            ..
+        } = config.extractor
+        else {
+            panic!("extractor of kafka_to_dst should be tidb cdc")
+        };
+        RdbKafkaRdbTestRunner::prepare_topic(&url, &topic).await?;
+
+        let mut config = ClientConfig::new();
+        config.set("bootstrap.servers", &url);
+        config.set("message.timeout.ms", "10000");
+        let producer: FutureProducer = config.create()?;
+        for message in self.ticdc_messages.iter() {
+            let (key, payload) = match protocol {
+                TicdcProtocol::CanalJson => (Vec::new(), message.as_bytes().to_vec()),
+                TicdcProtocol::OpenProtocol => Self::encode_open_protocol_message(message)?,
+            };
+            let record = FutureRecord::<Vec<u8>, Vec<u8>>::to(&topic)
+                .partition(partition)
+                .key(&key)
+                .payload(&payload);
+            if let Err((err, _)) = producer.send(record, Duration::from_secs(10)).await {
+                return Err(err.into());
+            }
+        }
+        Ok(())
+    }
+
+    /// key: [version][key_len][key][key_len][key]..., value: [value_len][value][value_len][value]...,
+    /// a null value (resolved event) is encoded as an empty item
+    fn encode_open_protocol_message(message: &str) -> anyhow::Result<(Vec<u8>, Vec<u8>)> {
+        let items: Vec<(Value, Value)> = serde_json::from_str(message)?;
+        let mut key = OPEN_PROTOCOL_BATCH_VERSION.to_be_bytes().to_vec();
+        let mut payload = Vec::new();
+        for (k, v) in items {
+            let k = serde_json::to_vec(&k)?;
+            let v = match v {
+                Value::Null => Vec::new(),
+                v => serde_json::to_vec(&v)?,
+            };
+            key.extend_from_slice(&(k.len() as u64).to_be_bytes());
+            key.extend_from_slice(&k);
+            payload.extend_from_slice(&(v.len() as u64).to_be_bytes());
+            payload.extend_from_slice(&v);
+        }
+        Ok((key, payload))
+    }
+}
@@ -0,0 +1,14 @@
+-- sqls executed in tidb when recording ticdc_messages.jsonl
+-- the first 3 inserts are in one transaction
+INSERT INTO test_db_1.tb_1 VALUES (1, -128, 18446744073709551615, 123.450, 1.5, '2024-05-19 09:42:29', 'abc', x'00FF', '{"k": 1}', 'medium', b'101');
+INSERT INTO test_db_1.tb_1 VALUES (2, 127, 0, -1.000, -0.25, '2024-05-19 09:42:30', NULL, NULL, NULL, 'small', NULL);
+INSERT INTO test_db_1.tb_2 VALUES (1, 'a');
+
+INSERT INTO test_db_1.tb_1 VALUES (3, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL, NULL);
+INSERT INTO test_db_1.tb_2 VALUES (2, 'b');
+
+UPDATE test_db_1.tb_1 SET c_tiny = 0, c_varchar = 'xyz' WHERE id = 1;
+UPDATE test_db_1.tb_2 SET value = 'c' WHERE id = 2;
+
+DELETE FROM test_db_1.tb_1 WHERE id = 3;
+DELETE FROM test_db_1.tb_2 WHERE id = 1;
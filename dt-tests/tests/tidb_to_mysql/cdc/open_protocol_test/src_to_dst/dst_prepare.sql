@@ -0,0 +1,7 @@
+DROP DATABASE IF EXISTS test_db_1;
+
+CREATE DATABASE test_db_1;
+
+CREATE TABLE test_db_1.tb_1 (id int, c_tiny tinyint, c_ubig bigint unsigned, c_decimal decimal(10,3), c_double double, c_datetime datetime, c_varchar varchar(255), c_blob blob, c_json json, c_enum enum('x-small','small','medium','large','x-large'), c_bit bit(8), PRIMARY KEY (id)) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
+
+CREATE TABLE test_db_1.tb_2 (id int, value varchar(255), PRIMARY KEY (id)) ENGINE=InnoDB DEFAULT CHARSET=utf8mb4;
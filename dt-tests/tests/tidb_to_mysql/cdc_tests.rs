@@ -0,0 +1,22 @@
+#[cfg(test)]
+mod test {
+
+    use serial_test::serial;
+
+    use crate::test_runner::test_base::TestBase;
+
+    /// messages recorded from TiCDC with sink-uri: kafka://.../ticdc?protocol=canal-json&enable-tidb-extension=true
+    #[tokio::test]
+    #[serial]
+    async fn cdc_canal_json_test() {
+        TestBase::run_tidb_cdc_test("tidb_to_mysql/cdc/canal_json_test", 3000, 5000).await;
+    }
+
+    /// messages of TiCDC with sink-uri: kafka://.../ticdc?protocol=open-protocol,
+    /// the first 2 transactions are sent again at the end as after a changefeed restarts
+    #[tokio::test]
+    #[serial]
+    async fn cdc_open_protocol_test() {
+        TestBase::run_tidb_cdc_test("tidb_to_mysql/cdc/open_protocol_test", 3000, 5000).await;
+    }
+}